@@ -20,6 +20,7 @@ const mockBuildMerkleTree = vi.hoisted(() => vi.fn());
 const mockSubmitMerkleRoot = vi.hoisted(() => vi.fn());
 const mockGetRateLimitedAvailable = vi.hoisted(() => vi.fn());
 const mockGetProof = vi.hoisted(() => vi.fn());
+const mockGetDomainSalt = vi.hoisted(() => vi.fn());
 const mockGetRpcEndpoint = vi.hoisted(() => vi.fn());
 const mockGetMintPda = vi.hoisted(() => vi.fn());
 const mockGetNowISO = vi.hoisted(() => vi.fn());
@@ -48,6 +49,7 @@ vi.mock('@/lib/solana', () => ({
   submitMerkleRoot: mockSubmitMerkleRoot,
   getRateLimitedAvailable: mockGetRateLimitedAvailable,
   getProof: mockGetProof,
+  getDomainSalt: mockGetDomainSalt,
   getRpcEndpoint: mockGetRpcEndpoint,
   getMintPda: mockGetMintPda,
 }));
@@ -95,6 +97,7 @@ describe('GET /api/cron/distribute', () => {
     mockGetMintPda.mockReturnValue([{ toBase58: () => 'mock-mint' }, 0]);
     // Default getProof mock returns a valid proof structure
     mockGetProof.mockReturnValue({ wallet: 'test', amount: BigInt(0), proof: [] });
+    mockGetDomainSalt.mockResolvedValue(new Uint8Array(16).fill(7));
   });
 
   afterEach(() => {
@@ -202,6 +205,9 @@ describe('GET /api/cron/distribute', () => {
     expect(data.totalAllocatablePoints).toBe(400);
     expect(data.submitTx).toBe('tx-abc123');
 
+    // Leaves are built with the deployment's domain_salt
+    expect(mockBuildMerkleTree).toHaveBeenCalledWith(expect.any(Array), new Uint8Array(16).fill(7));
+
     // Verify distribution was created
     expect(mockDb.createDistribution).toHaveBeenCalledWith(
       'test-uuid-123',
@@ -20,6 +20,7 @@ const mockDb = vi.hoisted(() => ({
 }));
 const mockBuildMerkleTree = vi.hoisted(() => vi.fn());
 const mockGetProof = vi.hoisted(() => vi.fn());
+const mockGetDomainSalt = vi.hoisted(() => vi.fn());
 
 // Mock dependencies before importing the route
 vi.mock('@/auth', () => ({ auth: mockAuthFn }));
@@ -33,6 +34,7 @@ vi.mock('@/lib/logger', () => ({
 vi.mock('@/lib/solana', () => ({
   buildMerkleTree: mockBuildMerkleTree,
   getProof: mockGetProof,
+  getDomainSalt: mockGetDomainSalt,
 }));
 vi.mock('@solana/kit', () => ({
   address: vi.fn((addr: string) => {
@@ -77,6 +79,7 @@ describe('GET /api/rewards/claim', () => {
   beforeEach(() => {
     vi.clearAllMocks();
     resetDbMocks();
+    mockGetDomainSalt.mockResolvedValue(new Uint8Array(16).fill(7));
   });
 
   it('returns 401 when not authenticated', async () => {
@@ -242,8 +245,11 @@ describe('GET /api/rewards/claim', () => {
 
       expect(status).toBe(200);
       expect(data.claimable).toBe(true);
-      // Should rebuild when JSON is invalid
-      expect(mockBuildMerkleTree).toHaveBeenCalled();
+      // Should rebuild when JSON is invalid, with the deployment's domain_salt
+      expect(mockBuildMerkleTree).toHaveBeenCalledWith(
+        expect.any(Array),
+        new Uint8Array(16).fill(7)
+      );
     });
   });
 
@@ -311,6 +317,7 @@ describe('POST /api/rewards/claim', () => {
   beforeEach(() => {
     vi.clearAllMocks();
     resetDbMocks();
+    mockGetDomainSalt.mockResolvedValue(new Uint8Array(16).fill(7));
   });
 
   it('returns 401 when not authenticated', async () => {
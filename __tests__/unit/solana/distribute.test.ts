@@ -7,7 +7,7 @@ vi.mock('@/lib/solana/explorer', () => ({
 }));
 
 // Import after mocks
-import { getRpcEndpoint } from '@/lib/solana/distribute';
+import { getRpcEndpoint, readDomainSalt } from '@/lib/solana/distribute';
 
 // Token decimals: 1 YAP = 1e9 raw units
 const YAP = (amount: number) => BigInt(Math.floor(amount * 1e9));
@@ -48,6 +48,22 @@ describe('distribute.ts', () => {
     });
   });
 
+  // ============================================================
+  // readDomainSalt tests
+  // ============================================================
+  describe('readDomainSalt', () => {
+    it('reads Config.claims.domain_salt at offset 1487', () => {
+      const data = new Uint8Array(1600);
+      data.set(new Uint8Array(16).fill(9), 1487);
+
+      expect(readDomainSalt(data)).toEqual(new Uint8Array(16).fill(9));
+    });
+
+    it('returns an empty salt for a config from before the salt', () => {
+      expect(readDomainSalt(new Uint8Array(1000))).toEqual(new Uint8Array(0));
+    });
+  });
+
   // ============================================================
   // Rate limiting math tests (pure calculations)
   // ============================================================
@@ -142,9 +158,9 @@ describe('distribute.ts', () => {
     });
 
     it('instruction data layout is correct', () => {
-      // Layout: [discriminator(1)] [amount(8)] [merkle_root(32)] = 41 bytes
-      const expectedSize = 1 + 8 + 32;
-      expect(expectedSize).toBe(41);
+      // Layout: [discriminator(1)] [amount(8)] [merkle_root(32)] [tree_depth(1)] = 42 bytes
+      const expectedSize = 1 + 8 + 32 + 1;
+      expect(expectedSize).toBe(42);
     });
   });
 
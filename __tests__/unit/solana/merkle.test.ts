@@ -57,6 +57,30 @@ describe('merkle.ts', () => {
 
       expect(hash1).not.toEqual(hash2);
     });
+
+    it('produces different hash for different salts', () => {
+      const hash1 = hashLeaf(ALICE, BigInt(100), new Uint8Array(16).fill(1));
+      const hash2 = hashLeaf(ALICE, BigInt(100), new Uint8Array(16).fill(2));
+
+      expect(hash1).not.toEqual(hash2);
+    });
+
+    it('treats an all-zero salt as unsalted', () => {
+      const hash1 = hashLeaf(ALICE, BigInt(100), new Uint8Array(16));
+      const hash2 = hashLeaf(ALICE, BigInt(100));
+
+      expect(hash1).toEqual(hash2);
+    });
+
+    it("matches the contract's compute_salted_leaf for a nonzero salt", () => {
+      // Same inputs as test_salted_leaf_vector in contracts/programs/yap/src/utils/merkle.rs
+      const salt = Uint8Array.from({ length: 16 }, (_, i) => i + 1);
+      const hash = hashLeaf(ALICE, BigInt(1_000_000_000), salt);
+
+      expect(Buffer.from(hash).toString('hex')).toBe(
+        '3eb0e65585f272a5a1b8a432c561d4c5ca32c78968adbd28288300425e7569df'
+      );
+    });
   });
 
   // ============================================================
@@ -105,6 +129,29 @@ describe('merkle.ts', () => {
       expect(distribution.entries).toHaveLength(100);
     });
 
+    it('pads to a power of two so every proof is depth long', () => {
+      const single = buildMerkleTree([{ wallet: ALICE, amount: BigInt(1000) }]);
+      expect(single.depth).toBe(1);
+      expect(getProof(single, ALICE)!.proof).toHaveLength(1);
+
+      // The third leaf of three gets a zero sibling instead of being promoted
+      const entries: RewardEntry[] = [
+        { wallet: ALICE, amount: BigInt(1000) },
+        { wallet: BOB, amount: BigInt(2000) },
+        { wallet: CHARLIE, amount: BigInt(3000) },
+      ];
+      const distribution = buildMerkleTree(entries);
+      expect(distribution.depth).toBe(2);
+      for (const entry of entries) {
+        const claimProof = getProof(distribution, entry.wallet)!;
+        expect(claimProof.proof).toHaveLength(2);
+        expect(
+          verifyProof(distribution.root, entry.wallet, entry.amount, claimProof.proof)
+        ).toBe(true);
+      }
+      expect(getProof(distribution, CHARLIE)!.proof[0]).toEqual(new Uint8Array(32));
+    });
+
     it('is deterministic (same entries = same root)', () => {
       const entries: RewardEntry[] = [
         { wallet: ALICE, amount: BigInt(1000) },
@@ -239,6 +286,15 @@ describe('merkle.ts', () => {
 
       expect(isValid).toBe(false);
     });
+
+    it('requires the salt the tree was built with', () => {
+      const salt = new Uint8Array(16).fill(7);
+      const salted = buildMerkleTree(entries, salt);
+      const claimProof = getProof(salted, ALICE)!;
+
+      expect(verifyProof(salted.root, ALICE, claimProof.amount, claimProof.proof, salt)).toBe(true);
+      expect(verifyProof(salted.root, ALICE, claimProof.amount, claimProof.proof)).toBe(false);
+    });
   });
 
   // ============================================================
@@ -9,6 +9,7 @@ import {
   buildMerkleTree,
   submitMerkleRoot,
   getRateLimitedAvailable,
+  getDomainSalt,
   getProof,
   getMintPda,
   getRpcEndpoint,
@@ -172,7 +173,8 @@ export async function GET(request: NextRequest) {
       amount: ua.cumulativeYapRaw,
     }));
 
-    const distribution = buildMerkleTree(entries);
+    // Leaves must carry the deployment's domain_salt to verify on-chain
+    const distribution = buildMerkleTree(entries, await getDomainSalt());
     const merkleRoot = distribution.root;
 
     // Total NEW amount being distributed this cycle (only valid allocations)
@@ -230,7 +232,7 @@ export async function GET(request: NextRequest) {
     // Submit distribution onchain (transfers tokens and sets merkle root)
     let submitTx: string | undefined;
     try {
-      submitTx = await submitMerkleRoot(merkleRoot, totalNewAmountBigInt);
+      submitTx = await submitMerkleRoot(merkleRoot, totalNewAmountBigInt, distribution.depth);
       logger.info({ submitTx }, 'Distribution submitted onchain');
 
       // Mark distribution as submitted
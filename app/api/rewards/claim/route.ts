@@ -3,7 +3,7 @@ import { randomUUID } from 'crypto';
 import { db } from '@/lib/database';
 import { apiLogger } from '@/lib/logger';
 import { address, type Address } from '@solana/kit';
-import { getProof, buildMerkleTree, type RewardEntry } from '@/lib/solana';
+import { getProof, buildMerkleTree, getDomainSalt, type RewardEntry } from '@/lib/solana';
 import {
   requireUser,
   isAuthError,
@@ -72,7 +72,7 @@ export async function GET(request: NextRequest) {
       amount: BigInt(r.amount),
     }));
 
-    const merkleDistribution = buildMerkleTree(entries);
+    const merkleDistribution = buildMerkleTree(entries, await getDomainSalt());
     const proof = getProof(merkleDistribution, userWallet);
 
     if (!proof) {
@@ -0,0 +1,88 @@
+//! Minimal program that burns YAP for its caller via CPI
+//!
+//! It has its own entrypoint and depends on `yap` with `no-entrypoint`, so
+//! both link into one binary without symbol collisions while YAP's
+//! `instruction` and `state` types stay available.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    program::invoke,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use yap::instruction::YapInstruction;
+
+#[cfg(not(feature = "no-entrypoint"))]
+solana_program::entrypoint!(process_instruction);
+
+/// Forward a YAP `Burn` of the little-endian `u64` in `instruction_data`
+///
+/// Accounts: YAP's `Burn` accounts in order, followed by the YAP program.
+pub fn process_instruction(
+    _program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    let amount = instruction_data
+        .try_into()
+        .map(u64::from_le_bytes)
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+
+    let (yap_program, burn_accounts) = accounts
+        .split_last()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    if *yap_program.key != yap::id() {
+        return Err(ProgramError::IncorrectProgramId);
+    }
+
+    let metas = burn_accounts
+        .iter()
+        .map(|account| AccountMeta {
+            pubkey: *account.key,
+            is_signer: account.is_signer,
+            is_writable: account.is_writable,
+        })
+        .collect();
+    invoke(&burn_instruction(metas, amount)?, burn_accounts)
+}
+
+/// A YAP `Burn` instruction over `accounts`
+pub fn burn_instruction(
+    accounts: Vec<AccountMeta>,
+    amount: u64,
+) -> Result<Instruction, ProgramError> {
+    let data = borsh::to_vec(&YapInstruction::Burn { amount })
+        .map_err(|_| ProgramError::InvalidInstructionData)?;
+    Ok(Instruction {
+        program_id: yap::id(),
+        accounts,
+        data,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_burn_instruction_round_trips() {
+        let ix = burn_instruction(vec![], 42).unwrap();
+        assert_eq!(ix.program_id, yap::id());
+        assert!(matches!(
+            YapInstruction::unpack(&ix.data),
+            Ok(YapInstruction::Burn { amount: 42 })
+        ));
+    }
+
+    #[test]
+    fn test_rejects_other_program() {
+        let key = Pubkey::new_unique();
+        let (mut lamports, mut data) = (0, vec![]);
+        let not_yap = AccountInfo::new(&key, false, false, &mut lamports, &mut data, &key, true);
+
+        let err = process_instruction(&key, &[not_yap], &7u64.to_le_bytes()).unwrap_err();
+        assert_eq!(err, ProgramError::IncorrectProgramId);
+    }
+}
@@ -0,0 +1,41 @@
+//! Cost of the constant-time root comparison next to one proof level
+//!
+//! Run with `cargo bench -p yap --bench ct_eq`.
+
+use std::{hint::black_box, time::Instant};
+
+use solana_program::pubkey::Pubkey;
+use yap::utils::merkle::{compute_leaf, compute_root, ct_eq};
+
+const ROUNDS: u32 = 100_000;
+
+fn main() {
+    let a = black_box(compute_leaf(&Pubkey::new_unique(), 1));
+    let b = black_box(compute_leaf(&Pubkey::new_unique(), 2));
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(black_box(&a) == black_box(&b));
+    }
+    let plain = start.elapsed();
+
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(ct_eq(black_box(&a), black_box(&b)));
+    }
+    let constant_time = start.elapsed();
+
+    // One keccak level, the unit a proof already pays MAX_PROOF_DEPTH times
+    let start = Instant::now();
+    for _ in 0..ROUNDS {
+        black_box(compute_root(black_box(&a), &[*black_box(&b)]));
+    }
+    let hash = start.elapsed();
+
+    println!(
+        "per call: == {:?}, ct_eq {:?}, one proof level {:?}",
+        plain / ROUNDS,
+        constant_time / ROUNDS,
+        hash / ROUNDS
+    );
+}
@@ -0,0 +1,413 @@
+//! Host-side instruction builders (feature `client`)
+//!
+//! Each `ix_*` function derives the PDAs the processor checks and lists the
+//! accounts in the order documented on `YapInstruction`, so integrators don't
+//! have to assemble `AccountMeta`s by hand. Optional trailing accounts (clock
+//! sysvar, destination owner) are left out, except the Associated Token
+//! program on `Claim`, which lets a missing ATA be created. `token_program` is
+//! SPL Token or Token-2022, whichever the deployment was initialized with.
+
+use solana_program::{
+    instruction::{AccountMeta, Instruction},
+    pubkey::Pubkey,
+    sysvar,
+};
+
+use crate::{
+    instruction::YapInstruction,
+    state::{
+        ClaimDelegate, Config, FrozenWallet, ProofBuffer, StakeAccount, UserClaimStatus,
+        ASSOCIATED_TOKEN_PROGRAM_ID, METADATA_PROGRAM_ID, METADATA_SEED, MINT_SEED,
+        PENDING_CLAIMS_SEED, STAKE_VAULT_SEED, VAULT_SEED,
+    },
+};
+
+/// Config PDA: `["config"]`
+pub fn config_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[Config::SEED], program_id).0
+}
+
+/// Mint PDA: `["mint"]`
+pub fn mint_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[MINT_SEED], program_id).0
+}
+
+/// Vault token account PDA: `["vault"]`
+pub fn vault_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[VAULT_SEED], program_id).0
+}
+
+/// Pending claims token account PDA: `["pending_claims"]`
+pub fn pending_claims_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[PENDING_CLAIMS_SEED], program_id).0
+}
+
+/// UserClaimStatus PDA: `["user_claim", user]`
+pub fn user_claim_pda(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], program_id).0
+}
+
+/// StakeAccount PDA: `["stake", user]`
+pub fn stake_pda(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[StakeAccount::SEED, user.as_ref()], program_id).0
+}
+
+/// Stake vault token account PDA: `["stake_vault"]`
+pub fn stake_vault_pda(program_id: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[STAKE_VAULT_SEED], program_id).0
+}
+
+/// ClaimDelegate PDA: `["delegate", user]`
+pub fn claim_delegate_pda(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ClaimDelegate::SEED, user.as_ref()], program_id).0
+}
+
+/// ProofBuffer PDA: `["proof_buffer", user]`
+pub fn proof_buffer_pda(program_id: &Pubkey, user: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[ProofBuffer::SEED, user.as_ref()], program_id).0
+}
+
+/// FrozenWallet PDA: `["frozen", wallet]`; append it to claims and burns while any
+/// wallet is frozen
+pub fn frozen_wallet_pda(program_id: &Pubkey, wallet: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(&[FrozenWallet::SEED, wallet.as_ref()], program_id).0
+}
+
+/// Metaplex metadata PDA of the YAP mint
+pub fn metadata_pda(program_id: &Pubkey) -> Pubkey {
+    let mint = mint_pda(program_id);
+    Pubkey::find_program_address(
+        &[METADATA_SEED, METADATA_PROGRAM_ID.as_ref(), mint.as_ref()],
+        &METADATA_PROGRAM_ID,
+    )
+    .0
+}
+
+/// The user's associated token account for the YAP mint
+pub fn user_ata(program_id: &Pubkey, token_program: &Pubkey, user: &Pubkey) -> Pubkey {
+    let mint = mint_pda(program_id);
+    Pubkey::find_program_address(
+        &[user.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0
+}
+
+fn build(
+    program_id: &Pubkey,
+    instruction: YapInstruction,
+    accounts: Vec<AccountMeta>,
+) -> Instruction {
+    Instruction {
+        program_id: *program_id,
+        accounts,
+        data: borsh::to_vec(&instruction).expect("YapInstruction serializes"),
+    }
+}
+
+/// `Initialize`, paid for by `admin`
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::{
+///     client::ix_initialize,
+///     state::{INITIAL_SUPPLY, SECONDS_PER_YEAR},
+/// };
+///
+/// let (admin, updater) = (Pubkey::new_unique(), Pubkey::new_unique());
+/// let token_program = spl_token::id();
+/// let ix = ix_initialize(
+///     &yap::id(),
+///     &token_program,
+///     &admin,
+///     updater,
+///     1000,
+///     INITIAL_SUPPLY,
+///     0,
+///     SECONDS_PER_YEAR,
+/// );
+/// assert_eq!(ix.accounts.len(), 10);
+/// assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+/// assert_eq!(ix.accounts[7].pubkey, spl_token::id());
+/// ```
+#[allow(clippy::too_many_arguments)]
+pub fn ix_initialize(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+    merkle_updater: Pubkey,
+    inflation_rate_bps: u16,
+    max_supply: u64,
+    min_distribution_interval: i64,
+    rate_period_secs: i64,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::Initialize {
+            merkle_updater,
+            inflation_rate_bps,
+            max_supply,
+            min_distribution_interval,
+            rate_period_secs,
+        },
+        vec![
+            AccountMeta::new(*admin, true),
+            AccountMeta::new(config_pda(program_id), false),
+            AccountMeta::new(mint_pda(program_id), false),
+            AccountMeta::new(vault_pda(program_id), false),
+            AccountMeta::new(pending_claims_pda(program_id), false),
+            AccountMeta::new(metadata_pda(program_id), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(METADATA_PROGRAM_ID, false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ],
+    )
+}
+
+/// `TriggerInflation`, signed by the admin
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_trigger_inflation;
+///
+/// let ix = ix_trigger_inflation(&yap::id(), &spl_token::id(), &Pubkey::new_unique());
+/// assert_eq!(ix.accounts.len(), 5);
+/// ```
+pub fn ix_trigger_inflation(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    admin: &Pubkey,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::TriggerInflation,
+        vec![
+            AccountMeta::new_readonly(*admin, true),
+            AccountMeta::new(config_pda(program_id), false),
+            AccountMeta::new(mint_pda(program_id), false),
+            AccountMeta::new(vault_pda(program_id), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+/// `Distribute`, signed by the merkle updater
+///
+/// `tree_depth` is the depth of the tree `merkle_root` belongs to
+/// (`MerkleTree::depth`).
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_distribute;
+///
+/// let updater = Pubkey::new_unique();
+/// let ix = ix_distribute(&yap::id(), &spl_token::id(), &updater, 1_000, [7u8; 32], 3);
+/// assert_eq!(ix.accounts.len(), 6);
+/// ```
+pub fn ix_distribute(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    merkle_updater: &Pubkey,
+    amount: u64,
+    merkle_root: [u8; 32],
+    tree_depth: u8,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::Distribute {
+            amount,
+            merkle_root,
+            tree_depth,
+        },
+        vec![
+            AccountMeta::new_readonly(*merkle_updater, true),
+            AccountMeta::new(config_pda(program_id), false),
+            AccountMeta::new(vault_pda(program_id), false),
+            AccountMeta::new(pending_claims_pda(program_id), false),
+            AccountMeta::new_readonly(mint_pda(program_id), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
+
+/// `Claim` into the user's ATA; the user pays for a first-time UserClaimStatus and ATA
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::{client::{ix_claim, user_ata}, utils::token::TOKEN_2022_PROGRAM_ID};
+///
+/// let user = Pubkey::new_unique();
+/// let ix = ix_claim(&yap::id(), &TOKEN_2022_PROGRAM_ID, &user, 1_000, vec![[1u8; 32]]);
+/// assert_eq!(ix.accounts.len(), 10);
+/// assert_eq!(ix.accounts[1].pubkey, user_ata(&yap::id(), &TOKEN_2022_PROGRAM_ID, &user));
+/// ```
+pub fn ix_claim(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::Claim { amount, proof },
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(user_ata(program_id, token_program, user), false),
+            AccountMeta::new(user_claim_pda(program_id, user), false),
+            AccountMeta::new(config_pda(program_id), false),
+            AccountMeta::new(pending_claims_pda(program_id), false),
+            AccountMeta::new_readonly(mint_pda(program_id), false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+            AccountMeta::new_readonly(ASSOCIATED_TOKEN_PROGRAM_ID, false),
+        ],
+    )
+}
+
+/// `Claim` signed by `user`, with `payer` covering rent and fees for new accounts
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_claim_sponsored;
+///
+/// let (payer, user) = (Pubkey::new_unique(), Pubkey::new_unique());
+/// let ix = ix_claim_sponsored(&yap::id(), &spl_token::id(), &payer, &user, 1_000, vec![]);
+/// assert_eq!(ix.accounts.len(), 11);
+/// assert!(ix.accounts[0].is_signer && ix.accounts[10].is_signer);
+/// assert_eq!(ix.accounts[10].pubkey, payer);
+/// ```
+pub fn ix_claim_sponsored(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    payer: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    let mut ix = ix_claim(program_id, token_program, user, amount, proof);
+    ix.accounts.push(AccountMeta::new(*payer, true));
+    ix
+}
+
+/// `GetClaimable` for `user`; simulate it and decode the return data
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_get_claimable;
+///
+/// let ix = ix_get_claimable(&yap::id(), &Pubkey::new_unique(), 1_000, vec![[1u8; 32]]);
+/// assert_eq!(ix.accounts.len(), 3);
+/// assert!(ix.accounts.iter().all(|meta| !meta.is_signer && !meta.is_writable));
+/// ```
+pub fn ix_get_claimable(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::GetClaimable { amount, proof },
+        vec![
+            AccountMeta::new_readonly(*user, false),
+            AccountMeta::new_readonly(user_claim_pda(program_id, user), false),
+            AccountMeta::new_readonly(config_pda(program_id), false),
+        ],
+    )
+}
+
+/// `Burn` from the user's ATA, tracked in their UserClaimStatus
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_burn;
+///
+/// let ix = ix_burn(&yap::id(), &spl_token::id(), &Pubkey::new_unique(), 500);
+/// assert_eq!(ix.accounts.len(), 8);
+/// ```
+pub fn ix_burn(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::Burn { amount },
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(user_ata(program_id, token_program, user), false),
+            AccountMeta::new(config_pda(program_id), false),
+            AccountMeta::new(mint_pda(program_id), false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new(user_claim_pda(program_id, user), false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ],
+    )
+}
+
+/// `Stake`: lock `amount` from the user's ATA in the stake vault
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_stake;
+///
+/// let ix = ix_stake(&yap::id(), &spl_token::id(), &Pubkey::new_unique(), 500);
+/// assert_eq!(ix.accounts.len(), 9);
+/// ```
+pub fn ix_stake(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::Stake { amount },
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(user_ata(program_id, token_program, user), false),
+            AccountMeta::new(stake_pda(program_id, user), false),
+            AccountMeta::new(stake_vault_pda(program_id), false),
+            AccountMeta::new_readonly(config_pda(program_id), false),
+            AccountMeta::new_readonly(mint_pda(program_id), false),
+            AccountMeta::new_readonly(*token_program, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::ID, false),
+        ],
+    )
+}
+
+/// `Unstake`: return `amount` from the stake vault to the user's ATA
+///
+/// ```
+/// use solana_program::pubkey::Pubkey;
+/// use yap::client::ix_unstake;
+///
+/// let ix = ix_unstake(&yap::id(), &spl_token::id(), &Pubkey::new_unique(), 500);
+/// assert_eq!(ix.accounts.len(), 7);
+/// ```
+pub fn ix_unstake(
+    program_id: &Pubkey,
+    token_program: &Pubkey,
+    user: &Pubkey,
+    amount: u64,
+) -> Instruction {
+    build(
+        program_id,
+        YapInstruction::Unstake { amount },
+        vec![
+            AccountMeta::new(*user, true),
+            AccountMeta::new(user_ata(program_id, token_program, user), false),
+            AccountMeta::new(stake_pda(program_id, user), false),
+            AccountMeta::new(stake_vault_pda(program_id), false),
+            AccountMeta::new_readonly(config_pda(program_id), false),
+            AccountMeta::new_readonly(mint_pda(program_id), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+    )
+}
@@ -0,0 +1,18 @@
+//! Program entrypoint, left out with the `no-entrypoint` feature so other
+//! programs can depend on this crate
+
+use solana_program::{
+    account_info::AccountInfo, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
+};
+
+use crate::processor;
+
+entrypoint!(process_instruction);
+
+fn process_instruction<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    instruction_data: &[u8],
+) -> ProgramResult {
+    processor::process(program_id, accounts, instruction_data)
+}
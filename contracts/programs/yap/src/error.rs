@@ -1,6 +1,8 @@
 use solana_program::program_error::ProgramError;
+use solana_program_error::ToStr;
 use thiserror::Error;
 
+/// Codes are the variant index and are never reordered; new variants go last
 #[derive(Error, Debug, Clone, Copy)]
 pub enum YapError {
     #[error("Invalid instruction")]
@@ -56,6 +58,117 @@ pub enum YapError {
 
     #[error("Merkle proof too long")]
     ProofTooLong,
+
+    #[error("Memo is empty or too long")]
+    InvalidMemo,
+
+    #[error("No rebase burn scheduled")]
+    NoRebaseBurnScheduled,
+
+    #[error("Scheduled rebase burn not yet executable")]
+    RebaseBurnNotReady,
+
+    #[error("Too many entries in batch")]
+    BatchTooLarge,
+
+    #[error("Claim has expired")]
+    ClaimExpired,
+
+    #[error("Claim not fully consumed")]
+    ClaimNotFullyConsumed,
+
+    #[error("Distribution not found in round history")]
+    DistributionNotFound,
+
+    #[error("Merkle proof too short")]
+    ProofTooShort,
+
+    #[error("Clock sysvar invalid or inconsistent")]
+    InvalidClock,
+
+    #[error("Program is paused")]
+    Paused,
+
+    #[error("Claim account limit reached")]
+    ClaimAccountLimitReached,
+
+    #[error("Max supply reached")]
+    MaxSupplyReached,
+
+    #[error("Malformed instruction data")]
+    MalformedInstructionData,
+
+    #[error("Minimum distribution interval not elapsed")]
+    DistributionTooSoon,
+
+    #[error("User token account does not exist")]
+    TokenAccountMissing,
+
+    #[error("Metadata URI is empty or too long")]
+    InvalidMetadataUri,
+
+    #[error("Claim cooldown has not elapsed")]
+    ClaimCooldownActive,
+
+    #[error("Proof length does not match the committed tree depth")]
+    ProofDepthMismatch,
+
+    #[error("Token account is not the expected associated token account")]
+    InvalidTokenAccount,
+
+    #[error("Token account is not an initialized token account")]
+    UninitializedTokenAccount,
+
+    #[error("Wallet is frozen")]
+    Frozen,
+
+    #[error("Signer listed more than once")]
+    DuplicateSigner,
+
+    #[error("Not enough updater signatures")]
+    UpdaterThresholdNotMet,
+
+    #[error("Queued distribution not yet executable")]
+    TimelockNotExpired,
+
+    #[error("Parameters do not match the queued distribution")]
+    PendingDistributionMismatch,
+
+    #[error("Clock moved backwards")]
+    ClockWentBackwards,
+
+    #[error("Distributable amount below min_amount")]
+    BelowMinAmount,
+
+    #[error("Claim window still open")]
+    ClaimWindowOpen,
+
+    #[error("Stake still locked")]
+    StakeLocked,
+
+    #[error("Nothing vested yet")]
+    NotYetVested,
+
+    #[error("Entitlement is below the amount already claimed")]
+    EntitlementDecreased,
+
+    #[error("Token balances do not reconcile with current_supply")]
+    SupplyMismatch,
+
+    #[error("Distribution nonce must exceed the last accepted nonce")]
+    StaleDistributionNonce,
+
+    #[error("Wallet has already claimed max_claim_per_wallet")]
+    WalletClaimCapReached,
+
+    #[error("Claim exceeds max_single_claim")]
+    SingleClaimTooLarge,
+
+    #[error("Bonus payout exceeds the funded bonus reserve")]
+    BonusNotFunded,
+
+    #[error("Distributions must be queued while a timelock is set")]
+    DistributionTimelocked,
 }
 
 impl From<YapError> for ProgramError {
@@ -63,3 +176,177 @@ impl From<YapError> for ProgramError {
         ProgramError::Custom(e as u32)
     }
 }
+
+impl YapError {
+    /// Every variant, indexed by its `Custom` code
+    pub const ALL: [YapError; 55] = [
+        YapError::InvalidInstruction,
+        YapError::AlreadyInitialized,
+        YapError::NotInitialized,
+        YapError::InvalidDiscriminator,
+        YapError::InvalidPda,
+        YapError::Unauthorized,
+        YapError::InvalidProof,
+        YapError::NothingToClaim,
+        YapError::AlreadyClaimed,
+        YapError::InflationNotReady,
+        YapError::AlreadyDistributedToday,
+        YapError::ExceedsDailyAllocation,
+        YapError::InsufficientBalance,
+        YapError::Overflow,
+        YapError::InvalidOwner,
+        YapError::InvalidMint,
+        YapError::InsufficientStakedBalance,
+        YapError::ProofTooLong,
+        YapError::InvalidMemo,
+        YapError::NoRebaseBurnScheduled,
+        YapError::RebaseBurnNotReady,
+        YapError::BatchTooLarge,
+        YapError::ClaimExpired,
+        YapError::ClaimNotFullyConsumed,
+        YapError::DistributionNotFound,
+        YapError::ProofTooShort,
+        YapError::InvalidClock,
+        YapError::Paused,
+        YapError::ClaimAccountLimitReached,
+        YapError::MaxSupplyReached,
+        YapError::MalformedInstructionData,
+        YapError::DistributionTooSoon,
+        YapError::TokenAccountMissing,
+        YapError::InvalidMetadataUri,
+        YapError::ClaimCooldownActive,
+        YapError::ProofDepthMismatch,
+        YapError::InvalidTokenAccount,
+        YapError::UninitializedTokenAccount,
+        YapError::Frozen,
+        YapError::DuplicateSigner,
+        YapError::UpdaterThresholdNotMet,
+        YapError::TimelockNotExpired,
+        YapError::PendingDistributionMismatch,
+        YapError::ClockWentBackwards,
+        YapError::BelowMinAmount,
+        YapError::ClaimWindowOpen,
+        YapError::StakeLocked,
+        YapError::NotYetVested,
+        YapError::EntitlementDecreased,
+        YapError::SupplyMismatch,
+        YapError::StaleDistributionNonce,
+        YapError::WalletClaimCapReached,
+        YapError::SingleClaimTooLarge,
+        YapError::BonusNotFunded,
+        YapError::DistributionTimelocked,
+    ];
+}
+
+/// `Custom(n)` back to the variant, for clients decoding a failed transaction
+impl TryFrom<u32> for YapError {
+    type Error = ProgramError;
+
+    fn try_from(code: u32) -> Result<Self, Self::Error> {
+        Self::ALL
+            .get(code as usize)
+            .copied()
+            .ok_or(ProgramError::Custom(code))
+    }
+}
+
+/// Variant names for `ProgramError::to_str::<YapError>()`
+impl ToStr for YapError {
+    fn to_str(&self) -> &'static str {
+        match self {
+            YapError::InvalidInstruction => "InvalidInstruction",
+            YapError::AlreadyInitialized => "AlreadyInitialized",
+            YapError::NotInitialized => "NotInitialized",
+            YapError::InvalidDiscriminator => "InvalidDiscriminator",
+            YapError::InvalidPda => "InvalidPda",
+            YapError::Unauthorized => "Unauthorized",
+            YapError::InvalidProof => "InvalidProof",
+            YapError::NothingToClaim => "NothingToClaim",
+            YapError::AlreadyClaimed => "AlreadyClaimed",
+            YapError::InflationNotReady => "InflationNotReady",
+            YapError::AlreadyDistributedToday => "AlreadyDistributedToday",
+            YapError::ExceedsDailyAllocation => "ExceedsDailyAllocation",
+            YapError::InsufficientBalance => "InsufficientBalance",
+            YapError::Overflow => "Overflow",
+            YapError::InvalidOwner => "InvalidOwner",
+            YapError::InvalidMint => "InvalidMint",
+            YapError::InsufficientStakedBalance => "InsufficientStakedBalance",
+            YapError::ProofTooLong => "ProofTooLong",
+            YapError::InvalidMemo => "InvalidMemo",
+            YapError::NoRebaseBurnScheduled => "NoRebaseBurnScheduled",
+            YapError::RebaseBurnNotReady => "RebaseBurnNotReady",
+            YapError::BatchTooLarge => "BatchTooLarge",
+            YapError::ClaimExpired => "ClaimExpired",
+            YapError::ClaimNotFullyConsumed => "ClaimNotFullyConsumed",
+            YapError::DistributionNotFound => "DistributionNotFound",
+            YapError::ProofTooShort => "ProofTooShort",
+            YapError::InvalidClock => "InvalidClock",
+            YapError::Paused => "Paused",
+            YapError::ClaimAccountLimitReached => "ClaimAccountLimitReached",
+            YapError::MaxSupplyReached => "MaxSupplyReached",
+            YapError::MalformedInstructionData => "MalformedInstructionData",
+            YapError::DistributionTooSoon => "DistributionTooSoon",
+            YapError::TokenAccountMissing => "TokenAccountMissing",
+            YapError::InvalidMetadataUri => "InvalidMetadataUri",
+            YapError::ClaimCooldownActive => "ClaimCooldownActive",
+            YapError::ProofDepthMismatch => "ProofDepthMismatch",
+            YapError::InvalidTokenAccount => "InvalidTokenAccount",
+            YapError::UninitializedTokenAccount => "UninitializedTokenAccount",
+            YapError::Frozen => "Frozen",
+            YapError::DuplicateSigner => "DuplicateSigner",
+            YapError::UpdaterThresholdNotMet => "UpdaterThresholdNotMet",
+            YapError::TimelockNotExpired => "TimelockNotExpired",
+            YapError::PendingDistributionMismatch => "PendingDistributionMismatch",
+            YapError::ClockWentBackwards => "ClockWentBackwards",
+            YapError::BelowMinAmount => "BelowMinAmount",
+            YapError::ClaimWindowOpen => "ClaimWindowOpen",
+            YapError::StakeLocked => "StakeLocked",
+            YapError::NotYetVested => "NotYetVested",
+            YapError::EntitlementDecreased => "EntitlementDecreased",
+            YapError::SupplyMismatch => "SupplyMismatch",
+            YapError::StaleDistributionNonce => "StaleDistributionNonce",
+            YapError::WalletClaimCapReached => "WalletClaimCapReached",
+            YapError::SingleClaimTooLarge => "SingleClaimTooLarge",
+            YapError::BonusNotFunded => "BonusNotFunded",
+            YapError::DistributionTimelocked => "DistributionTimelocked",
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_codes_round_trip_for_every_variant() {
+        assert_eq!(
+            YapError::ALL.len(),
+            YapError::DistributionTimelocked as usize + 1
+        );
+
+        for (code, error) in YapError::ALL.iter().enumerate() {
+            assert_eq!(*error as u32, code as u32);
+            let decoded = YapError::try_from(*error as u32).unwrap();
+            assert_eq!(decoded as u32, code as u32);
+        }
+
+        let past_end = YapError::ALL.len() as u32;
+        assert_eq!(
+            YapError::try_from(past_end).unwrap_err(),
+            ProgramError::Custom(past_end)
+        );
+    }
+
+    #[test]
+    fn test_custom_code_renders_variant_name() {
+        assert_eq!(ProgramError::Custom(6).to_str::<YapError>(), "InvalidProof");
+        assert_eq!(
+            ProgramError::from(YapError::SupplyMismatch).to_str::<YapError>(),
+            "SupplyMismatch"
+        );
+        assert_eq!(
+            ProgramError::Custom(u32::MAX).to_str::<YapError>(),
+            "Error: Unknown"
+        );
+    }
+}
@@ -0,0 +1,216 @@
+//! Structured program events for indexers
+//!
+//! Every event is logged via `sol_log_data` as `[discriminator, borsh(event)]`,
+//! so consumers demux on the first byte and decode the rest deterministically
+//! instead of parsing `msg!` lines.
+
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{entrypoint::ProgramResult, log::sol_log_data, pubkey::Pubkey};
+
+use crate::state::RoundRecord;
+
+/// A Borsh-encoded event with a one-byte discriminator
+pub trait Event: BorshSerialize {
+    const DISCRIMINATOR: u8;
+
+    /// Log the event as `[discriminator, borsh(event)]` via `sol_log_data`
+    fn emit(&self) -> ProgramResult {
+        let data = borsh::to_vec(self)?;
+        sol_log_data(&[&[Self::DISCRIMINATOR], &data]);
+        Ok(())
+    }
+}
+
+/// Emitted after every successful claim
+///
+/// Carries the wallet's and the program's cumulative totals so indexers can
+/// check consistency without replaying every event.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ClaimEvent {
+    pub user: Pubkey,
+    /// Tokens transferred by this claim (main + bonus)
+    pub amount: u64,
+    /// Wallet's cumulative claimed amount after this claim
+    pub claimed_amount: u64,
+    /// Wallet's cumulative bonus claimed amount after this claim
+    pub bonus_claimed_amount: u64,
+    /// Program-wide lifetime distributed total
+    pub total_distributed: u64,
+    /// Program-wide lifetime claimed total, including this claim
+    pub total_claimed: u64,
+}
+
+impl Event for ClaimEvent {
+    const DISCRIMINATOR: u8 = 0;
+}
+
+/// Emitted by `LogRecentRounds`: the last N distribution rounds, oldest first
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct RoundHistoryEvent {
+    pub rounds: Vec<RoundRecord>,
+}
+
+impl Event for RoundHistoryEvent {
+    const DISCRIMINATOR: u8 = 1;
+}
+
+/// Emitted after every successful distribution
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct DistributeEvent {
+    pub merkle_updater: Pubkey,
+    /// Tokens moved from vault to pending_claims
+    pub amount: u64,
+    /// Newly set merkle root
+    pub merkle_root: [u8; 32],
+    /// Id of this distribution
+    pub distribution_id: u64,
+    pub timestamp: i64,
+    /// Program-wide lifetime distributed total, including this distribution
+    pub total_distributed: u64,
+}
+
+impl Event for DistributeEvent {
+    const DISCRIMINATOR: u8 = 2;
+}
+
+/// Emitted after every successful user burn
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct BurnEvent {
+    pub user: Pubkey,
+    /// Tokens burned
+    pub amount: u64,
+    /// Supply after the burn
+    pub current_supply: u64,
+}
+
+impl Event for BurnEvent {
+    const DISCRIMINATOR: u8 = 3;
+}
+
+/// Emitted after every inflation mint
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct InflationEvent {
+    /// Tokens minted to the vault
+    pub amount: u64,
+    /// Supply after the mint and any immediate burn
+    pub current_supply: u64,
+    /// Annual rate applied, in basis points
+    pub inflation_rate_bps: u16,
+    pub timestamp: i64,
+    /// Portion of `amount` burned straight back out of the vault
+    pub burned: u64,
+    /// Portion of `amount` minted to the keeper (permissionless trigger only)
+    pub keeper_reward: u64,
+    /// Portion of `amount` minted to the treasury
+    pub treasury_amount: u64,
+    /// Lifetime inflation minted, including this mint; burns never reduce it
+    pub total_inflation_minted: u64,
+}
+
+impl Event for InflationEvent {
+    const DISCRIMINATOR: u8 = 4;
+}
+
+/// Emitted after every successful stake
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct StakeEvent {
+    pub user: Pubkey,
+    /// Tokens moved into the stake vault
+    pub amount: u64,
+    /// Wallet's staked total after this stake
+    pub staked_amount: u64,
+    pub timestamp: i64,
+}
+
+impl Event for StakeEvent {
+    const DISCRIMINATOR: u8 = 5;
+}
+
+/// Emitted after every successful unstake
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, PartialEq, Eq)]
+pub struct UnstakeEvent {
+    pub user: Pubkey,
+    /// Tokens returned from the stake vault
+    pub amount: u64,
+    /// Wallet's staked total after this unstake
+    pub staked_amount: u64,
+    pub timestamp: i64,
+}
+
+impl Event for UnstakeEvent {
+    const DISCRIMINATOR: u8 = 6;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Mirror of what an indexer does with the logged data
+    fn encode<E: Event>(event: &E) -> Vec<u8> {
+        let mut data = vec![E::DISCRIMINATOR];
+        data.extend(borsh::to_vec(event).unwrap());
+        data
+    }
+
+    #[test]
+    fn test_discriminators_are_unique() {
+        let discriminators = [
+            ClaimEvent::DISCRIMINATOR,
+            RoundHistoryEvent::DISCRIMINATOR,
+            DistributeEvent::DISCRIMINATOR,
+            BurnEvent::DISCRIMINATOR,
+            InflationEvent::DISCRIMINATOR,
+            StakeEvent::DISCRIMINATOR,
+            UnstakeEvent::DISCRIMINATOR,
+        ];
+        for (i, a) in discriminators.iter().enumerate() {
+            for b in &discriminators[i + 1..] {
+                assert_ne!(a, b);
+            }
+        }
+    }
+
+    #[test]
+    fn test_events_demux_and_decode() {
+        let burn = BurnEvent {
+            user: Pubkey::new_unique(),
+            amount: 500,
+            current_supply: 9_500,
+        };
+        let distribute = DistributeEvent {
+            merkle_updater: Pubkey::new_unique(),
+            amount: 1_000,
+            merkle_root: [3u8; 32],
+            distribution_id: 7,
+            timestamp: 1_700_000_000,
+            total_distributed: 12_000,
+        };
+        let inflation = InflationEvent {
+            amount: 42,
+            current_supply: 10_042,
+            inflation_rate_bps: 1000,
+            timestamp: 1_700_000_000,
+            burned: 0,
+            keeper_reward: 0,
+            treasury_amount: 0,
+            total_inflation_minted: 42,
+        };
+
+        for data in [encode(&burn), encode(&distribute), encode(&inflation)] {
+            match data[0] {
+                BurnEvent::DISCRIMINATOR => {
+                    assert_eq!(BurnEvent::try_from_slice(&data[1..]).unwrap(), burn)
+                }
+                DistributeEvent::DISCRIMINATOR => assert_eq!(
+                    DistributeEvent::try_from_slice(&data[1..]).unwrap(),
+                    distribute
+                ),
+                InflationEvent::DISCRIMINATOR => assert_eq!(
+                    InflationEvent::try_from_slice(&data[1..]).unwrap(),
+                    inflation
+                ),
+                other => panic!("unexpected discriminator {}", other),
+            }
+        }
+    }
+}
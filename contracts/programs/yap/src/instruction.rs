@@ -1,6 +1,16 @@
+use std::{
+    io::{self, Read},
+    ops::RangeInclusive,
+};
+
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::pubkey::Pubkey;
 
+use crate::{
+    error::YapError,
+    state::{MAX_BATCH_CLAIMS, MAX_CATEGORIES, MAX_PROOF_DEPTH, MAX_UPDATER_KEYS},
+};
+
 #[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
 pub enum YapInstruction {
     /// Initialize the YAP program
@@ -17,6 +27,14 @@ pub enum YapInstruction {
     Initialize {
         merkle_updater: Pubkey,
         inflation_rate_bps: u16,
+        /// Hard ceiling on total supply; inflation never mints past it.
+        /// Must be at least the initial supply.
+        max_supply: u64,
+        /// Minimum seconds between `Distribute` calls (0 = no limit)
+        min_distribution_interval: i64,
+        /// Seconds over which the vault is distributed and `inflation_rate_bps`
+        /// accrues (`SECONDS_PER_YEAR` for an annual cadence); must be positive
+        rate_period_secs: i64,
     },
 
     /// Trigger inflation (admin only, pro-rated by time)
@@ -27,13 +45,15 @@ pub enum YapInstruction {
     /// 2. `[writable]` Mint PDA
     /// 3. `[writable]` Vault PDA
     /// 4. `[]` Token program
+    /// 5. `[]` (optional) Clock sysvar, cross-checked against `Clock::get()`
     TriggerInflation,
 
     /// Distribute tokens with daily rate limiting
     ///
     /// Calculates available: vault_balance / days_left
     /// Transfers amount from vault to pending_claims
-    /// Sets merkle root for claims
+    /// Sets merkle root for claims, which must carry a proof of exactly
+    /// `tree_depth` nodes (1..=MAX_PROOF_DEPTH)
     ///
     /// Accounts:
     /// 0. `[signer]` Merkle updater
@@ -42,7 +62,12 @@ pub enum YapInstruction {
     /// 3. `[writable]` Pending claims token account
     /// 4. `[]` Mint
     /// 5. `[]` Token program
-    Distribute { amount: u64, merkle_root: [u8; 32] },
+    /// 6. `[]` (optional) Clock sysvar, cross-checked against `Clock::get()`
+    Distribute {
+        amount: u64,
+        merkle_root: [u8; 32],
+        tree_depth: u8,
+    },
 
     /// Claim tokens using merkle proof
     ///
@@ -50,13 +75,23 @@ pub enum YapInstruction {
     /// 0. `[signer]` User claiming
     /// 1. `[writable]` User's token account (ATA)
     /// 2. `[writable]` UserClaimStatus PDA
-    /// 3. `[]` Config PDA
+    /// 3. `[writable]` Config PDA
     /// 4. `[writable]` Pending claims token account
     /// 5. `[]` Mint PDA
     /// 6. `[]` Token program
     /// 7. `[]` System program
     /// 8. `[]` Rent sysvar
-    Claim { amount: u64, proof: Vec<[u8; 32]> },
+    /// 9. `[]` (optional) Destination owner; account 1 is then any token
+    ///    account of the mint owned by this key instead of the user's ATA
+    /// 10. `[signer, writable]` (optional) Payer sponsoring new accounts' rent
+    /// 11. `[]` (optional) The user's FrozenWallet PDA
+    /// 12. `[writable]` (optional) Treasury token account - required while
+    ///     `claim_fee_bps` is set
+    Claim {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
 
     /// Burn tokens (deflationary)
     ///
@@ -85,4 +120,1038 @@ pub enum YapInstruction {
     /// 0. `[signer]` Admin
     /// 1. `[writable]` Config PDA
     UpdateInflationRate { new_rate_bps: u16 },
+
+    /// Set the bonus merkle root and fund it from the vault (merkle updater only)
+    ///
+    /// `funding` moves into pending_claims as the bonus reserve that bonus
+    /// claims draw down; it is taken from the distribution budget and held to
+    /// `min_distribution_interval`. Fails with `DistributionTimelocked` while
+    /// a distribution timelock is set.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Merkle updater
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Vault token account
+    /// 3. `[writable]` Pending claims token account
+    /// 4. `[]` Mint
+    /// 5. `[]` Token program
+    /// 6. `[signer]` (with an updater set) Co-signers from `updater_keys`
+    SetBonusRoot {
+        bonus_root: [u8; 32],
+        funding: u64,
+        tree_depth: u8,
+    },
+
+    /// Claim tokens plus a bonus proven against the bonus merkle root
+    ///
+    /// Requires a valid main proof. Both amounts are cumulative.
+    ///
+    /// Accounts: same as `Claim`
+    ClaimWithBonus {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+        bonus_amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        bonus_proof: Vec<[u8; 32]>,
+    },
+
+    /// Distribute tokens and attach an SPL Memo for explorers
+    ///
+    /// Same as `Distribute`, plus a CPI to the SPL Memo program with `memo`
+    /// (1..=MAX_MEMO_LEN bytes).
+    ///
+    /// Accounts: same as `Distribute`, with the memo program before the optional clock:
+    /// 6. `[]` SPL Memo program
+    /// 7. `[]` (optional) Clock sysvar
+    DistributeWithMemo {
+        amount: u64,
+        merkle_root: [u8; 32],
+        memo: String,
+        tree_depth: u8,
+    },
+
+    /// Schedule a supply-contracting burn from the vault (admin only)
+    ///
+    /// Overwrites any pending schedule; `amount = 0` cancels it.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    ScheduleRebaseBurn { amount: u64, execute_ts: i64 },
+
+    /// Execute the scheduled rebase burn once `now >= execute_ts` (permissionless)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Caller
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Mint PDA
+    /// 3. `[writable]` Vault PDA
+    /// 4. `[]` Token program
+    ExecuteRebaseBurn,
+
+    /// Toggle earmarking of minted inflation for distribution (admin only)
+    ///
+    /// When enabled, `TriggerInflation` credits `undistributed_inflation`,
+    /// which `Distribute` draws from before the time-based allocation.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetInflationCompounding { enabled: bool },
+
+    /// Overwrite the accrual timestamps (admin only, test builds only)
+    ///
+    /// Only handled when the program is built with the `test-time` feature;
+    /// production builds reject it as an invalid instruction. The variant is
+    /// always present so instruction tags stay identical across builds.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetLastTimestamps {
+        inflation_ts: i64,
+        distribution_ts: i64,
+    },
+
+    /// Claim for up to `MAX_BATCH_CLAIMS` wallets at once (relayer-submitted)
+    ///
+    /// Atomic: a single invalid entry reverts the whole batch.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Relayer (pays for new UserClaimStatus PDAs)
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Pending claims token account
+    /// 3. `[]` Mint
+    /// 4. `[]` Token program
+    /// 5. `[]` System program
+    /// 6. `[]` Rent sysvar
+    /// 7. `[writable]` Treasury token account - only while `claim_fee_bps` is set
+    /// 8. `[writable]` Entry 0 wallet's token account (ATA)
+    /// 9. `[writable]` Entry 0 UserClaimStatus PDA
+    ///
+    /// Accounts 8-9 repeat for each entry, in order; without a fee they start at 7.
+    BatchClaim {
+        #[borsh(deserialize_with = "batch_entries")]
+        entries: Vec<ClaimEntry>,
+    },
+
+    /// Claim against a leaf that commits to a per-wallet expiry
+    ///
+    /// Leaf: keccak256(domain || salt || wallet || amount || expiry_ts). Only
+    /// valid while `config.claims.leaf_has_expiry` is set; rejected once
+    /// `now > expiry_ts`.
+    ///
+    /// Accounts: same as `Claim`
+    ClaimWithExpiry {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+        expiry_ts: i64,
+    },
+
+    /// Toggle the expiring leaf format (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetLeafHasExpiry { enabled: bool },
+
+    /// Toggle unpacking the user's ATA during claims to verify its owner (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetStrictAtaCheck { enabled: bool },
+
+    /// Close a fully claimed UserClaimStatus PDA, returning its rent to the user
+    ///
+    /// `amount`/`proof` prove the current cumulative entitlement, which must
+    /// equal `claimed_amount`.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User
+    /// 1. `[writable]` UserClaimStatus PDA
+    /// 2. `[writable]` Config PDA
+    CloseUserClaim {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Emit the last `count` distribution rounds via `sol_log_data` (read-only)
+    ///
+    /// `count` is bounded by `ROUND_HISTORY_LEN` and the rounds recorded so far.
+    ///
+    /// Accounts:
+    /// 0. `[]` Config PDA
+    LogRecentRounds { count: u8 },
+
+    /// Claim one distribution's allocation independently of other rounds
+    ///
+    /// Leaf: keccak256("YAP_EPOCH_V1" || salt || distribution_id || wallet ||
+    /// amount), verified against that round's root in the round history.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User claiming (pays for receipt PDA)
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` EpochClaimStatus PDA
+    /// 3. `[writable]` Config PDA
+    /// 4. `[writable]` Pending claims token account
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    /// 9. `[]` The user's FrozenWallet PDA (only while any wallet is frozen)
+    /// 10. `[writable]` (needs account 9) Treasury token account (only while
+    ///     `claim_fee_bps` is set)
+    ClaimEpoch {
+        distribution_id: u64,
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Set the minimum accepted claim proof length (admin only, 0 disables)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetMinProofDepth { min_proof_depth: u8 },
+
+    /// Move the balances of several user token accounts into the user's ATA,
+    /// then burn `amount` from it
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Token holder
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` UserClaimStatus PDA
+    /// 3. `[writable]` Config PDA
+    /// 4. `[writable]` Mint PDA
+    /// 5. `[]` Token program
+    /// 6. `[writable]` Source token account (one or more)
+    ConsolidateAndBurn { amount: u64 },
+
+    /// Nominate a new admin (admin only); `Pubkey::default()` cancels
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    TransferAdmin { new_admin: Pubkey },
+
+    /// Accept a pending admin transfer
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Pending admin
+    /// 1. `[writable]` Config PDA
+    AcceptAdmin,
+
+    /// Resize a config created under an older layout to the current one (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin (pays extra rent)
+    /// 1. `[writable]` Config PDA
+    /// 2. `[]` System program
+    MigrateConfig,
+
+    /// Pause or resume claims, distributions, burns and inflation (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetPaused { paused: bool },
+
+    /// Set the ceiling on live UserClaimStatus PDAs (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetMaxClaimAccounts { max_claim_accounts: u64 },
+
+    /// Set the share of each inflation mint that is burned immediately (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetInflationBurnBps { inflation_burn_bps: u16 },
+
+    /// Pay the burn reward owed on tokens burned since the last payout
+    ///
+    /// Accounts:
+    /// 0. `[signer]` User claiming
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` UserClaimStatus PDA
+    /// 3. `[]` Config PDA
+    /// 4. `[writable]` Vault PDA
+    /// 5. `[]` Mint (for transfer_checked validation)
+    /// 6. `[]` Token program
+    ClaimBurnReward,
+
+    /// Set the burn reward rate paid by `ClaimBurnReward` (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetBurnRewardBps { burn_reward_bps: u16 },
+
+    /// Enable or disable keeper-triggered inflation and set the keeper's cut (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetPermissionlessInflation {
+        enabled: bool,
+        keeper_reward_bps: u16,
+    },
+
+    /// Trigger inflation as any keeper, minting `keeper_reward_bps` of it to the caller
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Keeper
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Mint PDA
+    /// 3. `[writable]` Vault PDA
+    /// 4. `[writable]` Keeper's token account
+    /// 5. `[]` Token program
+    /// 6. `[]` (optional) Clock sysvar, cross-checked against `Clock::get()`
+    TriggerInflationPermissionless,
+
+    /// Set the bonus paid to the first claimer of each round (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetEarlyBirdBonus { early_bird_bonus: u64 },
+
+    /// Claim for several wallets against one shared merkle multiproof (relayer-submitted)
+    ///
+    /// `entries` must follow the multiproof's leaf order; `proof_flags` are
+    /// OpenZeppelin-style multiproof flags.
+    ///
+    /// Accounts: same as `BatchClaim`
+    BatchClaimMultiproof {
+        #[borsh(deserialize_with = "batch_entries")]
+        entries: Vec<MultiproofEntry>,
+        #[borsh(deserialize_with = "multiproof_nodes")]
+        proof: Vec<[u8; 32]>,
+        #[borsh(deserialize_with = "multiproof_flags")]
+        proof_flags: Vec<bool>,
+    },
+
+    /// Set the minimum seconds between distributions (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetMinDistributionInterval { min_distribution_interval: i64 },
+
+    /// Report the claimable amount via return data without transferring (read-only)
+    ///
+    /// Accounts:
+    /// 0. `[]` User wallet
+    /// 1. `[]` UserClaimStatus PDA (may not exist yet)
+    /// 2. `[]` Config PDA
+    GetClaimable {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Set the treasury token account and its share of each inflation mint (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    /// 2. `[]` Treasury token account (configured mint)
+    SetTreasury { treasury_inflation_bps: u16 },
+
+    /// Set the inflation halving interval and the most halvings applied (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetHalvingSchedule {
+        halving_interval: i64,
+        halving_count_cap: u8,
+    },
+
+    /// Permanently give up the admin role (admin only, irreversible)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    RenounceAdmin,
+
+    /// Point the token metadata at a new URI via Metaplex (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin (metadata update authority)
+    /// 1. `[]` Config PDA
+    /// 2. `[writable]` Metadata PDA
+    /// 3. `[]` Metaplex Token Metadata program
+    UpdateMetadataUri { uri: String },
+
+    /// Set the minimum seconds between a wallet's claims (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetClaimCooldown { claim_cooldown: i64 },
+
+    /// Distribute tokens and commit to the depth of the new merkle tree
+    ///
+    /// Same as `Distribute`, which takes the depth too since every root
+    /// commits to one; kept for existing clients.
+    ///
+    /// Accounts: same as `Distribute`
+    DistributeWithDepth {
+        amount: u64,
+        merkle_root: [u8; 32],
+        tree_depth: u8,
+    },
+
+    /// Create the claimed-bit account for an indexed distribution (merkle updater only)
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Merkle updater (pays for the bitmap)
+    /// 1. `[]` Config PDA
+    /// 2. `[writable]` ClaimBitmap PDA
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    /// 5. `[signer]` (with an updater set) Co-signers from `updater_keys`
+    InitClaimBitmap { distribution_id: u64, capacity: u32 },
+
+    /// Claim one fixed-index allocation of an indexed distribution
+    ///
+    /// Leaf: keccak256("YAP_INDEX_V1" || salt || index || wallet || amount),
+    /// verified against that round's root; the index's bit in the ClaimBitmap
+    /// is set.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` User claiming
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` ClaimBitmap PDA
+    /// 3. `[writable]` Config PDA
+    /// 4. `[writable]` Pending claims token account
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
+    /// 7. `[]` The user's FrozenWallet PDA (only while any wallet is frozen)
+    /// 8. `[writable]` (needs account 7) Treasury token account (only while
+    ///    `claim_fee_bps` is set)
+    ClaimIndexed {
+        distribution_id: u64,
+        index: u32,
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Block a wallet from claiming and burning (admin only)
+    ///
+    /// While any wallet is frozen, claims and burns must also pass the
+    /// wallet's FrozenWallet PDA (["frozen", wallet]).
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin (pays for the marker)
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` FrozenWallet PDA
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    FreezeWallet { wallet: Pubkey },
+
+    /// Lift a wallet's freeze, closing its marker (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin (receives the marker's rent)
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` FrozenWallet PDA
+    UnfreezeWallet { wallet: Pubkey },
+
+    /// Require `updater_threshold` distinct `updater_keys` signers on every
+    /// distribution (admin only); 0 with no keys restores the single updater
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetUpdaterSet {
+        updater_keys: Vec<Pubkey>,
+        updater_threshold: u8,
+    },
+
+    /// Queue a distribution that executes no earlier than `eta`
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Merkle updater (pays for the PendingDistribution PDA)
+    /// 1. `[]` Config PDA
+    /// 2. `[writable]` PendingDistribution PDA
+    /// 3. `[]` System program
+    /// 4. `[]` Rent sysvar
+    QueueDistribution {
+        amount: u64,
+        merkle_root: [u8; 32],
+        eta: i64,
+        tree_depth: u8,
+    },
+
+    /// Execute the queued distribution once `now >= eta`
+    ///
+    /// `amount` and `merkle_root` must match the queued values; the queued
+    /// `tree_depth` is committed with the root.
+    ///
+    /// Accounts: same as `Distribute`, with the PendingDistribution PDA at 6
+    ExecuteDistribution { amount: u64, merkle_root: [u8; 32] },
+
+    /// Drop the queued distribution (admin only)
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[]` Config PDA
+    /// 2. `[writable]` PendingDistribution PDA
+    CancelDistribution,
+
+    /// Claim tokens using merkle proof, then burn `burn_amount` of them
+    ///
+    /// Claim and burn happen atomically; `burn_amount` must not exceed the
+    /// amount paid out by the claim. A `destination_owner` other than the
+    /// user is rejected, since the burn signs as the user.
+    ///
+    /// Accounts: same as `Claim`, with the mint writable
+    ClaimAndBurn {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+        burn_amount: u64,
+    },
+
+    /// Distribute up to `amount`, but at least `min_amount`
+    ///
+    /// Moves `min(amount, available)` instead of failing when the budget has
+    /// shrunk since the transaction was built; reverts with `BelowMinAmount`
+    /// if that is less than `min_amount`.
+    ///
+    /// Accounts: same as `Distribute`
+    DistributeWithMinAmount {
+        amount: u64,
+        merkle_root: [u8; 32],
+        min_amount: u64,
+        tree_depth: u8,
+    },
+
+    /// Distribute tokens to one of the category roots (merkle updater only)
+    ///
+    /// Replaces `category_roots[category]` (0..MAX_CATEGORIES) instead of the
+    /// main root; the transfer draws on the same rate-limited budget.
+    ///
+    /// Accounts: same as `Distribute`
+    DistributeCategory {
+        category: u8,
+        amount: u64,
+        merkle_root: [u8; 32],
+        tree_depth: u8,
+    },
+
+    /// Claim a category allocation using a merkle proof
+    ///
+    /// Leaf: keccak256("YAP_CATEGORY_V1" || salt || category || wallet || amount),
+    /// cumulative per category.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User claiming
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` UserClaimStatus PDA
+    /// 3. `[writable]` Config PDA
+    /// 4. `[writable]` Pending claims token account
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    /// 9. `[]` The user's FrozenWallet PDA (only while any wallet is frozen)
+    /// 10. `[writable]` (needs account 9) Treasury token account (only while
+    ///     `claim_fee_bps` is set)
+    ClaimCategory {
+        category: u8,
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Set how long each distribution stays claimable (admin only)
+    ///
+    /// The next `Distribute` sets `claim_deadline_ts = now + claim_window`.
+    /// 0 disables the deadline.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetClaimWindow { claim_window: i64 },
+
+    /// Move what is left in pending_claims back out once the claim deadline passed (admin only)
+    ///
+    /// Goes to the vault, or to the treasury when `back_to_vault` is false.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Pending claims token account
+    /// 3. `[writable]` Vault or treasury token account
+    /// 4. `[]` Mint
+    /// 5. `[]` Token program
+    SweepUnclaimed { back_to_vault: bool },
+
+    /// Lock tokens in the stake vault; restarts the lock for the whole position
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User (pays for the StakeAccount PDA if new)
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` StakeAccount PDA
+    /// 3. `[writable]` Stake vault PDA
+    /// 4. `[]` Config PDA
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    Stake { amount: u64 },
+
+    /// Withdraw staked tokens once `MIN_STAKE_DURATION` has passed
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User
+    /// 1. `[writable]` User's token account (ATA)
+    /// 2. `[writable]` StakeAccount PDA
+    /// 3. `[writable]` Stake vault PDA
+    /// 4. `[]` Config PDA
+    /// 5. `[]` Mint
+    /// 6. `[]` Token program
+    Unstake { amount: u64 },
+
+    /// Distribute tokens under a root whose amounts vest linearly (merkle updater only)
+    ///
+    /// Claims pay nothing before `cliff_ts` (unix timestamp, 0 = none), then
+    /// the share of each leaf vested over `vesting_duration` seconds from now.
+    /// The next main-root distribution replaces the schedule.
+    ///
+    /// Accounts: same as `Distribute`
+    DistributeWithVesting {
+        amount: u64,
+        merkle_root: [u8; 32],
+        cliff_ts: i64,
+        vesting_duration: i64,
+        tree_depth: u8,
+    },
+
+    /// Let `delegate` claim on the signer's behalf into the signer's ATA
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User (pays for the ClaimDelegate PDA if new)
+    /// 1. `[writable]` ClaimDelegate PDA
+    /// 2. `[]` System program
+    /// 3. `[]` Rent sysvar
+    SetClaimDelegate { delegate: Pubkey },
+
+    /// Remove the signer's claim delegate
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User (receives the PDA's rent)
+    /// 1. `[writable]` ClaimDelegate PDA
+    RevokeClaimDelegate,
+
+    /// Claim for a user as their delegate; tokens go to the user's ATA
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Delegate (pays for the UserClaimStatus PDA if new)
+    /// 1. `[]` User wallet
+    /// 2. `[]` User's ClaimDelegate PDA
+    /// 3. `[writable]` User's token account (ATA)
+    /// 4. `[writable]` UserClaimStatus PDA
+    /// 5. `[writable]` Config PDA
+    /// 6. `[writable]` Pending claims token account
+    /// 7. `[]` Mint
+    /// 8. `[]` Token program
+    /// 9. `[]` System program
+    /// 10. `[]` Rent sysvar
+    /// 11. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+    /// 12. `[writable]` Treasury token account - required only while `claim_fee_bps` is set
+    ClaimAsDelegate {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+    },
+
+    /// Write proof nodes into the signer's ProofBuffer from `offset` on
+    ///
+    /// Lets a proof too long for one transaction be staged over several;
+    /// anything past the written nodes is cleared.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` User (pays for the ProofBuffer PDA if new)
+    /// 1. `[writable]` ProofBuffer PDA
+    /// 2. `[]` System program
+    /// 3. `[]` Rent sysvar
+    WriteProofBuffer {
+        offset: u8,
+        #[borsh(deserialize_with = "proof_nodes")]
+        nodes: Vec<[u8; 32]>,
+    },
+
+    /// Claim tokens with the proof staged in the user's ProofBuffer, which is then zeroed
+    ///
+    /// Accounts:
+    /// 0. `[writable]` The user's ProofBuffer PDA
+    /// 1.. same as `Claim`
+    ClaimWithProofBuffer { amount: u64 },
+
+    /// Claim at most `claim_amount` of the unclaimed, vested share of `amount`
+    ///
+    /// The proof is for the full cumulative `amount`; only the part paid out
+    /// is added to `claimed_amount`, and the rest stays claimable.
+    ///
+    /// Accounts: same as `Claim`
+    ClaimPartial {
+        amount: u64,
+        #[borsh(deserialize_with = "proof_nodes")]
+        proof: Vec<[u8; 32]>,
+        claim_amount: u64,
+    },
+
+    /// Burn the custody balances and close the vault, pending claims and Config (admin only)
+    ///
+    /// Only handled when the program is built with the `devnet` feature;
+    /// otherwise it fails with `InvalidInstruction`. Refuses with
+    /// `SupplyMismatch` unless every token in circulation sits in the vault or
+    /// pending claims. All rent goes to the admin.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Mint
+    /// 3. `[writable]` Vault PDA
+    /// 4. `[writable]` Pending claims PDA
+    /// 5. `[]` Token program
+    CloseProgram,
+
+    /// Initialize the program on top of an existing mint instead of creating one
+    ///
+    /// Nothing is minted and no metadata is created; `current_supply` starts
+    /// at the mint's supply. The mint authority must be the config PDA, or is
+    /// moved to it when its current holder signs as account 8.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Admin/deployer (pays for accounts)
+    /// 1. `[writable]` Config PDA
+    /// 2. `[writable]` Existing mint (`mint`)
+    /// 3. `[writable]` Vault PDA
+    /// 4. `[writable]` Pending Claims PDA
+    /// 5. `[]` System program
+    /// 6. `[]` Token program owning the mint
+    /// 7. `[]` Rent sysvar
+    /// 8. `[signer]` (optional) Current mint authority, while it isn't the config PDA
+    InitializeExisting {
+        mint: Pubkey,
+        merkle_updater: Pubkey,
+        inflation_rate_bps: u16,
+        /// Hard ceiling on total supply; must be at least the mint's supply
+        max_supply: u64,
+        /// Minimum seconds between `Distribute` calls (0 = no limit)
+        min_distribution_interval: i64,
+        /// Seconds over which the vault is distributed and `inflation_rate_bps`
+        /// accrues; must be positive
+        rate_period_secs: i64,
+    },
+
+    /// Distribute tokens at most once per `nonce` (merkle updater only)
+    ///
+    /// `nonce` must be greater than `config.last_distribution_nonce`, otherwise
+    /// `StaleDistributionNonce`, so a rebroadcast or retried transaction can't
+    /// apply twice.
+    ///
+    /// Accounts: same as `Distribute`
+    DistributeWithNonce {
+        amount: u64,
+        merkle_root: [u8; 32],
+        nonce: u64,
+        tree_depth: u8,
+    },
+
+    /// Set the cap on any wallet's cumulative claimed amount (admin only)
+    ///
+    /// 0 = unlimited.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetMaxClaimPerWallet { max_claim_per_wallet: u64 },
+
+    /// Split the distribution budget between the category roots (admin only)
+    ///
+    /// Shares in bps, summing to 10000; all zeros removes the split.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetCategoryBps { category_bps: [u16; MAX_CATEGORIES] },
+
+    /// Burn from a user's ATA as its SPL token delegate
+    ///
+    /// The signer must be the ATA's approved delegate with at least `amount`
+    /// still delegated. The burn counts toward the user's `total_burned`.
+    ///
+    /// Accounts:
+    /// 0. `[signer, writable]` Delegate (pays for UserClaimStatus PDA if needed)
+    /// 1. `[]` Token holder the ATA belongs to
+    /// 2. `[writable]` User's token account (ATA)
+    /// 3. `[writable]` Config PDA - to update current_supply
+    /// 4. `[writable]` Mint PDA - required for SPL burn
+    /// 5. `[]` Token program
+    /// 6. `[writable]` UserClaimStatus PDA
+    /// 7. `[]` System program
+    /// 8. `[]` Rent sysvar
+    /// 9. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+    BurnAsDelegate { amount: u64 },
+
+    /// Report what `Distribute` (or `DistributeCategory` for `category`) could
+    /// move right now via return data, without moving it or writing state
+    ///
+    /// Accounts:
+    /// 0. `[]` Config PDA
+    /// 1. `[]` Vault token account
+    SimulateDistribute { category: Option<u8> },
+
+    /// Move a foreign token sent by mistake out of a program PDA's ATA (admin only)
+    ///
+    /// The YAP mint is always refused.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[]` Config PDA
+    /// 2. `[]` PDA owning the source: config, vault or pending_claims
+    /// 3. `[writable]` Source: that PDA's ATA for the foreign mint
+    /// 4. `[writable]` Recipient token account for the foreign mint
+    /// 5. `[]` Foreign mint
+    /// 6. `[]` Token program owning the foreign mint
+    RescueTokens { amount: u64 },
+
+    /// Set the largest `claimable` a single claim may pay out (admin only)
+    ///
+    /// 0 removes the limit.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetMaxSingleClaim { max_single_claim: u64 },
+
+    /// Whitelist a token account `Distribute` may pay into instead of pending_claims (admin only)
+    ///
+    /// Distributions into it take an all-zero merkle root and leave the
+    /// roots and counters as they were. `Pubkey::default()` removes it.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetAllowedDistributionDest { allowed_distribution_dest: Pubkey },
+
+    /// Set the share of each claim payout sent to the treasury (admin only)
+    ///
+    /// At most `Config::MAX_CLAIM_FEE_BPS`; a nonzero fee needs a treasury set
+    /// with `SetTreasury` first. 0 disables it.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetClaimFee { claim_fee_bps: u16 },
+
+    /// Require distributions to be queued at least this many seconds ahead (admin only)
+    ///
+    /// While nonzero, `Distribute` and its variants fail with
+    /// `DistributionTimelocked`, and `QueueDistribution` rejects an eta sooner
+    /// than `now + distribution_timelock`. At most
+    /// `Config::MAX_DISTRIBUTION_TIMELOCK`; 0 disables it.
+    ///
+    /// Accounts:
+    /// 0. `[signer]` Admin
+    /// 1. `[writable]` Config PDA
+    SetDistributionTimelock { distribution_timelock: i64 },
+}
+
+impl YapInstruction {
+    /// Borsh tag of the last variant; bump when appending one
+    pub const LAST_TAG: u8 = 77;
+
+    /// Deserialize instruction data
+    ///
+    /// An empty buffer or unknown tag is `InvalidInstruction`; a known tag
+    /// whose payload fails to deserialize is `MalformedInstructionData`,
+    /// except for an oversized proof or batch, which keeps the error its
+    /// bounded field raised (`ProofTooLong`, `BatchTooLarge`).
+    pub fn unpack(data: &[u8]) -> Result<Self, YapError> {
+        let (&tag, _) = data.split_first().ok_or(YapError::InvalidInstruction)?;
+        if tag > Self::LAST_TAG {
+            return Err(YapError::InvalidInstruction);
+        }
+
+        Self::try_from_slice(data).map_err(|err| {
+            err.get_ref()
+                .and_then(|inner| inner.downcast_ref::<YapError>())
+                .copied()
+                .unwrap_or(YapError::MalformedInstructionData)
+        })
+    }
+
+    /// How many accounts the instruction accepts
+    ///
+    /// The processor rejects anything outside this range with
+    /// `InvalidInstruction` before dispatching, so a short or padded account
+    /// list fails up front instead of midway with an unrelated error.
+    /// Optional trailing accounts (clock, frozen marker, payer, ...) widen the
+    /// range; updater co-signers are bounded by `MAX_UPDATER_KEYS`.
+    /// `ConsolidateAndBurn` takes any number of source accounts.
+    pub fn expected_accounts(&self) -> RangeInclusive<usize> {
+        // Distribute's fixed accounts, then the optional clock and co-signers
+        const DISTRIBUTE: RangeInclusive<usize> = 6..=6 + 1 + (MAX_UPDATER_KEYS - 1);
+
+        match self {
+            Self::Initialize { .. } => 10..=10,
+            Self::InitializeExisting { .. } => 8..=9,
+            Self::TriggerInflation => 5..=7,
+            Self::TriggerInflationPermissionless => 6..=8,
+            Self::Distribute { .. }
+            | Self::DistributeWithDepth { .. }
+            | Self::DistributeWithMinAmount { .. }
+            | Self::DistributeCategory { .. }
+            | Self::DistributeWithVesting { .. }
+            | Self::DistributeWithNonce { .. } => DISTRIBUTE,
+            // The memo program follows the fixed accounts
+            Self::DistributeWithMemo { .. } => DISTRIBUTE.start() + 1..=DISTRIBUTE.end() + 1,
+            // The PendingDistribution PDA sits after the fixed accounts
+            Self::ExecuteDistribution { .. } => DISTRIBUTE.start() + 1..=DISTRIBUTE.end() + 1,
+            Self::QueueDistribution { .. } => 5..=5 + (MAX_UPDATER_KEYS - 1),
+            Self::Claim { .. }
+            | Self::ClaimWithBonus { .. }
+            | Self::ClaimWithExpiry { .. }
+            | Self::ClaimAndBurn { .. }
+            | Self::ClaimPartial { .. } => 9..=13,
+            // The proof buffer precedes the regular claim accounts
+            Self::ClaimWithProofBuffer { .. } => 10..=14,
+            Self::ClaimAsDelegate { .. } => 11..=13,
+            Self::ClaimCategory { .. } | Self::ClaimEpoch { .. } => 9..=11,
+            Self::ClaimIndexed { .. } => 7..=9,
+            Self::ClaimBurnReward => 7..=8,
+            // The treasury while a fee is set, then two accounts per entry,
+            // plus its frozen marker while any wallet is frozen
+            Self::BatchClaim { entries } => 7 + 2 * entries.len()..=8 + 3 * entries.len(),
+            Self::BatchClaimMultiproof { entries, .. } => {
+                7 + 2 * entries.len()..=8 + 3 * entries.len()
+            }
+            Self::Burn { .. } => 8..=9,
+            Self::BurnAsDelegate { .. } => 9..=10,
+            Self::ConsolidateAndBurn { .. } => 7..=usize::MAX,
+            Self::Stake { .. } => 9..=9,
+            Self::Unstake { .. } => 7..=7,
+            Self::InitClaimBitmap { .. } => 5..=5 + (MAX_UPDATER_KEYS - 1),
+            Self::SetClaimDelegate { .. } => 4..=4,
+            Self::WriteProofBuffer { .. } => 4..=4,
+            Self::LogRecentRounds { .. } => 1..=1,
+            Self::SimulateDistribute { .. } => 2..=2,
+            Self::FreezeWallet { .. } | Self::ExecuteRebaseBurn => 5..=5,
+            Self::UpdateMetadataUri { .. } => 4..=4,
+            Self::SweepUnclaimed { .. } | Self::CloseProgram => 6..=6,
+            Self::RescueTokens { .. } => 7..=7,
+            Self::SetBonusRoot { .. } => 6..=6 + (MAX_UPDATER_KEYS - 1),
+            Self::UnfreezeWallet { .. }
+            | Self::SetTreasury { .. }
+            | Self::MigrateConfig
+            | Self::CancelDistribution
+            | Self::CloseUserClaim { .. }
+            | Self::GetClaimable { .. } => 3..=3,
+            Self::UpdateMerkleUpdater { .. }
+            | Self::UpdateInflationRate { .. }
+            | Self::ScheduleRebaseBurn { .. }
+            | Self::SetInflationCompounding { .. }
+            | Self::SetLastTimestamps { .. }
+            | Self::SetLeafHasExpiry { .. }
+            | Self::SetStrictAtaCheck { .. }
+            | Self::SetMinProofDepth { .. }
+            | Self::TransferAdmin { .. }
+            | Self::AcceptAdmin
+            | Self::SetPaused { .. }
+            | Self::SetMaxClaimAccounts { .. }
+            | Self::SetInflationBurnBps { .. }
+            | Self::SetBurnRewardBps { .. }
+            | Self::SetPermissionlessInflation { .. }
+            | Self::SetEarlyBirdBonus { .. }
+            | Self::SetMinDistributionInterval { .. }
+            | Self::SetHalvingSchedule { .. }
+            | Self::RenounceAdmin
+            | Self::SetClaimCooldown { .. }
+            | Self::SetUpdaterSet { .. }
+            | Self::SetClaimWindow { .. }
+            | Self::RevokeClaimDelegate
+            | Self::SetMaxClaimPerWallet { .. }
+            | Self::SetMaxSingleClaim { .. }
+            | Self::SetAllowedDistributionDest { .. }
+            | Self::SetClaimFee { .. }
+            | Self::SetDistributionTimelock { .. }
+            | Self::SetCategoryBps { .. } => 2..=2,
+        }
+    }
+}
+
+/// A single wallet's claim within `BatchClaim`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct ClaimEntry {
+    pub user: Pubkey,
+    pub amount: u64,
+    #[borsh(deserialize_with = "proof_nodes")]
+    pub proof: Vec<[u8; 32]>,
+}
+
+/// A single wallet's claim within `BatchClaimMultiproof`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+pub struct MultiproofEntry {
+    pub user: Pubkey,
+    pub amount: u64,
+}
+
+/// Deserialize a Borsh `Vec` whose length prefix may not exceed `max`
+///
+/// The prefix is checked before any element is read, so a crafted length
+/// can't make the program allocate or loop for a huge vector. Fails with
+/// `error`, which `unpack` passes through.
+fn bounded_vec<R: Read, T: BorshDeserialize>(
+    reader: &mut R,
+    max: usize,
+    error: YapError,
+) -> io::Result<Vec<T>> {
+    let len = u32::deserialize_reader(reader)? as usize;
+    if len > max {
+        return Err(io::Error::other(error));
+    }
+    (0..len).map(|_| T::deserialize_reader(reader)).collect()
+}
+
+/// A merkle proof (or proof buffer chunk): at most `MAX_PROOF_DEPTH` nodes
+fn proof_nodes<R: Read>(reader: &mut R) -> io::Result<Vec<[u8; 32]>> {
+    bounded_vec(reader, MAX_PROOF_DEPTH, YapError::ProofTooLong)
+}
+
+/// A multiproof shared by up to `MAX_BATCH_CLAIMS` leaves
+fn multiproof_nodes<R: Read>(reader: &mut R) -> io::Result<Vec<[u8; 32]>> {
+    bounded_vec(
+        reader,
+        MAX_PROOF_DEPTH * MAX_BATCH_CLAIMS,
+        YapError::ProofTooLong,
+    )
+}
+
+/// One flag per hash a multiproof computes: fewer than leaves plus proof nodes
+fn multiproof_flags<R: Read>(reader: &mut R) -> io::Result<Vec<bool>> {
+    bounded_vec(
+        reader,
+        MAX_BATCH_CLAIMS + MAX_PROOF_DEPTH * MAX_BATCH_CLAIMS,
+        YapError::ProofTooLong,
+    )
+}
+
+/// `BatchClaim` and `BatchClaimMultiproof` entries: at most `MAX_BATCH_CLAIMS`
+fn batch_entries<R: Read, T: BorshDeserialize>(reader: &mut R) -> io::Result<Vec<T>> {
+    bounded_vec(reader, MAX_BATCH_CLAIMS, YapError::BatchTooLarge)
 }
@@ -1,89 +1,1227 @@
 use borsh::{BorshDeserialize, BorshSerialize};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
     msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
     pubkey::Pubkey,
+    rent::Rent,
+    sysvar::{Sysvar, SysvarSerialize},
 };
+use solana_system_interface::instruction as system_instruction;
 
-use crate::{error::YapError, state::Config};
+use crate::{
+    error::YapError,
+    instructions::claim::validate_claim_sysvars,
+    state::{
+        Config, FrozenWallet, CONFIG_VERSION, DECIMALS, FROZEN_WALLET_DISCRIMINATOR,
+        MAX_CATEGORIES, MAX_PROOF_DEPTH, MAX_UPDATER_KEYS, SECONDS_PER_YEAR,
+    },
+    utils::{account::close_account, token::unpack_token_account},
+};
+
+/// Update merkle updater address (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_update_merkle_updater(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_updater: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "UpdateMerkleUpdater: {} -> {}",
+        config.roles.merkle_updater,
+        new_updater
+    );
+
+    config.roles.merkle_updater = new_updater;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Update inflation rate (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_update_inflation_rate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    new_rate_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    // Validate rate range (0-10000 bps = 0-100%)
+    if new_rate_bps > Config::MAX_INFLATION_BPS {
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "UpdateInflationRate: {} -> {} bps",
+        config.tokenomics.inflation_rate_bps,
+        new_rate_bps
+    );
+
+    config.tokenomics.inflation_rate_bps = new_rate_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Enable or disable crediting minted inflation to `undistributed_inflation` (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_inflation_compounding(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetInflationCompounding: {} -> {}",
+        config.tokenomics.compound_inflation,
+        enabled
+    );
+
+    config.tokenomics.compound_inflation = enabled;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Toggle whether claim leaves commit to a per-wallet expiry (admin only)
+///
+/// Must match how the current merkle tree was built: with the flag set only
+/// `ClaimWithExpiry` is accepted, without it only the plain claim paths.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_leaf_has_expiry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetLeafHasExpiry: {} -> {}",
+        config.claims.leaf_has_expiry,
+        enabled
+    );
+
+    config.claims.leaf_has_expiry = enabled;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Toggle strict ATA owner verification in claims (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_strict_ata_check(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetStrictAtaCheck: {} -> {}",
+        config.claims.strict_ata_check,
+        enabled
+    );
+
+    config.claims.strict_ata_check = enabled;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the minimum claim proof depth (admin only)
+///
+/// Should not exceed the depth of the current tree, or every claim fails.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_min_proof_depth(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    min_proof_depth: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if min_proof_depth as usize > MAX_PROOF_DEPTH {
+        msg!(
+            "SetMinProofDepth: {} exceeds max proof depth {}",
+            min_proof_depth,
+            MAX_PROOF_DEPTH
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetMinProofDepth: {} -> {}",
+        config.claims.min_proof_depth,
+        min_proof_depth
+    );
+
+    config.claims.min_proof_depth = min_proof_depth;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Pause or resume claims, distributions, burns and inflation (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_paused(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    paused: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!("SetPaused: {} -> {}", config.paused, paused);
+
+    config.paused = paused;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the ceiling on live UserClaimStatus PDAs (admin only)
+///
+/// Lowering it below `total_claim_accounts` only blocks new wallets.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_max_claim_accounts(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_claim_accounts: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetMaxClaimAccounts: {} -> {} (current {})",
+        config.claims.max_claim_accounts,
+        max_claim_accounts,
+        config.claims.total_claim_accounts
+    );
+
+    config.claims.max_claim_accounts = max_claim_accounts;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the cap on any wallet's cumulative `claimed_amount` (admin only)
+///
+/// Holds regardless of what the merkle tree says; 0 removes the cap. Lowering
+/// it below a wallet's `claimed_amount` only stops that wallet's future claims.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_max_claim_per_wallet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_claim_per_wallet: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetMaxClaimPerWallet: {} -> {}",
+        config.claims.max_claim_per_wallet,
+        max_claim_per_wallet
+    );
+
+    config.claims.max_claim_per_wallet = max_claim_per_wallet;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the largest `claimable` a single claim may pay out (admin only)
+///
+/// A circuit-breaker for a mis-generated root: a claim above it fails with
+/// `SingleClaimTooLarge` even with a valid proof, giving the admin time to
+/// rotate the root. 0 removes the limit.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_max_single_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    max_single_claim: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetMaxSingleClaim: {} -> {}",
+        config.claims.max_single_claim,
+        max_single_claim
+    );
+
+    config.claims.max_single_claim = max_single_claim;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Whitelist a token account `Distribute` may pay into instead of pending_claims (admin only)
+///
+/// Lets distributions feed an external vesting or streaming program. Only
+/// the key is stored; `Distribute` checks the account's mint and token
+/// program each time. `Pubkey::default()` removes the whitelist.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_allowed_distribution_dest(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    allowed_distribution_dest: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    // pending_claims is always allowed, and the vault can't pay itself
+    if allowed_distribution_dest == config.addresses.pending_claims
+        || allowed_distribution_dest == config.addresses.vault
+    {
+        msg!("SetAllowedDistributionDest: Must be an external token account");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    msg!(
+        "SetAllowedDistributionDest: {} -> {}",
+        config.addresses.allowed_distribution_dest,
+        allowed_distribution_dest
+    );
+
+    config.addresses.allowed_distribution_dest = allowed_distribution_dest;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the share of each inflation mint burned back out of the vault (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_inflation_burn_bps(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    inflation_burn_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if inflation_burn_bps > Config::MAX_INFLATION_BURN_BPS {
+        msg!(
+            "SetInflationBurnBps: {} exceeds {}",
+            inflation_burn_bps,
+            Config::MAX_INFLATION_BURN_BPS
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetInflationBurnBps: {} -> {}",
+        config.tokenomics.inflation_burn_bps,
+        inflation_burn_bps
+    );
+
+    config.tokenomics.inflation_burn_bps = inflation_burn_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the share of newly burned tokens paid back by `ClaimBurnReward` (admin only)
+///
+/// The rate applies to burns not yet paid out, including ones made before the change.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_burn_reward_bps(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    burn_reward_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if burn_reward_bps > Config::MAX_BURN_REWARD_BPS {
+        msg!(
+            "SetBurnRewardBps: {} exceeds {}",
+            burn_reward_bps,
+            Config::MAX_BURN_REWARD_BPS
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetBurnRewardBps: {} -> {}",
+        config.tokenomics.burn_reward_bps,
+        burn_reward_bps
+    );
+
+    config.tokenomics.burn_reward_bps = burn_reward_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Enable or disable keeper-triggered inflation and set the keeper's cut (admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_permissionless_inflation(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    enabled: bool,
+    keeper_reward_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if keeper_reward_bps > Config::MAX_KEEPER_REWARD_BPS {
+        msg!(
+            "SetPermissionlessInflation: keeper reward {} exceeds {}",
+            keeper_reward_bps,
+            Config::MAX_KEEPER_REWARD_BPS
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetPermissionlessInflation: {} -> {}, keeper_reward_bps {} -> {}",
+        config.tokenomics.permissionless_inflation,
+        enabled,
+        config.tokenomics.keeper_reward_bps,
+        keeper_reward_bps
+    );
+
+    config.tokenomics.permissionless_inflation = enabled;
+    config.tokenomics.keeper_reward_bps = keeper_reward_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the bonus paid to the first claimer of each round (admin only)
+///
+/// Takes effect from the next `Distribute`, which tops up the reserve.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_early_bird_bonus(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    early_bird_bonus: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetEarlyBirdBonus: {} -> {} (reserved {})",
+        config.distribution.early_bird_bonus,
+        early_bird_bonus,
+        config.distribution.early_bird_reserved
+    );
+
+    config.distribution.early_bird_bonus = early_bird_bonus;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the minimum seconds between distributions (admin only)
+///
+/// Bounds how often a (possibly compromised) merkle updater can distribute.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_min_distribution_interval(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    min_distribution_interval: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if !(0..=Config::MAX_MIN_DISTRIBUTION_INTERVAL).contains(&min_distribution_interval) {
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetMinDistributionInterval: {} -> {}s",
+        config.timing.min_distribution_interval,
+        min_distribution_interval
+    );
+
+    config.timing.min_distribution_interval = min_distribution_interval;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the minimum seconds between a wallet's claims (admin only)
+///
+/// Throttles repeated partial claims against successive roots. Applies to
+/// `Claim` and `BatchClaim`; 0 disables the cooldown.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_claim_cooldown(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    claim_cooldown: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if !(0..=Config::MAX_CLAIM_COOLDOWN).contains(&claim_cooldown) {
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetClaimCooldown: {} -> {}s",
+        config.claims.claim_cooldown,
+        claim_cooldown
+    );
+
+    config.claims.claim_cooldown = claim_cooldown;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set how long each distribution stays claimable before it can be swept (admin only)
+///
+/// Applies from the next distribution, which sets `claim_deadline_ts` to
+/// `now + claim_window`; the current deadline is left alone. 0 disables the
+/// deadline, and with it `SweepUnclaimed`.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_claim_window(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    claim_window: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if !(0..=Config::MAX_CLAIM_WINDOW).contains(&claim_window) {
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetClaimWindow: {} -> {}s",
+        config.timing.claim_window,
+        claim_window
+    );
+
+    config.timing.claim_window = claim_window;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Require `updater_threshold` of `updater_keys` to co-sign distributions (admin only)
+///
+/// Up to `MAX_UPDATER_KEYS` distinct, non-default keys. A threshold of 0 (with no
+/// keys) returns distributions to the single `merkle_updater`.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_updater_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    updater_keys: Vec<Pubkey>,
+    updater_threshold: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let key_count = updater_keys.len();
+    let updater_keys = validate_updater_set(&updater_keys, updater_threshold)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetUpdaterSet: {} of {} keys (was {})",
+        updater_threshold,
+        key_count,
+        config.roles.updater_threshold
+    );
+
+    config.roles.updater_keys = updater_keys;
+    config.roles.updater_threshold = updater_threshold;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Check an updater set and pad it to `MAX_UPDATER_KEYS` with default keys
+fn validate_updater_set(
+    keys: &[Pubkey],
+    threshold: u8,
+) -> Result<[Pubkey; MAX_UPDATER_KEYS], YapError> {
+    if keys.len() > MAX_UPDATER_KEYS {
+        msg!("SetUpdaterSet: At most {} keys", MAX_UPDATER_KEYS);
+        return Err(YapError::InvalidInstruction);
+    }
+    // 0 disables the set, so it must come without keys; otherwise 1..=N
+    if (threshold == 0) != keys.is_empty() || threshold as usize > keys.len() {
+        msg!(
+            "SetUpdaterSet: Threshold {} invalid for {} keys",
+            threshold,
+            keys.len()
+        );
+        return Err(YapError::InvalidInstruction);
+    }
+
+    let mut updater_keys = [Pubkey::default(); MAX_UPDATER_KEYS];
+    for (i, key) in keys.iter().enumerate() {
+        if *key == Pubkey::default() {
+            return Err(YapError::InvalidInstruction);
+        }
+        if keys[..i].contains(key) {
+            return Err(YapError::DuplicateSigner);
+        }
+        updater_keys[i] = *key;
+    }
+    Ok(updater_keys)
+}
+
+/// Split the distribution budget between the category roots (admin only)
+///
+/// `category_bps[i]` is category `i`'s share, in bps, of the budget each
+/// `DistributeCategory` may draw. The shares must add up to
+/// `CATEGORY_BPS_TOTAL`; all zeros lets every category draw the whole budget.
+/// Budgets credited under the old split are released and re-split.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_category_bps(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    category_bps: [u16; MAX_CATEGORIES],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    validate_category_bps(&category_bps)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetCategoryBps: {:?} -> {:?}",
+        config.distribution.category_bps,
+        category_bps
+    );
+
+    config.distribution.category_bps = category_bps;
+    config.distribution.category_accrued = [0; MAX_CATEGORIES];
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Category shares must cover the whole budget, or all be zero (no split)
+fn validate_category_bps(category_bps: &[u16; MAX_CATEGORIES]) -> Result<(), YapError> {
+    let total: u32 = category_bps.iter().map(|&bps| bps as u32).sum();
+    if total != 0 && total != Config::CATEGORY_BPS_TOTAL as u32 {
+        msg!(
+            "SetCategoryBps: Shares add up to {}, not {}",
+            total,
+            Config::CATEGORY_BPS_TOTAL
+        );
+        return Err(YapError::InvalidInstruction);
+    }
+    Ok(())
+}
+
+/// Block a wallet from claiming and burning (admin only)
+///
+/// Creates the wallet's FrozenWallet marker and bumps `config.claims.frozen_wallets`;
+/// while that count is non-zero, claims and burns must pass the wallet's marker PDA.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Admin (pays for the marker)
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` FrozenWallet PDA
+/// 3. `[]` System program
+/// 4. `[]` Rent sysvar
+pub fn process_freeze_wallet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    wallet: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let marker_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let (marker_pda, marker_bump) =
+        Pubkey::find_program_address(&[FrozenWallet::SEED, wallet.as_ref()], program_id);
+    if marker_info.key != &marker_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if marker_info.owner == program_id && !marker_info.data_is_empty() {
+        msg!("FreezeWallet: {} is already frozen", wallet);
+        return Err(YapError::AlreadyInitialized.into());
+    }
+
+    let rent = Rent::from_account_info(rent_info)?;
+    invoke_signed(
+        &system_instruction::create_account(
+            admin.key,
+            marker_info.key,
+            rent.minimum_balance(FrozenWallet::LEN),
+            FrozenWallet::LEN as u64,
+            program_id,
+        ),
+        &[admin.clone(), marker_info.clone(), system_program.clone()],
+        &[&[FrozenWallet::SEED, wallet.as_ref(), &[marker_bump]]],
+    )?;
+
+    let marker = FrozenWallet {
+        discriminator: FROZEN_WALLET_DISCRIMINATOR,
+        wallet,
+        bump: marker_bump,
+    };
+    marker.serialize(&mut &mut marker_info.data.borrow_mut()[..])?;
+
+    config.claims.frozen_wallets = config
+        .claims
+        .frozen_wallets
+        .checked_add(1)
+        .ok_or(YapError::Overflow)?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "FreezeWallet: {} frozen ({} total)",
+        wallet,
+        config.claims.frozen_wallets
+    );
+
+    Ok(())
+}
+
+/// Lift a wallet's freeze (admin only)
+///
+/// Closes the FrozenWallet marker, returning its rent to the admin.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Admin (receives the marker's rent)
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` FrozenWallet PDA
+pub fn process_unfreeze_wallet(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    wallet: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let marker_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    let (marker_pda, _) =
+        Pubkey::find_program_address(&[FrozenWallet::SEED, wallet.as_ref()], program_id);
+    if marker_info.key != &marker_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if marker_info.owner != program_id || marker_info.data_is_empty() {
+        msg!("UnfreezeWallet: {} is not frozen", wallet);
+        return Err(YapError::NotInitialized.into());
+    }
+
+    close_account(marker_info, admin)?;
+
+    config.claims.frozen_wallets = config.claims.frozen_wallets.saturating_sub(1);
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "UnfreezeWallet: {} unfrozen ({} still frozen)",
+        wallet,
+        config.claims.frozen_wallets
+    );
+
+    Ok(())
+}
+
+/// Route a share of each inflation mint to a treasury token account (admin only)
+///
+/// The treasury must be a token account of the configured mint under the
+/// configured token program; its authority is up to the admin. A share of 0
+/// sends everything to the vault again.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+/// 2. `[]` Treasury token account
+pub fn process_set_treasury(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    treasury_inflation_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let treasury_info = next_account_info(account_info_iter)?;
+
+    if treasury_inflation_bps > Config::MAX_TREASURY_INFLATION_BPS {
+        msg!(
+            "SetTreasury: {} exceeds {}",
+            treasury_inflation_bps,
+            Config::MAX_TREASURY_INFLATION_BPS
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    if treasury_info.owner != &config.addresses.token_program {
+        msg!("SetTreasury: Treasury is not a token account");
+        return Err(YapError::InvalidOwner.into());
+    }
+    let treasury = unpack_token_account(&treasury_info.data.borrow()).map_err(|_| {
+        msg!("SetTreasury: Treasury could not be unpacked");
+        YapError::InvalidOwner
+    })?;
+    if treasury.mint != config.addresses.mint {
+        msg!(
+            "SetTreasury: Treasury mint mismatch (expected {}, got {})",
+            config.addresses.mint,
+            treasury.mint
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+
+    msg!(
+        "SetTreasury: {} at {} bps -> {} at {} bps",
+        config.addresses.treasury,
+        config.tokenomics.treasury_inflation_bps,
+        treasury_info.key,
+        treasury_inflation_bps
+    );
+
+    config.addresses.treasury = *treasury_info.key;
+    config.tokenomics.treasury_inflation_bps = treasury_inflation_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Skim a share of each claim payout into the treasury (admin only)
+///
+/// The fee goes to the treasury set with `SetTreasury`, so a nonzero fee
+/// needs one. Capped at `MAX_CLAIM_FEE_BPS`; 0 (the default) turns it off.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_claim_fee(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    claim_fee_bps: u16,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if claim_fee_bps > Config::MAX_CLAIM_FEE_BPS {
+        msg!(
+            "SetClaimFee: {} exceeds {}",
+            claim_fee_bps,
+            Config::MAX_CLAIM_FEE_BPS
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
 
-/// Update merkle updater address (admin only)
+    if claim_fee_bps > 0 && config.addresses.treasury == Pubkey::default() {
+        msg!("SetClaimFee: No treasury configured");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    msg!(
+        "SetClaimFee: {} bps -> {} bps",
+        config.claims.claim_fee_bps,
+        claim_fee_bps
+    );
+
+    config.claims.claim_fee_bps = claim_fee_bps;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Set the minimum delay between queueing and executing a distribution (admin only)
+///
+/// While nonzero, direct `Distribute*` calls fail and every round has to go
+/// through `QueueDistribution` with an eta at least this far out. 0 (the
+/// default) lifts the requirement.
 ///
 /// Accounts:
 /// 0. `[signer]` Admin
 /// 1. `[writable]` Config PDA
-pub fn process_update_merkle_updater(
+pub fn process_set_distribution_timelock(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    new_updater: Pubkey,
+    distribution_timelock: i64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
     let admin = next_account_info(account_info_iter)?;
     let config_info = next_account_info(account_info_iter)?;
 
-    // Verify admin is signer
-    if !admin.is_signer {
-        return Err(YapError::Unauthorized.into());
+    if !(0..=Config::MAX_DISTRIBUTION_TIMELOCK).contains(&distribution_timelock) {
+        return Err(YapError::InvalidInstruction.into());
     }
 
-    // Verify config PDA
-    let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], program_id);
-    if config_info.key != &config_pda {
-        return Err(YapError::InvalidPda.into());
-    }
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
 
-    if config_info.owner != program_id {
-        return Err(YapError::InvalidOwner.into());
-    }
+    msg!(
+        "SetDistributionTimelock: {} -> {}s",
+        config.timing.distribution_timelock,
+        distribution_timelock
+    );
 
-    let mut config = Config::try_from_slice(&config_info.data.borrow())?;
+    config.timing.distribution_timelock = distribution_timelock;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
 
-    if !config.is_valid() {
-        return Err(YapError::InvalidDiscriminator.into());
+    Ok(())
+}
+
+/// Set how often the inflation rate halves and how many halvings apply (admin only)
+///
+/// Halvings are counted from `genesis_ts`. Configs migrated from before it
+/// existed have none, so the first call stamps it with the current time.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_halving_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    halving_interval: i64,
+    halving_count_cap: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    if halving_interval < 0 || halving_count_cap > Config::MAX_HALVING_COUNT_CAP {
+        msg!(
+            "SetHalvingSchedule: Invalid interval {} or cap {} (max {})",
+            halving_interval,
+            halving_count_cap,
+            Config::MAX_HALVING_COUNT_CAP
+        );
+        return Err(YapError::InvalidInstruction.into());
     }
 
-    // Verify caller is admin
-    if admin.key != &config.admin {
-        return Err(YapError::Unauthorized.into());
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    if config.timing.genesis_ts == 0 {
+        config.timing.genesis_ts = Clock::get()?.unix_timestamp;
+        msg!(
+            "SetHalvingSchedule: genesis_ts set to {}",
+            config.timing.genesis_ts
+        );
     }
 
     msg!(
-        "UpdateMerkleUpdater: {} -> {}",
-        config.merkle_updater,
-        new_updater
+        "SetHalvingSchedule: every {}s up to {} -> every {}s up to {}",
+        config.tokenomics.halving_interval,
+        config.tokenomics.halving_count_cap,
+        halving_interval,
+        halving_count_cap
     );
 
-    config.merkle_updater = new_updater;
+    config.tokenomics.halving_interval = halving_interval;
+    config.tokenomics.halving_count_cap = halving_count_cap;
     config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
 
     Ok(())
 }
 
-/// Update inflation rate (admin only)
+/// Nominate a new admin (admin only)
+///
+/// Takes effect only once the nominee signs `AcceptAdmin`. Calling again
+/// overwrites the pending nominee; `Pubkey::default()` cancels.
 ///
 /// Accounts:
 /// 0. `[signer]` Admin
 /// 1. `[writable]` Config PDA
-pub fn process_update_inflation_rate(
+pub fn process_transfer_admin(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
-    new_rate_bps: u16,
+    new_admin: Pubkey,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
     let admin = next_account_info(account_info_iter)?;
     let config_info = next_account_info(account_info_iter)?;
 
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "TransferAdmin: pending {} -> {}",
+        config.roles.pending_admin,
+        new_admin
+    );
+
+    config.roles.pending_admin = new_admin;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Accept a pending admin transfer (pending admin only)
+///
+/// Accounts:
+/// 0. `[signer]` Pending admin
+/// 1. `[writable]` Config PDA
+pub fn process_accept_admin(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let new_admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    // Verify pending admin is signer
+    if !new_admin.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    let previous_admin = config.roles.admin;
+    accept_admin(&mut config, new_admin.key)?;
+
+    msg!("AcceptAdmin: {} -> {}", previous_admin, config.roles.admin);
+
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Promote `pending_admin` to `admin` if `signer` is the nominee
+fn accept_admin(config: &mut Config, signer: &Pubkey) -> Result<(), YapError> {
+    if config.roles.admin_renounced {
+        msg!("AcceptAdmin: Admin has been renounced");
+        return Err(YapError::Unauthorized);
+    }
+
+    if config.roles.pending_admin == Pubkey::default() {
+        msg!("AcceptAdmin: No pending admin transfer");
+        return Err(YapError::Unauthorized);
+    }
+
+    if *signer != config.roles.pending_admin {
+        msg!("AcceptAdmin: Signer is not the pending admin");
+        return Err(YapError::Unauthorized);
+    }
+
+    config.roles.admin = config.roles.pending_admin;
+    config.roles.pending_admin = Pubkey::default();
+
+    Ok(())
+}
+
+/// Give up the admin role for good (admin only)
+///
+/// Clears `admin` and any pending nominee and sets `admin_renounced`, which
+/// `Config::is_admin` checks first, so no admin-only instruction (including
+/// `AcceptAdmin` and `MigrateConfig`) can pass again. Irreversible.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_renounce_admin(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "RenounceAdmin: {} renounced the admin role",
+        config.roles.admin
+    );
+
+    renounce_admin(&mut config);
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn renounce_admin(config: &mut Config) {
+    config.roles.admin = Pubkey::default();
+    config.roles.pending_admin = Pubkey::default();
+    config.roles.admin_renounced = true;
+}
+
+/// Grow a config created under an older layout to `Config::LEN` (admin only)
+///
+/// New fields are appended, so the old bytes are zero-padded and
+/// re-deserialized: every added field starts at zero/false. The version is
+/// bumped to `CONFIG_VERSION` and the admin pays the extra rent. A no-op for
+/// an already current account, so it is safe to run twice.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Admin
+/// 1. `[writable]` Config PDA
+/// 2. `[]` System program
+pub fn process_migrate_config(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+
     // Verify admin is signer
     if !admin.is_signer {
         return Err(YapError::Unauthorized.into());
     }
 
-    // Validate rate range (0-10000 bps = 0-100%)
-    if new_rate_bps > Config::MAX_INFLATION_BPS {
-        return Err(YapError::InvalidInstruction.into());
+    // Verify system program
+    if *system_program.key != solana_system_interface::program::id() {
+        return Err(YapError::InvalidOwner.into());
     }
 
     // Verify config PDA
@@ -96,25 +1234,656 @@ pub fn process_update_inflation_rate(
         return Err(YapError::InvalidOwner.into());
     }
 
-    let mut config = Config::try_from_slice(&config_info.data.borrow())?;
+    let old_len = config_info.data_len();
+    let old_version = stored_config_version(&config_info.data.borrow());
+    let config = upgrade_config_data(&config_info.data.borrow())?;
 
     if !config.is_valid() {
         return Err(YapError::InvalidDiscriminator.into());
     }
 
     // Verify caller is admin
-    if admin.key != &config.admin {
+    if !config.is_admin(admin.key) {
         return Err(YapError::Unauthorized.into());
     }
 
-    msg!(
-        "UpdateInflationRate: {} -> {} bps",
-        config.inflation_rate_bps,
-        new_rate_bps
-    );
+    if old_len == Config::LEN && old_version == CONFIG_VERSION {
+        msg!(
+            "MigrateConfig: Already at version {} ({} bytes)",
+            old_version,
+            old_len
+        );
+        return Ok(());
+    }
+
+    // Top up rent for the larger account
+    let required = Rent::get()?.minimum_balance(Config::LEN);
+    let shortfall = required.saturating_sub(config_info.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(admin.key, config_info.key, shortfall),
+            &[admin.clone(), config_info.clone(), system_program.clone()],
+        )?;
+    }
 
-    config.inflation_rate_bps = new_rate_bps;
+    config_info.resize(Config::LEN)?;
     config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
 
+    msg!(
+        "MigrateConfig: v{} -> v{}, {} -> {} bytes, rent top-up {} lamports",
+        old_version,
+        CONFIG_VERSION,
+        old_len,
+        Config::LEN,
+        shortfall
+    );
+
     Ok(())
 }
+
+/// Config length once `max_claim_accounts` was added; shorter data predates the cap
+const CLAIM_CAP_LAYOUT_LEN: usize = 816;
+
+/// Config length once `max_supply` was added; shorter data predates the supply cap
+const MAX_SUPPLY_LAYOUT_LEN: usize = 824;
+
+/// Config length once `token_program` was added; shorter data predates Token-2022
+const TOKEN_PROGRAM_LAYOUT_LEN: usize = 887;
+
+/// Config length once `version` was added; shorter data is version 1
+const VERSION_LAYOUT_LEN: usize = 904;
+
+/// Config length once `decimals` was added; shorter data was initialized with `DECIMALS`
+const DECIMALS_LAYOUT_LEN: usize = 1303;
+
+/// Config length once `rate_period_secs` was added; shorter data used `SECONDS_PER_YEAR`
+const RATE_PERIOD_LAYOUT_LEN: usize = 1311;
+
+/// Layout version recorded in raw config data
+///
+/// The version byte never moves, so later layouts can read it the same way.
+fn stored_config_version(data: &[u8]) -> u8 {
+    if data.len() < VERSION_LAYOUT_LEN {
+        1
+    } else {
+        data[VERSION_LAYOUT_LEN - 1]
+    }
+}
+
+/// Deserialize config data written under the current or an older (shorter) layout
+///
+/// Padded fields whose default isn't zero are set explicitly, and the result
+/// is stamped with `CONFIG_VERSION`.
+fn upgrade_config_data(data: &[u8]) -> Result<Config, ProgramError> {
+    if data.len() > Config::LEN {
+        msg!(
+            "MigrateConfig: Account larger than current layout ({} > {})",
+            data.len(),
+            Config::LEN
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut padded = vec![0u8; Config::LEN];
+    padded[..data.len()].copy_from_slice(data);
+
+    let mut config = Config::try_from_slice(&padded)?;
+
+    if data.len() < CLAIM_CAP_LAYOUT_LEN {
+        config.claims.max_claim_accounts = u64::MAX;
+    }
+    if data.len() < MAX_SUPPLY_LAYOUT_LEN {
+        config.tokenomics.max_supply = u64::MAX;
+    }
+    if data.len() < TOKEN_PROGRAM_LAYOUT_LEN {
+        config.addresses.token_program = spl_token::id();
+    }
+    if data.len() < DECIMALS_LAYOUT_LEN {
+        config.tokenomics.decimals = DECIMALS;
+    }
+    if data.len() < RATE_PERIOD_LAYOUT_LEN {
+        config.timing.rate_period_secs = SECONDS_PER_YEAR;
+    }
+    config.version = CONFIG_VERSION;
+
+    Ok(config)
+}
+
+/// Verify the admin signer and load the config it controls
+pub(crate) fn load_config_for_admin(
+    program_id: &Pubkey,
+    admin: &AccountInfo,
+    config_info: &AccountInfo,
+) -> Result<Config, ProgramError> {
+    // Verify admin is signer
+    if !admin.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    // Verify caller is admin
+    if !config.is_admin(admin.key) {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    Ok(config)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Roles, CONFIG_DISCRIMINATOR};
+    use crate::test_utils::TestAccount;
+
+    fn config_with_admin(admin: Pubkey) -> Config {
+        Config {
+            discriminator: CONFIG_DISCRIMINATOR,
+            roles: Roles {
+                admin,
+                ..Roles::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_transfer_then_accept() {
+        let admin = Pubkey::new_unique();
+        let new_admin = Pubkey::new_unique();
+        let mut config = config_with_admin(admin);
+
+        config.roles.pending_admin = new_admin;
+        assert_eq!(config.roles.admin, admin);
+
+        accept_admin(&mut config, &new_admin).unwrap();
+        assert_eq!(config.roles.admin, new_admin);
+        assert_eq!(config.roles.pending_admin, Pubkey::default());
+
+        // Can't be accepted twice
+        assert!(matches!(
+            accept_admin(&mut config, &new_admin),
+            Err(YapError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_set_min_distribution_interval() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+
+        let mut set = |interval: i64| {
+            let accounts = [admin.info(), config.info()];
+            process_set_min_distribution_interval(&program_id, &accounts, interval)
+        };
+        assert_eq!(set(-1).unwrap_err(), YapError::InvalidInstruction.into());
+        assert_eq!(
+            set(Config::MAX_MIN_DISTRIBUTION_INTERVAL + 1).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(3_600).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.timing.min_distribution_interval, 3_600);
+    }
+
+    #[test]
+    fn test_set_claim_cooldown() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+
+        let mut set = |cooldown: i64| {
+            let accounts = [admin.info(), config.info()];
+            process_set_claim_cooldown(&program_id, &accounts, cooldown)
+        };
+        assert_eq!(set(-1).unwrap_err(), YapError::InvalidInstruction.into());
+        assert_eq!(
+            set(Config::MAX_CLAIM_COOLDOWN + 1).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(600).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.claims.claim_cooldown, 600);
+    }
+
+    #[test]
+    fn test_set_claim_window() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+
+        let mut set = |window: i64| {
+            let accounts = [admin.info(), config.info()];
+            process_set_claim_window(&program_id, &accounts, window)
+        };
+        assert_eq!(set(-1).unwrap_err(), YapError::InvalidInstruction.into());
+        assert_eq!(
+            set(Config::MAX_CLAIM_WINDOW + 1).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(SECONDS_PER_YEAR).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.timing.claim_window, SECONDS_PER_YEAR);
+        assert_eq!(stored.timing.claim_deadline_ts, 0);
+    }
+
+    #[test]
+    fn test_set_updater_set() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+        let (a, b) = (Pubkey::new_unique(), Pubkey::new_unique());
+
+        let mut set = |keys: Vec<Pubkey>, threshold: u8| {
+            let accounts = [admin.info(), config.info()];
+            process_set_updater_set(&program_id, &accounts, keys, threshold)
+        };
+        for (keys, threshold) in [
+            (vec![a, b], 3),
+            (vec![a, b], 0),
+            (vec![], 1),
+            (vec![a, Pubkey::default()], 1),
+            (vec![a; MAX_UPDATER_KEYS + 1], 1),
+        ] {
+            assert_eq!(
+                set(keys, threshold).unwrap_err(),
+                YapError::InvalidInstruction.into()
+            );
+        }
+        assert_eq!(
+            set(vec![a, a], 2).unwrap_err(),
+            YapError::DuplicateSigner.into()
+        );
+        set(vec![a, b], 2).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.roles.updater_threshold, 2);
+        assert_eq!(&stored.roles.updater_keys[..3], &[a, b, Pubkey::default()]);
+    }
+
+    #[test]
+    fn test_set_category_bps() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+
+        let mut set = |category_bps: [u16; MAX_CATEGORIES]| {
+            let accounts = [admin.info(), config.info()];
+            process_set_category_bps(&program_id, &accounts, category_bps)
+        };
+        for category_bps in [[6_000, 3_999, 0, 0], [6_000, 4_001, 0, 0], [u16::MAX; 4]] {
+            assert_eq!(
+                set(category_bps).unwrap_err(),
+                YapError::InvalidInstruction.into()
+            );
+        }
+        // All zeros (no split) or shares covering the whole budget
+        set([0; MAX_CATEGORIES]).unwrap();
+        set([6_000, 4_000, 0, 0]).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.distribution.category_bps, [6_000, 4_000, 0, 0]);
+    }
+
+    #[test]
+    fn test_freeze_wallet_creates_marker() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let (marker_pda, marker_bump) =
+            Pubkey::find_program_address(&[FrozenWallet::SEED, wallet.as_ref()], &program_id);
+
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+        // The create_account CPI is a no-op off-chain, so pre-size the marker
+        let mut marker = TestAccount {
+            data: vec![0u8; FrozenWallet::LEN],
+            ..TestAccount::new(marker_pda)
+        };
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let rent = Rent::default();
+        let mut rent_data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+        rent_data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        rent_data.push(rent.burn_percent);
+        let mut rent_sysvar = TestAccount {
+            data: rent_data,
+            ..TestAccount::new(solana_program::sysvar::rent::ID)
+        };
+
+        let accounts = [
+            admin.info(),
+            config.info(),
+            marker.info(),
+            system_program.info(),
+            rent_sysvar.info(),
+        ];
+        process_freeze_wallet(&program_id, &accounts, wallet).unwrap();
+        drop(accounts);
+
+        let stored = FrozenWallet::try_from_slice(&marker.data).unwrap();
+        assert!(stored.is_valid());
+        assert_eq!(stored.wallet, wallet);
+        assert_eq!(stored.bump, marker_bump);
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.claims.frozen_wallets, 1);
+
+        // Unfreezing a wallet without a marker is rejected
+        let other = Pubkey::new_unique();
+        let (other_pda, _) =
+            Pubkey::find_program_address(&[FrozenWallet::SEED, other.as_ref()], &program_id);
+        let mut other_marker = TestAccount::new(other_pda);
+        let accounts = [admin.info(), config.info(), other_marker.info()];
+        assert_eq!(
+            process_unfreeze_wallet(&program_id, &accounts, other).unwrap_err(),
+            YapError::NotInitialized.into()
+        );
+    }
+
+    #[test]
+    fn test_set_treasury_validates_token_account() {
+        use crate::state::Addresses;
+        use solana_program::program_pack::Pack;
+        use spl_token::state::{Account as TokenAccount, AccountState};
+
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(
+            &program_id,
+            Config {
+                addresses: Addresses {
+                    mint,
+                    token_program: spl_token::id(),
+                    ..Addresses::default()
+                },
+                ..config_with_admin(admin_key)
+            },
+        );
+        let token_account = |mint| {
+            let mut data = vec![0u8; TokenAccount::LEN];
+            let account = TokenAccount {
+                mint,
+                owner: Pubkey::new_unique(),
+                state: AccountState::Initialized,
+                ..TokenAccount::default()
+            };
+            TokenAccount::pack(account, &mut data).unwrap();
+            TestAccount {
+                owner: spl_token::id(),
+                data,
+                ..TestAccount::new(Pubkey::new_unique())
+            }
+        };
+
+        let mut treasury = token_account(mint);
+        let mut wrong_mint = token_account(Pubkey::new_unique());
+        let mut not_a_token_account = TestAccount {
+            data: treasury.data.clone(),
+            ..TestAccount::new(Pubkey::new_unique())
+        };
+
+        let cases = [
+            (
+                &mut treasury,
+                Config::MAX_TREASURY_INFLATION_BPS + 1,
+                YapError::InvalidInstruction,
+            ),
+            (&mut wrong_mint, 2_000, YapError::InvalidMint),
+            (&mut not_a_token_account, 2_000, YapError::InvalidOwner),
+        ];
+        for (account, bps, expected) in cases {
+            let accounts = [admin.info(), config.info(), account.info()];
+            let err = process_set_treasury(&program_id, &accounts, bps).unwrap_err();
+            assert_eq!(err, expected.into());
+        }
+
+        let accounts = [admin.info(), config.info(), treasury.info()];
+        process_set_treasury(&program_id, &accounts, 2_000).unwrap();
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.addresses.treasury, treasury.key);
+        assert_eq!(stored.tokenomics.treasury_inflation_bps, 2_000);
+    }
+
+    #[test]
+    fn test_set_claim_fee() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+
+        let mut set = |config: &mut TestAccount, bps: u16| {
+            let accounts = [admin.info(), config.info()];
+            process_set_claim_fee(&program_id, &accounts, bps)
+        };
+        // Off by default, and nowhere to send a fee until a treasury is set
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.claims.claim_fee_bps, 0);
+        assert_eq!(
+            set(&mut config, 100).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(&mut config, 0).unwrap();
+
+        let mut with_treasury = config_with_admin(admin_key);
+        with_treasury.addresses.treasury = Pubkey::new_unique();
+        let mut config = TestAccount::config(&program_id, with_treasury);
+        assert_eq!(
+            set(&mut config, Config::MAX_CLAIM_FEE_BPS + 1).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(&mut config, Config::MAX_CLAIM_FEE_BPS).unwrap();
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.claims.claim_fee_bps, Config::MAX_CLAIM_FEE_BPS);
+    }
+
+    #[test]
+    fn test_set_distribution_timelock() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+
+        let mut set = |timelock: i64| {
+            let accounts = [admin.info(), config.info()];
+            process_set_distribution_timelock(&program_id, &accounts, timelock)
+        };
+        assert_eq!(set(-1).unwrap_err(), YapError::InvalidInstruction.into());
+        assert_eq!(
+            set(Config::MAX_DISTRIBUTION_TIMELOCK + 1).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(86_400).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.timing.distribution_timelock, 86_400);
+    }
+
+    #[test]
+    fn test_set_halving_schedule() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = config_with_admin(admin_key);
+        config.timing.genesis_ts = 1_700_000_000;
+        let mut config = TestAccount::config(&program_id, config);
+
+        let mut set = |interval: i64, cap: u8| {
+            let accounts = [admin.info(), config.info()];
+            process_set_halving_schedule(&program_id, &accounts, interval, cap)
+        };
+        assert_eq!(set(-1, 4).unwrap_err(), YapError::InvalidInstruction.into());
+        assert_eq!(
+            set(SECONDS_PER_YEAR, Config::MAX_HALVING_COUNT_CAP + 1).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+        set(SECONDS_PER_YEAR, 4).unwrap();
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.tokenomics.halving_interval, SECONDS_PER_YEAR);
+        assert_eq!(stored.tokenomics.halving_count_cap, 4);
+        assert_eq!(stored.timing.genesis_ts, 1_700_000_000);
+    }
+
+    #[test]
+    fn test_accept_by_wrong_key_rejected() {
+        let admin = Pubkey::new_unique();
+        let mut config = config_with_admin(admin);
+        config.roles.pending_admin = Pubkey::new_unique();
+
+        assert!(matches!(
+            accept_admin(&mut config, &Pubkey::new_unique()),
+            Err(YapError::Unauthorized)
+        ));
+        // The current admin can't accept on the nominee's behalf either
+        assert!(matches!(
+            accept_admin(&mut config, &admin),
+            Err(YapError::Unauthorized)
+        ));
+        assert_eq!(config.roles.admin, admin);
+
+        // Nothing pending
+        config.roles.pending_admin = Pubkey::default();
+        assert!(matches!(
+            accept_admin(&mut config, &Pubkey::default()),
+            Err(YapError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_overwriting_pending_transfer() {
+        let admin = Pubkey::new_unique();
+        let typo = Pubkey::new_unique();
+        let intended = Pubkey::new_unique();
+        let mut config = config_with_admin(admin);
+
+        config.roles.pending_admin = typo;
+        config.roles.pending_admin = intended;
+
+        assert!(matches!(
+            accept_admin(&mut config, &typo),
+            Err(YapError::Unauthorized)
+        ));
+        accept_admin(&mut config, &intended).unwrap();
+        assert_eq!(config.roles.admin, intended);
+    }
+
+    #[test]
+    fn test_migrate_pads_legacy_layout() {
+        let admin = Pubkey::new_unique();
+        let mut config = config_with_admin(admin);
+        config.tokenomics.current_supply = 1_000;
+        config.tokenomics.inflation_rate_bps = 500;
+        config.bump = 254;
+
+        // Layout before any fields were appended after `bump`
+        let legacy_len = 8 + 32 * 5 + 8 * 3 + 32 + 2 + 1;
+        let data = borsh::to_vec(&config).unwrap();
+
+        let upgraded = upgrade_config_data(&data[..legacy_len]).unwrap();
+        assert!(upgraded.is_valid());
+        assert_eq!(upgraded.roles.admin, admin);
+        assert_eq!(upgraded.tokenomics.current_supply, 1_000);
+        assert_eq!(upgraded.tokenomics.inflation_rate_bps, 500);
+        assert_eq!(upgraded.bump, 254);
+        assert_eq!(upgraded.roles.pending_admin, Pubkey::default());
+        assert_eq!(upgraded.claims.max_claim_accounts, u64::MAX);
+        assert_eq!(upgraded.tokenomics.max_supply, u64::MAX);
+        assert_eq!(upgraded.addresses.token_program, spl_token::id());
+        assert_eq!(upgraded.tokenomics.decimals, DECIMALS);
+
+        // Data from before the cap, but otherwise full, also gets the default
+        let pre_cap = upgrade_config_data(&data[..CLAIM_CAP_LAYOUT_LEN - 16]).unwrap();
+        assert_eq!(pre_cap.claims.max_claim_accounts, u64::MAX);
+        assert_eq!(pre_cap.tokenomics.max_supply, u64::MAX);
+
+        // Data from before the supply cap keeps its claim-account ceiling
+        let pre_supply_cap = upgrade_config_data(&data[..MAX_SUPPLY_LAYOUT_LEN - 8]).unwrap();
+        assert_eq!(pre_supply_cap.claims.max_claim_accounts, 0);
+        assert_eq!(pre_supply_cap.tokenomics.max_supply, u64::MAX);
+
+        // Data from before Token-2022 support was on SPL Token
+        let pre_token_2022 = upgrade_config_data(&data[..TOKEN_PROGRAM_LAYOUT_LEN - 32]).unwrap();
+        assert_eq!(pre_token_2022.tokenomics.max_supply, 0);
+        assert_eq!(pre_token_2022.addresses.token_program, spl_token::id());
+
+        // Data from before decimals were recorded used the 9-decimal mint
+        let pre_decimals = upgrade_config_data(&data[..DECIMALS_LAYOUT_LEN - 1]).unwrap();
+        assert_eq!(pre_decimals.tokenomics.decimals, DECIMALS);
+
+        // Data from before the rate period was configurable ran on a yearly one
+        let pre_rate_period = upgrade_config_data(&data[..RATE_PERIOD_LAYOUT_LEN - 8]).unwrap();
+        assert_eq!(pre_rate_period.tokenomics.decimals, 0);
+        assert_eq!(pre_rate_period.timing.rate_period_secs, SECONDS_PER_YEAR);
+
+        // Current layout round-trips unchanged; oversized data is rejected
+        let current = upgrade_config_data(&data).unwrap();
+        assert_eq!(current.tokenomics.decimals, 0);
+        assert_eq!(current.timing.rate_period_secs, 0);
+        assert_eq!(current.claims.max_claim_accounts, 0);
+        assert_eq!(current.tokenomics.max_supply, 0);
+        assert_eq!(current.addresses.token_program, Pubkey::default());
+        let mut oversized = data.clone();
+        oversized.push(0);
+        assert!(upgrade_config_data(&oversized).is_err());
+    }
+
+    #[test]
+    fn test_migrate_v1_config_reads_new_fields() {
+        let admin = Pubkey::new_unique();
+        let mut config = config_with_admin(admin);
+        config.tokenomics.current_supply = 1_000;
+        config.distribution.accrued_allocation = 77;
+        config.timing.min_distribution_interval = 3_600;
+
+        // A v1 account: the layout deployed before Token-2022 and versioning
+        let data = borsh::to_vec(&config).unwrap();
+        let v1 = &data[..TOKEN_PROGRAM_LAYOUT_LEN];
+        assert_eq!(stored_config_version(v1), 1);
+        assert!(Config::try_from_slice(v1).is_err());
+
+        let migrated = upgrade_config_data(v1).unwrap();
+        assert_eq!(migrated.version, CONFIG_VERSION);
+        assert!(migrated.is_valid());
+        assert_eq!(migrated.tokenomics.current_supply, 1_000);
+        assert_eq!(migrated.distribution.accrued_allocation, 0);
+        assert_eq!(migrated.timing.min_distribution_interval, 0);
+
+        // Migrating the result again changes nothing
+        let written = borsh::to_vec(&migrated).unwrap();
+        assert_eq!(written.len(), Config::LEN);
+        assert_eq!(stored_config_version(&written), CONFIG_VERSION);
+        assert_eq!(upgrade_config_data(&written).unwrap(), migrated);
+    }
+
+    #[test]
+    fn test_migrate_current_config_is_noop() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config_with_admin(admin_key));
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let before = config.data.clone();
+
+        for _ in 0..2 {
+            let accounts = [admin.info(), config.info(), system_program.info()];
+            process_migrate_config(&program_id, &accounts).unwrap();
+        }
+        assert_eq!(config.data, before);
+
+        // Only the admin may migrate
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let accounts = [stranger.info(), config.info(), system_program.info()];
+        let err = process_migrate_config(&program_id, &accounts).unwrap_err();
+        assert_eq!(err, YapError::Unauthorized.into());
+    }
+}
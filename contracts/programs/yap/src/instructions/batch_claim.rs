@@ -0,0 +1,390 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::YapError,
+    events::Event,
+    instruction::{ClaimEntry, MultiproofEntry},
+    instructions::claim::{
+        check_claim_cooldown, check_not_frozen, check_single_claim, check_vested, commit_claim,
+        load_claim_config, load_or_create_claim_status, record_claim, resolve_fee_treasury,
+        split_claim_fee, transfer_claim_fee, transfer_from_pending_claims, validate_claim_sysvars,
+        validate_user_ata, verify_claim_proof, vested_claimable,
+    },
+    state::{Config, MAX_BATCH_CLAIMS, MAX_PROOF_DEPTH},
+    utils::merkle::{compute_salted_leaf, verify_multiproof, verify_multiproof_at_depth},
+};
+
+/// Claim on behalf of several wallets in one instruction (relayer-submitted)
+///
+/// Each entry is verified exactly like `Claim`, and tokens always go to the
+/// entry wallet's ATA. The instruction is atomic: any invalid entry reverts
+/// the whole batch.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Relayer (pays for new UserClaimStatus PDAs)
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Pending claims token account
+/// 3. `[]` Mint
+/// 4. `[]` Token program
+/// 5. `[]` System program
+/// 6. `[]` Rent sysvar
+/// 7. `[writable]` Treasury token account - only while `claim_fee_bps` is set
+///
+/// 8. `[writable]` Entry 0 wallet's token account (ATA)
+/// 9. `[writable]` Entry 0 UserClaimStatus PDA
+/// 10. `[]` Entry 0 wallet's FrozenWallet PDA - only while any wallet is frozen
+///
+/// Accounts 8-9 (8-10 while any wallet is frozen) repeat for each entry, in
+/// order. Without a fee there is no treasury and they start at 7.
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    entries: Vec<ClaimEntry>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let (ctx, mut config) = BatchContext::load(program_id, account_info_iter, entries.len())?;
+
+    let mut total_transferred: u64 = 0;
+
+    for entry in entries.iter() {
+        let user_token_account = next_account_info(account_info_iter)?;
+        let user_claim_status_info = next_account_info(account_info_iter)?;
+        ctx.check_entry_not_frozen(&config, &entry.user, account_info_iter)?;
+
+        // Reject zero amount claims
+        if entry.amount == 0 {
+            msg!("BatchClaim: Amount cannot be zero for {}", entry.user);
+            return Err(YapError::InvalidInstruction.into());
+        }
+
+        validate_user_ata(&config, &entry.user, user_token_account)?;
+        verify_claim_proof(&config, &entry.user, entry.amount, None, &entry.proof)?;
+
+        let claimed = ctx.pay_entry(
+            &mut config,
+            &entry.user,
+            entry.amount,
+            user_token_account,
+            user_claim_status_info,
+        )?;
+        total_transferred = total_transferred
+            .checked_add(claimed)
+            .ok_or(YapError::Overflow)?;
+    }
+
+    config.serialize(&mut &mut ctx.config_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "BatchClaim: Successfully claimed {} tokens for {} wallets",
+        total_transferred,
+        entries.len()
+    );
+
+    Ok(())
+}
+
+/// Like `BatchClaim`, but every entry is proven by one shared multiproof
+///
+/// Entries must be in the leaf order the multiproof was generated for.
+/// Not available with expiring leaves or a `min_proof_depth`, which are
+/// per-leaf checks; use `BatchClaim` there. A committed `tree_depth` holds
+/// every leaf of the multiproof at that depth.
+///
+/// Accounts: same as `BatchClaim`
+pub fn process_multiproof(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    entries: Vec<MultiproofEntry>,
+    proof: Vec<[u8; 32]>,
+    proof_flags: Vec<bool>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let (ctx, mut config) = BatchContext::load(program_id, account_info_iter, entries.len())?;
+
+    if config.claims.leaf_has_expiry || config.claims.min_proof_depth > 0 {
+        msg!("BatchClaimMultiproof: Per-leaf checks are enabled, use BatchClaim");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    if proof.len() > MAX_PROOF_DEPTH * MAX_BATCH_CLAIMS {
+        msg!("BatchClaimMultiproof: Proof too long ({})", proof.len());
+        return Err(YapError::ProofTooLong.into());
+    }
+
+    let leaves: Vec<[u8; 32]> = entries
+        .iter()
+        .map(|entry| {
+            compute_salted_leaf(&config.claims.domain_salt, &entry.user, entry.amount)
+        })
+        .collect();
+    let root = &config.distribution.merkle_root;
+    let verified = match config.distribution.tree_depth {
+        0 => verify_multiproof(root, &leaves, &proof, &proof_flags),
+        depth => verify_multiproof_at_depth(root, &leaves, &proof, &proof_flags, depth as usize),
+    };
+    if !verified {
+        msg!("BatchClaimMultiproof: Invalid merkle multiproof");
+        return Err(YapError::InvalidProof.into());
+    }
+
+    let mut total_transferred: u64 = 0;
+
+    for entry in entries.iter() {
+        let user_token_account = next_account_info(account_info_iter)?;
+        let user_claim_status_info = next_account_info(account_info_iter)?;
+        ctx.check_entry_not_frozen(&config, &entry.user, account_info_iter)?;
+
+        // Reject zero amount claims
+        if entry.amount == 0 {
+            msg!("BatchClaimMultiproof: Amount cannot be zero for {}", entry.user);
+            return Err(YapError::InvalidInstruction.into());
+        }
+
+        validate_user_ata(&config, &entry.user, user_token_account)?;
+
+        let claimed = ctx.pay_entry(
+            &mut config,
+            &entry.user,
+            entry.amount,
+            user_token_account,
+            user_claim_status_info,
+        )?;
+        total_transferred = total_transferred
+            .checked_add(claimed)
+            .ok_or(YapError::Overflow)?;
+    }
+
+    config.serialize(&mut &mut ctx.config_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "BatchClaimMultiproof: Successfully claimed {} tokens for {} wallets ({} proof nodes)",
+        total_transferred,
+        entries.len(),
+        proof.len()
+    );
+
+    Ok(())
+}
+
+/// Accounts shared by every entry of a batch
+struct BatchContext<'a, 'b> {
+    program_id: &'b Pubkey,
+    relayer: &'b AccountInfo<'a>,
+    config_info: &'b AccountInfo<'a>,
+    pending_claims_info: &'b AccountInfo<'a>,
+    mint_info: &'b AccountInfo<'a>,
+    token_program: &'b AccountInfo<'a>,
+    system_program: &'b AccountInfo<'a>,
+    rent_info: &'b AccountInfo<'a>,
+    /// Receives each entry's fee; `None` while `claim_fee_bps` is 0
+    treasury: Option<&'b AccountInfo<'a>>,
+    /// Current unix timestamp, for the per-wallet claim cooldown
+    now: i64,
+}
+
+impl<'a, 'b> BatchContext<'a, 'b> {
+    /// Read the shared accounts, check the relayer and batch size, and load the config
+    ///
+    /// The treasury follows the fixed accounts only while a claim fee is set.
+    fn load(
+        program_id: &'b Pubkey,
+        account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+        entry_count: usize,
+    ) -> Result<(Self, Config), ProgramError> {
+        let mut ctx = Self {
+            program_id,
+            relayer: next_account_info(account_info_iter)?,
+            config_info: next_account_info(account_info_iter)?,
+            pending_claims_info: next_account_info(account_info_iter)?,
+            mint_info: next_account_info(account_info_iter)?,
+            token_program: next_account_info(account_info_iter)?,
+            system_program: next_account_info(account_info_iter)?,
+            rent_info: next_account_info(account_info_iter)?,
+            treasury: None,
+            now: Clock::get()?.unix_timestamp,
+        };
+
+        // Verify relayer is signer
+        if !ctx.relayer.is_signer {
+            return Err(YapError::Unauthorized.into());
+        }
+
+        validate_batch_size(entry_count)?;
+
+        validate_claim_sysvars(ctx.system_program, ctx.rent_info)?;
+
+        let config = load_claim_config(
+            ctx.program_id,
+            ctx.config_info,
+            ctx.pending_claims_info,
+            ctx.mint_info,
+        )?;
+        let treasury = if config.claims.claim_fee_bps > 0 {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        ctx.treasury = resolve_fee_treasury(&config, treasury)?;
+
+        Ok((ctx, config))
+    }
+
+    /// Read the entry's FrozenWallet PDA (only passed while any wallet is frozen) and check it
+    fn check_entry_not_frozen(
+        &self,
+        config: &Config,
+        user: &Pubkey,
+        account_info_iter: &mut std::slice::Iter<'b, AccountInfo<'a>>,
+    ) -> ProgramResult {
+        let marker = if config.claims.frozen_wallets > 0 {
+            Some(next_account_info(account_info_iter)?)
+        } else {
+            None
+        };
+        check_not_frozen(self.program_id, config, user, marker)
+    }
+
+    /// Pay one verified entry its unclaimed amount, less the claim fee
+    ///
+    /// Returns the whole amount that left pending_claims, fee included.
+    fn pay_entry(
+        &self,
+        config: &mut Config,
+        user: &Pubkey,
+        amount: u64,
+        user_token_account: &AccountInfo<'a>,
+        user_claim_status_info: &AccountInfo<'a>,
+    ) -> Result<u64, ProgramError> {
+        let mut user_claim_status = load_or_create_claim_status(
+            self.program_id,
+            config,
+            self.relayer,
+            user,
+            user_claim_status_info,
+            self.system_program,
+            self.rent_info,
+        )?;
+
+        check_claim_cooldown(config, &user_claim_status, self.now)?;
+
+        // Zero claimable fails the whole batch, including duplicate entries
+        let (vested, claimable) = vested_claimable(config, &user_claim_status, amount, self.now)?;
+        check_single_claim(config, claimable)?;
+        if claimable == 0 {
+            check_vested(vested, amount)?;
+            msg!(
+                "BatchClaim: Nothing to claim for {}, already claimed {}",
+                user,
+                user_claim_status.claimed_amount
+            );
+            return Err(YapError::AlreadyClaimed.into());
+        }
+
+        // First claimer of the round also receives the early-bird bonus
+        let early_bird = config.take_early_bird_bonus();
+        let transferred = claimable
+            .checked_add(early_bird)
+            .ok_or(YapError::Overflow)?;
+        let (paid, fee) = split_claim_fee(config, transferred)?;
+
+        let event = record_claim(
+            config,
+            &mut user_claim_status,
+            user,
+            claimable,
+            None,
+            transferred,
+        )?;
+        user_claim_status.last_claim_ts = self.now;
+
+        commit_claim(
+            &user_claim_status,
+            user_claim_status_info,
+            config,
+            self.config_info,
+            || {
+                transfer_from_pending_claims(
+                    config,
+                    self.config_info,
+                    self.pending_claims_info,
+                    self.mint_info,
+                    user_token_account,
+                    self.token_program,
+                    paid,
+                )?;
+                transfer_claim_fee(
+                    config,
+                    self.config_info,
+                    self.pending_claims_info,
+                    self.mint_info,
+                    self.treasury,
+                    self.token_program,
+                    fee,
+                )
+            },
+        )?;
+
+        event.emit()?;
+
+        msg!("BatchClaim: user={}, claimed={}, fee={}", user, paid, fee);
+
+        Ok(transferred)
+    }
+}
+
+/// Reject empty batches and batches over `MAX_BATCH_CLAIMS`
+fn validate_batch_size(len: usize) -> Result<(), YapError> {
+    if len == 0 {
+        msg!("BatchClaim: No entries");
+        return Err(YapError::InvalidInstruction);
+    }
+    if len > MAX_BATCH_CLAIMS {
+        msg!("BatchClaim: Too many entries ({} > {})", len, MAX_BATCH_CLAIMS);
+        return Err(YapError::BatchTooLarge);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_batch_size_limits() {
+        assert!(matches!(
+            validate_batch_size(0),
+            Err(YapError::InvalidInstruction)
+        ));
+        assert!(validate_batch_size(1).is_ok());
+        assert!(validate_batch_size(MAX_BATCH_CLAIMS).is_ok());
+        assert!(matches!(
+            validate_batch_size(MAX_BATCH_CLAIMS + 1),
+            Err(YapError::BatchTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_claim_entry_round_trip() {
+        use borsh::BorshDeserialize;
+
+        let entry = ClaimEntry {
+            user: Pubkey::new_unique(),
+            amount: 42,
+            proof: vec![[7u8; 32]; 3],
+        };
+        let bytes = borsh::to_vec(&entry).unwrap();
+        let decoded = ClaimEntry::try_from_slice(&bytes).unwrap();
+        assert_eq!(decoded.user, entry.user);
+        assert_eq!(decoded.amount, entry.amount);
+        assert_eq!(decoded.proof, entry.proof);
+    }
+}
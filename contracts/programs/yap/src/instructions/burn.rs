@@ -1,39 +1,93 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
     msg,
     program::invoke,
+    program_option::COption,
     pubkey::Pubkey,
 };
+use spl_token_2022_interface::instruction as token_instruction;
 
 use crate::{
     error::YapError,
-    state::{Config, ASSOCIATED_TOKEN_PROGRAM_ID},
+    events::{BurnEvent, Event},
+    instructions::claim::{check_not_frozen, load_or_create_claim_status, validate_claim_sysvars},
+    state::{Config, UserClaimStatus, ASSOCIATED_TOKEN_PROGRAM_ID},
+    utils::token::{unpack_token_account, validate_token_program},
 };
 
 /// Burn tokens (deflationary)
 ///
-/// Phase 1: Simple burn - just burns tokens and updates current_supply.
-/// Phase 2: Will add per-user tracking for burn rewards.
+/// Burns tokens, updates current_supply and adds the amount to the user's
+/// `UserClaimStatus.total_burned` (creating the PDA on first use), which
+/// `ClaimBurnReward` pays out against.
 ///
 /// Accounts:
-/// 0. `[signer]` Token holder
+/// 0. `[signer, writable]` Token holder (pays for UserClaimStatus PDA if needed)
 /// 1. `[writable]` User's token account (ATA)
 /// 2. `[writable]` Config PDA - to update current_supply
 /// 3. `[writable]` Mint PDA - required for SPL burn
 /// 4. `[]` Token program
+/// 5. `[writable]` UserClaimStatus PDA
+/// 6. `[]` System program
+/// 7. `[]` Rent sysvar
+/// 8. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    process_burn(program_id, accounts, amount, false)
+}
+
+/// Burn from the user's ATA as its SPL token delegate
+///
+/// The delegate must be the one set on the ATA via SPL `Approve`, with a
+/// `delegated_amount` of at least `amount`; the token program draws the
+/// allowance down. The burn still counts toward the user's `total_burned`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Delegate (pays for UserClaimStatus PDA if needed)
+/// 1. `[]` Token holder the ATA belongs to
+/// 2. `[writable]` User's token account (ATA)
+/// 3. `[writable]` Config PDA - to update current_supply
+/// 4. `[writable]` Mint PDA - required for SPL burn
+/// 5. `[]` Token program
+/// 6. `[writable]` UserClaimStatus PDA
+/// 7. `[]` System program
+/// 8. `[]` Rent sysvar
+/// 9. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+pub fn process_as_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    process_burn(program_id, accounts, amount, true)
+}
+
+fn process_burn(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    as_delegate: bool,
+) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
-    let user = next_account_info(account_info_iter)?;
+    // The holder signs for a plain burn, the approved delegate otherwise
+    let authority = next_account_info(account_info_iter)?;
+    let user = if as_delegate {
+        next_account_info(account_info_iter)?
+    } else {
+        authority
+    };
     let user_token_account = next_account_info(account_info_iter)?;
     let config_info = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let frozen_marker = account_info_iter.next();
 
-    // Verify user is signer
-    if !user.is_signer {
+    // Verify the burn authority is signer
+    if !authority.is_signer {
         return Err(YapError::Unauthorized.into());
     }
 
@@ -43,88 +97,604 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> Pr
         return Err(YapError::InvalidInstruction.into());
     }
 
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    // Emergency pause
+    config.ensure_not_paused()?;
+
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+
+    // Verify mint matches config
+    if mint_info.key != &config.addresses.mint {
+        msg!("Burn: Mint does not match config");
+        return Err(YapError::InvalidMint.into());
+    }
+
     // Verify token program
-    if *token_program.key != spl_token::id() {
-        msg!("Burn: Invalid token program");
-        return Err(YapError::InvalidOwner.into());
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    // Verify user_token_account is ATA for user and correct mint
+    let expected_ata = Pubkey::find_program_address(
+        &[
+            user.key.as_ref(),
+            config.addresses.token_program.as_ref(),
+            config.addresses.mint.as_ref(),
+        ],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0;
+    if user_token_account.key != &expected_ata {
+        msg!("Burn: Invalid user token account, expected ATA");
+        return Err(YapError::InvalidTokenAccount.into());
     }
 
-    // Verify config PDA and owner
-    let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], program_id);
-    if config_info.key != &config_pda {
-        return Err(YapError::InvalidPda.into());
+    if as_delegate {
+        check_burn_delegate(&user_token_account.data.borrow(), authority.key, amount)?;
     }
-    if config_info.owner != program_id {
-        return Err(YapError::InvalidOwner.into());
+
+    let mut user_claim_status = load_or_create_claim_status(
+        program_id,
+        &mut config,
+        authority,
+        user.key,
+        user_claim_status_info,
+        system_program,
+        rent_info,
+    )?;
+
+    msg!(
+        "Burn: user={}, amount={}, current_supply={}",
+        user.key,
+        amount,
+        config.tokenomics.current_supply
+    );
+
+    burn_from_user(
+        &config,
+        authority,
+        user_token_account,
+        mint_info,
+        token_program,
+        amount,
+    )?;
+
+    // Update current_supply and the user's lifetime burned total
+    record_burn(&mut config, &mut user_claim_status, amount)?;
+
+    // Save updated state
+    user_claim_status.store(&mut user_claim_status_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    BurnEvent {
+        user: *user.key,
+        amount,
+        current_supply: config.tokenomics.current_supply,
     }
+    .emit()?;
 
-    // Load config
-    let mut config = Config::try_from_slice(&config_info.data.borrow())?;
-    if !config.is_valid() {
-        return Err(YapError::InvalidDiscriminator.into());
+    msg!(
+        "Burn: Successfully burned {} tokens, total_burned={}, new_supply={}",
+        amount,
+        user_claim_status.total_burned,
+        config.tokenomics.current_supply
+    );
+
+    Ok(())
+}
+
+/// Sweep several of the user's token accounts into their ATA, then burn from it
+///
+/// Every source must hold `config.addresses.mint` and be owned by the signer. The burn
+/// is recorded in the user's `UserClaimStatus.total_burned`.
+///
+/// Accounts:
+/// 0. `[signer]` Token holder
+/// 1. `[writable]` User's token account (ATA) - consolidation target
+/// 2. `[writable]` UserClaimStatus PDA
+/// 3. `[writable]` Config PDA - to update current_supply
+/// 4. `[writable]` Mint PDA
+/// 5. `[]` Token program
+/// 6. `[writable]` Source token account (one or more) to empty into the ATA. While any
+///    wallet is frozen, account 6 is the user's FrozenWallet PDA and sources follow it.
+pub fn process_consolidate_and_burn(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let sources: Vec<&AccountInfo> = account_info_iter.collect();
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    // Reject zero amount
+    if amount == 0 {
+        msg!("ConsolidateAndBurn: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    if sources.is_empty() {
+        msg!("ConsolidateAndBurn: No source accounts, use Burn");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    // Emergency pause
+    config.ensure_not_paused()?;
+
+    let sources = match sources.split_first() {
+        Some((frozen_marker, rest)) if config.claims.frozen_wallets > 0 => {
+            check_not_frozen(program_id, &config, user.key, Some(frozen_marker))?;
+            rest
+        }
+        _ => &sources[..],
+    };
+    if sources.is_empty() {
+        msg!("ConsolidateAndBurn: No source accounts, use Burn");
+        return Err(YapError::InvalidInstruction.into());
     }
 
     // Verify mint matches config
-    if mint_info.key != &config.mint {
-        msg!("Burn: Mint does not match config");
+    if mint_info.key != &config.addresses.mint {
+        msg!("ConsolidateAndBurn: Mint does not match config");
         return Err(YapError::InvalidMint.into());
     }
 
+    // Verify token program
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
     // Verify user_token_account is ATA for user and correct mint
     let expected_ata = Pubkey::find_program_address(
         &[
             user.key.as_ref(),
-            spl_token::id().as_ref(),
-            config.mint.as_ref(),
+            config.addresses.token_program.as_ref(),
+            config.addresses.mint.as_ref(),
         ],
         &ASSOCIATED_TOKEN_PROGRAM_ID,
     )
     .0;
     if user_token_account.key != &expected_ata {
-        msg!("Burn: Invalid user token account, expected ATA");
-        return Err(YapError::InvalidPda.into());
+        msg!("ConsolidateAndBurn: Invalid user token account, expected ATA");
+        return Err(YapError::InvalidTokenAccount.into());
     }
 
+    // Verify UserClaimStatus PDA
+    let mut user_claim_status =
+        UserClaimStatus::try_load(program_id, user.key, user_claim_status_info)?;
+
+    // Move every source balance into the ATA
+    for &source in sources {
+        if source.key == user_token_account.key {
+            msg!("ConsolidateAndBurn: Source cannot be the ATA itself");
+            return Err(YapError::InvalidInstruction.into());
+        }
+
+        let balance = source_balance(&source.data.borrow(), user.key, &config.addresses.mint)?;
+        if balance == 0 {
+            continue;
+        }
+
+        msg!("ConsolidateAndBurn: Moving {} from {}", balance, source.key);
+
+        invoke(
+            &token_instruction::transfer_checked(
+                &config.addresses.token_program,
+                source.key,
+                mint_info.key,
+                user_token_account.key,
+                user.key,
+                &[],
+                balance,
+                config.tokenomics.decimals,
+            )?,
+            &[
+                source.clone(),
+                mint_info.clone(),
+                user_token_account.clone(),
+                user.clone(),
+                token_program.clone(),
+            ],
+        )?;
+    }
+
+    // Burn from the consolidated ATA (fails if the balance is insufficient)
+    burn_from_user(
+        &config,
+        user,
+        user_token_account,
+        mint_info,
+        token_program,
+        amount,
+    )?;
+
+    record_burn(&mut config, &mut user_claim_status, amount)?;
+
+    user_claim_status.store(&mut user_claim_status_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    BurnEvent {
+        user: *user.key,
+        amount,
+        current_supply: config.tokenomics.current_supply,
+    }
+    .emit()?;
+
     msg!(
-        "Burn: user={}, amount={}, current_supply={}",
-        user.key,
+        "ConsolidateAndBurn: Burned {} tokens, total_burned={}, new_supply={}",
         amount,
-        config.current_supply
+        user_claim_status.total_burned,
+        config.tokenomics.current_supply
     );
 
-    // SPL Token burn instruction
-    // User is the authority over their own token account
+    Ok(())
+}
+
+/// Balance of a source token account, which must hold `mint` and be owned by `user`
+fn source_balance(data: &[u8], user: &Pubkey, mint: &Pubkey) -> Result<u64, YapError> {
+    let account = unpack_token_account(data).map_err(|_| {
+        msg!("ConsolidateAndBurn: Source account could not be unpacked");
+        YapError::UninitializedTokenAccount
+    })?;
+
+    if account.owner != *user {
+        msg!("ConsolidateAndBurn: Source not owned by signer");
+        return Err(YapError::InvalidOwner);
+    }
+    if account.mint != *mint {
+        msg!("ConsolidateAndBurn: Source holds a different mint");
+        return Err(YapError::InvalidMint);
+    }
+
+    Ok(account.amount)
+}
+
+/// Check that `delegate` is the token account's SPL delegate with at least `amount` approved
+fn check_burn_delegate(data: &[u8], delegate: &Pubkey, amount: u64) -> Result<(), YapError> {
+    let account = unpack_token_account(data).map_err(|_| {
+        msg!("Burn: Token account could not be unpacked");
+        YapError::UninitializedTokenAccount
+    })?;
+
+    if account.delegate != COption::Some(*delegate) {
+        msg!("Burn: {} is not the token account's delegate", delegate);
+        return Err(YapError::Unauthorized);
+    }
+    if account.delegated_amount < amount {
+        msg!(
+            "Burn: Amount {} exceeds delegated allowance {}",
+            amount,
+            account.delegated_amount
+        );
+        return Err(YapError::InsufficientBalance);
+    }
+
+    Ok(())
+}
+
+/// Burn `amount` from the user's token account; `authority` (the owner or its
+/// approved delegate) signs
+pub(crate) fn burn_from_user<'a>(
+    config: &Config,
+    authority: &AccountInfo<'a>,
+    user_token_account: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
     invoke(
-        &spl_token::instruction::burn(
-            &spl_token::id(),
+        &token_instruction::burn_checked(
+            &config.addresses.token_program,
             user_token_account.key,
             mint_info.key,
-            user.key,
+            authority.key,
             &[],
             amount,
+            config.tokenomics.decimals,
         )?,
         &[
             user_token_account.clone(),
             mint_info.clone(),
-            user.clone(),
+            authority.clone(),
             token_program.clone(),
         ],
-    )?;
+    )
+}
 
-    // Update current_supply
-    config.current_supply = config
+/// Apply a burn to the supply and the user's lifetime burned total
+pub(crate) fn record_burn(
+    config: &mut Config,
+    status: &mut UserClaimStatus,
+    amount: u64,
+) -> Result<(), YapError> {
+    config.tokenomics.current_supply = config
+        .tokenomics
         .current_supply
         .checked_sub(amount)
         .ok_or(YapError::Overflow)?;
+    status.total_burned = status
+        .total_burned
+        .checked_add(amount)
+        .ok_or(YapError::Overflow)?;
+    Ok(())
+}
 
-    // Save updated config
-    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Addresses, Tokenomics};
+    use crate::test_utils::TestAccount;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::Account as TokenAccount;
 
-    msg!(
-        "Burn: Successfully burned {} tokens, new_supply={}",
-        amount,
-        config.current_supply
-    );
+    #[test]
+    fn test_burns_revert_when_paused() {
+        let program_id = Pubkey::new_unique();
+        let paused = Config {
+            paused: true,
+            ..Config::default()
+        };
 
-    Ok(())
+        let mut user = TestAccount::signer(Pubkey::new_unique());
+        let mut ata = TestAccount::new(Pubkey::new_unique());
+        let mut status = TestAccount::new(Pubkey::new_unique());
+        let mut config = TestAccount::config(&program_id, paused.clone());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut source = TestAccount::new(Pubkey::new_unique());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        for expected in [YapError::Paused, YapError::InvalidMint] {
+            {
+                let accounts = [
+                    user.info(),
+                    ata.info(),
+                    config.info(),
+                    mint.info(),
+                    token_program.info(),
+                    status.info(),
+                    system_program.info(),
+                    rent.info(),
+                ];
+                let err = process(&program_id, &accounts, 10).unwrap_err();
+                assert_eq!(err, expected.into());
+            }
+            {
+                let accounts = [
+                    user.info(),
+                    ata.info(),
+                    status.info(),
+                    config.info(),
+                    mint.info(),
+                    token_program.info(),
+                    source.info(),
+                ];
+                let err = process_consolidate_and_burn(&program_id, &accounts, 10).unwrap_err();
+                assert_eq!(err, expected.into());
+            }
+
+            // After unpause the guard passes (and the unknown mint is rejected next)
+            config.set_config(Config {
+                paused: false,
+                ..paused.clone()
+            });
+        }
+    }
+
+    fn packed_token_account(owner: Pubkey, mint: Pubkey, amount: u64) -> Vec<u8> {
+        let account = TokenAccount {
+            mint,
+            owner,
+            amount,
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        let mut data = vec![0u8; TokenAccount::LEN];
+        TokenAccount::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_consolidate_two_accounts_then_burn() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata_balance = 100;
+
+        let sources = [
+            packed_token_account(user, mint, 250),
+            packed_token_account(user, mint, 50),
+        ];
+        let moved: u64 = sources
+            .iter()
+            .map(|data| source_balance(data, &user, &mint).unwrap())
+            .sum();
+        assert_eq!(moved, 300);
+
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 10_000,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+        let mut status = UserClaimStatus {
+            total_burned: 20,
+            ..UserClaimStatus::default()
+        };
+
+        // Burn more than the ATA held on its own
+        let burn = 350;
+        assert!(burn > ata_balance && burn <= ata_balance + moved);
+        record_burn(&mut config, &mut status, burn).unwrap();
+
+        assert_eq!(config.tokenomics.current_supply, 9_650);
+        assert_eq!(status.total_burned, 370);
+    }
+
+    #[test]
+    fn test_repeated_burns_accumulate() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 10_000,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+        let mut status = UserClaimStatus::default();
+
+        for amount in [100, 250, 1] {
+            record_burn(&mut config, &mut status, amount).unwrap();
+        }
+
+        assert_eq!(status.total_burned, 351);
+        assert_eq!(config.tokenomics.current_supply, 9_649);
+    }
+
+    #[test]
+    fn test_total_burned_overflow_is_rejected() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: u64::MAX,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+        let mut status = UserClaimStatus {
+            total_burned: u64::MAX - 5,
+            ..UserClaimStatus::default()
+        };
+
+        assert!(matches!(
+            record_burn(&mut config, &mut status, 6),
+            Err(YapError::Overflow)
+        ));
+        assert_eq!(status.total_burned, u64::MAX - 5);
+    }
+
+    fn delegated_token_account(
+        owner: Pubkey,
+        mint: Pubkey,
+        delegate: Pubkey,
+        allowance: u64,
+    ) -> Vec<u8> {
+        let account = TokenAccount {
+            mint,
+            owner,
+            amount: 1_000,
+            delegate: COption::Some(delegate),
+            delegated_amount: allowance,
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        let mut data = vec![0u8; TokenAccount::LEN];
+        TokenAccount::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_delegate_within_allowance_may_burn() {
+        let delegate = Pubkey::new_unique();
+        let data =
+            delegated_token_account(Pubkey::new_unique(), Pubkey::new_unique(), delegate, 100);
+
+        assert!(check_burn_delegate(&data, &delegate, 100).is_ok());
+        assert!(check_burn_delegate(&data, &delegate, 1).is_ok());
+
+        // Someone other than the approved delegate
+        assert!(matches!(
+            check_burn_delegate(&data, &Pubkey::new_unique(), 1),
+            Err(YapError::Unauthorized)
+        ));
+        // No delegate approved at all
+        let plain = packed_token_account(Pubkey::new_unique(), Pubkey::new_unique(), 100);
+        assert!(matches!(
+            check_burn_delegate(&plain, &delegate, 1),
+            Err(YapError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_delegate_over_allowance_rejected() {
+        let program_id = Pubkey::new_unique();
+        let user_key = Pubkey::new_unique();
+        let delegate_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let config = Config {
+            addresses: Addresses {
+                mint: mint_key,
+                token_program: spl_token::id(),
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+        let (ata_key, _) = Pubkey::find_program_address(
+            &[
+                user_key.as_ref(),
+                spl_token::id().as_ref(),
+                mint_key.as_ref(),
+            ],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        );
+
+        let mut delegate = TestAccount::signer(delegate_key);
+        let mut user = TestAccount::new(user_key);
+        let mut ata = TestAccount::new(ata_key);
+        ata.data = delegated_token_account(user_key, mint_key, delegate_key, 99);
+        let mut config = TestAccount::config(&program_id, config);
+        let mut mint = TestAccount::new(mint_key);
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut status = TestAccount::new(Pubkey::new_unique());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        let accounts = [
+            delegate.info(),
+            user.info(),
+            ata.info(),
+            config.info(),
+            mint.info(),
+            token_program.info(),
+            status.info(),
+            system_program.info(),
+            rent.info(),
+        ];
+        let err = process_as_delegate(&program_id, &accounts, 100).unwrap_err();
+        assert_eq!(err, YapError::InsufficientBalance.into());
+    }
+
+    #[test]
+    fn test_source_must_match_owner_and_mint() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let foreign = packed_token_account(Pubkey::new_unique(), mint, 10);
+        assert!(matches!(
+            source_balance(&foreign, &user, &mint),
+            Err(YapError::InvalidOwner)
+        ));
+
+        let other_mint = packed_token_account(user, Pubkey::new_unique(), 10);
+        assert!(matches!(
+            source_balance(&other_mint, &user, &mint),
+            Err(YapError::InvalidMint)
+        ));
+
+        // Not a token account at all
+        assert!(matches!(
+            source_balance(&[0u8; 10], &user, &mint),
+            Err(YapError::UninitializedTokenAccount)
+        ));
+    }
 }
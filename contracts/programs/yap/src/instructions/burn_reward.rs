@@ -0,0 +1,198 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+use spl_token_2022_interface::instruction as token_instruction;
+
+use crate::{
+    error::YapError,
+    instructions::claim::{check_not_frozen, validate_user_ata},
+    state::{Config, UserClaimStatus},
+    utils::token::validate_token_program,
+};
+
+/// Pay the burn reward owed on tokens burned since the last payout
+///
+/// reward = (total_burned - burn_rewarded) * burn_reward_bps / 10000, paid
+/// from the vault. `burn_rewarded` then catches up to `total_burned`, so each
+/// burned token is rewarded at most once.
+///
+/// Accounts:
+/// 0. `[signer]` User claiming
+/// 1. `[writable]` User's token account (ATA)
+/// 2. `[writable]` UserClaimStatus PDA
+/// 3. `[]` Config PDA
+/// 4. `[writable]` Vault PDA
+/// 5. `[]` Mint (for transfer_checked validation)
+/// 6. `[]` Token program
+/// 7. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let frozen_marker = account_info_iter.next();
+
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    // Emergency pause
+    config.ensure_not_paused()?;
+
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+
+    if mint_info.key != &config.addresses.mint {
+        msg!("ClaimBurnReward: Mint does not match config");
+        return Err(YapError::InvalidMint.into());
+    }
+    if vault_info.key != &config.addresses.vault {
+        msg!("ClaimBurnReward: Vault does not match config");
+        return Err(YapError::InvalidPda.into());
+    }
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    validate_user_ata(&config, user.key, user_token_account)?;
+
+    // Verify UserClaimStatus PDA (created by the user's first burn)
+    let mut user_claim_status =
+        UserClaimStatus::try_load(program_id, user.key, user_claim_status_info)?;
+
+    let reward = settle_burn_reward(&mut user_claim_status, config.tokenomics.burn_reward_bps)?;
+    if reward == 0 {
+        msg!(
+            "ClaimBurnReward: Nothing to claim (total_burned={}, rewarded={}, rate={}bps)",
+            user_claim_status.total_burned,
+            user_claim_status.burn_rewarded,
+            config.tokenomics.burn_reward_bps
+        );
+        return Err(YapError::NothingToClaim.into());
+    }
+
+    msg!(
+        "ClaimBurnReward: user={}, reward={}, total_burned={}",
+        user.key,
+        reward,
+        user_claim_status.total_burned
+    );
+
+    // Mark as paid before the transfer
+    user_claim_status.store(&mut user_claim_status_info.data.borrow_mut())?;
+
+    // Vault is owned by the config PDA; fails if the vault can't cover the reward
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            &config.addresses.token_program,
+            vault_info.key,
+            mint_info.key,
+            user_token_account.key,
+            config_info.key,
+            &[],
+            reward,
+            config.tokenomics.decimals,
+        )?,
+        &[
+            vault_info.clone(),
+            mint_info.clone(),
+            user_token_account.clone(),
+            config_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[Config::SEED, &[config.bump]]],
+    )?;
+
+    Ok(())
+}
+
+/// Reward owed on burns not yet paid out; marks them as rewarded
+///
+/// With a zero rate nothing is settled, so burns stay eligible if a rate is
+/// set later.
+fn settle_burn_reward(status: &mut UserClaimStatus, burn_reward_bps: u16) -> Result<u64, YapError> {
+    let unrewarded = status
+        .total_burned
+        .checked_sub(status.burn_rewarded)
+        .ok_or(YapError::Overflow)?;
+
+    let reward = (unrewarded as u128)
+        .checked_mul(burn_reward_bps as u128)
+        .ok_or(YapError::Overflow)?
+        / 10000;
+    let reward = u64::try_from(reward).map_err(|_| YapError::Overflow)?;
+
+    if reward > 0 {
+        status.burn_rewarded = status.total_burned;
+    }
+
+    Ok(reward)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn burned(total_burned: u64) -> UserClaimStatus {
+        UserClaimStatus {
+            total_burned,
+            ..UserClaimStatus::default()
+        }
+    }
+
+    #[test]
+    fn test_reward_paid_once_per_burn() {
+        let mut status = burned(1_000);
+
+        assert_eq!(settle_burn_reward(&mut status, 500).unwrap(), 50);
+        assert_eq!(status.burn_rewarded, 1_000);
+
+        // Claiming again without burning more pays nothing
+        assert_eq!(settle_burn_reward(&mut status, 500).unwrap(), 0);
+
+        // Only the new burn is rewarded
+        status.total_burned += 400;
+        assert_eq!(settle_burn_reward(&mut status, 500).unwrap(), 20);
+        assert_eq!(status.burn_rewarded, 1_400);
+    }
+
+    #[test]
+    fn test_zero_rate_keeps_burns_eligible() {
+        let mut status = burned(1_000);
+
+        assert_eq!(settle_burn_reward(&mut status, 0).unwrap(), 0);
+        assert_eq!(status.burn_rewarded, 0);
+
+        assert_eq!(settle_burn_reward(&mut status, 10_000).unwrap(), 1_000);
+    }
+
+    #[test]
+    fn test_dust_below_one_unit_is_not_settled() {
+        let mut status = burned(1);
+
+        // 1 * 1 / 10000 rounds to zero; the burn stays unrewarded
+        assert_eq!(settle_burn_reward(&mut status, 1).unwrap(), 0);
+        assert_eq!(status.burn_rewarded, 0);
+    }
+
+    #[test]
+    fn test_rewarded_above_burned_is_rejected() {
+        let mut status = UserClaimStatus {
+            total_burned: 10,
+            burn_rewarded: 11,
+            ..UserClaimStatus::default()
+        };
+        assert!(matches!(
+            settle_burn_reward(&mut status, 500),
+            Err(YapError::Overflow)
+        ));
+    }
+}
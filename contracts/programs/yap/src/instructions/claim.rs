@@ -1,44 +1,249 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
+    clock::Clock,
     entrypoint::ProgramResult,
-    keccak, msg,
-    program::invoke_signed,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
     pubkey::Pubkey,
     rent::Rent,
-    sysvar::SysvarSerialize,
+    sysvar::{Sysvar, SysvarSerialize},
 };
 use solana_system_interface::instruction as system_instruction;
+use spl_token_2022_interface::instruction as token_instruction;
 
 use crate::{
     error::YapError,
+    events::{BurnEvent, ClaimEvent, Event},
+    instructions::{
+        burn::{burn_from_user, record_burn},
+        claim_delegate::check_claim_delegate,
+        proof_buffer::{clear_proof, load_proof},
+        trigger_inflation::bps_share,
+    },
     state::{
-        Config, UserClaimStatus, ASSOCIATED_TOKEN_PROGRAM_ID, DECIMALS, MAX_PROOF_DEPTH,
-        USER_CLAIM_DISCRIMINATOR,
+        Config, FrozenWallet, UserClaimStatus, ASSOCIATED_TOKEN_PROGRAM_ID, MAX_CATEGORIES,
+        MAX_PROOF_DEPTH, USER_CLAIM_DISCRIMINATOR, USER_CLAIM_VERSION,
+    },
+    utils::{
+        merkle::{
+            compute_bonus_leaf, compute_leaf_with_expiry, compute_root, compute_salted_leaf, ct_eq,
+            verify_proof,
+        },
+        return_data,
+        token::{create_associated_token_account, unpack_token_account, validate_token_program},
+        vesting::VestingSchedule,
     },
 };
 
 /// Claim tokens using merkle proof
 ///
 /// Accounts:
-/// 0. `[signer, writable]` User claiming (pays for PDA if new)
-/// 1. `[writable]` User's token account (ATA)
+/// 0. `[signer, writable]` User claiming (pays for PDA and ATA if new, unless a payer is passed)
+/// 1. `[writable]` User's token account (ATA, created if empty)
 /// 2. `[writable]` UserClaimStatus PDA
-/// 3. `[]` Config PDA
+/// 3. `[writable]` Config PDA - to update total_claimed
 /// 4. `[writable]` Pending claims token account
 /// 5. `[]` Mint (for transfer_checked validation)
 /// 6. `[]` Token program
 /// 7. `[]` System program
 /// 8. `[]` Rent sysvar
+/// 9. `[]` (optional) Either the Associated Token program, needed to create a
+///    missing ATA, or a destination owner - then account 1 may be any token
+///    account of the configured mint owned by this key instead of the user's
+///    ATA. The leaf is still keyed to the signing user.
+/// 10. `[signer, writable]` (optional, needs account 9) Payer - sponsors the
+///     rent of a new UserClaimStatus PDA and ATA. The user still signs.
+/// 11. `[]` (needs accounts 9 and 10) The user's FrozenWallet PDA - required
+///     only while any wallet is frozen (`config.claims.frozen_wallets > 0`)
+/// 12. `[writable]` (needs accounts 9-11) Treasury token account - required
+///     only while `config.claims.claim_fee_bps` is set, which skims that share
+///     of the payout into it
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     amount: u64,
     proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    process_claim(program_id, accounts, amount, proof, None, None, None, None)
+}
+
+/// Claim tokens using merkle proof, plus a bonus proven against `config.distribution.bonus_root`
+///
+/// The main proof must be valid for the bonus to be paid. Both amounts are
+/// cumulative and transferred together in a single transfer.
+///
+/// Accounts: same as `process`
+pub fn process_with_bonus(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    bonus_amount: u64,
+    bonus_proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    process_claim(
+        program_id,
+        accounts,
+        amount,
+        proof,
+        Some((bonus_amount, bonus_proof)),
+        None,
+        None,
+        None,
+    )
+}
+
+/// Claim tokens against a leaf that also commits to `expiry_ts`
+///
+/// Requires `config.claims.leaf_has_expiry`; rejected with `ClaimExpired` once
+/// `now > expiry_ts`.
+///
+/// Accounts: same as `process`
+pub fn process_with_expiry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    expiry_ts: i64,
+) -> ProgramResult {
+    process_claim(
+        program_id,
+        accounts,
+        amount,
+        proof,
+        None,
+        Some(expiry_ts),
+        None,
+        None,
+    )
+}
+
+/// Claim tokens using merkle proof, then burn `burn_amount` of them from the same account
+///
+/// Atomic: the burn is recorded in `UserClaimStatus.total_burned` and
+/// `current_supply` like `Burn`. `burn_amount` must be 1..=the amount paid out.
+/// The payout must land in the user's own account: a `destination_owner`
+/// other than the user fails with `InvalidOwner` before anything moves.
+///
+/// Accounts: same as `process`, with the mint writable
+pub fn process_and_burn(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    burn_amount: u64,
+) -> ProgramResult {
+    process_claim(
+        program_id,
+        accounts,
+        amount,
+        proof,
+        None,
+        None,
+        Some(burn_amount),
+        None,
+    )
+}
+
+/// Claim only part of the unclaimed, vested share of the proven `amount`
+///
+/// Pays `min(claim_amount, claimable)` and adds it to `claimed_amount`, so the
+/// rest of the leaf stays claimable with the same proof later.
+///
+/// Accounts: same as `process`
+pub fn process_partial(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    claim_amount: u64,
+) -> ProgramResult {
+    process_claim(
+        program_id,
+        accounts,
+        amount,
+        proof,
+        None,
+        None,
+        None,
+        Some(claim_amount),
+    )
+}
+
+/// Claim tokens with a proof read from the user's ProofBuffer PDA
+///
+/// For proofs too long to fit in one transaction next to the claim accounts:
+/// the user fills the buffer with `WriteProofBuffer` first. The buffer is
+/// zeroed before the claim runs, so each written proof is used once.
+///
+/// Accounts:
+/// 0. `[writable]` The user's ProofBuffer PDA
+/// 1.. same as `process`
+pub fn process_with_proof_buffer(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let (buffer_info, claim_accounts) = accounts
+        .split_first()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let user = claim_accounts
+        .first()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    // Only the buffer's owner can spend it; process_claim checks the signature
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let proof = load_proof(program_id, user.key, buffer_info)?;
+    clear_proof(buffer_info)?;
+
+    process_claim(
+        program_id,
+        claim_accounts,
+        amount,
+        proof,
+        None,
+        None,
+        None,
+        None,
+    )
+}
+
+/// Claim for `user` as the delegate they set with `SetClaimDelegate`
+///
+/// The leaf is still keyed to `user` and tokens only go to the user's ATA,
+/// which must already exist. The delegate signs and pays for a new
+/// UserClaimStatus PDA. Burning and bonus claims stay with the user.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Delegate
+/// 1. `[]` User wallet the leaf is keyed to
+/// 2. `[]` User's ClaimDelegate PDA
+/// 3. `[writable]` User's token account (ATA)
+/// 4. `[writable]` UserClaimStatus PDA
+/// 5. `[writable]` Config PDA - to update total_claimed
+/// 6. `[writable]` Pending claims token account
+/// 7. `[]` Mint (for transfer_checked validation)
+/// 8. `[]` Token program
+/// 9. `[]` System program
+/// 10. `[]` Rent sysvar
+/// 11. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+/// 12. `[writable]` Treasury token account - required only while `claim_fee_bps` is set
+pub fn process_as_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
+    let delegate = next_account_info(account_info_iter)?;
     let user = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
     let user_token_account = next_account_info(account_info_iter)?;
     let user_claim_status_info = next_account_info(account_info_iter)?;
     let config_info = next_account_info(account_info_iter)?;
@@ -47,94 +252,192 @@ pub fn process(
     let token_program = next_account_info(account_info_iter)?;
     let system_program = next_account_info(account_info_iter)?;
     let rent_info = next_account_info(account_info_iter)?;
+    let frozen_marker = account_info_iter.next();
+    let treasury = account_info_iter.next();
 
-    // Verify user is signer
-    if !user.is_signer {
-        return Err(YapError::Unauthorized.into());
-    }
+    check_claim_delegate(program_id, user.key, delegate, delegate_info)?;
 
     // Reject zero amount claims
     if amount == 0 {
-        msg!("Claim: Amount cannot be zero");
+        msg!("ClaimAsDelegate: Amount cannot be zero");
         return Err(YapError::InvalidInstruction.into());
     }
 
-    // Reject excessively long proofs (DoS protection)
-    if proof.len() > MAX_PROOF_DEPTH {
-        msg!("Claim: Proof too long ({} > {})", proof.len(), MAX_PROOF_DEPTH);
-        return Err(YapError::ProofTooLong.into());
-    }
+    validate_claim_sysvars(system_program, rent_info)?;
 
-    // Note: token program validated by transfer_checked via check_program_account()
+    let mut config = load_claim_config(program_id, config_info, pending_claims_info, mint_info)?;
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+    let treasury = resolve_fee_treasury(&config, treasury)?;
 
-    // Verify system program
-    if *system_program.key != solana_system_interface::program::id() {
-        return Err(YapError::InvalidOwner.into());
-    }
+    // Tokens may only land in the user's own ATA
+    validate_user_ata(&config, user.key, user_token_account)?;
 
-    // Verify rent sysvar
-    if *rent_info.key != solana_program::sysvar::rent::ID {
-        return Err(YapError::InvalidOwner.into());
-    }
+    let now = Clock::get()?.unix_timestamp;
+    verify_claim_proof(&config, user.key, amount, None, &proof)?;
 
-    // Verify config PDA and owner
-    let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], program_id);
-    if config_info.key != &config_pda {
-        return Err(YapError::InvalidPda.into());
-    }
-    if config_info.owner != program_id {
-        return Err(YapError::InvalidOwner.into());
-    }
+    msg!(
+        "ClaimAsDelegate: delegate={}, user={}, amount={}, proof verified",
+        delegate.key,
+        user.key,
+        amount
+    );
 
-    let config = Config::try_from_slice(&config_info.data.borrow())?;
-    if !config.is_valid() {
-        return Err(YapError::InvalidDiscriminator.into());
+    let mut user_claim_status = load_or_create_claim_status(
+        program_id,
+        &mut config,
+        delegate,
+        user.key,
+        user_claim_status_info,
+        system_program,
+        rent_info,
+    )?;
+
+    check_claim_cooldown(&config, &user_claim_status, now)?;
+
+    let (vested, claimable) = vested_claimable(&config, &user_claim_status, amount, now)?;
+    check_single_claim(&config, claimable)?;
+    if claimable == 0 {
+        check_vested(vested, amount)?;
+        msg!(
+            "ClaimAsDelegate: Nothing to claim, already claimed {}",
+            user_claim_status.claimed_amount
+        );
+        return Err(YapError::AlreadyClaimed.into());
     }
 
-    // Verify merkle root is set (not empty)
-    if config.merkle_root == [0u8; 32] {
-        msg!("Claim: Merkle root not set");
-        return Err(YapError::NotInitialized.into());
+    // First claimer of the round also receives the early-bird bonus
+    let transferred = claimable
+        .checked_add(config.take_early_bird_bonus())
+        .ok_or(YapError::Overflow)?;
+    let (paid, fee) = split_claim_fee(&config, transferred)?;
+
+    let event = record_claim(
+        &mut config,
+        &mut user_claim_status,
+        user.key,
+        claimable,
+        None,
+        transferred,
+    )?;
+    user_claim_status.last_claim_ts = now;
+
+    commit_claim(
+        &user_claim_status,
+        user_claim_status_info,
+        &config,
+        config_info,
+        || {
+            transfer_from_pending_claims(
+                &config,
+                config_info,
+                pending_claims_info,
+                mint_info,
+                user_token_account,
+                token_program,
+                paid,
+            )?;
+            transfer_claim_fee(
+                &config,
+                config_info,
+                pending_claims_info,
+                mint_info,
+                treasury,
+                token_program,
+                fee,
+            )
+        },
+    )?;
+
+    event.emit()?;
+    return_data::set_amount(paid);
+
+    msg!("ClaimAsDelegate: Successfully claimed {} tokens", paid);
+
+    Ok(())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn process_claim(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+    bonus: Option<(u64, Vec<[u8; 32]>)>,
+    expiry_ts: Option<i64>,
+    burn_amount: Option<u64>,
+    claim_amount: Option<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let trailing_account = account_info_iter.next();
+    let payer = resolve_payer(user, account_info_iter.next())?;
+    let frozen_marker = account_info_iter.next();
+    let treasury = account_info_iter.next();
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
     }
 
-    // Verify pending_claims
-    if pending_claims_info.key != &config.pending_claims {
-        return Err(YapError::InvalidPda.into());
+    // Reject zero amount claims
+    if amount == 0 {
+        msg!("Claim: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
     }
 
-    // Verify mint matches config (for transfer_checked)
-    if mint_info.key != &config.mint {
-        return Err(YapError::InvalidMint.into());
+    if burn_amount.is_some() {
+        check_burn_destination(user.key, trailing_account)?;
     }
 
-    // Verify user_token_account is ATA for user and correct mint
-    let expected_ata = Pubkey::find_program_address(
-        &[
-            user.key.as_ref(),
-            spl_token::id().as_ref(),
-            config.mint.as_ref(),
-        ],
-        &ASSOCIATED_TOKEN_PROGRAM_ID,
-    )
-    .0;
-    if user_token_account.key != &expected_ata {
-        msg!("Claim: Invalid user token account, expected ATA");
-        return Err(YapError::InvalidPda.into());
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let mut config = load_claim_config(program_id, config_info, pending_claims_info, mint_info)?;
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+    let treasury = resolve_fee_treasury(&config, treasury)?;
+
+    match trailing_account {
+        Some(destination_owner) if destination_owner.key != &ASSOCIATED_TOKEN_PROGRAM_ID => {
+            validate_token_account(
+                &user_token_account.data.borrow(),
+                destination_owner.key,
+                &config.addresses.mint,
+            )?;
+            msg!(
+                "Claim: Delivering to {} owned by {}",
+                user_token_account.key,
+                destination_owner.key
+            );
+        }
+        associated_token_program => prepare_user_ata(
+            &config,
+            user,
+            payer,
+            user_token_account,
+            mint_info,
+            token_program,
+            system_program,
+            associated_token_program,
+        )?,
     }
 
-    // Verify UserClaimStatus PDA
-    let (user_claim_pda, user_claim_bump) =
-        Pubkey::find_program_address(&[UserClaimStatus::SEED, user.key.as_ref()], program_id);
-    if user_claim_status_info.key != &user_claim_pda {
-        return Err(YapError::InvalidPda.into());
+    let now = Clock::get()?.unix_timestamp;
+
+    // Reject expired claims before verifying the proof
+    if let Some(expiry_ts) = expiry_ts {
+        check_claim_expiry(expiry_ts, now)?;
     }
 
     // Verify merkle proof
-    let leaf = compute_leaf(user.key, amount);
-    if !verify_proof(&proof, &config.merkle_root, &leaf) {
-        msg!("Claim: Invalid merkle proof");
-        return Err(YapError::InvalidProof.into());
-    }
+    verify_claim_proof(&config, user.key, amount, expiry_ts, &proof)?;
 
     msg!(
         "Claim: user={}, amount={}, proof verified",
@@ -142,128 +445,2331 @@ pub fn process(
         amount
     );
 
+    // Verify bonus proof (only reachable with a valid main proof)
+    if let Some((bonus_amount, bonus_proof)) = &bonus {
+        verify_bonus_proof(&config, user.key, *bonus_amount, bonus_proof)?;
+        msg!("Claim: bonus_amount={}, bonus proof verified", bonus_amount);
+    }
+
     // Get or create UserClaimStatus
-    let mut user_claim_status = if user_claim_status_info.data_is_empty() {
-        // Create new UserClaimStatus PDA
-        let rent = Rent::from_account_info(rent_info)?;
-        let space = UserClaimStatus::LEN;
-        let lamports = rent.minimum_balance(space);
+    let mut user_claim_status = load_or_create_claim_status(
+        program_id,
+        &mut config,
+        payer,
+        user.key,
+        user_claim_status_info,
+        system_program,
+        rent_info,
+    )?;
 
-        invoke_signed(
-            &system_instruction::create_account(
-                user.key,
-                user_claim_status_info.key,
-                lamports,
-                space as u64,
-                program_id,
-            ),
-            &[
-                user.clone(),
-                user_claim_status_info.clone(),
-                system_program.clone(),
-            ],
-            &[&[UserClaimStatus::SEED, user.key.as_ref(), &[user_claim_bump]]],
-        )?;
+    check_claim_cooldown(&config, &user_claim_status, now)?;
 
-        UserClaimStatus {
-            discriminator: USER_CLAIM_DISCRIMINATOR,
-            claimed_amount: 0,
-            total_burned: 0,
-            bump: user_claim_bump,
-        }
-    } else {
-        // Load existing
-        if user_claim_status_info.owner != program_id {
-            return Err(YapError::InvalidOwner.into());
-        }
-        let status = UserClaimStatus::try_from_slice(&user_claim_status_info.data.borrow())?;
-        if !status.is_valid() {
-            return Err(YapError::InvalidDiscriminator.into());
+    // Calculate claimable amount (the vested share of `amount` only), capped
+    // at the requested part for a partial claim
+    let (vested, claimable) = vested_claimable(&config, &user_claim_status, amount, now)?;
+    let claimable = partial_claimable(claimable, claim_amount)?;
+
+    // Calculate claimable bonus (cumulative, tracked separately)
+    let bonus_claimable = match &bonus {
+        Some((bonus_amount, _)) => {
+            check_entitlement(*bonus_amount, user_claim_status.bonus_claimed_amount)?;
+            bonus_amount - user_claim_status.bonus_claimed_amount
         }
-        status
+        None => 0,
     };
+    let total_claimable = claimable
+        .checked_add(bonus_claimable)
+        .ok_or(YapError::Overflow)?;
+    check_single_claim(&config, total_claimable)?;
 
-    // Calculate claimable amount
-    let claimable = amount
-        .checked_sub(user_claim_status.claimed_amount)
-        .ok_or(YapError::AlreadyClaimed)?;
+    // Bonus payouts are limited to what SetBonusRoot moved in for them
+    config.draw_bonus_reserve(bonus_claimable)?;
 
-    if claimable == 0 {
+    if total_claimable == 0 {
+        check_vested(vested, amount)?;
         msg!(
-            "Claim: Nothing to claim, already claimed {}",
-            user_claim_status.claimed_amount
+            "Claim: Nothing to claim, already claimed {} (bonus {})",
+            user_claim_status.claimed_amount,
+            user_claim_status.bonus_claimed_amount
         );
         return Err(YapError::AlreadyClaimed.into());
     }
 
     msg!(
-        "Claim: claimable={} (total={}, already_claimed={})",
+        "Claim: claimable={} (total={}, vested={}, already_claimed={}), bonus_claimable={}",
         claimable,
         amount,
-        user_claim_status.claimed_amount
+        vested,
+        user_claim_status.claimed_amount,
+        bonus_claimable
     );
 
-    // Transfer tokens from pending_claims to user (transfer_checked validates mint & decimals)
-    invoke_signed(
-        &spl_token::instruction::transfer_checked(
-            &spl_token::id(),
-            pending_claims_info.key,
-            &config.mint, // mint for validation
-            user_token_account.key,
-            &config_pda, // pending_claims owner is config PDA
-            &[],
-            claimable,
-            DECIMALS, // decimals for validation
-        )?,
-        &[
-            pending_claims_info.clone(),
-            mint_info.clone(),
-            user_token_account.clone(),
-            config_info.clone(),
-            token_program.clone(),
-        ],
-        &[&[Config::SEED, &[config.bump]]],
+    // First claimer of the round also receives the early-bird bonus
+    let early_bird = config.take_early_bird_bonus();
+    if early_bird > 0 {
+        msg!("Claim: early-bird bonus {}", early_bird);
+    }
+    let total_claimable = total_claimable
+        .checked_add(early_bird)
+        .ok_or(YapError::Overflow)?;
+
+    // The treasury's cut comes out of the whole payout; only the rest can be burned
+    let (paid, fee) = split_claim_fee(&config, total_claimable)?;
+    if fee > 0 {
+        msg!("Claim: fee {} to treasury, {} to user", fee, paid);
+    }
+
+    if let Some(burn_amount) = burn_amount {
+        check_burn_amount(burn_amount, paid)?;
+    }
+
+    // Update claimed amounts and global counters
+    let event = record_claim(
+        &mut config,
+        &mut user_claim_status,
+        user.key,
+        claimable,
+        bonus.map(|(bonus_amount, _)| bonus_amount),
+        total_claimable,
+    )?;
+    user_claim_status.last_claim_ts = now;
+    if let Some(burn_amount) = burn_amount {
+        record_burn(&mut config, &mut user_claim_status, burn_amount)?;
+    }
+
+    commit_claim(
+        &user_claim_status,
+        user_claim_status_info,
+        &config,
+        config_info,
+        || {
+            transfer_from_pending_claims(
+                &config,
+                config_info,
+                pending_claims_info,
+                mint_info,
+                user_token_account,
+                token_program,
+                paid,
+            )?;
+            transfer_claim_fee(
+                &config,
+                config_info,
+                pending_claims_info,
+                mint_info,
+                treasury,
+                token_program,
+                fee,
+            )?;
+
+            // Burn straight out of the account just credited
+            if let Some(burn_amount) = burn_amount {
+                burn_from_user(
+                    &config,
+                    user,
+                    user_token_account,
+                    mint_info,
+                    token_program,
+                    burn_amount,
+                )?;
+                msg!("Claim: burned {} of the claimed tokens", burn_amount);
+            }
+            Ok(())
+        },
     )?;
 
-    // Update claimed amount
-    user_claim_status.claimed_amount = amount;
-    user_claim_status.serialize(&mut &mut user_claim_status_info.data.borrow_mut()[..])?;
+    event.emit()?;
+    if let Some(burn_amount) = burn_amount {
+        BurnEvent {
+            user: *user.key,
+            amount: burn_amount,
+            current_supply: config.tokenomics.current_supply,
+        }
+        .emit()?;
+    }
+    return_data::set_amount(paid);
 
-    msg!("Claim: Successfully claimed {} tokens", claimable);
+    msg!("Claim: Successfully claimed {} tokens", paid);
 
     Ok(())
 }
 
-/// Domain separator to prevent cross-protocol replay attacks
-const LEAF_DOMAIN: &[u8] = b"YAP_CLAIM_V1";
-
-/// Compute leaf hash: keccak256(domain || wallet_pubkey || amount)
-fn compute_leaf(wallet: &Pubkey, amount: u64) -> [u8; 32] {
-    let mut data = Vec::with_capacity(52); // 12 + 32 + 8
-    data.extend_from_slice(LEAF_DOMAIN);
-    data.extend_from_slice(wallet.as_ref());
-    data.extend_from_slice(&amount.to_le_bytes());
-    keccak::hash(&data).to_bytes()
-}
-
-/// Verify merkle proof
-fn verify_proof(proof: &[[u8; 32]], root: &[u8; 32], leaf: &[u8; 32]) -> bool {
-    let mut computed_hash = *leaf;
-
-    for proof_element in proof.iter() {
-        // Sort to ensure consistent ordering (smaller hash first)
-        if computed_hash <= *proof_element {
-            let mut combined = Vec::with_capacity(64);
-            combined.extend_from_slice(&computed_hash);
-            combined.extend_from_slice(proof_element);
-            computed_hash = keccak::hash(&combined).to_bytes();
-        } else {
-            let mut combined = Vec::with_capacity(64);
-            combined.extend_from_slice(proof_element);
-            combined.extend_from_slice(&computed_hash);
-            computed_hash = keccak::hash(&combined).to_bytes();
-        }
+/// Verify the system program and rent sysvar accounts used for PDA creation
+pub(crate) fn validate_claim_sysvars(
+    system_program: &AccountInfo,
+    rent_info: &AccountInfo,
+) -> ProgramResult {
+    // Verify system program
+    if *system_program.key != solana_system_interface::program::id() {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    // Verify rent sysvar
+    if *rent_info.key != solana_program::sysvar::rent::ID {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    Ok(())
+}
+
+/// Load config for a claim and verify the shared pending_claims and mint accounts
+pub(crate) fn load_claim_config(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+    pending_claims_info: &AccountInfo,
+    mint_info: &AccountInfo,
+) -> Result<Config, ProgramError> {
+    let config = Config::try_load(program_id, config_info)?;
+
+    // Emergency pause
+    config.ensure_not_paused()?;
+
+    // Verify merkle root is set (not empty)
+    if config.distribution.merkle_root == [0u8; 32] {
+        msg!("Claim: Merkle root not set");
+        return Err(YapError::NotInitialized.into());
+    }
+
+    // Verify pending_claims
+    if pending_claims_info.key != &config.addresses.pending_claims {
+        return Err(YapError::InvalidPda.into());
     }
 
-    computed_hash == *root
+    // Verify mint matches config (for transfer_checked)
+    if mint_info.key != &config.addresses.mint {
+        return Err(YapError::InvalidMint.into());
+    }
+
+    Ok(config)
+}
+
+/// Verify `user_token_account` is the user's ATA for the configured mint
+///
+/// With `config.claims.strict_ata_check`, also unpacks the account and requires its
+/// owner field to still be the user (catches reassigned ATA authority).
+pub(crate) fn validate_user_ata(
+    config: &Config,
+    user: &Pubkey,
+    user_token_account: &AccountInfo,
+) -> ProgramResult {
+    let expected_ata = Pubkey::find_program_address(
+        &[
+            user.as_ref(),
+            config.addresses.token_program.as_ref(),
+            config.addresses.mint.as_ref(),
+        ],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0;
+    if user_token_account.key != &expected_ata {
+        msg!("Claim: Invalid user token account, expected ATA");
+        return Err(YapError::InvalidTokenAccount.into());
+    }
+
+    if config.claims.strict_ata_check {
+        validate_token_account(&user_token_account.data.borrow(), user, &config.addresses.mint)?;
+    }
+
+    Ok(())
+}
+
+/// The account funding new claim accounts: the sponsor if passed, else the user
+///
+/// A sponsor must sign as well; the user's own signature is checked separately.
+fn resolve_payer<'b, 'a>(
+    user: &'b AccountInfo<'a>,
+    sponsor: Option<&'b AccountInfo<'a>>,
+) -> Result<&'b AccountInfo<'a>, YapError> {
+    let Some(sponsor) = sponsor else {
+        return Ok(user);
+    };
+    if !sponsor.is_signer {
+        msg!("Claim: Payer {} must sign", sponsor.key);
+        return Err(YapError::Unauthorized);
+    }
+    if sponsor.key != user.key {
+        msg!("Claim: Rent sponsored by {}", sponsor.key);
+    }
+    Ok(sponsor)
+}
+
+/// Verify the user's ATA and make sure it can receive the claim
+///
+/// A missing ATA is created through the Associated Token program, paid by
+/// `payer`; an existing one must hold the configured mint and belong to the user.
+#[allow(clippy::too_many_arguments)]
+fn prepare_user_ata<'a>(
+    config: &Config,
+    user: &AccountInfo<'a>,
+    payer: &AccountInfo<'a>,
+    user_token_account: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    associated_token_program: Option<&AccountInfo<'a>>,
+) -> ProgramResult {
+    validate_user_ata(config, user.key, user_token_account)?;
+
+    if !user_token_account.data_is_empty() {
+        return validate_token_account(
+            &user_token_account.data.borrow(),
+            user.key,
+            &config.addresses.mint,
+        );
+    }
+
+    let Some(associated_token_program) = associated_token_program else {
+        msg!("Claim: User token account missing, pass the ATA program to create it");
+        return Err(YapError::TokenAccountMissing.into());
+    };
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    msg!("Claim: Creating user token account {}", user_token_account.key);
+    invoke(
+        &create_associated_token_account(
+            payer.key,
+            user_token_account.key,
+            user.key,
+            &config.addresses.mint,
+            &config.addresses.token_program,
+        ),
+        &[
+            payer.clone(),
+            user_token_account.clone(),
+            user.clone(),
+            mint_info.clone(),
+            system_program.clone(),
+            token_program.clone(),
+            associated_token_program.clone(),
+        ],
+    )
+}
+
+/// Verify `token_account_data` is a token account of `mint` owned by `owner`
+pub(crate) fn validate_token_account(
+    token_account_data: &[u8],
+    owner: &Pubkey,
+    mint: &Pubkey,
+) -> ProgramResult {
+    let token_account = unpack_token_account(token_account_data).map_err(|_| {
+        msg!("Token account could not be unpacked");
+        YapError::UninitializedTokenAccount
+    })?;
+
+    if token_account.owner != *owner {
+        msg!(
+            "Token account owner mismatch (expected {}, got {})",
+            owner,
+            token_account.owner
+        );
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    if token_account.mint != *mint {
+        msg!(
+            "Token account mint mismatch (expected {}, got {})",
+            mint,
+            token_account.mint
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+
+    Ok(())
+}
+
+/// Verify `(user, amount[, expiry_ts])` against the current or a recent merkle root
+///
+/// `expiry_ts` must be present exactly when `config.claims.leaf_has_expiry` is set.
+/// Proofs against a root kept in `recent_roots` still verify, so claims built
+/// just before a `Distribute` don't fail; each is held to the depth committed
+/// with the root it resolves to. A rejected proof publishes its length and
+/// that depth (the current `tree_depth` if no root matched) as return data
+/// (`return_data::set_proof_depth`).
+pub(crate) fn verify_claim_proof(
+    config: &Config,
+    user: &Pubkey,
+    amount: u64,
+    expiry_ts: Option<i64>,
+    proof: &[[u8; 32]],
+) -> ProgramResult {
+    verify_claim_proof_against(config, user, amount, expiry_ts, proof, false)
+}
+
+/// Verify `(user, amount[, expiry_ts])` against the current merkle root only
+///
+/// For checks that must reflect the live entitlement, such as `CloseUserClaim`:
+/// a proof against a replaced root in `recent_roots` is rejected.
+pub(crate) fn verify_current_claim_proof(
+    config: &Config,
+    user: &Pubkey,
+    amount: u64,
+    expiry_ts: Option<i64>,
+    proof: &[[u8; 32]],
+) -> ProgramResult {
+    verify_claim_proof_against(config, user, amount, expiry_ts, proof, true)
+}
+
+fn verify_claim_proof_against(
+    config: &Config,
+    user: &Pubkey,
+    amount: u64,
+    expiry_ts: Option<i64>,
+    proof: &[[u8; 32]],
+    current_only: bool,
+) -> ProgramResult {
+    if let Err(err) = check_proof_depth(config, proof.len()) {
+        return_data::set_proof_depth(proof.len(), config.distribution.tree_depth);
+        return Err(err.into());
+    }
+
+    let leaf = match (config.claims.leaf_has_expiry, expiry_ts) {
+        (false, None) => compute_salted_leaf(&config.claims.domain_salt, user, amount),
+        (true, Some(expiry_ts)) => {
+            compute_leaf_with_expiry(&config.claims.domain_salt, user, amount, expiry_ts)
+        }
+        (true, None) => {
+            msg!("Claim: Leaves include an expiry, use ClaimWithExpiry");
+            return Err(YapError::InvalidInstruction.into());
+        }
+        (false, Some(_)) => {
+            msg!("Claim: Expiring leaves are not enabled");
+            return Err(YapError::InvalidInstruction.into());
+        }
+    };
+    let root = compute_root(&leaf, proof);
+    let tree_depth = if current_only {
+        (root != [0; 32] && ct_eq(&config.distribution.merkle_root, &root))
+            .then_some(config.distribution.tree_depth)
+    } else {
+        config.claim_root_depth(&root)
+    };
+    let Some(tree_depth) = tree_depth else {
+        msg!("Claim: Invalid merkle proof");
+        return_data::set_proof_depth(proof.len(), config.distribution.tree_depth);
+        return Err(YapError::InvalidProof.into());
+    };
+    if let Err(err) = check_tree_depth(tree_depth, proof.len()) {
+        return_data::set_proof_depth(proof.len(), tree_depth);
+        return Err(err.into());
+    }
+    Ok(())
+}
+
+/// Reject proofs longer than `MAX_PROOF_DEPTH` or shorter than `config.claims.min_proof_depth`
+///
+/// A shallow proof against a large distribution points at a malformed tree.
+pub(crate) fn check_proof_depth(config: &Config, depth: usize) -> Result<(), YapError> {
+    // Reject excessively long proofs (DoS protection)
+    if depth > MAX_PROOF_DEPTH {
+        msg!("Claim: Proof too long ({} > {})", depth, MAX_PROOF_DEPTH);
+        return Err(YapError::ProofTooLong);
+    }
+
+    if depth < config.claims.min_proof_depth as usize {
+        msg!(
+            "Claim: Proof too short ({} < {})",
+            depth,
+            config.claims.min_proof_depth
+        );
+        return Err(YapError::ProofTooShort);
+    }
+
+    Ok(())
+}
+
+/// Reject a proof whose length differs from the `tree_depth` committed with its root
+///
+/// A shorter proof that still reaches the root starts from an internal node,
+/// not a leaf. Not enforced for a root published with depth 0, which only
+/// roots from before every distribution committed a depth have.
+pub(crate) fn check_tree_depth(tree_depth: u8, depth: usize) -> Result<(), YapError> {
+    if tree_depth > 0 && depth != tree_depth as usize {
+        msg!("Claim: Proof length {} != tree depth {}", depth, tree_depth);
+        return Err(YapError::ProofDepthMismatch);
+    }
+    Ok(())
+}
+
+/// Verify `(user, bonus_amount)` against `bonus_root` at its committed depth
+fn verify_bonus_proof(
+    config: &Config,
+    user: &Pubkey,
+    bonus_amount: u64,
+    bonus_proof: &[[u8; 32]],
+) -> Result<(), YapError> {
+    if bonus_proof.len() > MAX_PROOF_DEPTH {
+        msg!(
+            "Claim: Bonus proof too long ({} > {})",
+            bonus_proof.len(),
+            MAX_PROOF_DEPTH
+        );
+        return Err(YapError::ProofTooLong);
+    }
+
+    if config.distribution.bonus_root == [0u8; 32] {
+        msg!("Claim: Bonus root not set");
+        return Err(YapError::NotInitialized);
+    }
+
+    let bonus_leaf = compute_bonus_leaf(&config.claims.domain_salt, user, bonus_amount);
+    if !verify_proof(&config.distribution.bonus_root, &bonus_leaf, bonus_proof) {
+        msg!("Claim: Invalid bonus merkle proof");
+        return Err(YapError::InvalidProof);
+    }
+    check_tree_depth(config.distribution.bonus_tree_depth, bonus_proof.len())
+}
+
+/// Reject a wallet that has a FrozenWallet marker
+///
+/// While no wallet is frozen the marker may be omitted. Otherwise `marker` must be
+/// the wallet's `["frozen", wallet]` PDA; an empty (unfunded) PDA means not frozen.
+pub(crate) fn check_not_frozen(
+    program_id: &Pubkey,
+    config: &Config,
+    wallet: &Pubkey,
+    marker: Option<&AccountInfo>,
+) -> ProgramResult {
+    if config.claims.frozen_wallets == 0 {
+        return Ok(());
+    }
+
+    let Some(marker) = marker else {
+        msg!("Frozen wallets exist; the FrozenWallet PDA must be passed");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+
+    let (marker_pda, _) =
+        Pubkey::find_program_address(&[FrozenWallet::SEED, wallet.as_ref()], program_id);
+    if marker.key != &marker_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    if marker.owner == program_id && !marker.data_is_empty() {
+        msg!("Wallet {} is frozen", wallet);
+        return Err(YapError::Frozen.into());
+    }
+    Ok(())
+}
+
+/// `ClaimAndBurn` burns with the user's signature, so it must pay into the user's own account
+///
+/// A `destination_owner` other than the user would take the payout and then
+/// fail the burn CPI; reject it before anything moves.
+fn check_burn_destination(
+    user: &Pubkey,
+    destination_owner: Option<&AccountInfo>,
+) -> Result<(), YapError> {
+    match destination_owner {
+        Some(owner) if owner.key != &ASSOCIATED_TOKEN_PROGRAM_ID && owner.key != user => {
+            msg!(
+                "ClaimAndBurn: Destination is owned by {}, not the claimant",
+                owner.key
+            );
+            Err(YapError::InvalidOwner)
+        }
+        _ => Ok(()),
+    }
+}
+
+/// A claim-and-burn may burn at most what the claim pays out, and not nothing
+fn check_burn_amount(burn_amount: u64, claimable: u64) -> Result<(), YapError> {
+    if burn_amount == 0 {
+        msg!("Claim: Burn amount cannot be zero, use Claim");
+        return Err(YapError::InvalidInstruction);
+    }
+    if burn_amount > claimable {
+        msg!(
+            "Claim: Burn amount {} exceeds claimable {}",
+            burn_amount,
+            claimable
+        );
+        return Err(YapError::InsufficientBalance);
+    }
+    Ok(())
+}
+
+/// Reject a claim whose encoded expiry has passed (`now == expiry_ts` is still valid)
+fn check_claim_expiry(expiry_ts: i64, now: i64) -> Result<(), YapError> {
+    if now > expiry_ts {
+        msg!("Claim: Expired at {} (now {})", expiry_ts, now);
+        return Err(YapError::ClaimExpired);
+    }
+    Ok(())
+}
+
+/// Reject a claim within `config.claims.claim_cooldown` seconds of the wallet's last one
+pub(crate) fn check_claim_cooldown(
+    config: &Config,
+    status: &UserClaimStatus,
+    now: i64,
+) -> Result<(), YapError> {
+    let cooldown = config.claims.claim_cooldown;
+    if cooldown > 0 && now.saturating_sub(status.last_claim_ts) < cooldown {
+        msg!(
+            "Claim: Cooldown active, last claim at {} (now {}, cooldown {}s)",
+            status.last_claim_ts,
+            now,
+            cooldown
+        );
+        return Err(YapError::ClaimCooldownActive);
+    }
+    Ok(())
+}
+
+/// Load the user's UserClaimStatus PDA, creating it (funded by `payer`) if empty
+///
+/// Creation counts against `config.claims.max_claim_accounts`; existing holders are
+/// never affected by the cap. An existing account in an older, shorter layout is
+/// grown to `UserClaimStatus::LEN`, with `payer` topping up its rent.
+pub(crate) fn load_or_create_claim_status<'a>(
+    program_id: &Pubkey,
+    config: &mut Config,
+    payer: &AccountInfo<'a>,
+    user: &Pubkey,
+    user_claim_status_info: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+) -> Result<UserClaimStatus, ProgramError> {
+    // Verify UserClaimStatus PDA
+    let (user_claim_pda, user_claim_bump) =
+        Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], program_id);
+    if user_claim_status_info.key != &user_claim_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    if user_claim_status_info.data_is_empty() {
+        config.reserve_claim_account()?;
+
+        // Create new UserClaimStatus PDA
+        let rent = Rent::from_account_info(rent_info)?;
+        let space = UserClaimStatus::LEN;
+        let lamports = rent.minimum_balance(space);
+
+        invoke_signed(
+            &system_instruction::create_account(
+                payer.key,
+                user_claim_status_info.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[
+                payer.clone(),
+                user_claim_status_info.clone(),
+                system_program.clone(),
+            ],
+            &[&[UserClaimStatus::SEED, user.as_ref(), &[user_claim_bump]]],
+        )?;
+
+        Ok(UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 0,
+            total_burned: 0,
+            bump: user_claim_bump,
+            bonus_claimed_amount: 0,
+            burn_rewarded: 0,
+            version: USER_CLAIM_VERSION,
+            last_claim_ts: 0,
+            category_claimed: [0; MAX_CATEGORIES],
+        })
+    } else {
+        // Load existing
+        if user_claim_status_info.owner != program_id {
+            return Err(YapError::InvalidOwner.into());
+        }
+        let mut status = UserClaimStatus::load(&user_claim_status_info.data.borrow())?;
+        if !status.is_valid() {
+            return Err(YapError::InvalidDiscriminator.into());
+        }
+        if user_claim_status_info.data_len() < UserClaimStatus::LEN {
+            grow_claim_status(payer, user_claim_status_info, system_program, rent_info)?;
+            status.version = USER_CLAIM_VERSION;
+        }
+        Ok(status)
+    }
+}
+
+/// Resize an older UserClaimStatus to the current layout, funded by `payer`
+///
+/// The new trailing fields start zeroed; the caller stores the loaded status
+/// back over the full layout.
+fn grow_claim_status<'a>(
+    payer: &AccountInfo<'a>,
+    user_claim_status_info: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent_info: &AccountInfo<'a>,
+) -> ProgramResult {
+    let old_len = user_claim_status_info.data_len();
+    let required = Rent::from_account_info(rent_info)?.minimum_balance(UserClaimStatus::LEN);
+    let shortfall = required.saturating_sub(user_claim_status_info.lamports());
+    if shortfall > 0 {
+        invoke(
+            &system_instruction::transfer(payer.key, user_claim_status_info.key, shortfall),
+            &[
+                payer.clone(),
+                user_claim_status_info.clone(),
+                system_program.clone(),
+            ],
+        )?;
+    }
+
+    user_claim_status_info.resize(UserClaimStatus::LEN)?;
+
+    msg!(
+        "UserClaimStatus: {} grown {} -> {} bytes, rent top-up {} lamports",
+        user_claim_status_info.key,
+        old_len,
+        UserClaimStatus::LEN,
+        shortfall
+    );
+
+    Ok(())
+}
+
+/// Transfer `amount` from pending_claims to `destination`, signed by the config PDA
+pub(crate) fn transfer_from_pending_claims<'a>(
+    config: &Config,
+    config_info: &AccountInfo<'a>,
+    pending_claims_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    // transfer_checked validates mint & decimals
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            &config.addresses.token_program,
+            pending_claims_info.key,
+            &config.addresses.mint, // mint for validation
+            destination.key,
+            config_info.key, // pending_claims owner is config PDA
+            &[],
+            amount,
+            config.tokenomics.decimals, // decimals for validation
+        )?,
+        &[
+            pending_claims_info.clone(),
+            mint_info.clone(),
+            destination.clone(),
+            config_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[Config::SEED, &[config.bump]]],
+    )
+}
+
+/// The vested part of the proven `amount` at `now` and how much of it is unclaimed
+///
+/// `claimed_amount` above `amount` is `EntitlementDecreased`; one merely above
+/// the vested part leaves nothing claimable yet. The vested figure never drops
+/// below `claimed_amount`, so the claimable part is never negative. The
+/// claimable part is also held to the wallet's `max_claim_per_wallet`.
+pub(crate) fn vested_claimable(
+    config: &Config,
+    status: &UserClaimStatus,
+    amount: u64,
+    now: i64,
+) -> Result<(u64, u64), YapError> {
+    check_entitlement(amount, status.claimed_amount)?;
+    let vested = VestingSchedule::from_timing(&config.timing)
+        .vested_amount(amount, now)
+        .max(status.claimed_amount);
+    let unclaimed = vested - status.claimed_amount;
+    let claimable = cap_wallet_claim(config, status.claimed_amount, unclaimed)?;
+    Ok((vested, claimable))
+}
+
+/// Clip `claimable` to what `max_claim_per_wallet` still allows
+///
+/// A wallet already at the cap with something left in its leaf fails with
+/// `WalletClaimCapReached` rather than reading as fully claimed.
+fn cap_wallet_claim(config: &Config, claimed: u64, claimable: u64) -> Result<u64, YapError> {
+    let headroom = config.wallet_claim_headroom(claimed);
+    if claimable > 0 && headroom == 0 {
+        msg!(
+            "Claim: Wallet already claimed {} of its {} cap",
+            claimed,
+            config.claims.max_claim_per_wallet
+        );
+        return Err(YapError::WalletClaimCapReached);
+    }
+    Ok(claimable.min(headroom))
+}
+
+/// Reject a claim transferring more than `max_single_claim` (0 = unlimited)
+///
+/// `transfer` is what this call pays out of the leaves: the main-tree share
+/// after vesting, the wallet cap and any partial limit, plus the bonus delta.
+/// The early-bird bonus is not counted. Unlike the wallet cap nothing is
+/// clipped: a valid proof for that much points at a bad root, so the claim
+/// fails until the admin rotates the root or lifts the limit.
+pub(crate) fn check_single_claim(config: &Config, transfer: u64) -> Result<(), YapError> {
+    let max_single_claim = config.claims.max_single_claim;
+    if max_single_claim != 0 && transfer > max_single_claim {
+        msg!(
+            "Claim: {} exceeds max_single_claim {}",
+            transfer,
+            max_single_claim
+        );
+        return Err(YapError::SingleClaimTooLarge);
+    }
+    Ok(())
+}
+
+/// Split a claim payout into what the user receives and the treasury's fee
+///
+/// The fee is `claim_fee_bps` of `payout`, rounded down, so it is 0 while
+/// the fee is off and never more than the payout. Returns `(paid, fee)`.
+pub(crate) fn split_claim_fee(config: &Config, payout: u64) -> Result<(u64, u64), YapError> {
+    let fee = bps_share(payout, config.claims.claim_fee_bps)?;
+    let paid = payout.checked_sub(fee).ok_or(YapError::Overflow)?;
+    Ok((paid, fee))
+}
+
+/// The treasury account a fee-charging claim pays into, `None` while the fee is off
+///
+/// `SetTreasury` already checked the account's mint; here it only has to be
+/// the configured one.
+pub(crate) fn resolve_fee_treasury<'b, 'a>(
+    config: &Config,
+    treasury: Option<&'b AccountInfo<'a>>,
+) -> Result<Option<&'b AccountInfo<'a>>, ProgramError> {
+    if config.claims.claim_fee_bps == 0 {
+        return Ok(None);
+    }
+
+    let Some(treasury) = treasury else {
+        msg!("Claim: Treasury account required while claim_fee_bps is set");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if treasury.key != &config.addresses.treasury {
+        msg!(
+            "Claim: Treasury mismatch (expected {}, got {})",
+            config.addresses.treasury,
+            treasury.key
+        );
+        return Err(YapError::InvalidPda.into());
+    }
+    Ok(Some(treasury))
+}
+
+/// Send a claim's fee from pending_claims to the treasury; nothing to do for a zero fee
+pub(crate) fn transfer_claim_fee<'a>(
+    config: &Config,
+    config_info: &AccountInfo<'a>,
+    pending_claims_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    treasury: Option<&AccountInfo<'a>>,
+    token_program: &AccountInfo<'a>,
+    fee: u64,
+) -> ProgramResult {
+    let Some(treasury) = treasury.filter(|_| fee > 0) else {
+        return Ok(());
+    };
+    transfer_from_pending_claims(
+        config,
+        config_info,
+        pending_claims_info,
+        mint_info,
+        treasury,
+        token_program,
+        fee,
+    )
+}
+
+/// Part of `claimable` to pay out when the user asked for at most `claim_amount`
+///
+/// `None` takes everything; a request above `claimable` is capped to it.
+pub(crate) fn partial_claimable(
+    claimable: u64,
+    claim_amount: Option<u64>,
+) -> Result<u64, YapError> {
+    match claim_amount {
+        Some(0) => {
+            msg!("Claim: Claim amount cannot be zero");
+            Err(YapError::InvalidInstruction)
+        }
+        Some(claim_amount) => Ok(claim_amount.min(claimable)),
+        None => Ok(claimable),
+    }
+}
+
+/// Cumulative amounts only grow: a proven `amount` below what was already
+/// `claimed` means the new root lowered the wallet's entitlement
+///
+/// Kept apart from `AlreadyClaimed`, which is an unchanged, fully paid leaf.
+pub(crate) fn check_entitlement(amount: u64, claimed: u64) -> Result<(), YapError> {
+    if amount < claimed {
+        msg!("Claim: Entitlement {} below {} already claimed", amount, claimed);
+        return Err(YapError::EntitlementDecreased);
+    }
+    Ok(())
+}
+
+/// With nothing claimable, tell an unvested remainder apart from a fully claimed leaf
+pub(crate) fn check_vested(vested: u64, amount: u64) -> Result<(), YapError> {
+    if vested < amount {
+        msg!("Claim: Only {} of {} vested so far", vested, amount);
+        return Err(YapError::NotYetVested);
+    }
+    Ok(())
+}
+
+/// Persist a claim's effects, then run its token CPIs
+///
+/// Checks-effects-interactions: `status` and `config` are written to their
+/// accounts before `interactions` runs, so anything re-entering the program
+/// from inside a CPI (e.g. a Token-2022 transfer hook) loads the raised
+/// `claimed_amount` and finds nothing left to claim. A failing CPI fails the
+/// instruction, and the runtime discards the writes with it.
+pub(crate) fn commit_claim(
+    status: &UserClaimStatus,
+    status_info: &AccountInfo,
+    config: &Config,
+    config_info: &AccountInfo,
+    interactions: impl FnOnce() -> ProgramResult,
+) -> ProgramResult {
+    status.store(&mut status_info.data.borrow_mut())?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+    interactions()
+}
+
+/// Apply a successful claim to the user's status and the global counters
+///
+/// `claimed` is the part of the main leaf paid out now and is added to
+/// `claimed_amount`, so a leaf can be drawn down over several partial claims;
+/// `bonus_amount` is the cumulative bonus leaf. Returns the event describing
+/// the new cumulative state.
+pub(crate) fn record_claim(
+    config: &mut Config,
+    status: &mut UserClaimStatus,
+    user: &Pubkey,
+    claimed: u64,
+    bonus_amount: Option<u64>,
+    transferred: u64,
+) -> Result<ClaimEvent, YapError> {
+    status.claimed_amount = status
+        .claimed_amount
+        .checked_add(claimed)
+        .ok_or(YapError::Overflow)?;
+    if let Some(bonus_amount) = bonus_amount {
+        check_entitlement(bonus_amount, status.bonus_claimed_amount)?;
+        status.bonus_claimed_amount = bonus_amount;
+    }
+
+    config.distribution.total_claimed = config
+        .distribution
+        .total_claimed
+        .checked_add(transferred)
+        .ok_or(YapError::Overflow)?;
+
+    Ok(ClaimEvent {
+        user: *user,
+        amount: transferred,
+        claimed_amount: status.claimed_amount,
+        bonus_claimed_amount: status.bonus_claimed_amount,
+        total_distributed: config.distribution.total_distributed,
+        total_claimed: config.distribution.total_claimed,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Addresses, ClaimRules, Distribution, Tokenomics, DOMAIN_SALT_LEN};
+    use crate::test_utils::TestAccount;
+    use crate::utils::{merkle::compute_leaf, token::TOKEN_2022_PROGRAM_ID};
+    use borsh::BorshDeserialize;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::Account as TokenAccount;
+
+    fn status_account(program_id: &Pubkey, user: &Pubkey, data: Vec<u8>) -> TestAccount {
+        let (pda, _) =
+            Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], program_id);
+        TestAccount {
+            owner: if data.is_empty() {
+                solana_system_interface::program::id()
+            } else {
+                *program_id
+            },
+            data,
+            ..TestAccount::new(pda)
+        }
+    }
+
+    #[test]
+    fn test_reentrant_claim_sees_committed_status() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+        let mut status = UserClaimStatus::default();
+        let mut status_account = status_account(&program_id, &user, vec![0; UserClaimStatus::LEN]);
+        let mut config_account = TestAccount::config(&program_id, config.clone());
+        let status_info = status_account.info();
+        let config_info = config_account.info();
+
+        let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+
+        // A transfer hook calling back into Claim with the same leaf mid-CPI
+        let mut reentered = false;
+        commit_claim(&status, &status_info, &config, &config_info, || {
+            let stored = UserClaimStatus::load(&status_info.data.borrow())?;
+            let stored_config = Config::try_from_slice(&config_info.data.borrow())?;
+            assert_eq!(stored.claimed_amount, 1_000);
+            assert_eq!(stored_config.distribution.total_claimed, 1_000);
+
+            let (_, claimable) = vested_claimable(&stored_config, &stored, 1_000, 0)?;
+            assert_eq!(claimable, 0);
+            reentered = true;
+            Ok(())
+        })
+        .unwrap();
+        assert!(reentered);
+
+        // A failing CPI fails the claim
+        let err = commit_claim(&status, &status_info, &config, &config_info, || {
+            Err(YapError::InsufficientBalance.into())
+        })
+        .unwrap_err();
+        assert_eq!(err, YapError::InsufficientBalance.into());
+    }
+
+    #[test]
+    fn test_claim_account_limit_reached() {
+        let mut config = Config {
+            claims: ClaimRules {
+                max_claim_accounts: 2,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+        config.reserve_claim_account().unwrap();
+        config.reserve_claim_account().unwrap();
+        assert!(matches!(
+            config.reserve_claim_account(),
+            Err(YapError::ClaimAccountLimitReached)
+        ));
+        assert_eq!(config.claims.total_claim_accounts, 2);
+
+        // A new wallet is rejected before any account is created
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let mut payer = TestAccount::signer(user);
+        let mut status = status_account(&program_id, &user, vec![]);
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+        let err = load_or_create_claim_status(
+            &program_id,
+            &mut config,
+            &payer.info(),
+            &user,
+            &status.info(),
+            &system_program.info(),
+            &rent.info(),
+        )
+        .unwrap_err();
+        assert_eq!(err, YapError::ClaimAccountLimitReached.into());
+    }
+
+    #[test]
+    fn test_existing_holder_claims_at_limit() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let mut config = Config {
+            claims: ClaimRules {
+                total_claim_accounts: 1,
+                max_claim_accounts: 1,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+
+        let existing = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 500,
+            version: USER_CLAIM_VERSION,
+            ..UserClaimStatus::default()
+        };
+        let mut payer = TestAccount::signer(user);
+        let mut status = status_account(&program_id, &user, borsh::to_vec(&existing).unwrap());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        let loaded = load_or_create_claim_status(
+            &program_id,
+            &mut config,
+            &payer.info(),
+            &user,
+            &status.info(),
+            &system_program.info(),
+            &rent.info(),
+        )
+        .unwrap();
+        assert_eq!(loaded.claimed_amount, 500);
+        assert_eq!(config.claims.total_claim_accounts, 1);
+    }
+
+    /// Rent sysvar account data (bincode layout)
+    fn bincode_rent(rent: &Rent) -> Vec<u8> {
+        let mut data = rent.lamports_per_byte_year.to_le_bytes().to_vec();
+        data.extend_from_slice(&rent.exemption_threshold.to_le_bytes());
+        data.push(rent.burn_percent);
+        data
+    }
+
+    #[test]
+    fn test_sponsor_pays_for_new_claim_status() {
+        let program_id = Pubkey::new_unique();
+        let user_key = Pubkey::new_unique();
+        let mut config = Config {
+            claims: ClaimRules {
+                max_claim_accounts: u64::MAX,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+
+        let mut user = TestAccount::signer(user_key);
+        let mut sponsor = TestAccount {
+            lamports: 1_000_000_000,
+            ..TestAccount::signer(Pubkey::new_unique())
+        };
+        let mut status = status_account(&program_id, &user_key, vec![]);
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount {
+            data: bincode_rent(&Rent::default()),
+            ..TestAccount::new(solana_program::sysvar::rent::ID)
+        };
+
+        let (user, sponsor) = (user.info(), sponsor.info());
+        let payer = resolve_payer(&user, Some(&sponsor)).unwrap();
+        assert_eq!(payer.key, sponsor.key);
+
+        // The PDA is still derived from and recorded for the user
+        let created = load_or_create_claim_status(
+            &program_id,
+            &mut config,
+            payer,
+            user.key,
+            &status.info(),
+            &system_program.info(),
+            &rent.info(),
+        )
+        .unwrap();
+        assert!(created.is_valid());
+        assert_eq!(created.claimed_amount, 0);
+        assert_eq!(config.claims.total_claim_accounts, 1);
+
+        // Without a separate payer the user pays, as before
+        assert_eq!(resolve_payer(&user, None).unwrap().key, &user_key);
+        assert_eq!(resolve_payer(&user, Some(&user)).unwrap().key, &user_key);
+    }
+
+    #[test]
+    fn test_older_claim_status_grows_to_current_layout() {
+        /// Runtime input layout `resize` relies on (see `utils::account` tests)
+        #[repr(C)]
+        struct SerializedKey {
+            original_data_len: u32,
+            key: Pubkey,
+        }
+        #[repr(C, align(8))]
+        struct SerializedData([u8; 8 + UserClaimStatus::LEN]);
+
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let mut config = Config {
+            claims: ClaimRules {
+                max_claim_accounts: u64::MAX,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+
+        // Version 2 account, created before `last_claim_ts`
+        let older = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 500,
+            version: 2,
+            ..UserClaimStatus::default()
+        };
+        let old_len = UserClaimStatus::V2_LEN;
+        let mut serialized = SerializedData([0u8; 8 + UserClaimStatus::LEN]);
+        let buffer = &mut serialized.0;
+        buffer[..8].copy_from_slice(&(old_len as u64).to_le_bytes());
+        buffer[8..8 + old_len].copy_from_slice(&borsh::to_vec(&older).unwrap()[..old_len]);
+
+        let (pda, _) =
+            Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], &program_id);
+        let status_key = SerializedKey {
+            original_data_len: old_len as u32,
+            key: pda,
+        };
+        let mut status_lamports = Rent::default().minimum_balance(old_len);
+        let status = AccountInfo::new(
+            &status_key.key,
+            false,
+            true,
+            &mut status_lamports,
+            &mut buffer[8..8 + old_len],
+            &program_id,
+            false,
+        );
+        let mut payer = TestAccount::signer(user);
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount {
+            data: bincode_rent(&Rent::default()),
+            ..TestAccount::new(solana_program::sysvar::rent::ID)
+        };
+
+        let mut loaded = load_or_create_claim_status(
+            &program_id,
+            &mut config,
+            &payer.info(),
+            &user,
+            &status,
+            &system_program.info(),
+            &rent.info(),
+        )
+        .unwrap();
+        assert_eq!(status.data_len(), UserClaimStatus::LEN);
+        assert_eq!((loaded.version, loaded.claimed_amount), (USER_CLAIM_VERSION, 500));
+        assert_eq!(config.claims.total_claim_accounts, 0);
+
+        // The grown account now keeps the claim timestamp
+        loaded.last_claim_ts = 1_000;
+        loaded.store(&mut status.data.borrow_mut()).unwrap();
+        let reloaded = UserClaimStatus::load(&status.data.borrow()).unwrap();
+        assert_eq!((reloaded.version, reloaded.last_claim_ts), (USER_CLAIM_VERSION, 1_000));
+    }
+
+    #[test]
+    fn test_claim_and_burn_accounting() {
+        let user = Pubkey::new_unique();
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 10_000,
+                ..Tokenomics::default()
+            },
+            distribution: Distribution {
+                total_distributed: 1_000,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        let mut status = UserClaimStatus {
+            claimed_amount: 200,
+            ..UserClaimStatus::default()
+        };
+
+        // Cumulative 1_000 with 200 already claimed pays 800, 300 of which is
+        // burned again, leaving the ATA 500 richer
+        let claimable = 800;
+        assert!(matches!(
+            check_burn_amount(claimable + 1, claimable),
+            Err(YapError::InsufficientBalance)
+        ));
+        assert!(matches!(
+            check_burn_amount(0, claimable),
+            Err(YapError::InvalidInstruction)
+        ));
+        check_burn_amount(300, claimable).unwrap();
+
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+        record_burn(&mut config, &mut status, 300).unwrap();
+
+        assert_eq!(status.claimed_amount, 1_000);
+        assert_eq!(status.total_burned, 300);
+        assert_eq!(config.distribution.total_claimed, 800);
+        assert_eq!(config.tokenomics.current_supply, 9_700);
+        assert_eq!(claimable - status.total_burned, 500);
+    }
+
+    #[test]
+    fn test_claim_and_burn_into_foreign_destination_rejected() {
+        use crate::test_utils::TestAccount;
+
+        let program_id = Pubkey::new_unique();
+        let user_key = Pubkey::new_unique();
+        let mut user = TestAccount::signer(user_key);
+        let mut vault_ata = TestAccount::new(Pubkey::new_unique());
+        let mut status = TestAccount::new(Pubkey::new_unique());
+        let mut config = TestAccount::config(&program_id, Config::default());
+        let mut pending_claims = TestAccount::new(Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+        let mut vault_owner = TestAccount::new(Pubkey::new_unique());
+        let accounts = [
+            user.info(),
+            vault_ata.info(),
+            status.info(),
+            config.info(),
+            pending_claims.info(),
+            mint.info(),
+            token_program.info(),
+            system_program.info(),
+            rent.info(),
+            vault_owner.info(),
+        ];
+
+        // The burn signs as the user, so paying someone else's account fails up front
+        let err = process_and_burn(&program_id, &accounts, 1_000, vec![], 100).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+
+        // The user's own account (or its ATA) is fine
+        check_burn_destination(&user_key, None).unwrap();
+        check_burn_destination(&user_key, Some(&accounts[0])).unwrap();
+        let mut ata_program = TestAccount::new(ASSOCIATED_TOKEN_PROGRAM_ID);
+        check_burn_destination(&user_key, Some(&ata_program.info())).unwrap();
+    }
+
+    #[test]
+    fn test_claim_pays_vested_share() {
+        const DAY: i64 = 24 * 60 * 60;
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+        let mut status = UserClaimStatus::default();
+
+        // 1_000 vesting over 10 days from day 0, with a 2-day cliff
+        config.timing.vesting_cliff_ts = 2 * DAY;
+        config.timing.vesting_duration = 10 * DAY;
+
+        // Pre-cliff: nothing claimable, and the error says why
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, DAY).unwrap();
+        assert_eq!((vested, claimable), (0, 0));
+        assert!(matches!(
+            check_vested(vested, 1_000),
+            Err(YapError::NotYetVested)
+        ));
+
+        // Mid-vest: the vested share, recorded as the claimed amount
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 4 * DAY).unwrap();
+        assert_eq!((vested, claimable), (400, 400));
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+        assert_eq!(status.claimed_amount, 400);
+
+        // Same moment again: nothing new yet
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 4 * DAY).unwrap();
+        assert_eq!(claimable, 0);
+        assert!(matches!(
+            check_vested(vested, 1_000),
+            Err(YapError::NotYetVested)
+        ));
+
+        // Post-vest: the remainder, after which the leaf is fully claimed
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 10 * DAY).unwrap();
+        assert_eq!((vested, claimable), (1_000, 600));
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+        assert_eq!(config.distribution.total_claimed, 1_000);
+        check_vested(1_000, 1_000).unwrap();
+        assert!(matches!(
+            vested_claimable(&config, &status, 999, 10 * DAY),
+            Err(YapError::EntitlementDecreased)
+        ));
+    }
+
+    #[test]
+    fn test_wallet_cap_binds_before_entitlement() {
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+        config.claims.max_claim_per_wallet = 600;
+        let mut status = UserClaimStatus::default();
+
+        // The tree says 1_000, the cap pays 600
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        assert_eq!((vested, claimable), (1_000, 600));
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+        assert_eq!(status.claimed_amount, 600);
+
+        // At the cap: the rest of the leaf is refused outright
+        assert!(matches!(
+            vested_claimable(&config, &status, 1_000, 0),
+            Err(YapError::WalletClaimCapReached)
+        ));
+
+        // A fully claimed leaf still reads as nothing to claim
+        config.claims.max_claim_per_wallet = 1_000;
+        let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+        assert_eq!(status.claimed_amount, 1_000);
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        assert_eq!((vested, claimable), (1_000, 0));
+    }
+
+    #[test]
+    fn test_wallet_cap_above_entitlement_does_not_bind() {
+        let config = Config {
+            claims: ClaimRules {
+                max_claim_per_wallet: 5_000,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+        let status = UserClaimStatus {
+            claimed_amount: 400,
+            ..UserClaimStatus::default()
+        };
+
+        let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        assert_eq!(claimable, 600);
+        assert_eq!(partial_claimable(claimable, Some(100)).unwrap(), 100);
+
+        // 0 is unlimited
+        let unlimited = Config::default();
+        let (_, claimable) = vested_claimable(&unlimited, &status, u64::MAX, 0).unwrap();
+        assert_eq!(claimable, u64::MAX - 400);
+    }
+
+    #[test]
+    fn test_single_claim_valve_binds() {
+        let mut config = Config::default();
+        config.claims.max_single_claim = 500;
+        let status = UserClaimStatus::default();
+
+        // A valid leaf for more than the valve fails outright
+        let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        assert!(matches!(
+            check_single_claim(&config, claimable),
+            Err(YapError::SingleClaimTooLarge)
+        ));
+
+        // A bonus delta counts on top of the main-tree share
+        let (_, claimable) = vested_claimable(&config, &status, 400, 0).unwrap();
+        assert!(matches!(
+            check_single_claim(&config, claimable + 200),
+            Err(YapError::SingleClaimTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_single_claim_valve_does_not_bind() {
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+        config.claims.max_single_claim = 500;
+        let mut status = UserClaimStatus::default();
+
+        // Exactly at the valve is allowed
+        let (_, claimable) = vested_claimable(&config, &status, 500, 0).unwrap();
+        check_single_claim(&config, claimable).unwrap();
+
+        // Only the transfer counts: a partial claim within the valve goes through
+        let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        let partial = partial_claimable(claimable, Some(500)).unwrap();
+        check_single_claim(&config, partial).unwrap();
+        record_claim(&mut config, &mut status, &user, partial, None, partial).unwrap();
+
+        // ... as does one the wallet cap clips to the valve
+        config.claims.max_claim_per_wallet = 1_000;
+        let (_, claimable) = vested_claimable(&config, &status, 5_000, 0).unwrap();
+        assert_eq!(claimable, 500);
+        check_single_claim(&config, claimable).unwrap();
+
+        // 0 is unlimited
+        check_single_claim(&Config::default(), u64::MAX).unwrap();
+    }
+
+    #[test]
+    fn test_claim_fee_disabled_by_default() {
+        let config = Config::default();
+        assert_eq!(config.claims.claim_fee_bps, 0);
+        assert_eq!(split_claim_fee(&config, 1_000).unwrap(), (1_000, 0));
+        assert_eq!(split_claim_fee(&config, u64::MAX).unwrap(), (u64::MAX, 0));
+
+        // No treasury is needed, or even looked at, while the fee is off
+        assert!(resolve_fee_treasury(&config, None).unwrap().is_none());
+        let mut stray = TestAccount::new(Pubkey::new_unique());
+        assert!(resolve_fee_treasury(&config, Some(&stray.info()))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn test_claim_fee_split() {
+        let mut config = Config::default();
+
+        // An explicit 0% pays everything to the user
+        config.claims.claim_fee_bps = 0;
+        assert_eq!(split_claim_fee(&config, 10_000).unwrap(), (10_000, 0));
+
+        // 2.5%: the fee rounds down, so the user keeps the remainder
+        config.claims.claim_fee_bps = 250;
+        assert_eq!(split_claim_fee(&config, 10_000).unwrap(), (9_750, 250));
+        assert_eq!(split_claim_fee(&config, 39).unwrap(), (39, 0));
+        assert_eq!(split_claim_fee(&config, 41).unwrap(), (40, 1));
+
+        // The cap holds at the top of the range without overflowing
+        config.claims.claim_fee_bps = Config::MAX_CLAIM_FEE_BPS;
+        let (paid, fee) = split_claim_fee(&config, u64::MAX).unwrap();
+        assert_eq!(fee, u64::MAX / 20);
+        assert_eq!(paid + fee, u64::MAX);
+    }
+
+    #[test]
+    fn test_claim_fee_treasury_must_match_config() {
+        let treasury_key = Pubkey::new_unique();
+        let mut config = Config::default();
+        config.addresses.treasury = treasury_key;
+        config.claims.claim_fee_bps = 100;
+
+        assert_eq!(
+            resolve_fee_treasury(&config, None).unwrap_err(),
+            ProgramError::NotEnoughAccountKeys
+        );
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        assert_eq!(
+            resolve_fee_treasury(&config, Some(&other.info())).unwrap_err(),
+            YapError::InvalidPda.into()
+        );
+        let mut treasury = TestAccount::new(treasury_key);
+        let info = treasury.info();
+        let resolved = resolve_fee_treasury(&config, Some(&info)).unwrap();
+        assert_eq!(resolved.map(|account| *account.key), Some(treasury_key));
+    }
+
+    #[test]
+    fn test_partial_claims_sum_to_entitlement() {
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+        let mut status = UserClaimStatus::default();
+
+        // Three draws against the same 1_000 leaf, the last asking for more than is left
+        for (claim_amount, paid) in [(300, 300), (450, 450), (400, 250)] {
+            let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+            let claimable = partial_claimable(claimable, Some(claim_amount)).unwrap();
+            assert_eq!(claimable, paid);
+            record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+        }
+        assert_eq!(status.claimed_amount, 1_000);
+        assert_eq!(config.distribution.total_claimed, 1_000);
+
+        // Fully drawn: any further request pays nothing
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        assert_eq!(partial_claimable(claimable, Some(1)).unwrap(), 0);
+        check_vested(vested, 1_000).unwrap();
+
+        // A later root raising the leaf frees exactly the difference
+        let (_, claimable) = vested_claimable(&config, &status, 1_200, 0).unwrap();
+        assert_eq!(partial_claimable(claimable, Some(u64::MAX)).unwrap(), 200);
+    }
+
+    #[test]
+    fn test_partial_claim_rejects_over_claim() {
+        const DAY: i64 = 24 * 60 * 60;
+        let mut config = Config::default();
+        config.timing.vesting_duration = 10 * DAY;
+        let status = UserClaimStatus {
+            claimed_amount: 100,
+            ..UserClaimStatus::default()
+        };
+
+        // 400 vested, 100 claimed: never more than the remaining 300
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 4 * DAY).unwrap();
+        assert_eq!((vested, claimable), (400, 300));
+        assert_eq!(partial_claimable(claimable, Some(1_000)).unwrap(), 300);
+        assert_eq!(partial_claimable(claimable, Some(50)).unwrap(), 50);
+        assert_eq!(partial_claimable(claimable, None).unwrap(), 300);
+        assert!(matches!(
+            partial_claimable(claimable, Some(0)),
+            Err(YapError::InvalidInstruction)
+        ));
+    }
+
+    #[test]
+    fn test_decreased_entitlement_vs_already_claimed() {
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+        let mut status = UserClaimStatus::default();
+
+        let (_, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        record_claim(&mut config, &mut status, &user, claimable, None, claimable).unwrap();
+
+        // Same leaf again: fully claimed, nothing new
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, 0).unwrap();
+        assert_eq!((vested, claimable), (1_000, 0));
+        check_vested(vested, 1_000).unwrap();
+
+        // A later root that lowers the leaf is reported as such
+        assert!(matches!(
+            vested_claimable(&config, &status, 999, 0),
+            Err(YapError::EntitlementDecreased)
+        ));
+        assert_eq!(status.claimed_amount, 1_000);
+
+        // Same for the bonus total
+        record_claim(&mut config, &mut status, &user, 0, Some(50), 50).unwrap();
+        assert!(matches!(
+            record_claim(&mut config, &mut status, &user, 0, Some(49), 0),
+            Err(YapError::EntitlementDecreased)
+        ));
+        assert_eq!(status.bonus_claimed_amount, 50);
+    }
+
+    #[test]
+    fn test_new_schedule_never_lowers_claimed_amount() {
+        const DAY: i64 = 24 * 60 * 60;
+        let mut config = Config::default();
+        let status = UserClaimStatus {
+            claimed_amount: 600,
+            ..UserClaimStatus::default()
+        };
+
+        // A fresh 10-day schedule vests 100 of the 1_000 leaf at day 1
+        config.timing.vesting_duration = 10 * DAY;
+        let (vested, claimable) = vested_claimable(&config, &status, 1_000, DAY).unwrap();
+        assert_eq!((vested, claimable), (600, 0));
+        assert!(matches!(
+            check_vested(vested, 1_000),
+            Err(YapError::NotYetVested)
+        ));
+    }
+
+    #[test]
+    fn test_frozen_wallet_is_rejected_until_unfrozen() {
+        let program_id = Pubkey::new_unique();
+        let wallet = Pubkey::new_unique();
+        let (marker_pda, _) =
+            Pubkey::find_program_address(&[FrozenWallet::SEED, wallet.as_ref()], &program_id);
+        let mut config = Config::default();
+
+        // Nothing frozen: no marker needed
+        check_not_frozen(&program_id, &config, &wallet, None).unwrap();
+
+        // Freeze: marker exists and the counter is raised
+        config.claims.frozen_wallets = 1;
+        let mut marker = TestAccount {
+            owner: program_id,
+            data: vec![0u8; FrozenWallet::LEN],
+            ..TestAccount::new(marker_pda)
+        };
+        assert_eq!(
+            check_not_frozen(&program_id, &config, &wallet, Some(&marker.info())).unwrap_err(),
+            YapError::Frozen.into()
+        );
+        assert_eq!(
+            check_not_frozen(&program_id, &config, &wallet, None).unwrap_err(),
+            ProgramError::NotEnoughAccountKeys
+        );
+        let mut wrong = TestAccount::new(Pubkey::new_unique());
+        assert_eq!(
+            check_not_frozen(&program_id, &config, &wallet, Some(&wrong.info())).unwrap_err(),
+            YapError::InvalidPda.into()
+        );
+
+        // Unfreeze: the marker is closed, but another wallet may still be frozen
+        let mut closed = TestAccount::new(marker_pda);
+        check_not_frozen(&program_id, &config, &wallet, Some(&closed.info())).unwrap();
+        config.claims.frozen_wallets = 0;
+        check_not_frozen(&program_id, &config, &wallet, None).unwrap();
+    }
+
+    #[test]
+    fn test_second_claim_inside_cooldown_fails() {
+        let mut config = Config {
+            claims: ClaimRules {
+                claim_cooldown: 600,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+        let user = Pubkey::new_unique();
+        let mut status = UserClaimStatus::default();
+
+        // A wallet that never claimed is not throttled
+        check_claim_cooldown(&config, &status, 1_000).unwrap();
+        record_claim(&mut config, &mut status, &user, 100, None, 100).unwrap();
+        status.last_claim_ts = 1_000;
+
+        assert!(matches!(
+            check_claim_cooldown(&config, &status, 1_599),
+            Err(YapError::ClaimCooldownActive)
+        ));
+        check_claim_cooldown(&config, &status, 1_600).unwrap();
+
+        // No cooldown configured
+        config.claims.claim_cooldown = 0;
+        check_claim_cooldown(&config, &status, 1_000).unwrap();
+    }
+
+    #[test]
+    fn test_sponsor_must_sign() {
+        let mut user = TestAccount::signer(Pubkey::new_unique());
+        let mut sponsor = TestAccount::new(Pubkey::new_unique());
+        let (user, sponsor) = (user.info(), sponsor.info());
+        assert!(matches!(
+            resolve_payer(&user, Some(&sponsor)),
+            Err(YapError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_claim_reverts_when_paused() {
+        let program_id = Pubkey::new_unique();
+        let paused = Config {
+            paused: true,
+            ..Config::default()
+        };
+
+        let mut user = TestAccount::signer(Pubkey::new_unique());
+        let mut ata = TestAccount::new(Pubkey::new_unique());
+        let mut status = TestAccount::new(Pubkey::new_unique());
+        let mut config = TestAccount::config(&program_id, paused.clone());
+        let mut pending = TestAccount::new(Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        // After unpause the guard passes (and the unset merkle root is rejected next)
+        for expected in [YapError::Paused, YapError::NotInitialized] {
+            {
+                let accounts = [
+                    user.info(),
+                    ata.info(),
+                    status.info(),
+                    config.info(),
+                    pending.info(),
+                    mint.info(),
+                    token_program.info(),
+                    system_program.info(),
+                    rent.info(),
+                ];
+                let err = process(&program_id, &accounts, 100, vec![]).unwrap_err();
+                assert_eq!(err, expected.into());
+            }
+
+            config.set_config(Config {
+                paused: false,
+                ..paused.clone()
+            });
+        }
+    }
+    use crate::utils::merkle::NODE_PREFIX;
+    use solana_program::keccak;
+
+    fn hash_sorted(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        keccak::hashv(&[&[NODE_PREFIX], left, right]).to_bytes()
+    }
+
+    fn packed_token_account(owner: Pubkey, mint: Pubkey) -> Vec<u8> {
+        let account = TokenAccount {
+            mint,
+            owner,
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        let mut data = vec![0u8; TokenAccount::LEN];
+        TokenAccount::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_destination_override_owned_by_destination_owner() {
+        let vault_owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let data = packed_token_account(vault_owner, mint);
+        assert!(validate_token_account(&data, &vault_owner, &mint).is_ok());
+    }
+
+    #[test]
+    fn test_destination_override_rejects_wrong_owner_or_mint() {
+        let vault_owner = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let wrong_owner = packed_token_account(Pubkey::new_unique(), mint);
+        let err = validate_token_account(&wrong_owner, &vault_owner, &mint).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+
+        let wrong_mint = packed_token_account(vault_owner, Pubkey::new_unique());
+        let err = validate_token_account(&wrong_mint, &vault_owner, &mint).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        let err = validate_token_account(&[0u8; TokenAccount::LEN], &vault_owner, &mint)
+            .unwrap_err();
+        assert_eq!(err, YapError::UninitializedTokenAccount.into());
+    }
+
+    fn user_ata(user: &Pubkey, token_program: &Pubkey, mint: &Pubkey) -> Pubkey {
+        Pubkey::find_program_address(
+            &[user.as_ref(), token_program.as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        )
+        .0
+    }
+
+    #[test]
+    fn test_strict_ata_check_rejects_reassigned_owner() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = user_ata(&user, &spl_token::id(), &mint);
+
+        // Correct derivation, but the account's owner field was changed
+        let mut data = packed_token_account(Pubkey::new_unique(), mint);
+        let mut lamports = 0;
+        let token_program = spl_token::id();
+        let account = AccountInfo::new(
+            &ata,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &token_program,
+            false,
+        );
+
+        let mut config = Config {
+            addresses: Addresses {
+                mint,
+                token_program: spl_token::id(),
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+        assert!(validate_user_ata(&config, &user, &account).is_ok());
+
+        config.claims.strict_ata_check = true;
+        let err = validate_user_ata(&config, &user, &account).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_strict_ata_check_accepts_user_owned_ata() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata = user_ata(&user, &spl_token::id(), &mint);
+
+        let mut data = packed_token_account(user, mint);
+        let mut lamports = 0;
+        let token_program = spl_token::id();
+        let account = AccountInfo::new(
+            &ata,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &token_program,
+            false,
+        );
+
+        let config = Config {
+            addresses: Addresses {
+                mint,
+                token_program: spl_token::id(),
+                ..Addresses::default()
+            },
+            claims: ClaimRules {
+                strict_ata_check: true,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+        assert!(validate_user_ata(&config, &user, &account).is_ok());
+    }
+
+    #[test]
+    fn test_user_ata_derived_with_configured_token_program() {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let ata_2022 = user_ata(&user, &TOKEN_2022_PROGRAM_ID, &mint);
+
+        let mut data = packed_token_account(user, mint);
+        let mut lamports = 0;
+        let account = AccountInfo::new(
+            &ata_2022,
+            false,
+            true,
+            &mut lamports,
+            &mut data,
+            &TOKEN_2022_PROGRAM_ID,
+            false,
+        );
+
+        let mut config = Config {
+            addresses: Addresses {
+                mint,
+                token_program: TOKEN_2022_PROGRAM_ID,
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+        assert!(validate_user_ata(&config, &user, &account).is_ok());
+
+        // The same wallet's SPL Token ATA is a different address
+        config.addresses.token_program = spl_token::id();
+        let err = validate_user_ata(&config, &user, &account).unwrap_err();
+        assert_eq!(err, YapError::InvalidTokenAccount.into());
+    }
+
+    /// Config, user, and the user's ATA (empty) for `prepare_user_ata`
+    fn ata_fixture() -> (Config, TestAccount, TestAccount) {
+        let user = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let config = Config {
+            addresses: Addresses {
+                mint,
+                token_program: spl_token::id(),
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+        let ata = TestAccount::new(user_ata(&user, &spl_token::id(), &mint));
+        (config, TestAccount::signer(user), ata)
+    }
+
+    #[test]
+    fn test_missing_ata_created_through_associated_token_program() {
+        let (config, mut user, mut ata) = ata_fixture();
+        let mut mint = TestAccount::new(config.addresses.mint);
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut ata_program = TestAccount::new(ASSOCIATED_TOKEN_PROGRAM_ID);
+
+        let (user, ata, mint) = (user.info(), ata.info(), mint.info());
+        let (token_program, system_program) = (token_program.info(), system_program.info());
+        let err = prepare_user_ata(
+            &config,
+            &user,
+            &user,
+            &ata,
+            &mint,
+            &token_program,
+            &system_program,
+            None,
+        )
+        .unwrap_err();
+        assert_eq!(err, YapError::TokenAccountMissing.into());
+
+        let ata_program = ata_program.info();
+        assert!(prepare_user_ata(
+            &config,
+            &user,
+            &user,
+            &ata,
+            &mint,
+            &token_program,
+            &system_program,
+            Some(&ata_program),
+        )
+        .is_ok());
+
+        // The ATA is still created under the configured token program only
+        let mut token_2022 = TestAccount::new(TOKEN_2022_PROGRAM_ID);
+        let err = prepare_user_ata(
+            &config,
+            &user,
+            &user,
+            &ata,
+            &mint,
+            &token_2022.info(),
+            &system_program,
+            Some(&ata_program),
+        )
+        .unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_existing_ata_must_hold_configured_mint_and_belong_to_user() {
+        let (config, mut user, mut ata) = ata_fixture();
+        let mut mint = TestAccount::new(config.addresses.mint);
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let user_key = user.key;
+        ata.owner = spl_token::id();
+
+        // Checked on every claim, not just with strict_ata_check
+        let cases = [
+            (packed_token_account(user_key, config.addresses.mint), None),
+            (
+                packed_token_account(user_key, Pubkey::new_unique()),
+                Some(YapError::InvalidMint),
+            ),
+            (
+                packed_token_account(Pubkey::new_unique(), config.addresses.mint),
+                Some(YapError::InvalidOwner),
+            ),
+        ];
+        for (data, expected) in cases {
+            ata.data = data;
+            let user = user.info();
+            let result = prepare_user_ata(
+                &config,
+                &user,
+                &user,
+                &ata.info(),
+                &mint.info(),
+                &token_program.info(),
+                &system_program.info(),
+                None,
+            );
+            match expected {
+                None => assert!(result.is_ok()),
+                Some(error) => assert_eq!(result.unwrap_err(), error.into()),
+            }
+        }
+    }
+
+    #[test]
+    fn test_bonus_proof_held_to_bonus_tree_depth() {
+        let user = Pubkey::new_unique();
+        let bonus_leaf = compute_bonus_leaf(&[0; DOMAIN_SALT_LEN], &user, 50);
+        let mut config = Config::default();
+        config.distribution.bonus_root = hash_sorted(&bonus_leaf, &[7u8; 32]);
+        config.distribution.bonus_tree_depth = 1;
+        verify_bonus_proof(&config, &user, 50, &[[7u8; 32]]).unwrap();
+
+        // A root one level up, committed at depth 2, turns down the one-node proof
+        let node = hash_sorted(&bonus_leaf, &[7u8; 32]);
+        config.distribution.bonus_root = hash_sorted(&node, &[8u8; 32]);
+        config.distribution.bonus_tree_depth = 2;
+        verify_bonus_proof(&config, &user, 50, &[[7u8; 32], [8u8; 32]]).unwrap();
+        config.distribution.bonus_root = node;
+        assert!(matches!(
+            verify_bonus_proof(&config, &user, 50, &[[7u8; 32]]),
+            Err(YapError::ProofDepthMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_claim_before_encoded_expiry() {
+        let user = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let expiry_ts = 1_700_000_000;
+
+        let leaf = compute_leaf_with_expiry(&[0; DOMAIN_SALT_LEN], &user, 1_000, expiry_ts);
+        let sibling =
+            compute_leaf_with_expiry(&[0; DOMAIN_SALT_LEN], &other, 2_000, expiry_ts + 86_400);
+        let config = Config {
+            distribution: Distribution {
+                merkle_root: hash_sorted(&leaf, &sibling),
+                ..Distribution::default()
+            },
+            claims: ClaimRules {
+                leaf_has_expiry: true,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+
+        assert!(check_claim_expiry(expiry_ts, expiry_ts - 1).is_ok());
+        assert!(check_claim_expiry(expiry_ts, expiry_ts).is_ok());
+        assert!(verify_claim_proof(&config, &user, 1_000, Some(expiry_ts), &[sibling]).is_ok());
+
+        // The expiry is part of the leaf, so it can't be extended by the claimer
+        assert!(verify_claim_proof(&config, &user, 1_000, Some(expiry_ts + 1), &[sibling]).is_err());
+        // Plain claims are rejected while the expiring format is enabled
+        assert!(verify_claim_proof(&config, &user, 1_000, None, &[sibling]).is_err());
+    }
+
+    #[test]
+    fn test_claim_after_encoded_expiry() {
+        let expiry_ts = 1_700_000_000;
+
+        assert!(matches!(
+            check_claim_expiry(expiry_ts, expiry_ts + 1),
+            Err(YapError::ClaimExpired)
+        ));
+
+        // Expiring leaves are refused while the flag is off
+        let user = Pubkey::new_unique();
+        let leaf = compute_leaf_with_expiry(&[0; DOMAIN_SALT_LEN], &user, 1_000, expiry_ts);
+        let config = Config {
+            distribution: Distribution {
+                merkle_root: leaf,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        assert!(verify_claim_proof(&config, &user, 1_000, Some(expiry_ts), &[]).is_err());
+    }
+
+    #[test]
+    fn test_proof_built_with_other_salt_rejected() {
+        use crate::utils::merkle::{deployment_salt, MerkleTree};
+
+        let users: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let staging = deployment_salt(&Pubkey::new_unique());
+        let prod = deployment_salt(&Pubkey::new_unique());
+        let leaves: Vec<[u8; 32]> = users
+            .iter()
+            .map(|user| compute_salted_leaf(&staging, user, 1_000))
+            .collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+
+        let mut config = Config {
+            distribution: Distribution {
+                merkle_root: tree.root(),
+                ..Distribution::default()
+            },
+            claims: ClaimRules {
+                domain_salt: staging,
+                ..ClaimRules::default()
+            },
+            ..Config::default()
+        };
+        assert!(verify_claim_proof(&config, &users[1], 1_000, None, &tree.proof(1)).is_ok());
+
+        // The same tree and proof under another deployment's salt
+        config.claims.domain_salt = prod;
+        let err = verify_claim_proof(&config, &users[1], 1_000, None, &tree.proof(1)).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+
+        // ... or under no salt at all
+        config.claims.domain_salt = [0; DOMAIN_SALT_LEN];
+        let err = verify_claim_proof(&config, &users[1], 1_000, None, &tree.proof(1)).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+    }
+
+    #[test]
+    fn test_proof_depth_bounds() {
+        let mut config = Config::default();
+        assert!(check_proof_depth(&config, 0).is_ok());
+        assert!(matches!(
+            check_proof_depth(&config, MAX_PROOF_DEPTH + 1),
+            Err(YapError::ProofTooLong)
+        ));
+
+        config.claims.min_proof_depth = 3;
+        assert!(check_proof_depth(&config, 3).is_ok());
+        assert!(check_proof_depth(&config, MAX_PROOF_DEPTH).is_ok());
+        assert!(matches!(
+            check_proof_depth(&config, 2),
+            Err(YapError::ProofTooShort)
+        ));
+    }
+
+    #[test]
+    fn test_shallow_proof_rejected_when_minimum_set() {
+        let user = Pubkey::new_unique();
+
+        // Single-leaf tree: the leaf is the root and the proof is empty
+        let mut config = Config {
+            distribution: Distribution {
+                merkle_root: compute_leaf(&user, 1_000),
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        assert!(verify_claim_proof(&config, &user, 1_000, None, &[]).is_ok());
+
+        config.claims.min_proof_depth = 1;
+        let err = verify_claim_proof(&config, &user, 1_000, None, &[]).unwrap_err();
+        assert_eq!(err, YapError::ProofTooShort.into());
+    }
+
+    #[test]
+    fn test_proof_of_wrong_length_rejected_at_committed_depth() {
+        use crate::utils::merkle::MerkleTree;
+
+        let users: Vec<Pubkey> = (0..3).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = users.iter().map(|u| compute_leaf(u, 1_000)).collect();
+
+        // A builder that promotes the lone third leaf reaches the root one level early
+        let pair = hash_sorted(&leaves[0], &leaves[1]);
+        let mut config = Config::default();
+        config.rotate_root(hash_sorted(&pair, &leaves[2]), 2);
+        let short = [pair];
+        let err = verify_claim_proof(&config, &users[2], 1_000, None, &short).unwrap_err();
+        assert_eq!(err, YapError::ProofDepthMismatch.into());
+        assert!(
+            verify_claim_proof(&config, &users[0], 1_000, None, &[leaves[1], leaves[2]]).is_ok()
+        );
+
+        // The padded tree gives the third leaf a full-length proof instead
+        let tree = MerkleTree::from_leaves(&leaves);
+        config.rotate_root(tree.root(), tree.depth() as u8);
+        for (i, user) in users.iter().enumerate() {
+            let proof = tree.proof(i);
+            assert_eq!(proof.len(), 2);
+            assert!(verify_claim_proof(&config, user, 1_000, None, &proof).is_ok());
+        }
+
+        let mut long = tree.proof(0);
+        long.push([0u8; 32]);
+        let err = verify_claim_proof(&config, &users[0], 1_000, None, &long).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+    }
+
+    #[test]
+    fn test_rejected_proof_reports_length_and_depth() {
+        use crate::test_utils::capture_return_data;
+        use crate::utils::merkle::MerkleTree;
+
+        let users: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = users.iter().map(|u| compute_leaf(u, 1_000)).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let config = Config {
+            distribution: Distribution {
+                merkle_root: tree.root(),
+                tree_depth: 2,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+
+        // Truncated proof: wrong length, so it reaches no root
+        let data = capture_return_data(|| {
+            let err = verify_claim_proof(&config, &users[0], 1_000, None, &tree.proof(0)[..1])
+                .unwrap_err();
+            assert_eq!(err, YapError::InvalidProof.into());
+        });
+        assert_eq!(return_data::decode_proof_depth(&data), Some((1, 2)));
+
+        // Right length, wrong tree
+        let data = capture_return_data(|| {
+            let err =
+                verify_claim_proof(&config, &users[0], 1_000, None, &tree.proof(1)).unwrap_err();
+            assert_eq!(err, YapError::InvalidProof.into());
+        });
+        assert_eq!(return_data::decode_proof_depth(&data), Some((2, 2)));
+
+        // Nothing published when the proof verifies
+        let data = capture_return_data(|| {
+            verify_claim_proof(&config, &users[0], 1_000, None, &tree.proof(0)).unwrap();
+        });
+        assert!(data.is_empty());
+    }
+
+    #[test]
+    fn test_claims_verify_against_recent_roots_until_evicted() {
+        use crate::state::RECENT_ROOTS_LEN;
+
+        let user = Pubkey::new_unique();
+        let sibling = [9u8; 32];
+        // Entitlement grows by 1_000 with each round
+        let round_root = |round: u64| hash_sorted(&compute_leaf(&user, round * 1_000), &sibling);
+
+        let mut config = Config::default();
+        for round in 1..=3 {
+            config.rotate_root(round_root(round), 1);
+        }
+
+        // A proof built for round 2 still lands after round 3 is distributed
+        assert!(verify_claim_proof(&config, &user, 2_000, None, &[sibling]).is_ok());
+        assert!(verify_claim_proof(&config, &user, 3_000, None, &[sibling]).is_ok());
+
+        // Once RECENT_ROOTS_LEN newer roots have been pushed, round 2 is gone
+        for round in 4..=(3 + RECENT_ROOTS_LEN as u64) {
+            config.rotate_root(round_root(round), 1);
+        }
+        assert_eq!(config.claim_root_depth(&round_root(3)), Some(1));
+        let err = verify_claim_proof(&config, &user, 2_000, None, &[sibling]).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+
+        // Re-sending the current root doesn't evict anything
+        config.rotate_root(config.distribution.merkle_root, 1);
+        assert_eq!(config.claim_root_depth(&round_root(3)), Some(1));
+    }
+
+    #[test]
+    fn test_recent_root_proofs_checked_against_their_own_depth() {
+        let user = Pubkey::new_unique();
+        let sibling = [9u8; 32];
+        let shallow = hash_sorted(&compute_leaf(&user, 1_000), &sibling);
+        let deep = hash_sorted(
+            &hash_sorted(&compute_leaf(&user, 2_000), &sibling),
+            &sibling,
+        );
+
+        let mut config = Config::default();
+        config.rotate_root(shallow, 1);
+        config.rotate_root(deep, 2);
+        assert_eq!(config.distribution.tree_depth, 2);
+        assert_eq!(config.claim_root_depth(&shallow), Some(1));
+
+        // The grace-period proof is one node long, as its own tree was
+        assert!(verify_claim_proof(&config, &user, 1_000, None, &[sibling]).is_ok());
+        assert!(verify_claim_proof(&config, &user, 2_000, None, &[sibling; 2]).is_ok());
+
+        // Padding it out to the current depth reaches no accepted root
+        let padded = [sibling, sibling];
+        let err = verify_claim_proof(&config, &user, 1_000, None, &padded).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+
+        // An intermediate node of the deep tree reaches it, but too short
+        let node = hash_sorted(&compute_leaf(&user, 2_000), &sibling);
+        assert_eq!(
+            config.claim_root_depth(&hash_sorted(&node, &sibling)),
+            Some(2)
+        );
+        assert!(matches!(
+            check_tree_depth(2, 1),
+            Err(YapError::ProofDepthMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_claim_event_cumulative_totals_match_state() {
+        let user = Pubkey::new_unique();
+        let mut config = Config {
+            distribution: Distribution {
+                total_distributed: 10_000,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        let mut status = UserClaimStatus::default();
+
+        // (claimed now, cumulative bonus, transferred)
+        let claims = [(100, None, 100), (150, Some(20), 170), (150, Some(20), 150)];
+        for (claimed, bonus, transferred) in claims {
+            let event =
+                record_claim(&mut config, &mut status, &user, claimed, bonus, transferred).unwrap();
+
+            assert_eq!(event.amount, transferred);
+            assert_eq!(event.claimed_amount, status.claimed_amount);
+            assert_eq!(event.bonus_claimed_amount, status.bonus_claimed_amount);
+            assert_eq!(event.total_distributed, config.distribution.total_distributed);
+            assert_eq!(event.total_claimed, config.distribution.total_claimed);
+        }
+
+        assert_eq!(status.claimed_amount, 400);
+        assert_eq!(status.bonus_claimed_amount, 20);
+        assert_eq!(config.distribution.total_claimed, 420);
+    }
+
+    #[test]
+    fn test_early_bird_bonus_goes_to_first_claimer_only() {
+        let mut config = Config {
+            distribution: Distribution {
+                distribution_id: 1,
+                early_bird_bonus: 50,
+                early_bird_reserved: 50,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+
+        // First claimer of round 1 gets the reserve, the second gets nothing
+        assert_eq!(config.take_early_bird_bonus(), 50);
+        assert_eq!(config.take_early_bird_bonus(), 0);
+        assert_eq!(config.distribution.early_bird_reserved, 0);
+        assert_eq!(config.distribution.early_bird_round, 1);
+
+        // The next round re-arms it once Distribute has topped the reserve up
+        config.distribution.distribution_id = 2;
+        config.distribution.early_bird_reserved = 50;
+        assert_eq!(config.take_early_bird_bonus(), 50);
+        assert_eq!(config.take_early_bird_bonus(), 0);
+    }
+
+    #[test]
+    fn test_early_bird_needs_a_round() {
+        let mut config = Config {
+            distribution: Distribution {
+                early_bird_reserved: 50,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+
+        assert_eq!(config.take_early_bird_bonus(), 0);
+        assert_eq!(config.distribution.early_bird_reserved, 50);
+    }
 }
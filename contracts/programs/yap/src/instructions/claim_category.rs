@@ -0,0 +1,376 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::YapError,
+    events::{ClaimEvent, Event},
+    instructions::claim::{
+        check_entitlement, check_not_frozen, check_proof_depth, check_single_claim,
+        check_tree_depth, commit_claim, load_claim_config, load_or_create_claim_status,
+        resolve_fee_treasury, split_claim_fee, transfer_claim_fee, transfer_from_pending_claims,
+        validate_claim_sysvars, validate_user_ata,
+    },
+    state::{Config, UserClaimStatus, MAX_CATEGORIES},
+    utils::{
+        merkle::{compute_category_leaf, verify_proof},
+        return_data,
+    },
+};
+
+/// Claim a category allocation against `config.distribution.category_roots[category]`
+///
+/// Amounts are cumulative per category, tracked in
+/// `UserClaimStatus.category_claimed`, so each category is claimed
+/// independently of the main distribution and of the other categories.
+///
+/// Accounts:
+/// 0. `[signer, writable]` User claiming (pays for the UserClaimStatus PDA if new)
+/// 1. `[writable]` User's token account (ATA)
+/// 2. `[writable]` UserClaimStatus PDA
+/// 3. `[writable]` Config PDA - to update total_claimed
+/// 4. `[writable]` Pending claims token account
+/// 5. `[]` Mint (for transfer_checked validation)
+/// 6. `[]` Token program
+/// 7. `[]` System program
+/// 8. `[]` Rent sysvar
+/// 9. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+/// 10. `[writable]` (needs account 9) Treasury token account - required only
+///     while `claim_fee_bps` is set
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    category: u8,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let frozen_marker = account_info_iter.next();
+    let treasury = account_info_iter.next();
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    // Reject zero amount claims
+    if amount == 0 {
+        msg!("ClaimCategory: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let mut config = load_claim_config(program_id, config_info, pending_claims_info, mint_info)?;
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+    let treasury = resolve_fee_treasury(&config, treasury)?;
+
+    validate_user_ata(&config, user.key, user_token_account)?;
+
+    // Verify merkle proof against the category's root
+    verify_category_proof(&config, category, user.key, amount, &proof)?;
+
+    msg!(
+        "ClaimCategory: user={}, category={}, amount={}, proof verified",
+        user.key,
+        category,
+        amount
+    );
+
+    let mut user_claim_status = load_or_create_claim_status(
+        program_id,
+        &mut config,
+        user,
+        user.key,
+        user_claim_status_info,
+        system_program,
+        rent_info,
+    )?;
+
+    let claimable = record_category_claim(&mut config, &mut user_claim_status, category, amount)?;
+    let (paid, fee) = split_claim_fee(&config, claimable)?;
+    if fee > 0 {
+        msg!("ClaimCategory: fee {} to treasury, {} to user", fee, paid);
+    }
+
+    commit_claim(
+        &user_claim_status,
+        user_claim_status_info,
+        &config,
+        config_info,
+        || {
+            transfer_from_pending_claims(
+                &config,
+                config_info,
+                pending_claims_info,
+                mint_info,
+                user_token_account,
+                token_program,
+                paid,
+            )?;
+            transfer_claim_fee(
+                &config,
+                config_info,
+                pending_claims_info,
+                mint_info,
+                treasury,
+                token_program,
+                fee,
+            )
+        },
+    )?;
+
+    // `claimed_amount` reports the cumulative total for this category
+    ClaimEvent {
+        user: *user.key,
+        amount: claimable,
+        claimed_amount: amount,
+        bonus_claimed_amount: 0,
+        total_distributed: config.distribution.total_distributed,
+        total_claimed: config.distribution.total_claimed,
+    }
+    .emit()?;
+    return_data::set_amount(paid);
+
+    msg!("ClaimCategory: Successfully claimed {} tokens", paid);
+
+    Ok(())
+}
+
+/// Verify `(category, user, amount)` against that category's root
+fn verify_category_proof(
+    config: &Config,
+    category: u8,
+    user: &Pubkey,
+    amount: u64,
+    proof: &[[u8; 32]],
+) -> Result<(), YapError> {
+    check_proof_depth(config, proof.len())?;
+
+    let Some(root) = config.distribution.category_roots.get(category as usize) else {
+        msg!("ClaimCategory: Category must be 0-{}", MAX_CATEGORIES - 1);
+        return Err(YapError::InvalidInstruction);
+    };
+    if root == &[0u8; 32] {
+        msg!("ClaimCategory: Category {} has no root", category);
+        return Err(YapError::NotInitialized);
+    }
+
+    let leaf = compute_category_leaf(&config.claims.domain_salt, category, user, amount);
+    if !verify_proof(root, &leaf, proof) {
+        msg!("ClaimCategory: Invalid merkle proof");
+        return Err(YapError::InvalidProof);
+    }
+    check_tree_depth(
+        config.distribution.category_root_depths[category as usize],
+        proof.len(),
+    )?;
+
+    Ok(())
+}
+
+/// Raise the category's cumulative claim to `amount`, returning what is newly claimable
+fn record_category_claim(
+    config: &mut Config,
+    status: &mut UserClaimStatus,
+    category: u8,
+    amount: u64,
+) -> Result<u64, YapError> {
+    let claimed = &mut status.category_claimed[category as usize];
+    check_entitlement(amount, *claimed)?;
+    let claimable = amount - *claimed;
+    if claimable == 0 {
+        msg!(
+            "ClaimCategory: Nothing to claim, already claimed {} in category {}",
+            claimed,
+            category
+        );
+        return Err(YapError::AlreadyClaimed);
+    }
+    check_single_claim(config, claimable)?;
+
+    *claimed = amount;
+    config.distribution.total_claimed = config
+        .distribution
+        .total_claimed
+        .checked_add(claimable)
+        .ok_or(YapError::Overflow)?;
+
+    Ok(claimable)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::DOMAIN_SALT_LEN;
+    use crate::utils::merkle::{compute_leaf, MerkleTree};
+
+    #[test]
+    fn test_categories_claimed_independently() {
+        let user = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut config = Config::default();
+        let mut status = UserClaimStatus::default();
+
+        // Creators (0) and referrers (1) get separate trees
+        let creators = MerkleTree::from_leaves(&[
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 0, &user, 1_000),
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 0, &other, 50),
+        ]);
+        let referrers = MerkleTree::from_leaves(&[
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 1, &user, 300),
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 1, &other, 75),
+        ]);
+        config.distribution.category_roots[0] = creators.root();
+        config.distribution.category_roots[1] = referrers.root();
+
+        verify_category_proof(&config, 0, &user, 1_000, &creators.proof(0)).unwrap();
+        assert_eq!(
+            record_category_claim(&mut config, &mut status, 0, 1_000).unwrap(),
+            1_000
+        );
+
+        // Claiming creators doesn't touch referrers (or the main claim)
+        verify_category_proof(&config, 1, &user, 300, &referrers.proof(0)).unwrap();
+        assert_eq!(
+            record_category_claim(&mut config, &mut status, 1, 300).unwrap(),
+            300
+        );
+        assert_eq!(status.category_claimed, [1_000, 300, 0, 0]);
+        assert_eq!(status.claimed_amount, 0);
+        assert_eq!(config.distribution.total_claimed, 1_300);
+
+        // Each category is cumulative on its own
+        assert!(matches!(
+            record_category_claim(&mut config, &mut status, 0, 1_000),
+            Err(YapError::AlreadyClaimed)
+        ));
+        assert!(matches!(
+            record_category_claim(&mut config, &mut status, 0, 999),
+            Err(YapError::EntitlementDecreased)
+        ));
+        assert_eq!(
+            record_category_claim(&mut config, &mut status, 1, 450).unwrap(),
+            150
+        );
+    }
+
+    #[test]
+    fn test_category_leaf_bound_to_its_root() {
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+
+        // Unset and out-of-range categories
+        assert!(matches!(
+            verify_category_proof(&config, 2, &user, 100, &[]),
+            Err(YapError::NotInitialized)
+        ));
+        assert!(matches!(
+            verify_category_proof(&config, MAX_CATEGORIES as u8, &user, 100, &[]),
+            Err(YapError::InvalidInstruction)
+        ));
+
+        // A category 0 leaf can't be replayed against category 1, nor a main leaf
+        config.distribution.category_roots[0] =
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 0, &user, 100);
+        config.distribution.category_roots[1] =
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 0, &user, 100);
+        verify_category_proof(&config, 0, &user, 100, &[]).unwrap();
+        assert!(matches!(
+            verify_category_proof(&config, 1, &user, 100, &[]),
+            Err(YapError::InvalidProof)
+        ));
+        config.distribution.category_roots[0] = compute_leaf(&user, 100);
+        assert!(matches!(
+            verify_category_proof(&config, 0, &user, 100, &[]),
+            Err(YapError::InvalidProof)
+        ));
+    }
+
+    #[test]
+    fn test_category_proof_held_to_category_depth() {
+        let user = Pubkey::new_unique();
+        let leaves = [
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 2, &user, 100),
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 2, &Pubkey::new_unique(), 50),
+            compute_category_leaf(&[0; DOMAIN_SALT_LEN], 2, &Pubkey::new_unique(), 25),
+        ];
+        let tree = MerkleTree::from_leaves(&leaves);
+        let mut config = Config::default();
+        config.distribution.category_roots[2] = tree.root();
+        config.distribution.category_root_depths[2] = tree.depth() as u8;
+        verify_category_proof(&config, 2, &user, 100, &tree.proof(0)).unwrap();
+
+        // A lone-leaf root reached with no proof at all, committed at depth 1
+        config.distribution.category_roots[2] = leaves[0];
+        config.distribution.category_root_depths[2] = 1;
+        assert!(matches!(
+            verify_category_proof(&config, 2, &user, 100, &[]),
+            Err(YapError::ProofDepthMismatch)
+        ));
+
+        // Another category's depth doesn't apply
+        config.distribution.category_root_depths[2] = 0;
+        config.distribution.category_root_depths[3] = 1;
+        verify_category_proof(&config, 2, &user, 100, &[]).unwrap();
+    }
+
+    #[test]
+    fn test_category_claim_needs_treasury_while_fee_set() {
+        use crate::test_utils::TestAccount;
+        use solana_program::program_error::ProgramError;
+
+        let program_id = Pubkey::new_unique();
+        let user_key = Pubkey::new_unique();
+        let mut stored = Config::default();
+        stored.distribution.merkle_root = [1; 32];
+        stored.addresses.pending_claims = Pubkey::new_unique();
+        stored.addresses.mint = Pubkey::new_unique();
+        stored.addresses.treasury = Pubkey::new_unique();
+        stored.claims.claim_fee_bps = 100;
+
+        let mut user = TestAccount::signer(user_key);
+        let mut user_ata = TestAccount::new(Pubkey::new_unique());
+        let mut status = TestAccount::new(Pubkey::new_unique());
+        let mut config = TestAccount::config(&program_id, stored.clone());
+        let mut pending_claims = TestAccount::new(stored.addresses.pending_claims);
+        let mut mint = TestAccount::new(stored.addresses.mint);
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+        let mut frozen_marker = TestAccount::new(Pubkey::new_unique());
+        let mut stray = TestAccount::new(Pubkey::new_unique());
+        let mut accounts = vec![
+            user.info(),
+            user_ata.info(),
+            status.info(),
+            config.info(),
+            pending_claims.info(),
+            mint.info(),
+            token_program.info(),
+            system_program.info(),
+            rent.info(),
+            frozen_marker.info(),
+        ];
+
+        // The fee has to go somewhere: no treasury, or the wrong one, fails
+        let err = process(&program_id, &accounts, 0, 100, vec![]).unwrap_err();
+        assert_eq!(err, ProgramError::NotEnoughAccountKeys);
+        accounts.push(stray.info());
+        let err = process(&program_id, &accounts, 0, 100, vec![]).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+    }
+}
@@ -0,0 +1,231 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::SysvarSerialize,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{
+    error::YapError,
+    instructions::claim::validate_claim_sysvars,
+    state::{ClaimDelegate, CLAIM_DELEGATE_DISCRIMINATOR},
+    utils::account::close_account,
+};
+
+/// Let `delegate` claim on the signer's behalf, replacing any previous delegate
+///
+/// Accounts:
+/// 0. `[signer, writable]` User (pays for the ClaimDelegate PDA if new)
+/// 1. `[writable]` ClaimDelegate PDA
+/// 2. `[]` System program
+/// 3. `[]` Rent sysvar
+pub fn process_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    delegate: Pubkey,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    if delegate == Pubkey::default() || delegate == *user.key {
+        msg!("SetClaimDelegate: Invalid delegate {}", delegate);
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let (delegate_pda, bump) =
+        Pubkey::find_program_address(&[ClaimDelegate::SEED, user.key.as_ref()], program_id);
+    if delegate_info.key != &delegate_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    if delegate_info.data_is_empty() {
+        let space = ClaimDelegate::LEN;
+        let lamports = Rent::from_account_info(rent_info)?.minimum_balance(space);
+        invoke_signed(
+            &system_instruction::create_account(
+                user.key,
+                delegate_info.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[user.clone(), delegate_info.clone(), system_program.clone()],
+            &[&[ClaimDelegate::SEED, user.key.as_ref(), &[bump]]],
+        )?;
+    } else if delegate_info.owner != program_id {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    let marker = ClaimDelegate {
+        discriminator: CLAIM_DELEGATE_DISCRIMINATOR,
+        user: *user.key,
+        delegate,
+        bump,
+    };
+    marker.serialize(&mut &mut delegate_info.data.borrow_mut()[..])?;
+
+    msg!("SetClaimDelegate: {} may claim for {}", delegate, user.key);
+
+    Ok(())
+}
+
+/// Remove the signer's claim delegate, refunding the PDA's rent
+///
+/// Accounts:
+/// 0. `[signer, writable]` User
+/// 1. `[writable]` ClaimDelegate PDA
+pub fn process_revoke(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let delegate_info = next_account_info(account_info_iter)?;
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let marker = load_claim_delegate(program_id, user.key, delegate_info)?;
+    close_account(delegate_info, user)?;
+
+    msg!(
+        "RevokeClaimDelegate: {} no longer claims for {}",
+        marker.delegate,
+        user.key
+    );
+
+    Ok(())
+}
+
+/// Verify `delegate` signed and is the delegate `user` set in their ClaimDelegate PDA
+pub(crate) fn check_claim_delegate(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    delegate: &AccountInfo,
+    delegate_info: &AccountInfo,
+) -> ProgramResult {
+    if !delegate.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let marker = load_claim_delegate(program_id, user, delegate_info)?;
+    if marker.delegate != *delegate.key {
+        msg!(
+            "Claim: {} is not the claim delegate of {}",
+            delegate.key,
+            user
+        );
+        return Err(YapError::Unauthorized.into());
+    }
+    Ok(())
+}
+
+/// Load `user`'s ClaimDelegate PDA; `NotInitialized` if none is set
+fn load_claim_delegate(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    delegate_info: &AccountInfo,
+) -> Result<ClaimDelegate, ProgramError> {
+    let (delegate_pda, _) =
+        Pubkey::find_program_address(&[ClaimDelegate::SEED, user.as_ref()], program_id);
+    if delegate_info.key != &delegate_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    if delegate_info.data_is_empty() {
+        msg!("Claim: {} has no claim delegate", user);
+        return Err(YapError::NotInitialized.into());
+    }
+    if delegate_info.owner != program_id {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    let marker = ClaimDelegate::try_from_slice(&delegate_info.data.borrow())?;
+    if !marker.is_valid() || marker.user != *user {
+        return Err(YapError::InvalidDiscriminator.into());
+    }
+    Ok(marker)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAccount;
+
+    fn marker_account(program_id: &Pubkey, user: &Pubkey, delegate: &Pubkey) -> TestAccount {
+        let (pda, bump) =
+            Pubkey::find_program_address(&[ClaimDelegate::SEED, user.as_ref()], program_id);
+        let marker = ClaimDelegate {
+            discriminator: CLAIM_DELEGATE_DISCRIMINATOR,
+            user: *user,
+            delegate: *delegate,
+            bump,
+        };
+        TestAccount {
+            owner: *program_id,
+            data: borsh::to_vec(&marker).unwrap(),
+            ..TestAccount::new(pda)
+        }
+    }
+
+    #[test]
+    fn test_authorized_delegate_accepted() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let delegate_key = Pubkey::new_unique();
+        let mut delegate = TestAccount::signer(delegate_key);
+        let mut marker = marker_account(&program_id, &user, &delegate_key);
+
+        check_claim_delegate(&program_id, &user, &delegate.info(), &marker.info()).unwrap();
+    }
+
+    #[test]
+    fn test_unauthorized_delegate_rejected() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let delegate_key = Pubkey::new_unique();
+        let mut marker = marker_account(&program_id, &user, &delegate_key);
+
+        // Someone other than the stored delegate
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let err =
+            check_claim_delegate(&program_id, &user, &stranger.info(), &marker.info()).unwrap_err();
+        assert_eq!(err, YapError::Unauthorized.into());
+
+        // The right key, but not signing
+        let mut unsigned = TestAccount::new(delegate_key);
+        let err =
+            check_claim_delegate(&program_id, &user, &unsigned.info(), &marker.info()).unwrap_err();
+        assert_eq!(err, YapError::Unauthorized.into());
+
+        // A marker set by another wallet doesn't carry over
+        let other = Pubkey::new_unique();
+        let mut delegate = TestAccount::signer(delegate_key);
+        let err = check_claim_delegate(&program_id, &other, &delegate.info(), &marker.info())
+            .unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+
+        // Revoked (closed) marker
+        let mut revoked = TestAccount::new(marker.key);
+        let err = check_claim_delegate(&program_id, &user, &delegate.info(), &revoked.info())
+            .unwrap_err();
+        assert_eq!(err, YapError::NotInitialized.into());
+    }
+}
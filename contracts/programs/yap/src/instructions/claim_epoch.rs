@@ -0,0 +1,300 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::SysvarSerialize,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{
+    error::YapError,
+    events::{ClaimEvent, Event},
+    instructions::claim::{
+        check_not_frozen, check_proof_depth, check_single_claim, check_tree_depth,
+        load_claim_config, resolve_fee_treasury, split_claim_fee, transfer_claim_fee,
+        transfer_from_pending_claims, validate_claim_sysvars, validate_user_ata,
+    },
+    state::{Config, EpochClaimStatus, EPOCH_CLAIM_DISCRIMINATOR},
+    utils::merkle::{compute_epoch_leaf, verify_proof},
+};
+
+/// Claim a single distribution's allocation, independent of other rounds
+///
+/// Unlike `Claim`, amounts are per distribution rather than cumulative, so a
+/// smaller later drop is never blocked by an earlier larger one. The round
+/// must still be in the config's round history.
+///
+/// Accounts:
+/// 0. `[signer, writable]` User claiming (pays for receipt PDA)
+/// 1. `[writable]` User's token account (ATA)
+/// 2. `[writable]` EpochClaimStatus PDA
+/// 3. `[writable]` Config PDA - to update total_claimed
+/// 4. `[writable]` Pending claims token account
+/// 5. `[]` Mint (for transfer_checked validation)
+/// 6. `[]` Token program
+/// 7. `[]` System program
+/// 8. `[]` Rent sysvar
+/// 9. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+/// 10. `[writable]` (needs account 9) Treasury token account - required only
+///     while `claim_fee_bps` is set
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    distribution_id: u64,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let epoch_claim_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let frozen_marker = account_info_iter.next();
+    let treasury = account_info_iter.next();
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    // Reject zero amount claims
+    if amount == 0 {
+        msg!("ClaimEpoch: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let mut config = load_claim_config(program_id, config_info, pending_claims_info, mint_info)?;
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+    let treasury = resolve_fee_treasury(&config, treasury)?;
+
+    validate_user_ata(&config, user.key, user_token_account)?;
+
+    // Verify merkle proof against that distribution's root
+    verify_epoch_proof(&config, distribution_id, user.key, amount, &proof)?;
+    check_single_claim(&config, amount)?;
+
+    msg!(
+        "ClaimEpoch: user={}, distribution_id={}, amount={}, proof verified",
+        user.key,
+        distribution_id,
+        amount
+    );
+
+    // The receipt PDA existing means this distribution was already claimed
+    let (epoch_claim_pda, epoch_claim_bump) = Pubkey::find_program_address(
+        &[
+            EpochClaimStatus::SEED,
+            user.key.as_ref(),
+            &distribution_id.to_le_bytes(),
+        ],
+        program_id,
+    );
+    if epoch_claim_info.key != &epoch_claim_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if !epoch_claim_info.data_is_empty() {
+        msg!("ClaimEpoch: Distribution {} already claimed", distribution_id);
+        return Err(YapError::AlreadyClaimed.into());
+    }
+
+    let rent = Rent::from_account_info(rent_info)?;
+    let space = EpochClaimStatus::LEN;
+    invoke_signed(
+        &system_instruction::create_account(
+            user.key,
+            epoch_claim_info.key,
+            rent.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[
+            user.clone(),
+            epoch_claim_info.clone(),
+            system_program.clone(),
+        ],
+        &[&[
+            EpochClaimStatus::SEED,
+            user.key.as_ref(),
+            &distribution_id.to_le_bytes(),
+            &[epoch_claim_bump],
+        ]],
+    )?;
+
+    let epoch_claim = EpochClaimStatus {
+        discriminator: EPOCH_CLAIM_DISCRIMINATOR,
+        distribution_id,
+        amount,
+        bump: epoch_claim_bump,
+    };
+    epoch_claim.serialize(&mut &mut epoch_claim_info.data.borrow_mut()[..])?;
+
+    config.distribution.total_claimed = config
+        .distribution
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(YapError::Overflow)?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    let (paid, fee) = split_claim_fee(&config, amount)?;
+    if fee > 0 {
+        msg!("ClaimEpoch: fee {} to treasury, {} to user", fee, paid);
+    }
+
+    // Effects are persisted first, as in `commit_claim`
+    transfer_from_pending_claims(
+        &config,
+        config_info,
+        pending_claims_info,
+        mint_info,
+        user_token_account,
+        token_program,
+        paid,
+    )?;
+    transfer_claim_fee(
+        &config,
+        config_info,
+        pending_claims_info,
+        mint_info,
+        treasury,
+        token_program,
+        fee,
+    )?;
+
+    // Epoch claims aren't cumulative, so the event reports this round's amount
+    ClaimEvent {
+        user: *user.key,
+        amount,
+        claimed_amount: amount,
+        bonus_claimed_amount: 0,
+        total_distributed: config.distribution.total_distributed,
+        total_claimed: config.distribution.total_claimed,
+    }
+    .emit()?;
+
+    msg!("ClaimEpoch: Successfully claimed {} tokens", paid);
+
+    Ok(())
+}
+
+/// Verify `(distribution_id, user, amount)` against that round's recorded root
+fn verify_epoch_proof(
+    config: &Config,
+    distribution_id: u64,
+    user: &Pubkey,
+    amount: u64,
+    proof: &[[u8; 32]],
+) -> Result<(), YapError> {
+    check_proof_depth(config, proof.len())?;
+
+    let (root, tree_depth) = config.round_root(distribution_id).ok_or_else(|| {
+        msg!(
+            "ClaimEpoch: Distribution {} not in round history",
+            distribution_id
+        );
+        YapError::DistributionNotFound
+    })?;
+
+    let leaf = compute_epoch_leaf(&config.claims.domain_salt, distribution_id, user, amount);
+    if !verify_proof(&root, &leaf, proof) {
+        msg!("ClaimEpoch: Invalid merkle proof");
+        return Err(YapError::InvalidProof);
+    }
+    check_tree_depth(tree_depth, proof.len())?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{DOMAIN_SALT_LEN, ROUND_HISTORY_LEN};
+    use crate::utils::merkle::NODE_PREFIX;
+    use solana_program::keccak;
+
+    fn hash_sorted(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        keccak::hashv(&[&[NODE_PREFIX], left, right]).to_bytes()
+    }
+
+    /// Simulate `Distribute` bumping the id and recording the round
+    fn distribute(config: &mut Config, root: [u8; 32]) -> u64 {
+        config.distribution.distribution_id += 1;
+        config.distribution.merkle_root = root;
+        config.record_round(root, 1, 0, 0);
+        config.distribution.distribution_id
+    }
+
+    #[test]
+    fn test_independent_epochs() {
+        let user = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+        let mut config = Config::default();
+
+        // Week 1: large drop
+        let leaf_1 = compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 1, &user, 1_000);
+        let sibling_1 = compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 1, &other, 50);
+        let id_1 = distribute(&mut config, hash_sorted(&leaf_1, &sibling_1));
+
+        // Week 2: smaller, independent drop
+        let leaf_2 = compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 2, &user, 200);
+        let sibling_2 = compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 2, &other, 75);
+        let id_2 = distribute(&mut config, hash_sorted(&leaf_2, &sibling_2));
+
+        assert_eq!((id_1, id_2), (1, 2));
+        // Both remain claimable, each against its own root
+        assert!(verify_epoch_proof(&config, 1, &user, 1_000, &[sibling_1]).is_ok());
+        assert!(verify_epoch_proof(&config, 2, &user, 200, &[sibling_2]).is_ok());
+
+        // A leaf can't be replayed against a different distribution
+        assert!(matches!(
+            verify_epoch_proof(&config, 2, &user, 1_000, &[sibling_1]),
+            Err(YapError::InvalidProof)
+        ));
+    }
+
+    #[test]
+    fn test_epoch_leaf_binds_distribution_id() {
+        let user = Pubkey::new_unique();
+        assert_ne!(
+            compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 1, &user, 100),
+            compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 2, &user, 100)
+        );
+        assert_ne!(
+            compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 1, &user, 100),
+            crate::utils::merkle::compute_leaf(&user, 100)
+        );
+    }
+
+    #[test]
+    fn test_evicted_epoch_not_claimable() {
+        let user = Pubkey::new_unique();
+        let mut config = Config::default();
+
+        let leaf = compute_epoch_leaf(&[0; DOMAIN_SALT_LEN], 1, &user, 500);
+        distribute(&mut config, leaf);
+        for _ in 0..ROUND_HISTORY_LEN {
+            distribute(&mut config, [9u8; 32]);
+        }
+
+        assert!(matches!(
+            verify_epoch_proof(&config, 1, &user, 500, &[]),
+            Err(YapError::DistributionNotFound)
+        ));
+        assert!(matches!(
+            verify_epoch_proof(&config, 0, &user, 500, &[]),
+            Err(YapError::DistributionNotFound)
+        ));
+    }
+}
@@ -0,0 +1,452 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::SysvarSerialize,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{
+    error::YapError,
+    events::{ClaimEvent, Event},
+    instructions::{
+        claim::{
+            check_not_frozen, check_proof_depth, check_single_claim, check_tree_depth,
+            load_claim_config, resolve_fee_treasury, split_claim_fee, transfer_claim_fee,
+            transfer_from_pending_claims, validate_claim_sysvars, validate_user_ata,
+        },
+        distribute::verify_updater_signers,
+    },
+    state::{ClaimBitmap, Config, CLAIM_BITMAP_DISCRIMINATOR},
+    utils::merkle::{compute_indexed_leaf, verify_proof},
+};
+
+/// Create the claimed-bit account for an indexed distribution (merkle updater only)
+///
+/// For a one-shot drop to `capacity` known recipients: one bit per index
+/// instead of a PDA per wallet. The distribution must be in the round history.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Merkle updater (pays for the bitmap)
+/// 1. `[]` Config PDA
+/// 2. `[writable]` ClaimBitmap PDA
+/// 3. `[]` System program
+/// 4. `[]` Rent sysvar
+/// 5. `[signer]` (with an updater set) Co-signers from `updater_keys`
+pub fn process_init_bitmap(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    distribution_id: u64,
+    capacity: u32,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let updater = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let bitmap_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let co_signers = account_info_iter.as_slice();
+
+    // Verify updater is signer
+    if !updater.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    if capacity == 0 || capacity > ClaimBitmap::MAX_CAPACITY {
+        msg!(
+            "InitClaimBitmap: Capacity must be 1-{}",
+            ClaimBitmap::MAX_CAPACITY
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    // Verify caller is authorized merkle updater (or a quorum of the updater set)
+    verify_updater_signers(&config.roles, updater, co_signers)?;
+
+    if config.round_root(distribution_id).is_none() {
+        msg!(
+            "InitClaimBitmap: Distribution {} not in round history",
+            distribution_id
+        );
+        return Err(YapError::DistributionNotFound.into());
+    }
+
+    let (bitmap_pda, bitmap_bump) = Pubkey::find_program_address(
+        &[ClaimBitmap::SEED, &distribution_id.to_le_bytes()],
+        program_id,
+    );
+    if bitmap_info.key != &bitmap_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if !bitmap_info.data_is_empty() {
+        return Err(YapError::AlreadyInitialized.into());
+    }
+
+    let rent = Rent::from_account_info(rent_info)?;
+    let space = ClaimBitmap::space(capacity);
+    invoke_signed(
+        &system_instruction::create_account(
+            updater.key,
+            bitmap_info.key,
+            rent.minimum_balance(space),
+            space as u64,
+            program_id,
+        ),
+        &[updater.clone(), bitmap_info.clone(), system_program.clone()],
+        &[&[
+            ClaimBitmap::SEED,
+            &distribution_id.to_le_bytes(),
+            &[bitmap_bump],
+        ]],
+    )?;
+
+    let bitmap = ClaimBitmap {
+        discriminator: CLAIM_BITMAP_DISCRIMINATOR,
+        distribution_id,
+        capacity,
+        bump: bitmap_bump,
+    };
+    bitmap.serialize(&mut &mut bitmap_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "InitClaimBitmap: distribution_id={}, capacity={} ({} bytes)",
+        distribution_id,
+        capacity,
+        space
+    );
+
+    Ok(())
+}
+
+/// Claim a fixed-index allocation of an indexed distribution
+///
+/// Leaf: keccak256(0x00 || "YAP_INDEX_V1" || salt || index || wallet || amount),
+/// verified against that round's root. Each index pays out once, tracked by
+/// one bit in the distribution's ClaimBitmap.
+///
+/// Accounts:
+/// 0. `[signer]` User claiming
+/// 1. `[writable]` User's token account (ATA)
+/// 2. `[writable]` ClaimBitmap PDA
+/// 3. `[writable]` Config PDA - to update total_claimed
+/// 4. `[writable]` Pending claims token account
+/// 5. `[]` Mint (for transfer_checked validation)
+/// 6. `[]` Token program
+/// 7. `[]` The user's FrozenWallet PDA - required only while any wallet is frozen
+/// 8. `[writable]` (needs account 7) Treasury token account - required only
+///    while `claim_fee_bps` is set
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    distribution_id: u64,
+    index: u32,
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let bitmap_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let frozen_marker = account_info_iter.next();
+    let treasury = account_info_iter.next();
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    // Reject zero amount claims
+    if amount == 0 {
+        msg!("ClaimIndexed: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let mut config = load_claim_config(program_id, config_info, pending_claims_info, mint_info)?;
+    check_not_frozen(program_id, &config, user.key, frozen_marker)?;
+    let treasury = resolve_fee_treasury(&config, treasury)?;
+
+    validate_user_ata(&config, user.key, user_token_account)?;
+
+    verify_indexed_proof(&config, distribution_id, index, user.key, amount, &proof)?;
+    check_single_claim(&config, amount)?;
+
+    msg!(
+        "ClaimIndexed: user={}, distribution_id={}, index={}, amount={}, proof verified",
+        user.key,
+        distribution_id,
+        index,
+        amount
+    );
+
+    let bitmap = load_bitmap(program_id, bitmap_info, distribution_id)?;
+    bitmap
+        .claim(&mut bitmap_info.data.borrow_mut(), index)
+        .inspect_err(|_| {
+            msg!(
+                "ClaimIndexed: Index {} already claimed or out of range",
+                index
+            )
+        })?;
+
+    config.distribution.total_claimed = config
+        .distribution
+        .total_claimed
+        .checked_add(amount)
+        .ok_or(YapError::Overflow)?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    let (paid, fee) = split_claim_fee(&config, amount)?;
+    if fee > 0 {
+        msg!("ClaimIndexed: fee {} to treasury, {} to user", fee, paid);
+    }
+
+    // Effects are persisted first, as in `commit_claim`
+    transfer_from_pending_claims(
+        &config,
+        config_info,
+        pending_claims_info,
+        mint_info,
+        user_token_account,
+        token_program,
+        paid,
+    )?;
+    transfer_claim_fee(
+        &config,
+        config_info,
+        pending_claims_info,
+        mint_info,
+        treasury,
+        token_program,
+        fee,
+    )?;
+
+    // Indexed claims aren't cumulative, so the event reports this allocation
+    ClaimEvent {
+        user: *user.key,
+        amount,
+        claimed_amount: amount,
+        bonus_claimed_amount: 0,
+        total_distributed: config.distribution.total_distributed,
+        total_claimed: config.distribution.total_claimed,
+    }
+    .emit()?;
+
+    msg!("ClaimIndexed: Successfully claimed {} tokens", paid);
+
+    Ok(())
+}
+
+/// Verify `(index, user, amount)` against the recorded root of `distribution_id`
+fn verify_indexed_proof(
+    config: &Config,
+    distribution_id: u64,
+    index: u32,
+    user: &Pubkey,
+    amount: u64,
+    proof: &[[u8; 32]],
+) -> Result<(), YapError> {
+    check_proof_depth(config, proof.len())?;
+
+    let (root, tree_depth) = config.round_root(distribution_id).ok_or_else(|| {
+        msg!(
+            "ClaimIndexed: Distribution {} not in round history",
+            distribution_id
+        );
+        YapError::DistributionNotFound
+    })?;
+
+    let leaf = compute_indexed_leaf(&config.claims.domain_salt, index, user, amount);
+    if !verify_proof(&root, &leaf, proof) {
+        msg!("ClaimIndexed: Invalid merkle proof");
+        return Err(YapError::InvalidProof);
+    }
+    check_tree_depth(tree_depth, proof.len())?;
+
+    Ok(())
+}
+
+/// Load the ClaimBitmap PDA of `distribution_id`
+fn load_bitmap(
+    program_id: &Pubkey,
+    bitmap_info: &AccountInfo,
+    distribution_id: u64,
+) -> Result<ClaimBitmap, YapError> {
+    let (bitmap_pda, _) = Pubkey::find_program_address(
+        &[ClaimBitmap::SEED, &distribution_id.to_le_bytes()],
+        program_id,
+    );
+    if bitmap_info.key != &bitmap_pda {
+        return Err(YapError::InvalidPda);
+    }
+    if bitmap_info.owner != program_id {
+        msg!(
+            "ClaimIndexed: No bitmap for distribution {}",
+            distribution_id
+        );
+        return Err(YapError::NotInitialized);
+    }
+
+    let bitmap = ClaimBitmap::load(&bitmap_info.data.borrow())
+        .map_err(|_| YapError::InvalidDiscriminator)?;
+    if !bitmap.is_valid() || bitmap.distribution_id != distribution_id {
+        return Err(YapError::InvalidDiscriminator);
+    }
+    Ok(bitmap)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::DOMAIN_SALT_LEN;
+    use crate::test_utils::TestAccount;
+    use crate::utils::merkle::{compute_leaf, MerkleTree};
+
+    fn bitmap_account(program_id: &Pubkey, distribution_id: u64, capacity: u32) -> TestAccount {
+        let (pda, bump) = Pubkey::find_program_address(
+            &[ClaimBitmap::SEED, &distribution_id.to_le_bytes()],
+            program_id,
+        );
+        let mut data = vec![0u8; ClaimBitmap::space(capacity)];
+        ClaimBitmap {
+            discriminator: CLAIM_BITMAP_DISCRIMINATOR,
+            distribution_id,
+            capacity,
+            bump,
+        }
+        .serialize(&mut &mut data[..])
+        .unwrap();
+        TestAccount {
+            owner: *program_id,
+            data,
+            ..TestAccount::new(pda)
+        }
+    }
+
+    #[test]
+    fn test_same_index_claims_once_and_indices_are_independent() {
+        let program_id = Pubkey::new_unique();
+        let mut account = bitmap_account(&program_id, 3, 20);
+        let info = account.info();
+        let bitmap = load_bitmap(&program_id, &info, 3).unwrap();
+        assert_eq!(info.data_len(), ClaimBitmap::HEADER_LEN + 3);
+
+        bitmap.claim(&mut info.data.borrow_mut(), 9).unwrap();
+        assert!(matches!(
+            bitmap.claim(&mut info.data.borrow_mut(), 9),
+            Err(YapError::AlreadyClaimed)
+        ));
+
+        // Neighbours in the same byte and other bytes are unaffected
+        for index in [8, 10, 0, 19] {
+            bitmap.claim(&mut info.data.borrow_mut(), index).unwrap();
+        }
+        assert!(matches!(
+            bitmap.claim(&mut info.data.borrow_mut(), 20),
+            Err(YapError::InvalidInstruction)
+        ));
+
+        // Claimed bits persist across loads
+        let reloaded = load_bitmap(&program_id, &info, 3).unwrap();
+        assert!(matches!(
+            reloaded.claim(&mut info.data.borrow_mut(), 8),
+            Err(YapError::AlreadyClaimed)
+        ));
+        reloaded.claim(&mut info.data.borrow_mut(), 11).unwrap();
+    }
+
+    #[test]
+    fn test_init_bitmap_needs_updater_quorum() {
+        let program_id = Pubkey::new_unique();
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let mut config = Config::default();
+        config.roles.updater_keys[..2].copy_from_slice(&keys);
+        config.roles.updater_threshold = 2;
+        config.roles.merkle_updater = Pubkey::new_unique();
+        let legacy_key = config.roles.merkle_updater;
+        let mut config = TestAccount::config(&program_id, config);
+
+        let mut bitmap = TestAccount::new(Pubkey::new_unique());
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        for (signer, expected) in [
+            (keys[0], YapError::UpdaterThresholdNotMet),
+            (legacy_key, YapError::Unauthorized),
+        ] {
+            let mut updater = TestAccount::signer(signer);
+            let accounts = [
+                updater.info(),
+                config.info(),
+                bitmap.info(),
+                system_program.info(),
+                rent.info(),
+            ];
+            assert_eq!(
+                process_init_bitmap(&program_id, &accounts, 1, 8).unwrap_err(),
+                expected.into()
+            );
+        }
+    }
+
+    #[test]
+    fn test_bitmap_bound_to_its_distribution() {
+        let program_id = Pubkey::new_unique();
+        let mut account = bitmap_account(&program_id, 3, 8);
+        assert!(matches!(
+            load_bitmap(&program_id, &account.info(), 4),
+            Err(YapError::InvalidPda)
+        ));
+
+        // Truncated data no longer matches the recorded capacity
+        account.data.pop();
+        assert!(matches!(
+            load_bitmap(&program_id, &account.info(), 3),
+            Err(YapError::InvalidDiscriminator)
+        ));
+    }
+
+    #[test]
+    fn test_indexed_proof_verifies_against_round_root() {
+        let users: Vec<Pubkey> = (0..5).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = users
+            .iter()
+            .enumerate()
+            .map(|(i, user)| compute_indexed_leaf(&[0; DOMAIN_SALT_LEN], i as u32, user, 100))
+            .collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+
+        let mut config = Config::default();
+        config.distribution.distribution_id = 1;
+        config.record_round(tree.root(), tree.depth() as u8, 0, 500);
+
+        for (i, user) in users.iter().enumerate() {
+            let proof = tree.proof(i);
+            assert!(verify_indexed_proof(&config, 1, i as u32, user, 100, &proof).is_ok());
+        }
+
+        // The index is part of the leaf, and indexed leaves aren't claim leaves
+        assert!(matches!(
+            verify_indexed_proof(&config, 1, 1, &users[0], 100, &tree.proof(0)),
+            Err(YapError::InvalidProof)
+        ));
+        assert_ne!(leaves[0], compute_leaf(&users[0], 100));
+        assert!(matches!(
+            verify_indexed_proof(&config, 2, 0, &users[0], 100, &tree.proof(0)),
+            Err(YapError::DistributionNotFound)
+        ));
+    }
+}
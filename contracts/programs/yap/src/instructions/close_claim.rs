@@ -0,0 +1,214 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::YapError,
+    instructions::claim::verify_current_claim_proof,
+    state::{Config, UserClaimStatus},
+    utils::account::close_account,
+};
+
+/// Close a fully claimed UserClaimStatus PDA and return its rent to the user
+///
+/// The user proves their current cumulative entitlement; the account can only
+/// be closed once `claimed_amount` equals it. Closing resets the wallet's
+/// cumulative counter, so the merkle updater must only include entitlement
+/// earned after the close in the wallet's leaves for later roots.
+///
+/// Accounts:
+/// 0. `[signer, writable]` User (receives the rent)
+/// 1. `[writable]` UserClaimStatus PDA
+/// 2. `[writable]` Config PDA - to release the claim account slot
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    // Verify UserClaimStatus PDA belongs to the signer
+    let user_claim_status =
+        UserClaimStatus::try_load(program_id, user.key, user_claim_status_info)?;
+
+    // Prove the entitlement under the current root (a replaced root could
+    // understate it), then require it to be fully consumed
+    verify_current_claim_proof(&config, user.key, amount, None, &proof)?;
+    ensure_fully_claimed(&config, &user_claim_status, amount)?;
+
+    let lamports = close_account(user_claim_status_info, user)?;
+
+    config.claims.total_claim_accounts = config.claims.total_claim_accounts.saturating_sub(1);
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "CloseUserClaim: user={}, claimed={}, reclaimed {} lamports",
+        user.key,
+        user_claim_status.claimed_amount,
+        lamports
+    );
+
+    Ok(())
+}
+
+/// Allow closing only when nothing is left to claim under the current roots
+///
+/// A bonus campaign blocks closing, since a fresh status would reset
+/// `bonus_claimed_amount` and let the bonus be claimed again.
+fn ensure_fully_claimed(
+    config: &Config,
+    status: &UserClaimStatus,
+    proven_amount: u64,
+) -> Result<(), YapError> {
+    if status.claimed_amount != proven_amount {
+        msg!(
+            "CloseUserClaim: Claimed {} of {}, claim the rest first",
+            status.claimed_amount,
+            proven_amount
+        );
+        return Err(YapError::ClaimNotFullyConsumed);
+    }
+
+    if config.distribution.bonus_root != [0u8; 32] {
+        msg!("CloseUserClaim: Bonus distribution active");
+        return Err(YapError::ClaimNotFullyConsumed);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::claim::{record_claim, verify_claim_proof},
+        state::{Distribution, USER_CLAIM_DISCRIMINATOR},
+        utils::merkle::{compute_leaf, verify_proof, NODE_PREFIX},
+    };
+    use solana_program::keccak;
+
+    fn hash_sorted(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        keccak::hashv(&[&[NODE_PREFIX], left, right]).to_bytes()
+    }
+
+    fn status(claimed_amount: u64) -> UserClaimStatus {
+        UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount,
+            ..UserClaimStatus::default()
+        }
+    }
+
+    #[test]
+    fn test_close_requires_full_claim() {
+        let config = Config::default();
+
+        assert!(ensure_fully_claimed(&config, &status(1_000), 1_000).is_ok());
+        assert!(matches!(
+            ensure_fully_claimed(&config, &status(400), 1_000),
+            Err(YapError::ClaimNotFullyConsumed)
+        ));
+        // Proving a lower, stale entitlement doesn't count either
+        assert!(matches!(
+            ensure_fully_claimed(&config, &status(1_000), 400),
+            Err(YapError::ClaimNotFullyConsumed)
+        ));
+
+        let config = Config {
+            distribution: Distribution {
+                bonus_root: [1u8; 32],
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        assert!(matches!(
+            ensure_fully_claimed(&config, &status(1_000), 1_000),
+            Err(YapError::ClaimNotFullyConsumed)
+        ));
+    }
+
+    #[test]
+    fn test_close_then_reclaim_against_new_root() {
+        let user = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        // Round A: fully claim 1_000, then close
+        let leaf_a = compute_leaf(&user, 1_000);
+        let sibling_a = compute_leaf(&other, 5_000);
+        let mut config = Config {
+            distribution: Distribution {
+                merkle_root: hash_sorted(&leaf_a, &sibling_a),
+                total_distributed: 6_000,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        let mut closed = status(0);
+        record_claim(&mut config, &mut closed, &user, 1_000, None, 1_000).unwrap();
+        closed.total_burned = 250;
+        assert!(verify_current_claim_proof(&config, &user, 1_000, None, &[sibling_a]).is_ok());
+        assert!(ensure_fully_claimed(&config, &closed, 1_000).is_ok());
+
+        // Round B: new root only carries entitlement earned since the close
+        let leaf_b = compute_leaf(&user, 300);
+        let sibling_b = compute_leaf(&other, 7_000);
+        config.distribution.merkle_root = hash_sorted(&leaf_b, &sibling_b);
+        config.distribution.total_distributed += 2_300;
+        assert!(verify_proof(
+            &config.distribution.merkle_root,
+            &leaf_b,
+            &[sibling_b]
+        ));
+
+        // Re-created status starts from a clean slate
+        let mut reopened = status(0);
+        let event = record_claim(&mut config, &mut reopened, &user, 300, None, 300).unwrap();
+        assert_eq!(event.claimed_amount, 300);
+        assert_eq!(reopened.total_burned, 0);
+        assert_eq!(reopened.bonus_claimed_amount, 0);
+        assert_eq!(config.distribution.total_claimed, 1_300);
+    }
+
+    #[test]
+    fn test_close_rejects_replaced_root_proof() {
+        let user = Pubkey::new_unique();
+        let other = Pubkey::new_unique();
+
+        // Round A entitles the user to 1_000, which they claim in full
+        let leaf_a = compute_leaf(&user, 1_000);
+        let sibling_a = compute_leaf(&other, 5_000);
+        let mut config = Config::default();
+        config.rotate_root(hash_sorted(&leaf_a, &sibling_a), 1);
+
+        // Round B raises it to 1_600, and round A's root moves to recent_roots
+        let leaf_b = compute_leaf(&user, 1_600);
+        let sibling_b = compute_leaf(&other, 7_000);
+        config.rotate_root(hash_sorted(&leaf_b, &sibling_b), 1);
+
+        // Claims may still use the old proof, but closing on it would drop the 600
+        assert!(verify_claim_proof(&config, &user, 1_000, None, &[sibling_a]).is_ok());
+        assert!(ensure_fully_claimed(&config, &status(1_000), 1_000).is_ok());
+        assert_eq!(
+            verify_current_claim_proof(&config, &user, 1_000, None, &[sibling_a]).unwrap_err(),
+            YapError::InvalidProof.into()
+        );
+        assert!(verify_current_claim_proof(&config, &user, 1_600, None, &[sibling_b]).is_ok());
+    }
+}
@@ -0,0 +1,264 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+use spl_token_2022_interface::instruction as token_instruction;
+
+use crate::{
+    error::YapError,
+    instructions::admin::load_config_for_admin,
+    state::Config,
+    utils::{
+        account::close_account,
+        token::{unpack_mint, unpack_token_account, validate_token_program},
+    },
+};
+
+/// Tear down a devnet deployment so the same PDAs can be initialized again
+///
+/// Burns whatever the vault and pending claims hold, closes both token
+/// accounts and then the Config PDA, returning all rent to the admin. The mint
+/// and its metadata can't be closed and are taken over by the next
+/// `Initialize`.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Admin
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Mint
+/// 3. `[writable]` Vault PDA
+/// 4. `[writable]` Pending claims PDA
+/// 5. `[]` Token program
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let config = load_config_for_admin(program_id, admin, config_info)?;
+
+    if mint_info.key != &config.addresses.mint {
+        return Err(YapError::InvalidMint.into());
+    }
+    if vault_info.key != &config.addresses.vault
+        || pending_claims_info.key != &config.addresses.pending_claims
+    {
+        return Err(YapError::InvalidPda.into());
+    }
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    let mint_supply = unpack_mint(&mint_info.data.borrow())?.supply;
+    let vault_balance = unpack_token_account(&vault_info.data.borrow())?.amount;
+    let pending_balance = unpack_token_account(&pending_claims_info.data.borrow())?.amount;
+    check_supply_reconciles(&config, mint_supply, vault_balance, pending_balance)?;
+
+    for (account, balance) in [
+        (vault_info, vault_balance),
+        (pending_claims_info, pending_balance),
+    ] {
+        if balance > 0 {
+            invoke_signed(
+                &token_instruction::burn_checked(
+                    &config.addresses.token_program,
+                    account.key,
+                    mint_info.key,
+                    config_info.key,
+                    &[],
+                    balance,
+                    config.tokenomics.decimals,
+                )?,
+                &[
+                    account.clone(),
+                    mint_info.clone(),
+                    config_info.clone(),
+                    token_program.clone(),
+                ],
+                &[&[Config::SEED, &[config.bump]]],
+            )?;
+        }
+
+        invoke_signed(
+            &token_instruction::close_account(
+                &config.addresses.token_program,
+                account.key,
+                admin.key,
+                config_info.key,
+                &[],
+            )?,
+            &[
+                account.clone(),
+                admin.clone(),
+                config_info.clone(),
+                token_program.clone(),
+            ],
+            &[&[Config::SEED, &[config.bump]]],
+        )?;
+    }
+
+    close_account(config_info, admin)?;
+
+    msg!(
+        "CloseProgram: burned {} (vault {}, pending {}), closed config",
+        mint_supply,
+        vault_balance,
+        pending_balance
+    );
+
+    Ok(())
+}
+
+/// Only close when nothing is in circulation outside the program's custody
+///
+/// The mint supply must match `current_supply`, and the vault and pending
+/// claims together must hold all of it; tokens in user wallets or stakes
+/// would otherwise outlive the Config that accounts for them.
+fn check_supply_reconciles(
+    config: &Config,
+    mint_supply: u64,
+    vault_balance: u64,
+    pending_balance: u64,
+) -> Result<(), YapError> {
+    let held = vault_balance
+        .checked_add(pending_balance)
+        .ok_or(YapError::Overflow)?;
+    let current_supply = config.tokenomics.current_supply;
+    if mint_supply != current_supply || held != current_supply {
+        msg!(
+            "CloseProgram: supply {} (mint {}) but vault and pending hold {}",
+            current_supply,
+            mint_supply,
+            held
+        );
+        return Err(YapError::SupplyMismatch);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::initialize::{check_config_unclaimed, check_reusable_mint};
+    use crate::state::{Tokenomics, CONFIG_DISCRIMINATOR, DECIMALS, MINT_SEED};
+    use crate::test_utils::TestAccount;
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use solana_program::program_pack::Pack;
+    use spl_token::state::Mint;
+
+    /// Mirrors the runtime input layout `resize` relies on: the original data
+    /// length sits in the 4 bytes before the key.
+    #[repr(C)]
+    struct SerializedKey {
+        original_data_len: u32,
+        key: Pubkey,
+    }
+
+    /// Config data with the runtime's 8-byte length prefix in front
+    #[repr(C, align(8))]
+    struct SerializedData([u8; 8 + Config::LEN]);
+
+    fn config_with_supply(current_supply: u64) -> Config {
+        Config {
+            tokenomics: Tokenomics {
+                current_supply,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_refuses_unless_supply_reconciles() {
+        let config = config_with_supply(1_000);
+
+        check_supply_reconciles(&config, 1_000, 600, 400).unwrap();
+
+        // 100 tokens sit in a user wallet
+        assert!(matches!(
+            check_supply_reconciles(&config, 1_000, 600, 300),
+            Err(YapError::SupplyMismatch)
+        ));
+        // Config and mint disagree
+        assert!(matches!(
+            check_supply_reconciles(&config, 1_100, 700, 400),
+            Err(YapError::SupplyMismatch)
+        ));
+    }
+
+    #[test]
+    fn test_full_close_allows_reinitialize() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+        let (mint_pda, _) = Pubkey::find_program_address(&[MINT_SEED], &program_id);
+
+        // Live config: init refuses
+        let config = Config {
+            discriminator: CONFIG_DISCRIMINATOR,
+            ..config_with_supply(1_000)
+        };
+        let mut serialized = SerializedData([0u8; 8 + Config::LEN]);
+        let buffer = &mut serialized.0;
+        buffer[..8].copy_from_slice(&(Config::LEN as u64).to_le_bytes());
+        config.serialize(&mut &mut buffer[8..]).unwrap();
+        let config_key = SerializedKey {
+            original_data_len: Config::LEN as u32,
+            key: config_pda,
+        };
+        let mut config_lamports = 10_000_000u64;
+        let config_info = AccountInfo::new(
+            &config_key.key,
+            false,
+            true,
+            &mut config_lamports,
+            &mut buffer[8..],
+            &program_id,
+            false,
+        );
+        assert_eq!(
+            check_config_unclaimed(&config_info).unwrap_err(),
+            YapError::AlreadyInitialized.into()
+        );
+
+        // The mint still has tokens out: it can't be taken over yet
+        let mut mint_data = vec![0u8; Mint::LEN];
+        let mut mint_state = Mint {
+            mint_authority: Some(config_pda).into(),
+            supply: 1_000,
+            decimals: DECIMALS,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        Mint::pack(mint_state, &mut mint_data).unwrap();
+        let mut mint = TestAccount {
+            owner: spl_token::id(),
+            data: mint_data,
+            ..TestAccount::new(mint_pda)
+        };
+        assert_eq!(
+            check_reusable_mint(&config_pda, &spl_token::id(), &mint.info()).unwrap_err(),
+            YapError::InvalidMint.into()
+        );
+
+        // CloseProgram: custody burned to zero supply, config closed
+        check_supply_reconciles(&config, 1_000, 700, 300).unwrap();
+        mint_state.supply = 0;
+        Mint::pack(mint_state, &mut mint.data).unwrap();
+        let mut admin = TestAccount::signer(Pubkey::new_unique());
+        close_account(&config_info, &admin.info()).unwrap();
+
+        // Initialize now sees a blank config and an empty mint it can reuse
+        assert!(Config::try_from_slice(&config_info.data.borrow()).is_err());
+        check_config_unclaimed(&config_info).unwrap();
+        assert!(check_reusable_mint(&config_pda, &spl_token::id(), &mint.info()).unwrap());
+        assert_eq!(admin.lamports, 10_000_000);
+
+        // A brand-new deployment has no mint yet
+        let mut fresh = TestAccount::new(mint_pda);
+        assert!(!check_reusable_mint(&config_pda, &spl_token::id(), &fresh.info()).unwrap());
+    }
+}
@@ -1,50 +1,375 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
     entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
     msg,
-    program::invoke_signed,
-    program_pack::Pack,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
     pubkey::Pubkey,
-    sysvar::Sysvar,
+    sysvar::{self, Sysvar},
 };
-use spl_token::state::Account as TokenAccount;
+use spl_token_2022_interface::instruction as token_instruction;
 
 use crate::{
     error::YapError,
-    state::{Config, DECIMALS, SECONDS_PER_YEAR},
+    events::{DistributeEvent, Event},
+    state::{Config, Roles, MAX_CATEGORIES, MAX_MEMO_LEN, MAX_PROOF_DEPTH, MEMO_PROGRAM_ID},
+    utils::{
+        clock::{elapsed_since, rate_period, validate_clock_account},
+        return_data,
+        token::{unpack_mint, unpack_token_account, validate_token_program},
+        vesting::VestingSchedule,
+    },
 };
 
 /// Distribute tokens with time-based rate limiting
 ///
-/// Rate limit formula: available = (elapsed_seconds / SECONDS_PER_YEAR) * vault_balance
+/// Rate limit formula: available = (elapsed_seconds / rate_period_secs) * vault_balance
 ///
 /// This instruction:
-/// 1. Calculates available allocation based on time elapsed
+/// 1. Calculates available allocation based on time elapsed, plus any
+///    allocation left unspent by earlier rounds
 /// 2. Verifies amount <= available
 /// 3. Transfers amount from vault to pending_claims
-/// 4. Updates merkle_root and last_distribution_ts
+/// 4. Updates merkle_root, last_distribution_ts and the carried allocation
 ///
 /// Accounts:
 /// 0. `[signer]` Merkle updater
 /// 1. `[writable]` Config PDA
 /// 2. `[writable]` Vault token account
-/// 3. `[writable]` Pending claims token account
+/// 3. `[writable]` Pending claims token account, or `allowed_distribution_dest`
 /// 4. `[]` Mint
 /// 5. `[]` Token program
+/// 6. `[]` (optional) Clock sysvar - cross-checked against `Clock::get()`
+/// 7. `[signer]` (with an updater set) Co-signers from `updater_keys`, after the
+///    clock if one is passed. Account 0 must then be in the set too.
+///
+/// Claims against `merkle_root` must present a proof of exactly `tree_depth`
+/// nodes, so an intermediate node can't be passed off as a leaf.
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     amount: u64,
     merkle_root: [u8; 32],
+    tree_depth: u8,
+) -> ProgramResult {
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            tree_depth,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Run a queued distribution once its eta has passed
+///
+/// Called by `ExecuteDistribution`, the one path that stays open while a
+/// `distribution_timelock` is set.
+///
+/// Accounts: same as `process`
+pub(crate) fn process_queued(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    tree_depth: u8,
+) -> ProgramResult {
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            tree_depth,
+            queued: true,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Distribute tokens and label the transaction with an SPL Memo
+///
+/// Accounts: same as `process`, with the memo program before the optional clock:
+/// 6. `[]` SPL Memo program
+/// 7. `[]` (optional) Clock sysvar
+pub fn process_with_memo(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    memo: String,
+    tree_depth: u8,
+) -> ProgramResult {
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            memo: Some(memo),
+            tree_depth,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Distribute up to `amount`, reverting if fewer than `min_amount` tokens would move
+///
+/// The budget depends on the live vault balance, so a mint or another
+/// distribution landing first can shrink it. Instead of failing outright when
+/// `amount` is no longer available, this transfers whatever is available,
+/// provided that is at least `min_amount`.
+///
+/// Accounts: same as `process`
+pub fn process_with_min_amount(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    min_amount: u64,
+    tree_depth: u8,
+) -> ProgramResult {
+    if min_amount > amount {
+        msg!(
+            "Distribute: min_amount {} exceeds amount {}",
+            min_amount,
+            amount
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            min_amount: Some(min_amount),
+            tree_depth,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Distribute tokens to one of the `MAX_CATEGORIES` category roots
+///
+/// Categories (e.g. creators, referrers, stakers) are tracked independently
+/// of the main root and of each other, but draw on the same rate-limited
+/// budget. Only `category_roots[category]` is replaced: the main root, round
+/// history and `distribution_id` are left alone, and no early-bird top-up is
+/// made.
+///
+/// Accounts: same as `process`
+pub fn process_category(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    category: u8,
+    amount: u64,
+    merkle_root: [u8; 32],
+    tree_depth: u8,
+) -> ProgramResult {
+    if category as usize >= MAX_CATEGORIES {
+        msg!("Distribute: Category must be 0-{}", MAX_CATEGORIES - 1);
+        return Err(YapError::InvalidInstruction.into());
+    }
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            category: Some(category),
+            tree_depth,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Distribute tokens under a new root whose amounts vest instead of unlocking at once
+///
+/// Claims of this root pay out the vested share of the proven amount: nothing
+/// before `cliff_ts`, then linearly from now until `now + vesting_duration`.
+/// The schedule covers the whole cumulative leaf amount and lasts until the
+/// next main-root distribution, which replaces it (plain `Distribute` clears it).
+///
+/// Accounts: same as `process`
+pub fn process_with_vesting(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    cliff_ts: i64,
+    vesting_duration: i64,
+    tree_depth: u8,
+) -> ProgramResult {
+    validate_vesting(cliff_ts, vesting_duration)?;
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            vesting: Some((cliff_ts, vesting_duration)),
+            tree_depth,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Distribute tokens unless a distribution with `nonce` or a later one has landed
+///
+/// The operator numbers its distributions; a rebroadcast or retried
+/// transaction reuses its nonce and fails with `StaleDistributionNonce`
+/// instead of spending fresh time accrual a second time.
+///
+/// Accounts: same as `process`
+pub fn process_with_nonce(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    nonce: u64,
+    tree_depth: u8,
+) -> ProgramResult {
+    process_distribute(
+        program_id,
+        accounts,
+        amount,
+        merkle_root,
+        DistributeOptions {
+            nonce: Some(nonce),
+            tree_depth,
+            ..DistributeOptions::default()
+        },
+    )
+}
+
+/// Report the amount `Distribute` could move right now, without moving it
+///
+/// Runs the same interval check and budget calculation as `Distribute`
+/// against the live vault balance and `last_distribution_ts`, then publishes
+/// `available` via return data. With a `category_bps` split, that category's
+/// budget is reported instead, or for no category what the split leaves.
+/// Nothing is written and no signer is needed, so it also works while the
+/// program is paused. The
+/// early-bird top-up of a main-root round comes on top of this figure.
+///
+/// Accounts:
+/// 0. `[]` Config PDA
+/// 1. `[]` Vault token account
+pub fn process_simulate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    category: Option<u8>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let config_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+
+    if category.is_some_and(|category| category as usize >= MAX_CATEGORIES) {
+        msg!(
+            "SimulateDistribute: Category must be 0-{}",
+            MAX_CATEGORIES - 1
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    if vault_info.key != &config.addresses.vault {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    let elapsed = elapsed_since(now, config.timing.last_distribution_ts)?;
+    check_distribution_interval(elapsed, config.timing.min_distribution_interval)?;
+
+    let vault_balance = unpack_token_account(&vault_info.data.borrow())?.amount;
+    let (_, available) = distribution_budget(&config, elapsed, vault_balance, category)?;
+
+    msg!(
+        "SimulateDistribute: elapsed={}s, vault={}, available={}",
+        elapsed,
+        vault_balance,
+        available
+    );
+    return_data::set_amount(available);
+
+    Ok(())
+}
+
+/// Reject a tree depth outside 1..=MAX_PROOF_DEPTH; every published root commits to one
+pub(crate) fn validate_tree_depth(tree_depth: u8) -> Result<(), YapError> {
+    if tree_depth == 0 || tree_depth as usize > MAX_PROOF_DEPTH {
+        msg!("Distribute: Tree depth must be 1-{}", MAX_PROOF_DEPTH);
+        return Err(YapError::InvalidInstruction);
+    }
+    Ok(())
+}
+
+/// Reject negative parameters and a schedule that vests nothing
+fn validate_vesting(cliff_ts: i64, vesting_duration: i64) -> Result<(), YapError> {
+    if cliff_ts < 0 || vesting_duration < 0 || (cliff_ts == 0 && vesting_duration == 0) {
+        msg!(
+            "Distribute: Invalid vesting cliff_ts={} duration={}",
+            cliff_ts,
+            vesting_duration
+        );
+        return Err(YapError::InvalidInstruction);
+    }
+    Ok(())
+}
+
+/// What sets the distribute variants apart from plain `Distribute`
+#[derive(Debug, Default)]
+struct DistributeOptions {
+    /// Memo attached via the SPL Memo program
+    memo: Option<String>,
+    /// Proof length committed with the new root (1..=MAX_PROOF_DEPTH)
+    tree_depth: u8,
+    /// Makes `amount` an upper bound rather than exact
+    min_amount: Option<u64>,
+    /// Category root to replace instead of the main root
+    category: Option<u8>,
+    /// `(cliff_ts, vesting_duration)` for the new main root; `None` unlocks immediately
+    vesting: Option<(i64, i64)>,
+    /// Must exceed `last_distribution_nonce`, which it then replaces
+    nonce: Option<u64>,
+    /// Executing a `QueueDistribution` whose eta has passed
+    queued: bool,
+}
+
+fn process_distribute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    options: DistributeOptions,
 ) -> ProgramResult {
+    let DistributeOptions {
+        memo,
+        tree_depth,
+        min_amount,
+        category,
+        vesting,
+        nonce,
+        queued,
+    } = options;
+
+    validate_tree_depth(tree_depth)?;
+
     let account_info_iter = &mut accounts.iter();
 
     let updater = next_account_info(account_info_iter)?;
     let config_info = next_account_info(account_info_iter)?;
     let vault_info = next_account_info(account_info_iter)?;
-    let pending_claims_info = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
 
@@ -53,121 +378,1835 @@ pub fn process(
         return Err(YapError::Unauthorized.into());
     }
 
-    // Verify config PDA
-    let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], program_id);
-    if config_info.key != &config_pda {
-        return Err(YapError::InvalidPda.into());
-    }
+    // Validate memo and the memo program when labeling the distribution
+    let memo_program = match &memo {
+        Some(memo) => {
+            if memo.is_empty() || memo.len() > MAX_MEMO_LEN {
+                msg!("Distribute: Memo must be 1-{} bytes", MAX_MEMO_LEN);
+                return Err(YapError::InvalidMemo.into());
+            }
 
-    if config_info.owner != program_id {
-        return Err(YapError::InvalidOwner.into());
-    }
+            let memo_program = next_account_info(account_info_iter)?;
+            if *memo_program.key != MEMO_PROGRAM_ID {
+                msg!("Distribute: Invalid memo program");
+                return Err(YapError::InvalidOwner.into());
+            }
+            Some(memo_program)
+        }
+        None => None,
+    };
 
-    let mut config = Config::try_from_slice(&config_info.data.borrow())?;
+    let mut config = Config::try_load(program_id, config_info)?;
 
-    if !config.is_valid() {
-        return Err(YapError::InvalidDiscriminator.into());
-    }
+    // Emergency pause
+    config.ensure_not_paused()?;
 
-    // Verify caller is authorized merkle updater
-    if updater.key != &config.merkle_updater {
-        return Err(YapError::Unauthorized.into());
+    // Trailing accounts: the optional clock, then any updater co-signers
+    let trailing = account_info_iter.as_slice();
+    let (clock_info, co_signers) = match trailing.split_first() {
+        Some((clock_info, co_signers))
+            if config.roles.updater_threshold == 0 || clock_info.key == &sysvar::clock::ID =>
+        {
+            (Some(clock_info), co_signers)
+        }
+        _ => (None, trailing),
+    };
+
+    // Verify caller is authorized merkle updater (or a quorum of the updater set)
+    verify_updater_signers(&config.roles, updater, co_signers)?;
+
+    // With a timelock set, every distribution has to go through the queue
+    if !queued && config.timing.distribution_timelock > 0 {
+        msg!(
+            "Distribute: Timelock of {}s set; use QueueDistribution",
+            config.timing.distribution_timelock
+        );
+        return Err(YapError::DistributionTimelocked.into());
     }
 
-    // Verify vault
-    if vault_info.key != &config.vault {
-        return Err(YapError::InvalidPda.into());
+    // Exactly-once: a replayed or out-of-order nonce is rejected
+    if let Some(nonce) = nonce {
+        check_distribution_nonce(nonce, config.distribution.last_distribution_nonce)?;
+        config.distribution.last_distribution_nonce = nonce;
     }
 
-    // Verify pending_claims
-    if pending_claims_info.key != &config.pending_claims {
+    // Verify vault
+    if vault_info.key != &config.addresses.vault {
         return Err(YapError::InvalidPda.into());
     }
 
     // Verify mint
-    if mint_info.key != &config.mint {
+    if mint_info.key != &config.addresses.mint {
         return Err(YapError::InvalidMint.into());
     }
 
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    // Verify mint decimals before transfer_checked so a mismatch fails clearly
+    validate_mint_decimals(&mint_info.data.borrow(), config.tokenomics.decimals)?;
+
+    // pending_claims, or the whitelisted external destination
+    let external = validate_destination(&config, config_info.key, destination)?;
+
+    // Tokens paid out externally can't be claimed, so there is no root to publish
+    if external && merkle_root != [0; 32] {
+        msg!("Distribute: An external distribution takes an all-zero merkle root");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
     // Get current time
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
 
+    if let Some(clock_info) = clock_info {
+        validate_clock_account(clock_info, now)?;
+    }
+
     // Calculate time elapsed since last distribution
-    let elapsed = now.saturating_sub(config.last_distribution_ts);
+    let elapsed = elapsed_since(now, config.timing.last_distribution_ts)?;
+
+    // Bound how often the updater can distribute
+    check_distribution_interval(elapsed, config.timing.min_distribution_interval)?;
 
     // Get vault balance
-    let vault_account = TokenAccount::unpack(&vault_info.data.borrow())?;
+    let vault_account = unpack_token_account(&vault_info.data.borrow())?;
     let vault_balance = vault_account.amount;
 
-    // Calculate available allocation: (elapsed / SECONDS_PER_YEAR) * vault_balance
-    // Using u128 to prevent overflow
-    let available = (elapsed as u128)
-        .checked_mul(vault_balance as u128)
-        .unwrap_or(0)
-        .checked_div(SECONDS_PER_YEAR as u128)
-        .unwrap_or(0) as u64;
+    // Calculate available allocation: earmarked inflation plus carried budget
+    // plus (elapsed / rate_period_secs) * rest of vault
+    let (allocation, available) = distribution_budget(&config, elapsed, vault_balance, category)?;
 
     msg!(
-        "Distribute: elapsed={}s, vault={}, available={}, requested={}",
+        "Distribute: elapsed={}s, vault={}, undistributed_inflation={}, carried={}, available={}, requested={}",
         elapsed,
         vault_balance,
+        config.tokenomics.undistributed_inflation,
+        config.distribution.accrued_allocation,
         available,
         amount
     );
 
     // Verify amount doesn't exceed available allocation
-    if amount > available {
+    let amount = distribution_amount(amount, available, min_amount)?;
+
+    // Top up the early-bird reserve alongside the round (outside the rate limit);
+    // the reserve is held in pending_claims, so an external destination skips it
+    let early_bird_top_up = match category {
+        Some(_) => 0,
+        None if external => 0,
+        None => early_bird_top_up(&config),
+    };
+    let transfer_amount = amount
+        .checked_add(early_bird_top_up)
+        .ok_or(YapError::Overflow)?;
+    check_vault_balance(transfer_amount, vault_balance)?;
+
+    // Skip transfer if amount is 0 (no activity)
+    if transfer_amount > 0 {
         msg!(
-            "Distribute: Amount {} exceeds available {}",
+            "Distribute: Transferring {} (+{} early-bird) from vault to {}",
             amount,
-            available
+            early_bird_top_up,
+            if external {
+                "external destination"
+            } else {
+                "pending_claims"
+            }
         );
-        return Err(YapError::ExceedsDailyAllocation.into());
+
+        // Transfer from vault to pending_claims (or the external destination)
+        transfer_from_vault(
+            &config,
+            config_info,
+            vault_info,
+            mint_info,
+            destination,
+            token_program,
+            transfer_amount,
+        )?;
     }
 
-    // Skip transfer if amount is 0 (no activity)
-    if amount > 0 {
+    // Draw from earmarked inflation first, carry the unspent time budget forward
+    settle_category_budgets(&mut config, &allocation, category, amount);
+    draw_undistributed_inflation(&mut config, amount);
+    config.distribution.accrued_allocation = allocation.carry_forward(amount);
+    config.timing.last_distribution_ts = now;
+
+    publish_round(
+        &mut config,
+        external,
+        category,
+        merkle_root,
+        tree_depth,
+        vesting,
+        now,
+        amount,
+        early_bird_top_up,
+    )?;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    // Attach memo (shows up in explorers' memo field)
+    if let (Some(memo), Some(memo_program)) = (memo, memo_program) {
+        invoke(
+            &build_memo_instruction(&memo, updater.key),
+            &[updater.clone(), memo_program.clone()],
+        )?;
+    }
+
+    DistributeEvent {
+        merkle_updater: *updater.key,
+        amount,
+        merkle_root,
+        distribution_id: config.distribution.distribution_id,
+        timestamp: now,
+        total_distributed: config.distribution.total_distributed,
+    }
+    .emit()?;
+    return_data::set_amount(transfer_amount);
+
+    msg!("Distribute: Success! Distributed {} tokens", amount);
+
+    Ok(())
+}
+
+/// Publish a round's root, counters and vesting schedule
+///
+/// A category round only replaces its category root. A main round rotates
+/// the root, bumps `distribution_id`, records the round and replaces the
+/// vesting schedule. Either way the transfer counts towards
+/// `total_distributed` and restarts the claim window. An external
+/// distribution changes none of it: nothing was added to pending_claims.
+#[allow(clippy::too_many_arguments)]
+fn publish_round(
+    config: &mut Config,
+    external: bool,
+    category: Option<u8>,
+    merkle_root: [u8; 32],
+    tree_depth: u8,
+    vesting: Option<(i64, i64)>,
+    now: i64,
+    amount: u64,
+    early_bird_top_up: u64,
+) -> Result<(), YapError> {
+    if external {
+        return Ok(());
+    }
+    config.timing.claim_deadline_ts = claim_deadline(config.timing.claim_window, now);
+
+    match category {
+        Some(category) => {
+            let root = &mut config.distribution.category_roots[category as usize];
+            msg!(
+                "Distribute: category {} {:?}... -> {:?}...",
+                category,
+                &root[..4],
+                &merkle_root[..4]
+            );
+            *root = merkle_root;
+            config.distribution.category_root_depths[category as usize] = tree_depth;
+        }
+        None => {
+            msg!(
+                "Distribute: {:?}... -> {:?}...",
+                &config.distribution.merkle_root[..4],
+                &merkle_root[..4]
+            );
+            config.rotate_root(merkle_root, tree_depth);
+            config.distribution.distribution_id = config
+                .distribution
+                .distribution_id
+                .checked_add(1)
+                .ok_or(YapError::Overflow)?;
+            config.record_round(merkle_root, tree_depth, now, amount);
+            config.distribution.early_bird_reserved = config
+                .distribution
+                .early_bird_reserved
+                .checked_add(early_bird_top_up)
+                .ok_or(YapError::Overflow)?;
+
+            // A new root replaces the previous schedule
+            let schedule = match vesting {
+                Some((cliff_ts, duration)) => VestingSchedule {
+                    start_ts: now,
+                    cliff_ts,
+                    duration,
+                },
+                None => VestingSchedule::default(),
+            };
+            schedule.apply(&mut config.timing);
+        }
+    }
+
+    let transfer_amount = amount
+        .checked_add(early_bird_top_up)
+        .ok_or(YapError::Overflow)?;
+    config.distribution.total_distributed = config
+        .distribution
+        .total_distributed
+        .checked_add(transfer_amount)
+        .ok_or(YapError::Overflow)?;
+    Ok(())
+}
+
+/// Spendable budget for one distribution
+///
+/// Earmarked inflation is fully available. The rest of the vault accrues
+/// linearly over `rate_period_secs` on top of the budget carried forward from
+/// earlier rounds; both are capped at that part of the vault, so a shrinking
+/// vault also shrinks the carried budget.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct Allocation {
+    /// Undistributed inflation still backed by the vault
+    pub earmarked: u64,
+    /// Carried budget plus this period's time-based accrual
+    pub accrued: u64,
+}
+
+impl Allocation {
+    /// Computed in u128 with checked arithmetic throughout
+    ///
+    /// A negative `elapsed` is `ClockWentBackwards` and an overflow is
+    /// `Overflow`; neither is ever read as a zero budget. A period that isn't
+    /// positive is `InvalidInstruction`.
+    pub(crate) fn new(
+        elapsed: i64,
+        rate_period_secs: i64,
+        vault_balance: u64,
+        undistributed_inflation: u64,
+        accrued_allocation: u64,
+    ) -> Result<Self, YapError> {
+        let earmarked = undistributed_inflation.min(vault_balance);
+        let accruing = vault_balance - earmarked;
+
+        let elapsed = u128::try_from(elapsed).map_err(|_| YapError::ClockWentBackwards)?;
+        let time_based = elapsed
+            .checked_mul(accruing as u128)
+            .ok_or(YapError::Overflow)?
+            / rate_period(rate_period_secs)?;
+        let accrued = time_based
+            .checked_add(accrued_allocation as u128)
+            .ok_or(YapError::Overflow)?
+            .min(accruing as u128);
+
+        Ok(Self {
+            earmarked,
+            accrued: u64::try_from(accrued).map_err(|_| YapError::Overflow)?,
+        })
+    }
+
+    /// Total spendable this round
+    pub(crate) fn available(&self) -> u64 {
+        self.earmarked.saturating_add(self.accrued)
+    }
+
+    /// Each category's budget under `category_bps`
+    ///
+    /// A category keeps what it was credited in earlier rounds
+    /// (`category_accrued`) and gets its slice of whatever part of
+    /// `available()` hasn't been credited to any category yet. Each is capped
+    /// at `available()`, in case the vault shrank below the credited total.
+    pub(crate) fn category_budgets(
+        &self,
+        category_bps: &[u16; MAX_CATEGORIES],
+        category_accrued: &[u64; MAX_CATEGORIES],
+    ) -> [u64; MAX_CATEGORIES] {
+        let available = self.available();
+        let uncredited = available.saturating_sub(saturating_sum(category_accrued));
+        std::array::from_fn(|category| {
+            category_accrued[category]
+                .saturating_add(bps_slice(uncredited, category_bps, category))
+                .min(available)
+        })
+    }
+
+    /// Time-accrued budget left after distributing `amount` (earmarked first)
+    pub(crate) fn carry_forward(&self, amount: u64) -> u64 {
+        self.accrued.saturating_sub(amount.saturating_sub(self.earmarked))
+    }
+}
+
+/// `category`'s slice of `amount`, cut at cumulative bps boundaries
+///
+/// The slices add up to exactly `amount` and each is within one token of its
+/// exact share.
+fn bps_slice(amount: u64, category_bps: &[u16; MAX_CATEGORIES], category: usize) -> u64 {
+    let amount = amount as u128;
+    let boundary = |bps: u128| (amount * bps / Config::CATEGORY_BPS_TOTAL as u128) as u64;
+    let before: u128 = category_bps[..category]
+        .iter()
+        .map(|&bps| bps as u128)
+        .sum();
+    boundary(before + category_bps[category] as u128) - boundary(before)
+}
+
+fn saturating_sum(amounts: &[u64]) -> u64 {
+    amounts
+        .iter()
+        .fold(0u64, |sum, &amount| sum.saturating_add(amount))
+}
+
+/// Check that the distribution is signed by the merkle updater, or by
+/// `updater_threshold` distinct members of `updater_keys` when a set is configured
+pub(crate) fn verify_updater_signers<'a>(
+    roles: &Roles,
+    updater: &AccountInfo<'a>,
+    co_signers: &[AccountInfo<'a>],
+) -> Result<(), YapError> {
+    if roles.updater_threshold == 0 {
+        if updater.key != &roles.merkle_updater {
+            return Err(YapError::Unauthorized);
+        }
+        return Ok(());
+    }
+
+    let mut signers: Vec<&Pubkey> = Vec::with_capacity(1 + co_signers.len());
+    for signer in std::iter::once(updater).chain(co_signers) {
+        let in_set = *signer.key != Pubkey::default() && roles.updater_keys.contains(signer.key);
+        if !signer.is_signer || !in_set {
+            msg!("Distribute: {} is not a signing updater", signer.key);
+            return Err(YapError::Unauthorized);
+        }
+        if signers.contains(&signer.key) {
+            msg!("Distribute: Updater {} passed twice", signer.key);
+            return Err(YapError::DuplicateSigner);
+        }
+        signers.push(signer.key);
+    }
+
+    if signers.len() < roles.updater_threshold as usize {
         msg!(
-            "Distribute: Transferring {} from vault to pending_claims",
-            amount
+            "Distribute: {} of {} required updater signatures",
+            signers.len(),
+            roles.updater_threshold
         );
+        return Err(YapError::UpdaterThresholdNotMet);
+    }
+    Ok(())
+}
+
+/// The allocation a distribution `elapsed` seconds after the last one draws
+/// on, and how much of it the round may spend
+///
+/// With a `category_bps` split, a category round gets only its own budget
+/// (`Allocation::category_budgets`), and a round without a category only
+/// what no category has been credited with.
+fn distribution_budget(
+    config: &Config,
+    elapsed: i64,
+    vault_balance: u64,
+    category: Option<u8>,
+) -> Result<(Allocation, u64), YapError> {
+    let allocation = Allocation::new(
+        elapsed,
+        config.timing.rate_period_secs,
+        vault_balance,
+        config.tokenomics.undistributed_inflation,
+        config.distribution.accrued_allocation,
+    )?;
+    let distribution = &config.distribution;
+    if distribution.category_bps == [0; MAX_CATEGORIES] {
+        return Ok((allocation, allocation.available()));
+    }
+
+    let budgets =
+        allocation.category_budgets(&distribution.category_bps, &distribution.category_accrued);
+    let available = match category {
+        Some(category) => budgets[category as usize],
+        None => allocation
+            .available()
+            .saturating_sub(saturating_sum(&budgets)),
+    };
+    Ok((allocation, available))
+}
 
-        // Transfer from vault to pending_claims
-        invoke_signed(
-            &spl_token::instruction::transfer_checked(
-                &spl_token::id(),
-                vault_info.key,
-                mint_info.key,
-                pending_claims_info.key,
-                &config_pda,
-                &[],
+/// Credit each category its budget for this round, less what `category` spent
+///
+/// Without a split nothing is tracked. A round without a category only spends
+/// uncredited budget, so the categories' budgets are left whole.
+fn settle_category_budgets(
+    config: &mut Config,
+    allocation: &Allocation,
+    category: Option<u8>,
+    amount: u64,
+) {
+    let distribution = &mut config.distribution;
+    if distribution.category_bps == [0; MAX_CATEGORIES] {
+        return;
+    }
+
+    let mut budgets =
+        allocation.category_budgets(&distribution.category_bps, &distribution.category_accrued);
+    if let Some(category) = category {
+        let budget = &mut budgets[category as usize];
+        *budget = budget.saturating_sub(amount);
+    }
+    distribution.category_accrued = budgets;
+}
+
+/// Amount to distribute out of `available`
+///
+/// Without `min_amount` the request is all or nothing. With it, `amount` is
+/// clipped to what is available, and the distribution reverts only if that
+/// falls below `min_amount`.
+fn distribution_amount(
+    amount: u64,
+    available: u64,
+    min_amount: Option<u64>,
+) -> Result<u64, YapError> {
+    match min_amount {
+        None if amount > available => {
+            msg!(
+                "Distribute: Amount {} exceeds available {}",
                 amount,
-                DECIMALS,
-            )?,
-            &[
-                vault_info.clone(),
-                mint_info.clone(),
-                pending_claims_info.clone(),
-                config_info.clone(),
-                token_program.clone(),
-            ],
-            &[&[Config::SEED, &[config.bump]]],
+                available
+            );
+            Err(YapError::ExceedsDailyAllocation)
+        }
+        None => Ok(amount),
+        Some(min_amount) => {
+            let amount = amount.min(available);
+            if amount < min_amount {
+                msg!(
+                    "Distribute: Only {} available, below min_amount {}",
+                    amount,
+                    min_amount
+                );
+                return Err(YapError::BelowMinAmount);
+            }
+            Ok(amount)
+        }
+    }
+}
+
+/// A nonce must be strictly greater than the last one accepted
+fn check_distribution_nonce(nonce: u64, last_nonce: u64) -> Result<(), YapError> {
+    if nonce <= last_nonce {
+        msg!(
+            "Distribute: Nonce {} not above last accepted {}",
+            nonce,
+            last_nonce
+        );
+        return Err(YapError::StaleDistributionNonce);
+    }
+    Ok(())
+}
+
+/// Fail with a YAP error, rather than SPL's, when the vault can't cover the transfer
+fn check_vault_balance(transfer_amount: u64, vault_balance: u64) -> Result<(), YapError> {
+    if transfer_amount > vault_balance {
+        msg!(
+            "Distribute: Transfer {} exceeds vault balance {}",
+            transfer_amount,
+            vault_balance
+        );
+        return Err(YapError::InsufficientBalance);
+    }
+    Ok(())
+}
+
+/// End of the claim window opened by a distribution at `now` (0 = no deadline)
+pub(crate) fn claim_deadline(claim_window: i64, now: i64) -> i64 {
+    if claim_window == 0 {
+        return 0;
+    }
+    now.saturating_add(claim_window)
+}
+
+/// Reject a distribution less than `min_interval` seconds after the previous one
+fn check_distribution_interval(elapsed: i64, min_interval: i64) -> Result<(), YapError> {
+    if elapsed < min_interval {
+        msg!(
+            "Distribute: Only {}s since last distribution, minimum is {}s",
+            elapsed,
+            min_interval
+        );
+        return Err(YapError::DistributionTooSoon);
+    }
+    Ok(())
+}
+
+/// Consume earmarked inflation before the time-accrued budget
+fn draw_undistributed_inflation(config: &mut Config, amount: u64) {
+    let from_inflation = amount.min(config.tokenomics.undistributed_inflation);
+    config.tokenomics.undistributed_inflation -= from_inflation;
+}
+
+/// Amount needed to bring the early-bird reserve back up to `early_bird_bonus`
+///
+/// An unclaimed reserve carries over, so at most one bonus is held at a time.
+fn early_bird_top_up(config: &Config) -> u64 {
+    config
+        .distribution
+        .early_bird_bonus
+        .saturating_sub(config.distribution.early_bird_reserved)
+}
+
+/// Check account 3 and report whether it is the external destination
+///
+/// It must be pending_claims, validated as before, or the admin-whitelisted
+/// `allowed_distribution_dest`: a token account of our mint under the
+/// configured token program. Anything else is `InvalidPda`.
+fn validate_destination(
+    config: &Config,
+    config_pda: &Pubkey,
+    destination: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    if destination.key == &config.addresses.pending_claims {
+        // Defense in depth: pending_claims must be a config-owned account of our mint
+        validate_pending_claims_account(
+            &destination.data.borrow(),
+            config_pda,
+            &config.addresses.mint,
         )?;
+        return Ok(false);
+    }
+
+    let allowed = config.addresses.allowed_distribution_dest;
+    if allowed == Pubkey::default() || destination.key != &allowed {
+        msg!(
+            "Distribute: {} is not an allowed destination",
+            destination.key
+        );
+        return Err(YapError::InvalidPda.into());
+    }
+
+    if destination.owner != &config.addresses.token_program {
+        msg!("Distribute: Destination is not a token account");
+        return Err(YapError::InvalidOwner.into());
+    }
+    let account = unpack_token_account(&destination.data.borrow()).map_err(|_| {
+        msg!("Distribute: Destination could not be unpacked");
+        YapError::InvalidOwner
+    })?;
+    if account.mint != config.addresses.mint {
+        msg!(
+            "Distribute: Destination mint mismatch (expected {}, got {})",
+            config.addresses.mint,
+            account.mint
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+    // Held by the config PDA, the tokens would be stranded outside the vault
+    // and pending_claims instead of leaving the program
+    if account.owner == *config_pda {
+        msg!("Distribute: Destination is owned by the config PDA");
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    Ok(true)
+}
+
+/// Verify the pending_claims token account is owned by the config PDA and holds `mint`
+fn validate_pending_claims_account(
+    pending_claims_data: &[u8],
+    config_pda: &Pubkey,
+    mint: &Pubkey,
+) -> ProgramResult {
+    let pending_claims = unpack_token_account(pending_claims_data).map_err(|_| {
+        msg!("Distribute: Pending claims account could not be unpacked");
+        YapError::InvalidOwner
+    })?;
+
+    if pending_claims.owner != *config_pda {
+        msg!(
+            "Distribute: Pending claims owner mismatch (expected {}, got {})",
+            config_pda,
+            pending_claims.owner
+        );
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    if pending_claims.mint != *mint {
+        msg!(
+            "Distribute: Pending claims mint mismatch (expected {}, got {})",
+            mint,
+            pending_claims.mint
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+
+    Ok(())
+}
+
+/// Unpack the mint and confirm it still has the decimals recorded in config
+fn validate_mint_decimals(mint_data: &[u8], decimals: u8) -> ProgramResult {
+    let mint = unpack_mint(mint_data).map_err(|_| {
+        msg!("Distribute: Mint account could not be unpacked");
+        YapError::InvalidMint
+    })?;
+
+    if mint.decimals != decimals {
+        msg!(
+            "Distribute: Mint decimals mismatch (expected {}, got {})",
+            decimals,
+            mint.decimals
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+
+    Ok(())
+}
+
+/// Transfer `amount` out of the vault, signed by the config PDA
+fn transfer_from_vault<'a>(
+    config: &Config,
+    config_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    amount: u64,
+) -> ProgramResult {
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            &config.addresses.token_program,
+            vault_info.key,
+            mint_info.key,
+            destination.key,
+            config_info.key,
+            &[],
+            amount,
+            config.tokenomics.decimals,
+        )?,
+        &[
+            vault_info.clone(),
+            mint_info.clone(),
+            destination.clone(),
+            config_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[Config::SEED, &[config.bump]]],
+    )
+}
+
+/// Build an SPL Memo instruction signed by `signer`
+fn build_memo_instruction(memo: &str, signer: &Pubkey) -> Instruction {
+    Instruction {
+        program_id: MEMO_PROGRAM_ID,
+        accounts: vec![AccountMeta::new_readonly(*signer, true)],
+        data: memo.as_bytes().to_vec(),
+    }
+}
+
+/// Set the bonus merkle root and fund its payouts (merkle updater only)
+///
+/// Moves `funding` from the vault into pending_claims and adds it to
+/// `bonus_reserved`, which bonus claims draw down, so they can never spend
+/// the balance backing main-tree claims. Bonus leaves are cumulative, so
+/// `funding` is what the new root adds on top of the previous one. It comes
+/// out of the distribution budget like a `Distribute` (earmarked inflation
+/// first, the rest carried forward) without counting as a round, and must wait
+/// out `min_distribution_interval` like one. Bonus roots can't be queued, so
+/// they are rejected while a `distribution_timelock` is set, just as a plain
+/// `Distribute` is. Bonus proofs must be exactly `tree_depth` nodes long.
+///
+/// Accounts:
+/// 0. `[signer]` Merkle updater
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Vault token account
+/// 3. `[writable]` Pending claims token account
+/// 4. `[]` Mint
+/// 5. `[]` Token program
+/// 6. `[signer]` (with an updater set) Co-signers from `updater_keys`
+pub fn process_set_bonus_root(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    bonus_root: [u8; 32],
+    funding: u64,
+    tree_depth: u8,
+) -> ProgramResult {
+    validate_tree_depth(tree_depth)?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let updater = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let co_signers = account_info_iter.as_slice();
+
+    // Verify updater is signer
+    if !updater.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    // Verify caller is authorized merkle updater (or a quorum of the updater set)
+    verify_updater_signers(&config.roles, updater, co_signers)?;
+
+    // A bonus root moves funds like a round, and can't be queued
+    if config.timing.distribution_timelock > 0 {
+        msg!(
+            "SetBonusRoot: Timelock of {}s set; bonus roots are disabled",
+            config.timing.distribution_timelock
+        );
+        return Err(YapError::DistributionTimelocked.into());
+    }
+
+    if vault_info.key != &config.addresses.vault
+        || pending_claims_info.key != &config.addresses.pending_claims
+    {
+        return Err(YapError::InvalidPda.into());
+    }
+    validate_pending_claims_account(
+        &pending_claims_info.data.borrow(),
+        config_info.key,
+        &config.addresses.mint,
+    )?;
+    if mint_info.key != &config.addresses.mint {
+        return Err(YapError::InvalidMint.into());
     }
+    validate_token_program(token_program, &config.addresses.token_program)?;
+    validate_mint_decimals(&mint_info.data.borrow(), config.tokenomics.decimals)?;
 
-    // Update config
     msg!(
-        "Distribute: {:?}... -> {:?}...",
-        &config.merkle_root[..4],
-        &merkle_root[..4]
+        "SetBonusRoot: {:?}... -> {:?}..., funding {}",
+        &config.distribution.bonus_root[..4],
+        &bonus_root[..4],
+        funding
     );
 
-    config.merkle_root = merkle_root;
-    config.last_distribution_ts = now;
+    if funding > 0 {
+        let now = Clock::get()?.unix_timestamp;
+        let vault_balance = unpack_token_account(&vault_info.data.borrow())?.amount;
+        let allocation = bonus_funding_budget(&config, now, vault_balance, funding)?;
+
+        transfer_from_vault(
+            &config,
+            config_info,
+            vault_info,
+            mint_info,
+            pending_claims_info,
+            token_program,
+            funding,
+        )?;
+
+        settle_category_budgets(&mut config, &allocation, None, funding);
+        draw_undistributed_inflation(&mut config, funding);
+        config.distribution.accrued_allocation = allocation.carry_forward(funding);
+        config.timing.last_distribution_ts = now;
+        fund_bonus_reserve(&mut config, funding)?;
+    }
+
+    config.distribution.bonus_root = bonus_root;
+    config.distribution.bonus_tree_depth = tree_depth;
     config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
 
-    msg!("Distribute: Success! Distributed {} tokens", amount);
+    Ok(())
+}
 
+/// Budget for `funding` a bonus root at `now`, held to the same interval and
+/// accrual limits as a round
+fn bonus_funding_budget(
+    config: &Config,
+    now: i64,
+    vault_balance: u64,
+    funding: u64,
+) -> Result<Allocation, YapError> {
+    let elapsed = elapsed_since(now, config.timing.last_distribution_ts)?;
+    check_distribution_interval(elapsed, config.timing.min_distribution_interval)?;
+    let (allocation, available) = distribution_budget(config, elapsed, vault_balance, None)?;
+    distribution_amount(funding, available, None)?;
+    check_vault_balance(funding, vault_balance)?;
+    Ok(allocation)
+}
+
+/// Account for `funding` moved into pending_claims for bonus claims
+fn fund_bonus_reserve(config: &mut Config, funding: u64) -> Result<(), YapError> {
+    config.distribution.bonus_reserved = config
+        .distribution
+        .bonus_reserved
+        .checked_add(funding)
+        .ok_or(YapError::Overflow)?;
+    config.distribution.total_distributed = config
+        .distribution
+        .total_distributed
+        .checked_add(funding)
+        .ok_or(YapError::Overflow)?;
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{
+        Distribution, Timing, Tokenomics, DECIMALS, MAX_UPDATER_KEYS, SECONDS_PER_YEAR,
+    };
+    use crate::test_utils::TestAccount;
+    use borsh::BorshDeserialize;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::{Account as TokenAccount, Mint};
+
+    #[test]
+    fn test_distribute_reverts_when_paused() {
+        let program_id = Pubkey::new_unique();
+        let updater_key = Pubkey::new_unique();
+        let paused = Config {
+            paused: true,
+            roles: Roles {
+                merkle_updater: updater_key,
+                ..Roles::default()
+            },
+            ..Config::default()
+        };
+
+        let mut updater = TestAccount::signer(updater_key);
+        let mut config = TestAccount::config(&program_id, paused.clone());
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut pending = TestAccount::new(Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+
+        {
+            let accounts = [
+                updater.info(),
+                config.info(),
+                vault.info(),
+                pending.info(),
+                mint.info(),
+                token_program.info(),
+            ];
+            let err = process(&program_id, &accounts, 0, [1u8; 32], 1).unwrap_err();
+            assert_eq!(err, YapError::Paused.into());
+        }
+
+        // After unpause the guard passes (and the unknown vault is rejected next)
+        config.set_config(Config {
+            paused: false,
+            ..paused
+        });
+        let accounts = [
+            updater.info(),
+            config.info(),
+            vault.info(),
+            pending.info(),
+            mint.info(),
+            token_program.info(),
+        ];
+        let err = process(&program_id, &accounts, 0, [1u8; 32], 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+    }
+
+    #[test]
+    fn test_direct_distribute_rejected_under_timelock() {
+        let program_id = Pubkey::new_unique();
+        let updater_key = Pubkey::new_unique();
+        let timelocked = Config {
+            roles: Roles {
+                merkle_updater: updater_key,
+                ..Roles::default()
+            },
+            timing: Timing {
+                distribution_timelock: 3_600,
+                ..Timing::default()
+            },
+            ..Config::default()
+        };
+
+        let mut updater = TestAccount::signer(updater_key);
+        let mut config = TestAccount::config(&program_id, timelocked);
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut pending = TestAccount::new(Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+        let accounts = [
+            updater.info(),
+            config.info(),
+            vault.info(),
+            pending.info(),
+            mint.info(),
+            token_program.info(),
+        ];
+
+        let err = process(&program_id, &accounts, 0, [1u8; 32], 1).unwrap_err();
+        assert_eq!(err, YapError::DistributionTimelocked.into());
+        let err = process_with_nonce(&program_id, &accounts, 0, [1u8; 32], 1, 1).unwrap_err();
+        assert_eq!(err, YapError::DistributionTimelocked.into());
+
+        // The queued path gets past the guard (and the unknown vault is rejected next)
+        let err = process_queued(&program_id, &accounts, 0, [1u8; 32], 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+    }
+
+    fn packed_mint(decimals: u8) -> Vec<u8> {
+        let mint = Mint {
+            mint_authority: Some(Pubkey::new_unique()).into(),
+            supply: 1_000,
+            decimals,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        let mut data = vec![0u8; Mint::LEN];
+        Mint::pack(mint, &mut data).unwrap();
+        data
+    }
+
+    fn packed_token_account(owner: Pubkey, mint: Pubkey) -> Vec<u8> {
+        let account = TokenAccount {
+            mint,
+            owner,
+            amount: 1_000,
+            state: spl_token::state::AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        let mut data = vec![0u8; TokenAccount::LEN];
+        TokenAccount::pack(account, &mut data).unwrap();
+        data
+    }
+
+    #[test]
+    fn test_every_root_needs_a_tree_depth() {
+        use crate::instructions::queued_distribution::process_queue;
+
+        let program_id = Pubkey::new_unique();
+        for tree_depth in [0, MAX_PROOF_DEPTH as u8 + 1] {
+            let results = [
+                process(&program_id, &[], 1, [1; 32], tree_depth),
+                process_with_memo(&program_id, &[], 1, [1; 32], String::new(), tree_depth),
+                process_with_min_amount(&program_id, &[], 1, [1; 32], 1, tree_depth),
+                process_category(&program_id, &[], 0, 1, [1; 32], tree_depth),
+                process_with_vesting(&program_id, &[], 1, [1; 32], 0, 86_400, tree_depth),
+                process_with_nonce(&program_id, &[], 1, [1; 32], 1, tree_depth),
+                process_set_bonus_root(&program_id, &[], [1; 32], 0, tree_depth),
+                process_queue(&program_id, &[], 1, [1; 32], 0, tree_depth),
+            ];
+            for result in results {
+                assert_eq!(result.unwrap_err(), YapError::InvalidInstruction.into());
+            }
+        }
+        validate_tree_depth(1).unwrap();
+        validate_tree_depth(MAX_PROOF_DEPTH as u8).unwrap();
+    }
+
+    #[test]
+    fn test_distribute_min_amount_above_amount_rejected() {
+        let program_id = Pubkey::new_unique();
+        let err = process_with_min_amount(&program_id, &[], 100, [1; 32], 101, 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidInstruction.into());
+    }
+
+    #[test]
+    fn test_distribute_vesting_params_validated() {
+        validate_vesting(1_000, 0).unwrap();
+        validate_vesting(0, 86_400).unwrap();
+        for (cliff_ts, duration) in [(0, 0), (-1, 86_400), (1_000, -1)] {
+            assert!(matches!(
+                validate_vesting(cliff_ts, duration),
+                Err(YapError::InvalidInstruction)
+            ));
+        }
+
+        let program_id = Pubkey::new_unique();
+        let err = process_with_vesting(&program_id, &[], 100, [1; 32], 0, 0, 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidInstruction.into());
+    }
+
+    #[test]
+    fn test_min_amount_guard_after_budget_shrinks() {
+        const DAY: i64 = 86_400;
+        let vault = 365_000_000u64 + 2_000_000;
+
+        // Built against 2_000_000 of earmarked inflation plus a day's accrual
+        let before = available(DAY, vault, 2_000_000, 0);
+        assert_eq!(before, 3_000_000);
+        assert_eq!(
+            distribution_amount(2_500_000, before, Some(2_000_000)).unwrap(),
+            2_500_000
+        );
+
+        // Another distribution drains the earmarked inflation first
+        let after = available(DAY, vault - 2_000_000, 0, 0);
+        assert_eq!(after, 1_000_000);
+
+        // Plain requests fail as before, min-out ones clip or revert
+        assert!(matches!(
+            distribution_amount(2_500_000, after, None),
+            Err(YapError::ExceedsDailyAllocation)
+        ));
+        assert_eq!(
+            distribution_amount(2_500_000, after, Some(500_000)).unwrap(),
+            1_000_000
+        );
+        assert!(matches!(
+            distribution_amount(2_500_000, after, Some(2_000_000)),
+            Err(YapError::BelowMinAmount)
+        ));
+    }
+
+    #[test]
+    fn test_distribution_nonce_must_increase() {
+        let mut last_nonce = 0;
+        for nonce in [1, 2, 5, 6] {
+            check_distribution_nonce(nonce, last_nonce).unwrap();
+            last_nonce = nonce;
+        }
+
+        // Replayed and out-of-order nonces
+        for nonce in [0, 5, 6] {
+            assert!(matches!(
+                check_distribution_nonce(nonce, last_nonce),
+                Err(YapError::StaleDistributionNonce)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_transfer_over_vault_balance_rejected() {
+        assert!(check_vault_balance(0, 0).is_ok());
+        assert!(check_vault_balance(1_000, 1_000).is_ok());
+        assert!(matches!(
+            check_vault_balance(1_001, 1_000),
+            Err(YapError::InsufficientBalance)
+        ));
+    }
+
+    #[test]
+    fn test_distribute_category_bounds() {
+        let program_id = Pubkey::new_unique();
+        let err =
+            process_category(&program_id, &[], MAX_CATEGORIES as u8, 1, [1; 32], 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidInstruction.into());
+    }
+
+    fn updater_set(keys: &[Pubkey], threshold: u8) -> Roles {
+        let mut updater_keys = [Pubkey::default(); MAX_UPDATER_KEYS];
+        updater_keys[..keys.len()].copy_from_slice(keys);
+        Roles {
+            merkle_updater: Pubkey::new_unique(),
+            updater_keys,
+            updater_threshold: threshold,
+            ..Roles::default()
+        }
+    }
+
+    #[test]
+    fn test_updater_threshold_met_exactly() {
+        let keys: [Pubkey; 3] = std::array::from_fn(|_| Pubkey::new_unique());
+        let roles = updater_set(&keys, 2);
+        let mut first = TestAccount::signer(keys[0]);
+        let mut third = TestAccount::signer(keys[2]);
+
+        let co_signers = [third.info()];
+        verify_updater_signers(&roles, &first.info(), &co_signers).unwrap();
+    }
+
+    #[test]
+    fn test_updater_threshold_not_met() {
+        let keys: [Pubkey; 3] = std::array::from_fn(|_| Pubkey::new_unique());
+        let roles = updater_set(&keys, 3);
+        let mut first = TestAccount::signer(keys[0]);
+        let mut second = TestAccount::signer(keys[1]);
+        let mut third = TestAccount {
+            is_signer: false,
+            ..TestAccount::new(keys[2])
+        };
+
+        let co_signers = [second.info()];
+        assert!(matches!(
+            verify_updater_signers(&roles, &first.info(), &co_signers),
+            Err(YapError::UpdaterThresholdNotMet)
+        ));
+
+        // A set member that didn't sign doesn't count, and fails the call
+        let co_signers = [second.info(), third.info()];
+        assert!(matches!(
+            verify_updater_signers(&roles, &first.info(), &co_signers),
+            Err(YapError::Unauthorized)
+        ));
+
+        // The single merkle updater no longer suffices
+        let mut legacy = TestAccount::signer(roles.merkle_updater);
+        assert!(matches!(
+            verify_updater_signers(&roles, &legacy.info(), &[]),
+            Err(YapError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_set_bonus_root_needs_updater_quorum() {
+        let program_id = Pubkey::new_unique();
+        let keys: [Pubkey; 3] = std::array::from_fn(|_| Pubkey::new_unique());
+        let roles = updater_set(&keys, 2);
+        let legacy_key = roles.merkle_updater;
+        let mut config = TestAccount::config(
+            &program_id,
+            Config {
+                roles,
+                ..Config::default()
+            },
+        );
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut pending = TestAccount::new(Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+
+        // One set member below the threshold, or the single updater, can't set it
+        for (signer, expected) in [
+            (keys[0], YapError::UpdaterThresholdNotMet),
+            (legacy_key, YapError::Unauthorized),
+        ] {
+            let mut updater = TestAccount::signer(signer);
+            let accounts = [
+                updater.info(),
+                config.info(),
+                vault.info(),
+                pending.info(),
+                mint.info(),
+                token_program.info(),
+            ];
+            assert_eq!(
+                process_set_bonus_root(&program_id, &accounts, [7u8; 32], 0, 1).unwrap_err(),
+                expected.into()
+            );
+        }
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.distribution.bonus_root, [0u8; 32]);
+    }
+
+    #[test]
+    fn test_updater_duplicate_or_outside_signer_rejected() {
+        let keys = [Pubkey::new_unique(), Pubkey::new_unique()];
+        let roles = updater_set(&keys, 2);
+        let mut first = TestAccount::signer(keys[0]);
+        let mut first_again = TestAccount::signer(keys[0]);
+        let mut outsider = TestAccount::signer(Pubkey::new_unique());
+
+        let co_signers = [first_again.info()];
+        assert!(matches!(
+            verify_updater_signers(&roles, &first.info(), &co_signers),
+            Err(YapError::DuplicateSigner)
+        ));
+
+        let co_signers = [outsider.info()];
+        assert!(matches!(
+            verify_updater_signers(&roles, &first.info(), &co_signers),
+            Err(YapError::Unauthorized)
+        ));
+    }
+
+    #[test]
+    fn test_pending_claims_account_valid() {
+        let config_pda = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let data = packed_token_account(config_pda, mint);
+        assert!(validate_pending_claims_account(&data, &config_pda, &mint).is_ok());
+    }
+
+    #[test]
+    fn test_pending_claims_account_wrong_owner_or_mint() {
+        let config_pda = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+
+        let wrong_owner = packed_token_account(Pubkey::new_unique(), mint);
+        let err = validate_pending_claims_account(&wrong_owner, &config_pda, &mint).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+
+        let wrong_mint = packed_token_account(config_pda, Pubkey::new_unique());
+        let err = validate_pending_claims_account(&wrong_mint, &config_pda, &mint).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        // Uninitialized / garbage token account data
+        let err =
+            validate_pending_claims_account(&[0u8; TokenAccount::LEN], &config_pda, &mint)
+                .unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_distribute_to_allowed_destination() {
+        let config_pda = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut config = Config::default();
+        config.addresses.mint = mint;
+        config.addresses.pending_claims = Pubkey::new_unique();
+        config.addresses.token_program = spl_token::id();
+
+        // A streaming program's token account, not owned by the config PDA
+        let mut stream = TestAccount {
+            owner: spl_token::id(),
+            data: packed_token_account(Pubkey::new_unique(), mint),
+            ..TestAccount::new(Pubkey::new_unique())
+        };
+
+        // Not whitelisted yet
+        let err = validate_destination(&config, &config_pda, &stream.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+
+        config.addresses.allowed_distribution_dest = stream.key;
+        assert!(validate_destination(&config, &config_pda, &stream.info()).unwrap());
+
+        // pending_claims keeps working and isn't reported as external
+        let mut pending_claims = TestAccount {
+            owner: spl_token::id(),
+            data: packed_token_account(config_pda, mint),
+            ..TestAccount::new(config.addresses.pending_claims)
+        };
+        assert!(!validate_destination(&config, &config_pda, &pending_claims.info()).unwrap());
+
+        // The whitelisted account must still hold our mint under our token program
+        stream.data = packed_token_account(Pubkey::new_unique(), Pubkey::new_unique());
+        let err = validate_destination(&config, &config_pda, &stream.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        stream.data = packed_token_account(Pubkey::new_unique(), mint);
+        stream.owner = Pubkey::new_unique();
+        let err = validate_destination(&config, &config_pda, &stream.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_external_destination_owned_by_config_rejected() {
+        let config_pda = Pubkey::new_unique();
+        let mint = Pubkey::new_unique();
+        let mut config = Config::default();
+        config.addresses.mint = mint;
+        config.addresses.token_program = spl_token::id();
+
+        let mut stranded = TestAccount {
+            owner: spl_token::id(),
+            data: packed_token_account(config_pda, mint),
+            ..TestAccount::new(Pubkey::new_unique())
+        };
+        config.addresses.allowed_distribution_dest = stranded.key;
+
+        let err = validate_destination(&config, &config_pda, &stranded.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_external_distribution_takes_zero_root() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+        let updater_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let mut stream = TestAccount {
+            owner: spl_token::id(),
+            data: packed_token_account(Pubkey::new_unique(), mint_key),
+            ..TestAccount::new(Pubkey::new_unique())
+        };
+        let mut vault = TestAccount {
+            owner: spl_token::id(),
+            data: packed_token_account(config_pda, mint_key),
+            ..TestAccount::new(Pubkey::new_unique())
+        };
+        let mut mint = TestAccount {
+            owner: spl_token::id(),
+            data: packed_mint(DECIMALS),
+            ..TestAccount::new(mint_key)
+        };
+
+        let mut stored = Config::default();
+        stored.roles.merkle_updater = updater_key;
+        stored.tokenomics.decimals = DECIMALS;
+        stored.addresses.vault = vault.key;
+        stored.addresses.mint = mint_key;
+        stored.addresses.token_program = spl_token::id();
+        stored.addresses.allowed_distribution_dest = stream.key;
+
+        let mut updater = TestAccount::signer(updater_key);
+        let mut config = TestAccount::config(&program_id, stored);
+        let mut token_program = TestAccount::new(spl_token::id());
+        let accounts = [
+            updater.info(),
+            config.info(),
+            vault.info(),
+            stream.info(),
+            mint.info(),
+            token_program.info(),
+        ];
+
+        let err = process(&program_id, &accounts, 0, [1; 32], 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidInstruction.into());
+
+        // An all-zero root gets past the check (and on to the clock)
+        let err = process(&program_id, &accounts, 0, [0; 32], 1).unwrap_err();
+        assert_ne!(err, YapError::InvalidInstruction.into());
+    }
+
+    #[test]
+    fn test_external_round_leaves_roots_and_counters() {
+        let mut config = Config::default();
+        config.distribution.merkle_root = [1; 32];
+        config.distribution.recent_roots[0] = [2; 32];
+        config.distribution.distribution_id = 4;
+        config.distribution.total_distributed = 1_000;
+        config.distribution.category_roots[1] = [3; 32];
+        config.timing.vesting_cliff_ts = 50;
+        let recent_roots = config.distribution.recent_roots;
+        let category_roots = config.distribution.category_roots;
+
+        for category in [None, Some(1)] {
+            publish_round(&mut config, true, category, [0; 32], 0, None, 100, 500, 0).unwrap();
+        }
+        assert_eq!(config.distribution.merkle_root, [1; 32]);
+        assert_eq!(config.distribution.recent_roots, recent_roots);
+        assert_eq!(config.distribution.distribution_id, 4);
+        assert_eq!(config.distribution.total_distributed, 1_000);
+        assert_eq!(config.distribution.rounds_recorded, 0);
+        assert_eq!(config.distribution.category_roots, category_roots);
+        assert_eq!(config.timing.vesting_cliff_ts, 50);
+
+        // A pending_claims round publishes all of it
+        publish_round(&mut config, false, None, [9; 32], 0, None, 100, 500, 20).unwrap();
+        assert_eq!(config.distribution.merkle_root, [9; 32]);
+        assert_eq!(config.distribution.recent_roots[0], [1; 32]);
+        assert_eq!(config.distribution.distribution_id, 5);
+        assert_eq!(config.distribution.total_distributed, 1_520);
+        assert_eq!(config.distribution.rounds_recorded, 1);
+        assert_eq!(config.timing.vesting_cliff_ts, 0);
+    }
+
+    #[test]
+    fn test_set_bonus_root_rejected_under_timelock() {
+        let program_id = Pubkey::new_unique();
+        let updater_key = Pubkey::new_unique();
+        let mut updater = TestAccount::signer(updater_key);
+        let mut config = TestAccount::config(
+            &program_id,
+            Config {
+                roles: Roles {
+                    merkle_updater: updater_key,
+                    ..Roles::default()
+                },
+                timing: Timing {
+                    distribution_timelock: 3_600,
+                    ..Timing::default()
+                },
+                ..Config::default()
+            },
+        );
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut pending = TestAccount::new(Pubkey::new_unique());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+        let accounts = [
+            updater.info(),
+            config.info(),
+            vault.info(),
+            pending.info(),
+            mint.info(),
+            token_program.info(),
+        ];
+
+        // Funded or not, a bonus root can't skip the queue
+        for funding in [0, 1_000] {
+            assert_eq!(
+                process_set_bonus_root(&program_id, &accounts, [7u8; 32], funding, 1).unwrap_err(),
+                YapError::DistributionTimelocked.into()
+            );
+        }
+        drop(accounts);
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert_eq!(stored.distribution.bonus_root, [0u8; 32]);
+        assert_eq!(stored.distribution.bonus_reserved, 0);
+    }
+
+    #[test]
+    fn test_bonus_funding_held_to_min_interval() {
+        const HOUR: i64 = 3_600;
+        let config = Config {
+            timing: Timing {
+                last_distribution_ts: 1_000,
+                min_distribution_interval: HOUR,
+                rate_period_secs: SECONDS_PER_YEAR,
+                ..Timing::default()
+            },
+            ..Config::default()
+        };
+        let vault_balance = 1_000_000_000;
+
+        assert!(matches!(
+            bonus_funding_budget(&config, 1_000 + HOUR - 1, vault_balance, 1),
+            Err(YapError::DistributionTooSoon)
+        ));
+        bonus_funding_budget(&config, 1_000 + HOUR, vault_balance, 1).unwrap();
+
+        // Before the last distribution is reported, not treated as too soon
+        assert!(matches!(
+            bonus_funding_budget(&config, 999, vault_balance, 1),
+            Err(YapError::ClockWentBackwards)
+        ));
+    }
+
+    #[test]
+    fn test_bonus_payouts_bounded_by_funding() {
+        let mut config = Config::default();
+
+        // Nothing funded: no bonus can be paid, however valid its proof
+        assert!(matches!(
+            config.draw_bonus_reserve(1),
+            Err(YapError::BonusNotFunded)
+        ));
+        config.draw_bonus_reserve(0).unwrap();
+
+        fund_bonus_reserve(&mut config, 1_000).unwrap();
+        assert_eq!(config.distribution.total_distributed, 1_000);
+        config.draw_bonus_reserve(600).unwrap();
+
+        // Past the reserve fails and leaves it untouched
+        assert!(matches!(
+            config.draw_bonus_reserve(401),
+            Err(YapError::BonusNotFunded)
+        ));
+        assert_eq!(config.distribution.bonus_reserved, 400);
+
+        // Topping up with the next root makes room again
+        fund_bonus_reserve(&mut config, 100).unwrap();
+        config.draw_bonus_reserve(500).unwrap();
+        assert_eq!(config.distribution.bonus_reserved, 0);
+        assert_eq!(config.distribution.total_distributed, 1_100);
+    }
+
+    #[test]
+    fn test_mint_decimals_match() {
+        assert!(validate_mint_decimals(&packed_mint(DECIMALS), DECIMALS).is_ok());
+
+        // A deployment on a 6-decimal mint checks against its own decimals
+        assert!(validate_mint_decimals(&packed_mint(6), 6).is_ok());
+    }
+
+    #[test]
+    fn test_mint_decimals_mismatch_rejected() {
+        let err = validate_mint_decimals(&packed_mint(6), DECIMALS).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+        let err = validate_mint_decimals(&packed_mint(DECIMALS), 6).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        // Uninitialized / garbage mint data
+        let err = validate_mint_decimals(&[0u8; Mint::LEN], DECIMALS).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+    }
+
+    fn available(elapsed: i64, vault: u64, undistributed: u64, carried: u64) -> u64 {
+        Allocation::new(elapsed, SECONDS_PER_YEAR, vault, undistributed, carried)
+            .unwrap()
+            .available()
+    }
+
+    #[test]
+    fn test_available_allocation_without_inflation_counter() {
+        let vault = 365_000_000u64;
+        assert_eq!(available(0, vault, 0, 0), 0);
+        assert_eq!(available(86_400, vault, 0, 0), 1_000_000);
+    }
+
+    #[test]
+    fn test_available_allocation_draws_inflation_first() {
+        let vault = 365_000_000u64 + 5_000;
+
+        // Earmarked inflation is spendable immediately, the rest accrues by time
+        assert_eq!(available(0, vault, 5_000, 0), 5_000);
+        assert_eq!(available(86_400, vault, 5_000, 0), 5_000 + 1_000_000);
+
+        // Counter can never exceed what the vault actually holds
+        assert_eq!(available(0, 1_000, 5_000, 0), 1_000);
+    }
+
+    #[test]
+    fn test_available_scales_inversely_with_rate_period() {
+        const DAY: i64 = 86_400;
+        let vault = 360_000_000u64;
+        let accrued = |elapsed, period| {
+            Allocation::new(elapsed, period, vault, 0, 0)
+                .unwrap()
+                .available()
+        };
+
+        // "Distribute the vault over 90 days": 4x the daily budget of a 360-day period
+        assert_eq!(accrued(DAY, 90 * DAY), 4_000_000);
+        assert_eq!(accrued(DAY, 360 * DAY), 1_000_000);
+
+        // The whole vault is available once a period has passed
+        assert_eq!(accrued(90 * DAY, 90 * DAY), vault);
+        assert_eq!(accrued(91 * DAY, 90 * DAY), vault);
+
+        for period in [0, -DAY] {
+            let err = Allocation::new(DAY, period, vault, 0, 0).unwrap_err();
+            assert!(matches!(err, YapError::InvalidInstruction));
+        }
+    }
+
+    #[test]
+    fn test_category_shares_sum_to_available() {
+        const DAY: i64 = 86_400;
+        let split = [6_000, 4_000, 0, 0];
+        for vault in [0, 1, 7, 999, 1_000_000, u64::MAX / 2] {
+            let allocation = Allocation::new(DAY, DAY, vault, vault / 3, 0).unwrap();
+            let shares: Vec<u64> = (0..MAX_CATEGORIES)
+                .map(|category| bps_slice(allocation.available(), &split, category))
+                .collect();
+            assert_eq!(shares.iter().sum::<u64>(), allocation.available());
+            assert_eq!(&shares[2..], &[0, 0]);
+        }
+
+        // Creators 60%, stakers 40%
+        let allocation = Allocation::new(DAY, DAY, 1_000_000, 0, 0).unwrap();
+        assert_eq!(bps_slice(allocation.available(), &split, 0), 600_000);
+        assert_eq!(bps_slice(allocation.available(), &split, 1), 400_000);
+
+        // Uneven splits stay within one token of the exact share
+        let thirds = [3_333, 3_333, 3_334, 0];
+        let allocation = Allocation::new(DAY, DAY, 100, 0, 0).unwrap();
+        let shares: Vec<u64> = (0..3)
+            .map(|category| bps_slice(allocation.available(), &thirds, category))
+            .collect();
+        assert_eq!(shares, vec![33, 33, 34]);
+    }
+
+    #[test]
+    fn test_simulated_budget_is_what_distribute_moves() {
+        const DAY: i64 = 86_400;
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                undistributed_inflation: 50_000,
+                ..Tokenomics::default()
+            },
+            timing: Timing {
+                rate_period_secs: 100 * DAY,
+                ..Timing::default()
+            },
+            distribution: Distribution {
+                accrued_allocation: 7_000,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        let vault = 1_050_000;
+
+        // SimulateDistribute and Distribute share the budget calculation: earmarked
+        // inflation, the carried budget and a day of the rest of the vault
+        let (allocation, simulated) = distribution_budget(&config, DAY, vault, None).unwrap();
+        assert_eq!(simulated, 50_000 + 7_000 + 10_000);
+
+        // A distribute at the same moment moves exactly that, and no more
+        let available = allocation.available();
+        assert_eq!(
+            distribution_amount(simulated, available, None).unwrap(),
+            simulated
+        );
+        assert!(matches!(
+            distribution_amount(simulated + 1, available, None),
+            Err(YapError::ExceedsDailyAllocation)
+        ));
+        assert_eq!(
+            distribution_amount(u64::MAX, available, Some(1)).unwrap(),
+            simulated
+        );
+        assert_eq!(allocation.carry_forward(simulated), 0);
+
+        // A category with a split sees its share of the same budget
+        let split = [2_500, 7_500, 0, 0];
+        config.distribution.category_bps = split;
+        let (_, category) = distribution_budget(&config, DAY, vault, Some(1)).unwrap();
+        assert_eq!(category, bps_slice(allocation.available(), &split, 1));
+    }
+
+    #[test]
+    fn test_category_draws_bounded_by_share() {
+        const DAY: i64 = 86_400;
+        let mut config = Config {
+            timing: Timing {
+                rate_period_secs: 10 * DAY,
+                ..Timing::default()
+            },
+            distribution: Distribution {
+                category_bps: [6_000, 4_000, 0, 0],
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        let mut vault = 10_000_000u64;
+
+        // Spend a round's whole budget the way `Distribute` books it
+        let mut draw = |config: &mut Config, elapsed: i64, category: Option<u8>| {
+            let (allocation, available) =
+                distribution_budget(config, elapsed, vault, category).unwrap();
+            settle_category_budgets(config, &allocation, category, available);
+            draw_undistributed_inflation(config, available);
+            config.distribution.accrued_allocation = allocation.carry_forward(available);
+            vault -= available;
+            available
+        };
+
+        // A day accrues 1_000_000; creators draw over and over in the same window
+        let mut creators = draw(&mut config, DAY, Some(0));
+        for _ in 0..10 {
+            creators += draw(&mut config, 0, Some(0));
+        }
+        assert_eq!(creators, 600_000);
+
+        // The split covers the whole budget, so a round without a category gets nothing
+        assert_eq!(draw(&mut config, 0, None), 0);
+
+        // Stakers' 40% is still there, and the next day is split again
+        assert_eq!(draw(&mut config, 0, Some(1)), 400_000);
+        assert_eq!(draw(&mut config, DAY, Some(0)), 540_000);
+        assert_eq!(config.distribution.category_accrued, [0, 360_000, 0, 0]);
+    }
+
+    #[test]
+    fn test_unused_allocation_carries_forward() {
+        const DAY: i64 = 86_400;
+        let mut vault = 365_000_000u64;
+        let mut carried = 0;
+
+        // Day 1: 1_000_000 accrues, only 400_000 is distributed
+        let allocation = Allocation::new(DAY, SECONDS_PER_YEAR, vault, 0, carried).unwrap();
+        assert_eq!(allocation.available(), 1_000_000);
+        carried = allocation.carry_forward(400_000);
+        vault -= 400_000;
+        assert_eq!(carried, 600_000);
+
+        // Day 2: the leftover adds to the new day's accrual
+        let allocation = Allocation::new(DAY, SECONDS_PER_YEAR, vault, 0, carried).unwrap();
+        assert_eq!(allocation.available(), 600_000 + 998_904);
+        carried = allocation.carry_forward(0);
+        assert_eq!(carried, 1_598_904);
+
+        // Day 3: the whole carried budget plus today can be spent at once
+        let allocation = Allocation::new(DAY, SECONDS_PER_YEAR, vault, 0, carried).unwrap();
+        let available = allocation.available();
+        assert_eq!(available, 1_598_904 + 998_904);
+        assert_eq!(allocation.carry_forward(available), 0);
+    }
+
+    #[test]
+    fn test_carried_allocation_spends_inflation_first() {
+        let vault = 365_000_000u64 + 5_000;
+        let allocation = Allocation::new(86_400, SECONDS_PER_YEAR, vault, 5_000, 250_000).unwrap();
+        assert_eq!(allocation.available(), 5_000 + 1_250_000);
+
+        // Earmarked inflation covers the first 5_000 of the round
+        assert_eq!(allocation.carry_forward(5_000), 1_250_000);
+        assert_eq!(allocation.carry_forward(105_000), 1_150_000);
+    }
+
+    #[test]
+    fn test_carried_allocation_capped_by_vault() {
+        // Vault shrank below the carried budget (e.g. a rebase burn)
+        let allocation = Allocation::new(0, SECONDS_PER_YEAR, 1_000, 0, 50_000).unwrap();
+        assert_eq!(allocation.available(), 1_000);
+        assert_eq!(allocation.carry_forward(400), 600);
+
+        // Earmarked inflation isn't double counted in the carried budget
+        let allocation = Allocation::new(0, SECONDS_PER_YEAR, 1_000, 800, 50_000).unwrap();
+        assert_eq!((allocation.earmarked, allocation.accrued), (800, 200));
+
+        // Huge carry and elapsed time are capped at the vault instead of overflowing
+        let allocation =
+            Allocation::new(i64::MAX, SECONDS_PER_YEAR, u64::MAX, 0, u64::MAX).unwrap();
+        assert_eq!(allocation.available(), u64::MAX);
+    }
+
+    #[test]
+    fn test_allocation_extremes_error_instead_of_zeroing() {
+        // The largest product of elapsed and vault still fits in u128
+        let allocation = Allocation::new(i64::MAX, SECONDS_PER_YEAR, u64::MAX, 0, 0).unwrap();
+        assert_eq!(allocation.available(), u64::MAX);
+        let allocation =
+            Allocation::new(SECONDS_PER_YEAR - 1, SECONDS_PER_YEAR, u64::MAX, 0, 0).unwrap();
+        assert_eq!(
+            allocation.available(),
+            ((SECONDS_PER_YEAR as u128 - 1) * u64::MAX as u128 / SECONDS_PER_YEAR as u128) as u64
+        );
+
+        // A negative elapsed time is reported, not read as an empty budget
+        for elapsed in [-1, i64::MIN] {
+            assert!(matches!(
+                Allocation::new(elapsed, SECONDS_PER_YEAR, u64::MAX, 0, u64::MAX),
+                Err(YapError::ClockWentBackwards)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_distributions_rate_limited_by_min_interval() {
+        const HOUR: i64 = 3_600;
+        let mut last_distribution_ts = 1_000;
+
+        // Back-to-back distributions inside the interval are rejected
+        for now in [1_000, 1_000 + 1, 1_000 + HOUR - 1] {
+            assert!(matches!(
+                check_distribution_interval(now - last_distribution_ts, HOUR),
+                Err(YapError::DistributionTooSoon)
+            ));
+        }
+
+        // Once the interval has passed the next one goes through
+        let now = last_distribution_ts + HOUR;
+        assert!(check_distribution_interval(now - last_distribution_ts, HOUR).is_ok());
+        last_distribution_ts = now;
+
+        // ... and the window starts over
+        assert!(matches!(
+            check_distribution_interval(now + 1 - last_distribution_ts, HOUR),
+            Err(YapError::DistributionTooSoon)
+        ));
+
+        // No limit configured
+        assert!(check_distribution_interval(0, 0).is_ok());
+    }
+
+    #[test]
+    fn test_distribution_decrements_inflation_counter() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                undistributed_inflation: 5_000,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        draw_undistributed_inflation(&mut config, 3_000);
+        assert_eq!(config.tokenomics.undistributed_inflation, 2_000);
+
+        // Remainder comes from the time-based budget
+        draw_undistributed_inflation(&mut config, 10_000);
+        assert_eq!(config.tokenomics.undistributed_inflation, 0);
+    }
+
+    #[test]
+    fn test_memo_instruction_carries_memo() {
+        let updater = Pubkey::new_unique();
+        let ix = build_memo_instruction("YAP distribution #42", &updater);
+
+        assert_eq!(ix.program_id, MEMO_PROGRAM_ID);
+        assert_eq!(ix.data, b"YAP distribution #42");
+        assert_eq!(ix.accounts.len(), 1);
+        assert_eq!(ix.accounts[0].pubkey, updater);
+        assert!(ix.accounts[0].is_signer);
+        assert!(!ix.accounts[0].is_writable);
+    }
+
+    #[test]
+    fn test_early_bird_reserve_tops_up_to_bonus() {
+        let mut config = Config {
+            distribution: Distribution {
+                early_bird_bonus: 50,
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(early_bird_top_up(&config), 50);
+
+        // Unclaimed reserve carries over; only the difference is moved
+        config.distribution.early_bird_reserved = 20;
+        assert_eq!(early_bird_top_up(&config), 30);
+        config.distribution.early_bird_reserved = 50;
+        assert_eq!(early_bird_top_up(&config), 0);
+
+        // Lowering the bonus never pulls tokens back
+        config.distribution.early_bird_bonus = 10;
+        assert_eq!(early_bird_top_up(&config), 0);
+
+        // Publishing a top-up the reserve can't hold fails instead of wrapping
+        config.distribution.early_bird_reserved = u64::MAX;
+        assert!(matches!(
+            publish_round(&mut config, false, None, [9; 32], 1, None, 100, 0, 1),
+            Err(YapError::Overflow)
+        ));
+    }
+}
@@ -0,0 +1,209 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::YapError,
+    instructions::claim::{split_claim_fee, verify_claim_proof},
+    state::{Config, UserClaimStatus},
+    utils::{return_data, vesting::VestingSchedule},
+};
+
+/// Report what `Claim` would pay `user`, without moving tokens or writing state
+///
+/// Verifies the proof exactly like `Claim` (same errors), then publishes
+/// `amount - claimed_amount` via return data, counting only the vested part
+/// of `amount` while the current root vests, less any `claim_fee_bps` fee. A
+/// wallet that never claimed has no UserClaimStatus yet and counts as having
+/// claimed nothing. Works while the program is paused.
+///
+/// Accounts:
+/// 0. `[]` User wallet the leaf is keyed to (need not sign)
+/// 1. `[]` UserClaimStatus PDA (may not exist yet)
+/// 2. `[]` Config PDA
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    proof: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_claim_status_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    // Reject zero amount claims
+    if amount == 0 {
+        msg!("GetClaimable: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    if config.distribution.merkle_root == [0u8; 32] {
+        msg!("GetClaimable: Merkle root not set");
+        return Err(YapError::NotInitialized.into());
+    }
+
+    verify_claim_proof(&config, user.key, amount, None, &proof)?;
+
+    let claimed_amount = load_claimed_amount(program_id, user.key, user_claim_status_info)?;
+    let schedule = VestingSchedule::from_timing(&config.timing);
+    let vested = if schedule.is_active() {
+        schedule.vested_amount(amount, Clock::get()?.unix_timestamp)
+    } else {
+        amount
+    };
+    let claimable = vested
+        .saturating_sub(claimed_amount)
+        .min(config.wallet_claim_headroom(claimed_amount));
+    let (paid, _) = split_claim_fee(&config, claimable)?;
+
+    msg!(
+        "GetClaimable: user={}, amount={}, claimed={}, claimable={}",
+        user.key,
+        amount,
+        claimed_amount,
+        claimable
+    );
+    return_data::set_amount(paid);
+
+    Ok(())
+}
+
+/// `claimed_amount` of the user's UserClaimStatus, or 0 if it doesn't exist yet
+fn load_claimed_amount(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    user_claim_status_info: &AccountInfo,
+) -> Result<u64, YapError> {
+    let (user_claim_pda, _) =
+        Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], program_id);
+    if user_claim_status_info.key != &user_claim_pda {
+        return Err(YapError::InvalidPda);
+    }
+
+    if user_claim_status_info.data_is_empty() {
+        return Ok(0);
+    }
+    if user_claim_status_info.owner != program_id {
+        return Err(YapError::InvalidOwner);
+    }
+
+    let status = UserClaimStatus::load(&user_claim_status_info.data.borrow())
+        .map_err(|_| YapError::InvalidDiscriminator)?;
+    if !status.is_valid() {
+        return Err(YapError::InvalidDiscriminator);
+    }
+    Ok(status.claimed_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        state::{Distribution, USER_CLAIM_DISCRIMINATOR, USER_CLAIM_VERSION},
+        test_utils::TestAccount,
+        utils::merkle::{compute_leaf, NODE_PREFIX},
+    };
+    use borsh::BorshSerialize;
+    use solana_program::keccak;
+
+    fn hash_sorted(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+        let (left, right) = if a <= b { (a, b) } else { (b, a) };
+        keccak::hashv(&[&[NODE_PREFIX], left, right]).to_bytes()
+    }
+
+    fn status_account(program_id: &Pubkey, user: &Pubkey, claimed_amount: u64) -> TestAccount {
+        let (pda, _) =
+            Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], program_id);
+        let status = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount,
+            version: USER_CLAIM_VERSION,
+            ..UserClaimStatus::default()
+        };
+        TestAccount {
+            owner: *program_id,
+            data: borsh::to_vec(&status).unwrap(),
+            ..TestAccount::new(pda)
+        }
+    }
+
+    #[test]
+    fn test_get_claimable_verifies_proof_without_writing() {
+        let program_id = Pubkey::new_unique();
+        let (user_key, other) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let sibling = compute_leaf(&other, 5_000);
+        let config = Config {
+            distribution: Distribution {
+                merkle_root: hash_sorted(&compute_leaf(&user_key, 1_000), &sibling),
+                ..Distribution::default()
+            },
+            ..Config::default()
+        };
+
+        let mut user = TestAccount::new(user_key);
+        let mut status = status_account(&program_id, &user_key, 400);
+        let mut config_account = TestAccount::config(&program_id, config);
+        let (status_before, config_before) = (status.data.clone(), config_account.data.clone());
+
+        let accounts = [user.info(), status.info(), config_account.info()];
+        assert!(process(&program_id, &accounts, 1_000, vec![sibling]).is_ok());
+
+        // Same errors as Claim for a wrong amount or proof
+        let err = process(&program_id, &accounts, 2_000, vec![sibling]).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+        let err = process(&program_id, &accounts, 1_000, vec![[9u8; 32]]).unwrap_err();
+        assert_eq!(err, YapError::InvalidProof.into());
+        let err = process(&program_id, &accounts, 0, vec![sibling]).unwrap_err();
+        assert_eq!(err, YapError::InvalidInstruction.into());
+
+        assert_eq!(status.data, status_before);
+        assert_eq!(config_account.data, config_before);
+    }
+
+    #[test]
+    fn test_claimed_amount_of_missing_or_existing_status() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+
+        // Never claimed: the PDA is still an empty system account
+        let (pda, _) =
+            Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], &program_id);
+        let mut missing = TestAccount::new(pda);
+        assert_eq!(
+            load_claimed_amount(&program_id, &user, &missing.info()).unwrap(),
+            0
+        );
+
+        let mut existing = status_account(&program_id, &user, 750);
+        assert_eq!(
+            load_claimed_amount(&program_id, &user, &existing.info()).unwrap(),
+            750
+        );
+
+        // Someone else's status can't stand in
+        let mut foreign = status_account(&program_id, &Pubkey::new_unique(), 0);
+        assert!(matches!(
+            load_claimed_amount(&program_id, &user, &foreign.info()),
+            Err(YapError::InvalidPda)
+        ));
+
+        // Uninitialized data at the right address
+        let mut garbage = status_account(&program_id, &user, 0);
+        UserClaimStatus::default()
+            .serialize(&mut &mut garbage.data[..])
+            .unwrap();
+        assert!(matches!(
+            load_claimed_amount(&program_id, &user, &garbage.info()),
+            Err(YapError::InvalidDiscriminator)
+        ));
+    }
+}
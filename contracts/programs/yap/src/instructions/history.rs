@@ -0,0 +1,102 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{events::{Event, RoundHistoryEvent}, state::Config};
+
+/// Emit the last `count` distribution rounds for dashboards
+///
+/// Logs a `RoundHistoryEvent` (oldest first) via `sol_log_data`. `count` is
+/// bounded by the ring buffer size and the number of rounds recorded.
+///
+/// Accounts:
+/// 0. `[]` Config PDA
+pub fn process_log_recent_rounds(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    count: u8,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let config_info = next_account_info(account_info_iter)?;
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    let event = RoundHistoryEvent {
+        rounds: config.recent_rounds(count as usize),
+    };
+
+    msg!(
+        "LogRecentRounds: requested={}, emitted={}, recorded={}",
+        count,
+        event.rounds.len(),
+        config.distribution.rounds_recorded
+    );
+
+    event.emit()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{RoundRecord, ROUND_HISTORY_LEN};
+    use borsh::BorshDeserialize;
+
+    fn round(i: u64) -> RoundRecord {
+        RoundRecord {
+            distribution_id: i,
+            merkle_root: [i as u8; 32],
+            timestamp: 1_700_000_000 + i as i64 * 86_400,
+            amount: i * 1_000,
+        }
+    }
+
+    #[test]
+    fn test_recent_rounds_before_buffer_wraps() {
+        let mut config = Config::default();
+        for i in 1..=3 {
+            let r = round(i);
+            config.distribution.distribution_id = i;
+            config.record_round(r.merkle_root, 1, r.timestamp, r.amount);
+        }
+
+        assert_eq!(config.recent_rounds(2), vec![round(2), round(3)]);
+        // Bounded by what has been recorded
+        assert_eq!(config.recent_rounds(10), vec![round(1), round(2), round(3)]);
+        assert!(config.recent_rounds(0).is_empty());
+    }
+
+    #[test]
+    fn test_emitted_history_matches_ring_buffer_in_order() {
+        let mut config = Config::default();
+        let total = ROUND_HISTORY_LEN as u64 + 3;
+        for i in 1..=total {
+            let r = round(i);
+            config.distribution.distribution_id = i;
+            config.record_round(r.merkle_root, 1, r.timestamp, r.amount);
+        }
+        assert_eq!(config.distribution.rounds_recorded, total);
+
+        // Bounded by the buffer size, oldest surviving round first
+        let event = RoundHistoryEvent {
+            rounds: config.recent_rounds(u8::MAX as usize),
+        };
+        let expected: Vec<_> = (4..=total).map(round).collect();
+        assert_eq!(event.rounds, expected);
+
+        // Every emitted record is present in the stored buffer
+        for record in &event.rounds {
+            assert!(config.distribution.round_history.contains(record));
+        }
+
+        // Event payload decodes back to the same history
+        let data = borsh::to_vec(&event).unwrap();
+        let decoded = RoundHistoryEvent::try_from_slice(&data).unwrap();
+        assert_eq!(decoded, event);
+
+        assert_eq!(config.recent_rounds(2), vec![round(total - 1), round(total)]);
+    }
+}
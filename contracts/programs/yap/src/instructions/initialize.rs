@@ -5,13 +5,17 @@ use solana_program::{
     instruction::{AccountMeta, Instruction},
     msg,
     program::{invoke, invoke_signed},
-    program_pack::Pack,
+    program_error::ProgramError,
+    program_option::COption,
     pubkey::Pubkey,
     rent::Rent,
     sysvar::SysvarSerialize,
 };
 use solana_system_interface::instruction as system_instruction;
-use spl_token::state::{Account as TokenAccount, Mint};
+use spl_token_2022_interface::{
+    instruction::{self as token_instruction, AuthorityType},
+    state::{Account as TokenAccount, Mint},
+};
 
 use solana_program::clock::Clock;
 use solana_program::sysvar::Sysvar;
@@ -19,9 +23,18 @@ use solana_program::sysvar::Sysvar;
 use crate::{
     error::YapError,
     state::{
-        Config, CONFIG_DISCRIMINATOR, DECIMALS, INITIAL_SUPPLY, MINT_SEED,
+        Config, CONFIG_DISCRIMINATOR, CONFIG_VERSION, DECIMALS, INITIAL_SUPPLY, MINT_SEED,
         PENDING_CLAIMS_SEED, VAULT_SEED,
         METADATA_PROGRAM_ID, METADATA_SEED, TOKEN_NAME, TOKEN_SYMBOL, TOKEN_URI,
+        RoundRecord, ROUND_HISTORY_LEN, MAX_UPDATER_KEYS, MAX_CATEGORIES, RECENT_ROOTS_LEN,
+        DOMAIN_SALT_LEN, Addresses, ClaimRules, Distribution, Roles, Timing, Tokenomics,
+    },
+    utils::{
+        merkle::deployment_salt,
+        token::{
+            check_created_layout, is_supported_token_program, mint_space, token_account_space,
+            unpack_mint, validate_mint_authority, TOKEN_2022_PROGRAM_ID,
+        },
     },
 };
 
@@ -35,7 +48,7 @@ use crate::{
 /// 4. `[writable]` Pending Claims PDA (token account for distributed-but-unclaimed tokens)
 /// 5. `[writable]` Metadata PDA (Metaplex token metadata account)
 /// 6. `[]` System program
-/// 7. `[]` Token program
+/// 7. `[]` Token program (SPL Token or Token-2022; recorded in config)
 /// 8. `[]` Metaplex Token Metadata program
 /// 9. `[]` Rent sysvar
 pub fn process(
@@ -43,6 +56,9 @@ pub fn process(
     accounts: &[AccountInfo],
     merkle_updater: Pubkey,
     inflation_rate_bps: u16,
+    max_supply: u64,
+    min_distribution_interval: i64,
+    rate_period_secs: i64,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
@@ -61,28 +77,24 @@ pub fn process(
         return Err(YapError::Unauthorized.into());
     }
 
-    if *system_program.key != solana_system_interface::program::id() {
-        return Err(YapError::InvalidOwner.into());
-    }
-
-    // Note: token program validated by spl_token instructions via check_program_account()
+    validate_init_programs(system_program, token_program, rent_info)?;
 
-    if *rent_info.key != solana_program::sysvar::rent::ID {
-        return Err(YapError::InvalidOwner.into());
-    }
-
-    // Validate inflation rate range (0-10000 bps = 0-100%)
-    if inflation_rate_bps > Config::MAX_INFLATION_BPS {
-        return Err(YapError::InvalidInstruction.into());
-    }
+    let params = InitParams {
+        merkle_updater,
+        inflation_rate_bps,
+        max_supply,
+        min_distribution_interval,
+        rate_period_secs,
+        domain_salt: deployment_salt(program_id),
+    };
+    // The cap must leave room for the initial mint
+    params.validate(INITIAL_SUPPLY)?;
 
     msg!("Initialize: admin={}", admin.key);
     msg!("Initialize: merkle_updater={}", merkle_updater);
 
     let (config_pda, config_bump) = Pubkey::find_program_address(&[Config::SEED], program_id);
     let (mint_pda, mint_bump) = Pubkey::find_program_address(&[MINT_SEED], program_id);
-    let (vault_pda, vault_bump) = Pubkey::find_program_address(&[VAULT_SEED], program_id);
-    let (pending_claims_pda, pending_claims_bump) = Pubkey::find_program_address(&[PENDING_CLAIMS_SEED], program_id);
 
     if config_info.key != &config_pda {
         msg!("Invalid Config PDA: expected {}, got {}", config_pda, config_info.key);
@@ -92,14 +104,7 @@ pub fn process(
         msg!("Invalid Mint PDA: expected {}, got {}", mint_pda, mint_info.key);
         return Err(YapError::InvalidPda.into());
     }
-    if vault_info.key != &vault_pda {
-        msg!("Invalid Vault PDA: expected {}, got {}", vault_pda, vault_info.key);
-        return Err(YapError::InvalidPda.into());
-    }
-    if pending_claims_info.key != &pending_claims_pda {
-        msg!("Invalid Pending Claims PDA: expected {}, got {}", pending_claims_pda, pending_claims_info.key);
-        return Err(YapError::InvalidPda.into());
-    }
+    let custody_bumps = check_custody_pdas(program_id, vault_info, pending_claims_info)?;
 
     // Validate metadata PDA (derived from Metaplex program)
     let (metadata_pda, _metadata_bump) = Pubkey::find_program_address(
@@ -116,13 +121,467 @@ pub fn process(
         return Err(YapError::InvalidOwner.into());
     }
 
-    if !config_info.data_is_empty() {
-        return Err(YapError::AlreadyInitialized.into());
+    check_config_unclaimed(config_info)?;
+
+    let rent = Rent::from_account_info(rent_info)?;
+
+    // 1. Create config account
+    create_config_account(
+        program_id,
+        admin,
+        config_info,
+        system_program,
+        &rent,
+        config_bump,
+    )?;
+
+    // A mint left behind by a devnet `CloseProgram` is taken over again
+    let reuse_mint = check_reusable_mint(&config_pda, token_program.key, mint_info)?;
+
+    if reuse_mint {
+        msg!("Reusing existing mint {}", mint_info.key);
+    } else {
+        // 2. Create mint account
+        msg!("Creating mint account...");
+        let mint_space = mint_space(token_program.key)?;
+        let mint_lamports = rent.minimum_balance(mint_space);
+
+        invoke_signed(
+            &system_instruction::create_account(
+                admin.key,
+                mint_info.key,
+                mint_lamports,
+                mint_space as u64,
+                token_program.key,
+            ),
+            &[admin.clone(), mint_info.clone(), system_program.clone()],
+            &[&[MINT_SEED, &[mint_bump]]],
+        )?;
+
+        // 3. Initialize mint (authority = config PDA for trustless minting)
+        msg!("Initializing mint...");
+        invoke(
+            &token_instruction::initialize_mint2(
+                token_program.key,
+                mint_info.key,
+                &config_pda, // mint authority = config PDA
+                None,        // no freeze authority
+                DECIMALS,
+            )?,
+            &[mint_info.clone(), rent_info.clone(), token_program.clone()],
+        )?;
+        check_created_layout::<Mint>("mint", &mint_info.data.borrow(), mint_space)?;
+    }
+
+    // The initial and all later mints are signed by the config PDA
+    validate_mint_authority(&mint_info.data.borrow(), &config_pda)?;
+
+    // Read the decimals back from the mint; every later checked CPI uses them
+    let decimals = unpack_mint(&mint_info.data.borrow())?.decimals;
+
+    // 4-7. Create and initialize the vault and pending_claims token accounts
+    create_custody_accounts(
+        admin,
+        &config_pda,
+        mint_info,
+        vault_info,
+        pending_claims_info,
+        system_program,
+        token_program,
+        &rent,
+        custody_bumps,
+    )?;
+
+    // 8. Mint initial supply to vault (mint_to_checked validates decimals)
+    msg!("Minting {} tokens to vault...", INITIAL_SUPPLY);
+    invoke_signed(
+        &token_instruction::mint_to_checked(
+            token_program.key,
+            mint_info.key,
+            vault_info.key,
+            &config_pda, // mint authority
+            &[],
+            INITIAL_SUPPLY,
+            decimals,
+        )?,
+        &[
+            mint_info.clone(),
+            vault_info.clone(),
+            config_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[Config::SEED, &[config_bump]]],
+    )?;
+
+    // 9. Write config data before the last CPI, so nothing after it can leave
+    // token accounts behind without a config. Any failure still reverts the
+    // whole transaction, leaving the PDAs empty for a retry.
+    msg!("Writing config data...");
+
+    // Re-read the account we created: it must be ours and still blank
+    check_config_created(program_id, config_info)?;
+
+    let clock = Clock::get()?;
+    let now = clock.unix_timestamp;
+
+    let addresses = Addresses {
+        mint: *mint_info.key,
+        vault: *vault_info.key,
+        pending_claims: *pending_claims_info.key,
+        token_program: *token_program.key,
+        treasury: Pubkey::default(),
+        allowed_distribution_dest: Pubkey::default(),
+    };
+    let config = params.initial_config(
+        config_bump,
+        *admin.key,
+        addresses,
+        INITIAL_SUPPLY,
+        decimals,
+        now,
+    );
+
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    if reuse_mint && !metadata_info.data_is_empty() {
+        msg!("Keeping existing token metadata {}", metadata_info.key);
+    } else {
+        // 10. Create token metadata via CPI to Metaplex
+        // Using raw invoke_signed to avoid SDK version conflicts
+        msg!("Creating token metadata via Metaplex CPI...");
+        msg!("  Metadata account: {}", metadata_info.key);
+        msg!("  Mint authority: {}", config_pda);
+        msg!("  Update authority: {}", admin.key);
+
+        let create_metadata_ix = build_create_metadata_v3_instruction(
+            metadata_info.key,
+            mint_info.key,
+            &config_pda,        // mint authority (Config PDA)
+            admin.key,          // payer
+            admin.key,          // update authority
+        );
+
+        invoke_signed(
+            &create_metadata_ix,
+            &[
+                metadata_info.clone(),
+                mint_info.clone(),
+                config_info.clone(),
+                admin.clone(),
+                system_program.clone(),
+                rent_info.clone(),
+            ],
+            &[&[Config::SEED, &[config_bump]]],
+        ).map_err(|e| {
+            msg!("Metaplex CPI failed: {:?}", e);
+            msg!("This may indicate insufficient rent or invalid authorities");
+            e
+        })?;
+
+        msg!("Token metadata created successfully");
+    }
+
+    msg!("Initialize complete!");
+    msg!("  Config: {}", config_info.key);
+    msg!("  Mint: {}", mint_info.key);
+    msg!("  Metadata: {}", metadata_info.key);
+    msg!("  Vault: {}", vault_info.key);
+    msg!("  Pending Claims: {}", pending_claims_info.key);
+    msg!("  Supply: {}", INITIAL_SUPPLY);
+    msg!("  Max Supply: {}", max_supply);
+    msg!("  Token Name: {}", TOKEN_NAME);
+    msg!("  Token Symbol: {}", TOKEN_SYMBOL);
+
+    Ok(())
+}
+
+/// Initialize the YAP program on top of a mint that already exists
+///
+/// The vault and pending_claims are created for `mint` and no YAP mint or
+/// metadata is made. Nothing is minted: `current_supply` starts at the mint's
+/// supply, and the vault is funded by transferring tokens in. The mint
+/// authority must already be the config PDA, or be handed to it here by its
+/// current holder.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Admin/deployer (pays for accounts)
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Existing mint (`mint`)
+/// 3. `[writable]` Vault PDA
+/// 4. `[writable]` Pending Claims PDA
+/// 5. `[]` System program
+/// 6. `[]` Token program owning the mint (recorded in config)
+/// 7. `[]` Rent sysvar
+/// 8. `[signer]` (optional) Current mint authority, while it isn't the config PDA
+#[allow(clippy::too_many_arguments)]
+pub fn process_existing(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    mint: Pubkey,
+    merkle_updater: Pubkey,
+    inflation_rate_bps: u16,
+    max_supply: u64,
+    min_distribution_interval: i64,
+    rate_period_secs: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let mint_authority = next_account_info(account_info_iter).ok();
+
+    if !admin.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    validate_init_programs(system_program, token_program, rent_info)?;
+
+    if mint_info.key != &mint {
+        msg!(
+            "InitializeExisting: mint account {} is not {}",
+            mint_info.key,
+            mint
+        );
+        return Err(YapError::InvalidMint.into());
     }
 
+    let (config_pda, config_bump) = Pubkey::find_program_address(&[Config::SEED], program_id);
+    if config_info.key != &config_pda {
+        msg!(
+            "Invalid Config PDA: expected {}, got {}",
+            config_pda,
+            config_info.key
+        );
+        return Err(YapError::InvalidPda.into());
+    }
+    let custody_bumps = check_custody_pdas(program_id, vault_info, pending_claims_info)?;
+
+    check_config_unclaimed(config_info)?;
+
+    let (existing, handover) =
+        check_existing_mint(&config_pda, token_program.key, mint_info, mint_authority)?;
+
+    let params = InitParams {
+        merkle_updater,
+        inflation_rate_bps,
+        max_supply,
+        min_distribution_interval,
+        rate_period_secs,
+        domain_salt: deployment_salt(program_id),
+    };
+    // Tokens already in circulation count towards the cap
+    params.validate(existing.supply)?;
+
+    msg!("InitializeExisting: admin={}", admin.key);
+    msg!("InitializeExisting: merkle_updater={}", merkle_updater);
+    msg!(
+        "InitializeExisting: mint={} supply={}",
+        mint,
+        existing.supply
+    );
+
     let rent = Rent::from_account_info(rent_info)?;
 
     // 1. Create config account
+    create_config_account(
+        program_id,
+        admin,
+        config_info,
+        system_program,
+        &rent,
+        config_bump,
+    )?;
+
+    // 2. Hand the mint authority to the config PDA
+    if let Some(authority) = handover {
+        msg!("Moving mint authority from {} to config", authority.key);
+        invoke(
+            &token_instruction::set_authority(
+                token_program.key,
+                mint_info.key,
+                Some(&config_pda),
+                AuthorityType::MintTokens,
+                authority.key,
+                &[],
+            )?,
+            &[mint_info.clone(), authority.clone(), token_program.clone()],
+        )?;
+    }
+
+    // All later mints are signed by the config PDA
+    validate_mint_authority(&mint_info.data.borrow(), &config_pda)?;
+
+    // 3-6. Create and initialize the vault and pending_claims token accounts
+    create_custody_accounts(
+        admin,
+        &config_pda,
+        mint_info,
+        vault_info,
+        pending_claims_info,
+        system_program,
+        token_program,
+        &rent,
+        custody_bumps,
+    )?;
+
+    // 7. Write config data
+    msg!("Writing config data...");
+    check_config_created(program_id, config_info)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let addresses = Addresses {
+        mint,
+        vault: *vault_info.key,
+        pending_claims: *pending_claims_info.key,
+        token_program: *token_program.key,
+        treasury: Pubkey::default(),
+        allowed_distribution_dest: Pubkey::default(),
+    };
+    let config = params.initial_config(
+        config_bump,
+        *admin.key,
+        addresses,
+        existing.supply,
+        existing.decimals,
+        now,
+    );
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    msg!("InitializeExisting complete!");
+    msg!("  Config: {}", config_info.key);
+    msg!("  Mint: {}", mint_info.key);
+    msg!("  Vault: {}", vault_info.key);
+    msg!("  Pending Claims: {}", pending_claims_info.key);
+    msg!("  Supply: {}", existing.supply);
+    msg!("  Max Supply: {}", max_supply);
+
+    Ok(())
+}
+
+/// Reject a config account that is already in use
+///
+/// A stored config is `AlreadyInitialized`; any other data (a zero-filled or
+/// partially written account) is `InvalidDiscriminator`, since init can't tell
+/// who put it there.
+pub(crate) fn check_config_unclaimed(config_info: &AccountInfo) -> ProgramResult {
+    let data = config_info.try_borrow_data()?;
+    if data.starts_with(&CONFIG_DISCRIMINATOR) {
+        msg!("Initialize: config {} already initialized", config_info.key);
+        return Err(YapError::AlreadyInitialized.into());
+    }
+    if !data.is_empty() {
+        msg!(
+            "Initialize: config holds {} bytes of unknown data",
+            data.len()
+        );
+        return Err(YapError::InvalidDiscriminator.into());
+    }
+    Ok(())
+}
+
+/// Whether `mint_info` holds a mint left by `CloseProgram` that init can take over
+///
+/// Empty is a first init. Otherwise it must be this program's mint (authority =
+/// config PDA) with nothing in circulation, so minting `INITIAL_SUPPLY` again
+/// starts `current_supply` from a true zero.
+pub(crate) fn check_reusable_mint(
+    config_pda: &Pubkey,
+    token_program: &Pubkey,
+    mint_info: &AccountInfo,
+) -> Result<bool, ProgramError> {
+    if mint_info.data_is_empty() {
+        return Ok(false);
+    }
+    if mint_info.owner != token_program {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    let mint = unpack_mint(&mint_info.data.borrow())?;
+    if mint.mint_authority != Some(*config_pda).into()
+        || mint.decimals != DECIMALS
+        || mint.supply != 0
+    {
+        msg!(
+            "Initialize: mint {} can't be reused (supply {})",
+            mint_info.key,
+            mint.supply
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+    Ok(true)
+}
+
+/// Check an external mint `InitializeExisting` can build on
+///
+/// It must belong to `token_program` and still have a mint authority. Returns
+/// the mint and, unless the config PDA already holds the authority, the
+/// `mint_authority` account that has to sign it over (which must be the
+/// current authority and a signer).
+pub(crate) fn check_existing_mint<'a, 'b>(
+    config_pda: &Pubkey,
+    token_program: &Pubkey,
+    mint_info: &AccountInfo<'a>,
+    mint_authority: Option<&'b AccountInfo<'a>>,
+) -> Result<(Mint, Option<&'b AccountInfo<'a>>), ProgramError> {
+    if mint_info.data_is_empty() {
+        msg!("InitializeExisting: mint {} does not exist", mint_info.key);
+        return Err(YapError::InvalidMint.into());
+    }
+    if mint_info.owner != token_program {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    let mint = unpack_mint(&mint_info.data.borrow()).map_err(|_| {
+        msg!("InitializeExisting: {} is not a mint", mint_info.key);
+        YapError::InvalidMint
+    })?;
+
+    let current = match mint.mint_authority {
+        COption::Some(current) => current,
+        COption::None => {
+            msg!(
+                "InitializeExisting: mint {} has a fixed supply",
+                mint_info.key
+            );
+            return Err(YapError::InvalidMint.into());
+        }
+    };
+    if current == *config_pda {
+        return Ok((mint, None));
+    }
+
+    match mint_authority {
+        Some(authority) if *authority.key == current => {
+            if !authority.is_signer {
+                return Err(YapError::Unauthorized.into());
+            }
+            Ok((mint, Some(authority)))
+        }
+        _ => {
+            msg!(
+                "InitializeExisting: mint authority {} must sign the handover",
+                current
+            );
+            Err(YapError::InvalidMint.into())
+        }
+    }
+}
+
+/// Create the Config PDA, sized for `Config` and owned by the program
+fn create_config_account<'a>(
+    program_id: &Pubkey,
+    admin: &AccountInfo<'a>,
+    config_info: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+    config_bump: u8,
+) -> ProgramResult {
     msg!("Creating config account...");
     let config_space = Config::LEN;
     let config_lamports = rent.minimum_balance(config_space);
@@ -137,41 +596,240 @@ pub fn process(
         ),
         &[admin.clone(), config_info.clone(), system_program.clone()],
         &[&[Config::SEED, &[config_bump]]],
-    )?;
+    )
+}
+
+/// Verify the config account just created is program-owned, sized for
+/// `Config` and still zeroed before it is written
+fn check_config_created(program_id: &Pubkey, config_info: &AccountInfo) -> ProgramResult {
+    if config_info.owner != program_id {
+        return Err(YapError::InvalidOwner.into());
+    }
+    let data = config_info.try_borrow_data()?;
+    if data.starts_with(&CONFIG_DISCRIMINATOR) {
+        msg!("Initialize: config {} written during init", config_info.key);
+        return Err(YapError::AlreadyInitialized.into());
+    }
+    if data.len() != Config::LEN || data.iter().any(|&b| b != 0) {
+        msg!("Initialize: config is not a blank Config account");
+        return Err(YapError::InvalidDiscriminator.into());
+    }
+    Ok(())
+}
 
-    // 2. Create mint account
-    msg!("Creating mint account...");
-    let mint_space = Mint::LEN;
-    let mint_lamports = rent.minimum_balance(mint_space);
+/// Parameters `Initialize` and `InitializeExisting` share
+struct InitParams {
+    merkle_updater: Pubkey,
+    inflation_rate_bps: u16,
+    max_supply: u64,
+    min_distribution_interval: i64,
+    rate_period_secs: i64,
+    /// Folded into this deployment's claim leaves
+    domain_salt: [u8; DOMAIN_SALT_LEN],
+}
 
-    invoke_signed(
-        &system_instruction::create_account(
-            admin.key,
-            mint_info.key,
-            mint_lamports,
-            mint_space as u64,
-            &spl_token::id(),
-        ),
-        &[admin.clone(), mint_info.clone(), system_program.clone()],
-        &[&[MINT_SEED, &[mint_bump]]],
-    )?;
+impl InitParams {
+    /// Range-check the parameters; `max_supply` must cover `initial_supply`
+    fn validate(&self, initial_supply: u64) -> ProgramResult {
+        // Validate inflation rate range (0-10000 bps = 0-100%)
+        if self.inflation_rate_bps > Config::MAX_INFLATION_BPS {
+            return Err(YapError::InvalidInstruction.into());
+        }
 
-    // 3. Initialize mint (authority = config PDA for trustless minting)
-    msg!("Initializing mint...");
-    invoke(
-        &spl_token::instruction::initialize_mint2(
-            &spl_token::id(),
-            mint_info.key,
-            &config_pda, // mint authority = config PDA
-            None,        // no freeze authority
-            DECIMALS,
-        )?,
-        &[mint_info.clone(), rent_info.clone(), token_program.clone()],
-    )?;
+        if self.max_supply < initial_supply {
+            msg!(
+                "max_supply {} is below initial supply {}",
+                self.max_supply,
+                initial_supply
+            );
+            return Err(YapError::InvalidInstruction.into());
+        }
+
+        if !(0..=Config::MAX_MIN_DISTRIBUTION_INTERVAL).contains(&self.min_distribution_interval) {
+            msg!(
+                "min_distribution_interval {} out of range",
+                self.min_distribution_interval
+            );
+            return Err(YapError::InvalidInstruction.into());
+        }
 
+        // Both accrual formulas divide by the period
+        if self.rate_period_secs <= 0 {
+            msg!(
+                "rate_period_secs {} must be positive",
+                self.rate_period_secs
+            );
+            return Err(YapError::InvalidInstruction.into());
+        }
+
+        Ok(())
+    }
+
+    /// The Config a fresh deployment starts from, accruing from `now`
+    fn initial_config(
+        &self,
+        config_bump: u8,
+        admin: Pubkey,
+        addresses: Addresses,
+        current_supply: u64,
+        decimals: u8,
+        now: i64,
+    ) -> Config {
+        Config {
+            discriminator: CONFIG_DISCRIMINATOR,
+            version: CONFIG_VERSION,
+            bump: config_bump,
+            paused: false,
+            roles: Roles {
+                admin,
+                pending_admin: Pubkey::default(),
+                merkle_updater: self.merkle_updater,
+                admin_renounced: false,
+                updater_keys: [Pubkey::default(); MAX_UPDATER_KEYS],
+                updater_threshold: 0,
+            },
+            addresses,
+            tokenomics: Tokenomics {
+                current_supply,
+                max_supply: self.max_supply,
+                inflation_rate_bps: self.inflation_rate_bps,
+                compound_inflation: false,
+                undistributed_inflation: 0,
+                inflation_burn_bps: 0,
+                burn_reward_bps: 0,
+                permissionless_inflation: false,
+                keeper_reward_bps: 0,
+                scheduled_burn_amount: 0,
+                scheduled_burn_ts: 0,
+                treasury_inflation_bps: 0,
+                halving_interval: 0,
+                halving_count_cap: 0,
+                decimals,
+                inflation_remainder: 0,
+                total_inflation_minted: 0,
+            },
+            timing: Timing {
+                last_inflation_ts: now,    // inflation accrues from now
+                last_distribution_ts: now, // distribution accrues from now
+                min_distribution_interval: self.min_distribution_interval,
+                genesis_ts: now, // halvings count from now
+                claim_window: 0,
+                claim_deadline_ts: 0,
+                vesting_start_ts: 0,
+                vesting_cliff_ts: 0,
+                vesting_duration: 0,
+                rate_period_secs: self.rate_period_secs,
+                distribution_timelock: 0,
+            },
+            distribution: Distribution {
+                merkle_root: [0u8; 32], // empty initially
+                bonus_root: [0u8; 32],  // bonus tree disabled initially
+                distribution_id: 0,
+                round_history: [RoundRecord::default(); ROUND_HISTORY_LEN],
+                rounds_recorded: 0,
+                total_distributed: 0,
+                total_claimed: 0,
+                early_bird_bonus: 0,
+                early_bird_reserved: 0,
+                early_bird_round: 0,
+                accrued_allocation: 0,
+                tree_depth: 0,
+                category_roots: [[0; 32]; MAX_CATEGORIES],
+                recent_roots: [[0; 32]; RECENT_ROOTS_LEN],
+                last_distribution_nonce: 0,
+                category_bps: [0; MAX_CATEGORIES],
+                bonus_reserved: 0,
+                category_accrued: [0; MAX_CATEGORIES],
+                recent_root_depths: [0; RECENT_ROOTS_LEN],
+                category_root_depths: [0; MAX_CATEGORIES],
+                bonus_tree_depth: 0,
+                round_depths: [0; ROUND_HISTORY_LEN],
+            },
+            claims: ClaimRules {
+                leaf_has_expiry: false,
+                strict_ata_check: false,
+                min_proof_depth: 0,
+                total_claim_accounts: 0,
+                max_claim_accounts: u64::MAX,
+                claim_cooldown: 0,
+                frozen_wallets: 0,
+                max_claim_per_wallet: 0,
+                domain_salt: self.domain_salt,
+                max_single_claim: 0,
+                claim_fee_bps: 0,
+            },
+        }
+    }
+}
+
+/// Check the system program, a supported token program and the rent sysvar
+fn validate_init_programs(
+    system_program: &AccountInfo,
+    token_program: &AccountInfo,
+    rent_info: &AccountInfo,
+) -> ProgramResult {
+    if *system_program.key != solana_system_interface::program::id() {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    if !is_supported_token_program(token_program.key) {
+        msg!("Unsupported token program {}", token_program.key);
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    if *rent_info.key != solana_program::sysvar::rent::ID {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    Ok(())
+}
+
+/// Verify the vault and pending_claims PDAs, returning their bumps
+fn check_custody_pdas(
+    program_id: &Pubkey,
+    vault_info: &AccountInfo,
+    pending_claims_info: &AccountInfo,
+) -> Result<(u8, u8), ProgramError> {
+    let (vault_pda, vault_bump) = Pubkey::find_program_address(&[VAULT_SEED], program_id);
+    let (pending_claims_pda, pending_claims_bump) =
+        Pubkey::find_program_address(&[PENDING_CLAIMS_SEED], program_id);
+
+    if vault_info.key != &vault_pda {
+        msg!(
+            "Invalid Vault PDA: expected {}, got {}",
+            vault_pda,
+            vault_info.key
+        );
+        return Err(YapError::InvalidPda.into());
+    }
+    if pending_claims_info.key != &pending_claims_pda {
+        msg!(
+            "Invalid Pending Claims PDA: expected {}, got {}",
+            pending_claims_pda,
+            pending_claims_info.key
+        );
+        return Err(YapError::InvalidPda.into());
+    }
+
+    Ok((vault_bump, pending_claims_bump))
+}
+
+/// Create the vault and pending_claims token accounts for `mint_info`, both owned by the config PDA
+#[allow(clippy::too_many_arguments)]
+fn create_custody_accounts<'a>(
+    admin: &AccountInfo<'a>,
+    config_pda: &Pubkey,
+    mint_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    pending_claims_info: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    rent: &Rent,
+    (vault_bump, pending_claims_bump): (u8, u8),
+) -> ProgramResult {
     // 4. Create vault token account
     msg!("Creating vault account...");
-    let vault_space = TokenAccount::LEN;
+    let vault_space = token_account_space(token_program.key)?;
     let vault_lamports = rent.minimum_balance(vault_space);
 
     invoke_signed(
@@ -180,7 +838,7 @@ pub fn process(
             vault_info.key,
             vault_lamports,
             vault_space as u64,
-            &spl_token::id(),
+            token_program.key,
         ),
         &[admin.clone(), vault_info.clone(), system_program.clone()],
         &[&[VAULT_SEED, &[vault_bump]]],
@@ -188,19 +846,21 @@ pub fn process(
 
     // 5. Initialize vault (owner = config PDA for trustless transfers)
     msg!("Initializing vault...");
+    initialize_immutable_owner(token_program, vault_info)?;
     invoke(
-        &spl_token::instruction::initialize_account3(
-            &spl_token::id(),
+        &token_instruction::initialize_account3(
+            token_program.key,
             vault_info.key,
             mint_info.key,
-            &config_pda, // owner = config PDA
+            config_pda, // owner = config PDA
         )?,
         &[vault_info.clone(), mint_info.clone(), token_program.clone()],
     )?;
+    check_created_layout::<TokenAccount>("vault", &vault_info.data.borrow(), vault_space)?;
 
     // 6. Create pending_claims token account
     msg!("Creating pending_claims account...");
-    let pending_claims_space = TokenAccount::LEN;
+    let pending_claims_space = token_account_space(token_program.key)?;
     let pending_claims_lamports = rent.minimum_balance(pending_claims_space);
 
     invoke_signed(
@@ -209,115 +869,55 @@ pub fn process(
             pending_claims_info.key,
             pending_claims_lamports,
             pending_claims_space as u64,
-            &spl_token::id(),
+            token_program.key,
         ),
-        &[admin.clone(), pending_claims_info.clone(), system_program.clone()],
+        &[
+            admin.clone(),
+            pending_claims_info.clone(),
+            system_program.clone(),
+        ],
         &[&[PENDING_CLAIMS_SEED, &[pending_claims_bump]]],
     )?;
 
     // 7. Initialize pending_claims (owner = config PDA for trustless transfers)
     msg!("Initializing pending_claims...");
+    initialize_immutable_owner(token_program, pending_claims_info)?;
     invoke(
-        &spl_token::instruction::initialize_account3(
-            &spl_token::id(),
+        &token_instruction::initialize_account3(
+            token_program.key,
             pending_claims_info.key,
             mint_info.key,
-            &config_pda, // owner = config PDA
-        )?,
-        &[pending_claims_info.clone(), mint_info.clone(), token_program.clone()],
-    )?;
-
-    // 8. Mint initial supply to vault (mint_to_checked validates decimals)
-    msg!("Minting {} tokens to vault...", INITIAL_SUPPLY);
-    invoke_signed(
-        &spl_token::instruction::mint_to_checked(
-            &spl_token::id(),
-            mint_info.key,
-            vault_info.key,
-            &config_pda, // mint authority
-            &[],
-            INITIAL_SUPPLY,
-            DECIMALS,
+            config_pda, // owner = config PDA
         )?,
         &[
+            pending_claims_info.clone(),
             mint_info.clone(),
-            vault_info.clone(),
-            config_info.clone(),
             token_program.clone(),
         ],
-        &[&[Config::SEED, &[config_bump]]],
     )?;
-
-    // 9. Create token metadata via CPI to Metaplex
-    // Using raw invoke_signed to avoid SDK version conflicts
-    msg!("Creating token metadata via Metaplex CPI...");
-    msg!("  Metadata account: {}", metadata_info.key);
-    msg!("  Mint authority: {}", config_pda);
-    msg!("  Update authority: {}", admin.key);
-
-    let create_metadata_ix = build_create_metadata_v3_instruction(
-        metadata_info.key,
-        mint_info.key,
-        &config_pda,        // mint authority (Config PDA)
-        admin.key,          // payer
-        admin.key,          // update authority
-    );
-
-    invoke_signed(
-        &create_metadata_ix,
-        &[
-            metadata_info.clone(),
-            mint_info.clone(),
-            config_info.clone(),
-            admin.clone(),
-            system_program.clone(),
-            rent_info.clone(),
-        ],
-        &[&[Config::SEED, &[config_bump]]],
-    ).map_err(|e| {
-        msg!("Metaplex CPI failed: {:?}", e);
-        msg!("This may indicate insufficient rent or invalid authorities");
-        e
-    })?;
-
-    msg!("Token metadata created successfully");
-
-    // 10. Write config data
-    msg!("Writing config data...");
-
-    let clock = Clock::get()?;
-    let now = clock.unix_timestamp;
-
-    let config = Config {
-        discriminator: CONFIG_DISCRIMINATOR,
-        mint: *mint_info.key,
-        vault: *vault_info.key,
-        pending_claims: *pending_claims_info.key,
-        merkle_root: [0u8; 32], // empty initially
-        merkle_updater,
-        current_supply: INITIAL_SUPPLY,
-        last_inflation_ts: now,      // inflation accrues from now
-        last_distribution_ts: now,   // distribution accrues from now
-        admin: *admin.key,
-        inflation_rate_bps,
-        bump: config_bump,
-    };
-
-    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
-
-    msg!("Initialize complete!");
-    msg!("  Config: {}", config_info.key);
-    msg!("  Mint: {}", mint_info.key);
-    msg!("  Metadata: {}", metadata_info.key);
-    msg!("  Vault: {}", vault_info.key);
-    msg!("  Pending Claims: {}", pending_claims_info.key);
-    msg!("  Supply: {}", INITIAL_SUPPLY);
-    msg!("  Token Name: {}", TOKEN_NAME);
-    msg!("  Token Symbol: {}", TOKEN_SYMBOL);
+    check_created_layout::<TokenAccount>(
+        "pending_claims",
+        &pending_claims_info.data.borrow(),
+        pending_claims_space,
+    )?;
 
     Ok(())
 }
 
+/// Token-2022 only: mark a not yet initialized token account's owner as immutable
+pub(crate) fn initialize_immutable_owner<'a>(
+    token_program: &AccountInfo<'a>,
+    token_account: &AccountInfo<'a>,
+) -> ProgramResult {
+    if *token_program.key != TOKEN_2022_PROGRAM_ID {
+        return Ok(());
+    }
+    invoke(
+        &token_instruction::initialize_immutable_owner(token_program.key, token_account.key)?,
+        &[token_account.clone(), token_program.clone()],
+    )
+}
+
 /// Build CreateMetadataAccountV3 instruction manually
 /// This avoids SDK version conflicts between mpl-token-metadata and solana-program
 ///
@@ -392,3 +992,176 @@ fn build_create_metadata_v3_instruction(
         data,
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAccount;
+    use solana_program::program_pack::Pack;
+
+    /// A project's own mint, created outside YAP
+    fn external_mint(authority: Option<Pubkey>, supply: u64) -> TestAccount {
+        let mint = spl_token::state::Mint {
+            mint_authority: authority.into(),
+            supply,
+            decimals: 6,
+            is_initialized: true,
+            freeze_authority: None.into(),
+        };
+        let mut data = vec![0u8; spl_token::state::Mint::LEN];
+        spl_token::state::Mint::pack(mint, &mut data).unwrap();
+        TestAccount {
+            owner: spl_token::id(),
+            data,
+            ..TestAccount::new(Pubkey::new_unique())
+        }
+    }
+
+    #[test]
+    fn test_squatted_config_rejected() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+
+        // Nothing there yet: init may proceed
+        let mut fresh = TestAccount::new(config_pda);
+        check_config_unclaimed(&fresh.info()).unwrap();
+
+        // Zero-filled account of the right size can't be taken over
+        let mut squatted = TestAccount {
+            owner: program_id,
+            data: vec![0u8; Config::LEN],
+            ..TestAccount::new(config_pda)
+        };
+        let err = check_config_unclaimed(&squatted.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidDiscriminator.into());
+
+        // A live config is reported as such
+        let mut live = TestAccount::config(&program_id, Config::default());
+        let err = check_config_unclaimed(&live.info()).unwrap_err();
+        assert_eq!(err, YapError::AlreadyInitialized.into());
+    }
+
+    #[test]
+    fn test_existing_mint_authority_handover() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+        let project_key = Pubkey::new_unique();
+        let mut mint = external_mint(Some(project_key), 5_000_000);
+        let token_program = spl_token::id();
+
+        // The current authority has to sign the handover
+        let err = check_existing_mint(&config_pda, &token_program, &mint.info(), None).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        let mut stranger = TestAccount::signer(Pubkey::new_unique());
+        let stranger = stranger.info();
+        let err = check_existing_mint(&config_pda, &token_program, &mint.info(), Some(&stranger))
+            .unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        let mut unsigned = TestAccount::new(project_key);
+        let unsigned = unsigned.info();
+        let err = check_existing_mint(&config_pda, &token_program, &mint.info(), Some(&unsigned))
+            .unwrap_err();
+        assert_eq!(err, YapError::Unauthorized.into());
+
+        let mut project = TestAccount::signer(project_key);
+        let project = project.info();
+        let mint_info = mint.info();
+        let (existing, handover) =
+            check_existing_mint(&config_pda, &token_program, &mint_info, Some(&project)).unwrap();
+        assert_eq!(handover.unwrap().key, &project_key);
+        assert_eq!((existing.supply, existing.decimals), (5_000_000, 6));
+
+        // Already handed over: no signer needed
+        let mut handed_over = external_mint(Some(config_pda), 5_000_000);
+        let (_, handover) =
+            check_existing_mint(&config_pda, &token_program, &handed_over.info(), None).unwrap();
+        assert!(handover.is_none());
+    }
+
+    #[test]
+    fn test_existing_mint_rejects_unusable_mints() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+
+        // Fixed supply: inflation could never mint
+        let mut fixed = external_mint(None, 5_000_000);
+        let err =
+            check_existing_mint(&config_pda, &spl_token::id(), &fixed.info(), None).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+
+        // Owned by a different token program than the one passed
+        let mut mint = external_mint(Some(config_pda), 5_000_000);
+        let err = check_existing_mint(&config_pda, &TOKEN_2022_PROGRAM_ID, &mint.info(), None)
+            .unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+
+        let mut missing = TestAccount::new(Pubkey::new_unique());
+        let err =
+            check_existing_mint(&config_pda, &spl_token::id(), &missing.info(), None).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+    }
+
+    #[test]
+    fn test_existing_mint_config_starts_from_its_supply() {
+        let params = InitParams {
+            merkle_updater: Pubkey::new_unique(),
+            inflation_rate_bps: 500,
+            max_supply: 10_000_000,
+            min_distribution_interval: 0,
+            rate_period_secs: 86_400,
+            domain_salt: [7; DOMAIN_SALT_LEN],
+        };
+        let mint = Pubkey::new_unique();
+
+        // Circulating tokens count towards the cap
+        params.validate(5_000_000).unwrap();
+        assert_eq!(
+            params.validate(10_000_001).unwrap_err(),
+            YapError::InvalidInstruction.into()
+        );
+
+        let addresses = Addresses {
+            mint,
+            ..Addresses::default()
+        };
+        let config = params.initial_config(254, Pubkey::new_unique(), addresses, 5_000_000, 6, 77);
+        assert!(config.is_valid());
+        assert_eq!(config.addresses.mint, mint);
+        assert_eq!(config.tokenomics.current_supply, 5_000_000);
+        assert_eq!(config.tokenomics.decimals, 6);
+        assert_eq!(config.tokenomics.max_supply, 10_000_000);
+        assert_eq!(config.timing.last_inflation_ts, 77);
+        assert_eq!(config.distribution.merkle_root, [0; 32]);
+        assert_eq!(config.claims.domain_salt, [7; DOMAIN_SALT_LEN]);
+    }
+
+    #[test]
+    fn test_created_config_must_be_blank() {
+        let program_id = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+
+        let mut created = TestAccount {
+            owner: program_id,
+            data: vec![0u8; Config::LEN],
+            ..TestAccount::new(config_pda)
+        };
+        check_config_created(&program_id, &created.info()).unwrap();
+
+        created.data[Config::LEN - 1] = 1;
+        let err = check_config_created(&program_id, &created.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidDiscriminator.into());
+
+        let mut live = TestAccount::config(&program_id, Config::default());
+        let err = check_config_created(&program_id, &live.info()).unwrap_err();
+        assert_eq!(err, YapError::AlreadyInitialized.into());
+
+        let mut foreign = TestAccount {
+            data: vec![0u8; Config::LEN],
+            ..TestAccount::new(config_pda)
+        };
+        let err = check_config_created(&program_id, &foreign.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+}
@@ -1,6 +1,26 @@
 pub mod admin;
+pub mod batch_claim;
 pub mod burn;
+pub mod burn_reward;
 pub mod claim;
+pub mod claim_category;
+pub mod claim_delegate;
+pub mod claim_epoch;
+pub mod claim_indexed;
+pub mod close_claim;
+#[cfg(feature = "devnet")]
+pub mod close_program;
 pub mod distribute;
+pub mod get_claimable;
+pub mod history;
 pub mod initialize;
+pub mod proof_buffer;
+pub mod queued_distribution;
+pub mod rebase;
+pub mod rescue;
+pub mod stake;
+pub mod sweep_unclaimed;
+#[cfg(feature = "test-time")]
+pub mod test_time;
 pub mod trigger_inflation;
+pub mod update_metadata;
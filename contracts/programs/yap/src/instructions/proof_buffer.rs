@@ -0,0 +1,273 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::SysvarSerialize,
+};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{
+    error::YapError,
+    instructions::claim::validate_claim_sysvars,
+    state::{ProofBuffer, MAX_PROOF_DEPTH, PROOF_BUFFER_DISCRIMINATOR},
+};
+
+/// Write proof nodes into the signer's ProofBuffer, creating it if needed
+///
+/// `nodes` replace everything from `offset` on, so a long proof can be
+/// written over several transactions and a fresh one starts at offset 0.
+///
+/// Accounts:
+/// 0. `[signer, writable]` User (pays for the ProofBuffer PDA if new)
+/// 1. `[writable]` ProofBuffer PDA
+/// 2. `[]` System program
+/// 3. `[]` Rent sysvar
+pub fn process_write(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    offset: u8,
+    nodes: Vec<[u8; 32]>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let buffer_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let end = offset as usize + nodes.len();
+    if end > MAX_PROOF_DEPTH {
+        msg!(
+            "WriteProofBuffer: Proof too long ({} > {})",
+            end,
+            MAX_PROOF_DEPTH
+        );
+        return Err(YapError::ProofTooLong.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let (buffer_pda, bump) =
+        Pubkey::find_program_address(&[ProofBuffer::SEED, user.key.as_ref()], program_id);
+    if buffer_info.key != &buffer_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    let mut buffer = if buffer_info.data_is_empty() {
+        let space = ProofBuffer::LEN;
+        let lamports = Rent::from_account_info(rent_info)?.minimum_balance(space);
+        invoke_signed(
+            &system_instruction::create_account(
+                user.key,
+                buffer_info.key,
+                lamports,
+                space as u64,
+                program_id,
+            ),
+            &[user.clone(), buffer_info.clone(), system_program.clone()],
+            &[&[ProofBuffer::SEED, user.key.as_ref(), &[bump]]],
+        )?;
+        ProofBuffer {
+            discriminator: PROOF_BUFFER_DISCRIMINATOR,
+            user: *user.key,
+            bump,
+            ..ProofBuffer::default()
+        }
+    } else {
+        load_proof_buffer(program_id, user.key, buffer_info)?
+    };
+
+    if offset > buffer.len {
+        msg!(
+            "WriteProofBuffer: Offset {} leaves a gap after {} nodes",
+            offset,
+            buffer.len
+        );
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    buffer.nodes[offset as usize..end].copy_from_slice(&nodes);
+    buffer.nodes[end..].fill([0; 32]);
+    buffer.len = end as u8;
+    buffer.serialize(&mut &mut buffer_info.data.borrow_mut()[..])?;
+
+    msg!("WriteProofBuffer: {} nodes for {}", end, user.key);
+
+    Ok(())
+}
+
+/// Read the proof `user` wrote into their ProofBuffer
+pub(crate) fn load_proof(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    buffer_info: &AccountInfo,
+) -> Result<Vec<[u8; 32]>, ProgramError> {
+    let buffer = load_proof_buffer(program_id, user, buffer_info)?;
+    Ok(buffer.proof().to_vec())
+}
+
+/// Zero the stored proof once it has been used
+pub(crate) fn clear_proof(buffer_info: &AccountInfo) -> ProgramResult {
+    let mut buffer = ProofBuffer::try_from_slice(&buffer_info.data.borrow())?;
+    buffer.len = 0;
+    buffer.nodes = [[0; 32]; MAX_PROOF_DEPTH];
+    buffer.serialize(&mut &mut buffer_info.data.borrow_mut()[..])?;
+    Ok(())
+}
+
+/// Load `user`'s ProofBuffer PDA; it must be theirs and program-owned
+fn load_proof_buffer(
+    program_id: &Pubkey,
+    user: &Pubkey,
+    buffer_info: &AccountInfo,
+) -> Result<ProofBuffer, ProgramError> {
+    let (buffer_pda, _) =
+        Pubkey::find_program_address(&[ProofBuffer::SEED, user.as_ref()], program_id);
+    if buffer_info.key != &buffer_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    if buffer_info.data_is_empty() {
+        msg!("Claim: {} has no proof buffer", user);
+        return Err(YapError::NotInitialized.into());
+    }
+    if buffer_info.owner != program_id {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    let buffer = ProofBuffer::try_from_slice(&buffer_info.data.borrow())?;
+    if !buffer.is_valid() || buffer.user != *user {
+        return Err(YapError::InvalidDiscriminator.into());
+    }
+    Ok(buffer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::claim::verify_claim_proof;
+    use crate::state::Config;
+    use crate::test_utils::TestAccount;
+    use crate::utils::merkle::{compute_leaf, NODE_PREFIX};
+    use solana_program::keccak;
+
+    fn buffer_account(program_id: &Pubkey, user: &Pubkey) -> TestAccount {
+        let (pda, bump) =
+            Pubkey::find_program_address(&[ProofBuffer::SEED, user.as_ref()], program_id);
+        let buffer = ProofBuffer {
+            discriminator: PROOF_BUFFER_DISCRIMINATOR,
+            user: *user,
+            bump,
+            ..ProofBuffer::default()
+        };
+        TestAccount {
+            owner: *program_id,
+            data: borsh::to_vec(&buffer).unwrap(),
+            ..TestAccount::new(pda)
+        }
+    }
+
+    #[test]
+    fn test_deep_proof_claims_through_buffer() {
+        let program_id = Pubkey::new_unique();
+        let user_key = Pubkey::new_unique();
+        let mut user = TestAccount::signer(user_key);
+        let mut buffer = buffer_account(&program_id, &user_key);
+        let mut system = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        // A 30-level proof (960 bytes) for the user's leaf
+        let leaf = compute_leaf(&user_key, 1_000);
+        let proof: Vec<[u8; 32]> = (0..30u8).map(|i| [i; 32]).collect();
+        let root = proof.iter().fold(leaf, |node, sibling| {
+            let (left, right) = if node <= *sibling {
+                (node, *sibling)
+            } else {
+                (*sibling, node)
+            };
+            keccak::hashv(&[&[NODE_PREFIX], &left, &right]).to_bytes()
+        });
+
+        // Written in two transactions
+        for (offset, chunk) in [(0u8, &proof[..16]), (16, &proof[16..])] {
+            let accounts = [user.info(), buffer.info(), system.info(), rent.info()];
+            process_write(&program_id, &accounts, offset, chunk.to_vec()).unwrap();
+        }
+
+        let mut config = Config::default();
+        config.distribution.merkle_root = root;
+        config.distribution.tree_depth = 30;
+
+        let loaded = load_proof(&program_id, &user_key, &buffer.info()).unwrap();
+        assert_eq!(loaded, proof);
+        verify_claim_proof(&config, &user_key, 1_000, None, &loaded).unwrap();
+
+        // Consumed: zeroed, nothing left to replay
+        clear_proof(&buffer.info()).unwrap();
+        assert!(load_proof(&program_id, &user_key, &buffer.info())
+            .unwrap()
+            .is_empty());
+        assert!(buffer.data[8 + 32 + 1..].iter().all(|&b| b == 0));
+    }
+
+    #[test]
+    fn test_buffer_bound_to_its_user() {
+        let program_id = Pubkey::new_unique();
+        let owner = Pubkey::new_unique();
+        let mut buffer = buffer_account(&program_id, &owner);
+
+        // Another wallet can't read (or claim with) someone else's buffer
+        let other = Pubkey::new_unique();
+        let err = load_proof(&program_id, &other, &buffer.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+
+        // ... nor write into it
+        let mut signer = TestAccount::signer(other);
+        let mut system = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+        let accounts = [signer.info(), buffer.info(), system.info(), rent.info()];
+        let err = process_write(&program_id, &accounts, 0, vec![[1; 32]]).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+
+        // Foreign-owned data at the right address is rejected
+        buffer.owner = Pubkey::new_unique();
+        let err = load_proof(&program_id, &owner, &buffer.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_write_rejects_gaps_and_overlong_proofs() {
+        let program_id = Pubkey::new_unique();
+        let user_key = Pubkey::new_unique();
+        let mut user = TestAccount::signer(user_key);
+        let mut buffer = buffer_account(&program_id, &user_key);
+        let mut system = TestAccount::new(solana_system_interface::program::id());
+        let mut rent = TestAccount::new(solana_program::sysvar::rent::ID);
+
+        let mut write = |offset: u8, len: usize| {
+            let accounts = [user.info(), buffer.info(), system.info(), rent.info()];
+            process_write(&program_id, &accounts, offset, vec![[7; 32]; len])
+        };
+
+        let err = write(1, 1).unwrap_err();
+        assert_eq!(err, YapError::InvalidInstruction.into());
+        let err = write(0, MAX_PROOF_DEPTH + 1).unwrap_err();
+        assert_eq!(err, YapError::ProofTooLong.into());
+
+        // Rewriting from an earlier offset truncates the rest
+        write(0, 4).unwrap();
+        write(2, 1).unwrap();
+        let loaded = load_proof(&program_id, &user_key, &buffer.info()).unwrap();
+        assert_eq!(loaded.len(), 3);
+    }
+}
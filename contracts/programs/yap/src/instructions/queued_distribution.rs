@@ -0,0 +1,356 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::{Sysvar, SysvarSerialize},
+};
+use solana_system_interface::instruction as system_instruction;
+
+use crate::{
+    error::YapError,
+    instructions::{
+        admin::load_config_for_admin, claim::validate_claim_sysvars, distribute,
+        distribute::verify_updater_signers,
+    },
+    state::{Config, PendingDistribution, PENDING_DISTRIBUTION_DISCRIMINATOR},
+    utils::account::close_account,
+};
+
+/// Queue a distribution that can only execute once `now >= eta`
+///
+/// Gives holders time to inspect the new root before it goes live. Only one
+/// distribution can be queued at a time.
+///
+/// Accounts:
+/// 0. `[signer, writable]` Merkle updater (pays for the PendingDistribution PDA)
+/// 1. `[]` Config PDA
+/// 2. `[writable]` PendingDistribution PDA
+/// 3. `[]` System program
+/// 4. `[]` Rent sysvar
+/// 5. `[signer]` (with an updater set) Co-signers from `updater_keys`
+pub fn process_queue(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+    eta: i64,
+    tree_depth: u8,
+) -> ProgramResult {
+    distribute::validate_tree_depth(tree_depth)?;
+
+    let account_info_iter = &mut accounts.iter();
+
+    let updater = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+    let co_signers = account_info_iter.as_slice();
+
+    // Verify updater is signer
+    if !updater.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let config = Config::try_load(program_id, config_info)?;
+
+    // Verify caller is authorized merkle updater (or a quorum of the updater set)
+    verify_updater_signers(&config.roles, updater, co_signers)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    check_eta(eta, now, config.timing.distribution_timelock)?;
+
+    let (pending_pda, pending_bump) =
+        Pubkey::find_program_address(&[PendingDistribution::SEED], program_id);
+    if pending_info.key != &pending_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if !pending_info.data_is_empty() {
+        msg!("QueueDistribution: A distribution is already queued");
+        return Err(YapError::AlreadyInitialized.into());
+    }
+
+    let rent = Rent::from_account_info(rent_info)?;
+    invoke_signed(
+        &system_instruction::create_account(
+            updater.key,
+            pending_info.key,
+            rent.minimum_balance(PendingDistribution::LEN),
+            PendingDistribution::LEN as u64,
+            program_id,
+        ),
+        &[
+            updater.clone(),
+            pending_info.clone(),
+            system_program.clone(),
+        ],
+        &[&[PendingDistribution::SEED, &[pending_bump]]],
+    )?;
+
+    let pending = PendingDistribution {
+        discriminator: PENDING_DISTRIBUTION_DISCRIMINATOR,
+        amount,
+        merkle_root,
+        eta,
+        bump: pending_bump,
+        tree_depth,
+    };
+    pending.serialize(&mut &mut pending_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "QueueDistribution: amount={}, root={:?}, eta={}, depth={}",
+        amount,
+        merkle_root,
+        eta,
+        tree_depth
+    );
+
+    Ok(())
+}
+
+/// Execute the queued distribution once its eta has passed
+///
+/// `amount` and `merkle_root` must repeat the queued values. The distribution
+/// then runs exactly like `Distribute` (rate limit, interval, updater
+/// signatures) with the queued `tree_depth`, and the PendingDistribution PDA
+/// is closed to account 0.
+///
+/// Accounts: same as `Distribute`, with the PendingDistribution PDA before the
+/// optional clock:
+/// 6. `[writable]` PendingDistribution PDA
+pub fn process_execute(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    merkle_root: [u8; 32],
+) -> ProgramResult {
+    let updater = accounts.first().ok_or(ProgramError::NotEnoughAccountKeys)?;
+    let pending_info = accounts.get(6).ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let pending = load_pending(program_id, pending_info)?;
+    let now = Clock::get()?.unix_timestamp;
+    check_queued_distribution(&pending, amount, &merkle_root, now)?;
+
+    // Everything but the PDA is laid out as `Distribute` expects
+    let distribute_accounts: Vec<AccountInfo> = accounts[..6]
+        .iter()
+        .chain(&accounts[7..])
+        .cloned()
+        .collect();
+    distribute::process_queued(
+        program_id,
+        &distribute_accounts,
+        amount,
+        merkle_root,
+        pending.tree_depth,
+    )?;
+
+    close_account(pending_info, updater)?;
+
+    msg!("ExecuteDistribution: Queued distribution executed");
+
+    Ok(())
+}
+
+/// Drop the queued distribution (admin only)
+///
+/// Accounts:
+/// 0. `[signer, writable]` Admin (receives the PDA's rent)
+/// 1. `[]` Config PDA
+/// 2. `[writable]` PendingDistribution PDA
+pub fn process_cancel(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_info = next_account_info(account_info_iter)?;
+
+    load_config_for_admin(program_id, admin, config_info)?;
+
+    let pending = load_pending(program_id, pending_info)?;
+
+    close_account(pending_info, admin)?;
+
+    msg!(
+        "CancelDistribution: Dropped amount={}, eta={}",
+        pending.amount,
+        pending.eta
+    );
+
+    Ok(())
+}
+
+/// Load the PendingDistribution PDA, failing with `NotInitialized` if nothing is queued
+fn load_pending(
+    program_id: &Pubkey,
+    pending_info: &AccountInfo,
+) -> Result<PendingDistribution, ProgramError> {
+    let (pending_pda, _) = Pubkey::find_program_address(&[PendingDistribution::SEED], program_id);
+    if pending_info.key != &pending_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if pending_info.owner != program_id || pending_info.data_is_empty() {
+        msg!("PendingDistribution: Nothing queued");
+        return Err(YapError::NotInitialized.into());
+    }
+
+    // Queued before `tree_depth` was added: loads with depth 0, which executing
+    // rejects, so it can only be cancelled and queued again
+    let mut data = pending_info.data.borrow().to_vec();
+    if data.len() < PendingDistribution::LEN {
+        data.resize(PendingDistribution::LEN, 0);
+    }
+    let pending = PendingDistribution::try_from_slice(&data)?;
+    if !pending.is_valid() {
+        return Err(YapError::InvalidDiscriminator.into());
+    }
+    Ok(pending)
+}
+
+/// Reject an eta that isn't in the future or comes sooner than the configured timelock
+fn check_eta(eta: i64, now: i64, timelock: i64) -> Result<(), YapError> {
+    let earliest = now.saturating_add(timelock.max(1));
+    if eta < earliest {
+        msg!(
+            "QueueDistribution: eta {} is before {} (now={}, timelock={}s)",
+            eta,
+            earliest,
+            now,
+            timelock
+        );
+        return Err(YapError::InvalidInstruction);
+    }
+    Ok(())
+}
+
+/// Reject execution before `eta` or with parameters other than the queued ones
+fn check_queued_distribution(
+    pending: &PendingDistribution,
+    amount: u64,
+    merkle_root: &[u8; 32],
+    now: i64,
+) -> Result<(), YapError> {
+    if amount != pending.amount || merkle_root != &pending.merkle_root {
+        msg!(
+            "ExecuteDistribution: Expected amount={}, root={:?}",
+            pending.amount,
+            pending.merkle_root
+        );
+        return Err(YapError::PendingDistributionMismatch);
+    }
+
+    if now < pending.eta {
+        msg!(
+            "ExecuteDistribution: Not executable until {} (now={})",
+            pending.eta,
+            now
+        );
+        return Err(YapError::TimelockNotExpired);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAccount;
+
+    fn queued(amount: u64, merkle_root: [u8; 32], eta: i64) -> PendingDistribution {
+        PendingDistribution {
+            discriminator: PENDING_DISTRIBUTION_DISCRIMINATOR,
+            amount,
+            merkle_root,
+            eta,
+            bump: 255,
+            tree_depth: 3,
+        }
+    }
+
+    #[test]
+    fn test_queue_eta_must_cover_timelock() {
+        assert!(matches!(
+            check_eta(1_000, 1_000, 0),
+            Err(YapError::InvalidInstruction)
+        ));
+        check_eta(1_001, 1_000, 0).unwrap();
+
+        assert!(matches!(
+            check_eta(1_000 + 3_599, 1_000, 3_600),
+            Err(YapError::InvalidInstruction)
+        ));
+        check_eta(1_000 + 3_600, 1_000, 3_600).unwrap();
+    }
+
+    #[test]
+    fn test_queued_distribution_rejects_early_execution() {
+        let pending = queued(500, [7; 32], 1_000);
+        assert!(matches!(
+            check_queued_distribution(&pending, 500, &[7; 32], 999),
+            Err(YapError::TimelockNotExpired)
+        ));
+    }
+
+    #[test]
+    fn test_queued_distribution_executes_after_eta() {
+        let pending = queued(500, [7; 32], 1_000);
+        check_queued_distribution(&pending, 500, &[7; 32], 1_000).unwrap();
+        check_queued_distribution(&pending, 500, &[7; 32], 5_000).unwrap();
+    }
+
+    #[test]
+    fn test_queued_distribution_requires_matching_params() {
+        let pending = queued(500, [7; 32], 1_000);
+        for (amount, merkle_root) in [(501, [7; 32]), (500, [8; 32])] {
+            assert!(matches!(
+                check_queued_distribution(&pending, amount, &merkle_root, 5_000),
+                Err(YapError::PendingDistributionMismatch)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_load_pending_requires_queued_pda() {
+        let program_id = Pubkey::new_unique();
+        let (pending_pda, _) =
+            Pubkey::find_program_address(&[PendingDistribution::SEED], &program_id);
+
+        let mut empty = TestAccount::new(pending_pda);
+        assert_eq!(
+            load_pending(&program_id, &empty.info()).unwrap_err(),
+            YapError::NotInitialized.into()
+        );
+
+        let mut wrong = TestAccount::new(Pubkey::new_unique());
+        assert_eq!(
+            load_pending(&program_id, &wrong.info()).unwrap_err(),
+            YapError::InvalidPda.into()
+        );
+
+        let mut stored = TestAccount {
+            owner: program_id,
+            data: borsh::to_vec(&queued(500, [7; 32], 1_000)).unwrap(),
+            ..TestAccount::new(pending_pda)
+        };
+        let pending = load_pending(&program_id, &stored.info()).unwrap();
+        assert_eq!(pending.amount, 500);
+        assert_eq!(pending.eta, 1_000);
+        assert_eq!(pending.tree_depth, 3);
+
+        // Queued before the depth byte: loads (so it can be cancelled) with depth 0
+        stored.data.pop();
+        let pending = load_pending(&program_id, &stored.info()).unwrap();
+        assert_eq!((pending.amount, pending.tree_depth), (500, 0));
+        assert!(matches!(
+            distribute::validate_tree_depth(pending.tree_depth),
+            Err(YapError::InvalidInstruction)
+        ));
+    }
+}
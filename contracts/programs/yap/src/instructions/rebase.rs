@@ -0,0 +1,225 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+use spl_token_2022_interface::instruction as token_instruction;
+
+use crate::{
+    error::YapError,
+    state::Config,
+    utils::token::{unpack_token_account, validate_token_program},
+};
+
+/// Schedule a rebase burn (admin only)
+///
+/// Negative rebase: burns `amount` from the vault at a predictable time,
+/// reducing supply. Overwrites any pending schedule; `amount = 0` cancels it.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_schedule(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+    execute_ts: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    // Verify admin is signer
+    if !admin.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    // Verify caller is admin
+    if !config.is_admin(admin.key) {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    if amount == 0 {
+        msg!("ScheduleRebaseBurn: cancelled");
+        config.tokenomics.scheduled_burn_amount = 0;
+        config.tokenomics.scheduled_burn_ts = 0;
+    } else {
+        let now = Clock::get()?.unix_timestamp;
+        if execute_ts < now {
+            msg!(
+                "ScheduleRebaseBurn: execute_ts {} is in the past (now={})",
+                execute_ts,
+                now
+            );
+            return Err(YapError::InvalidInstruction.into());
+        }
+
+        msg!(
+            "ScheduleRebaseBurn: amount={}, execute_ts={}",
+            amount,
+            execute_ts
+        );
+        config.tokenomics.scheduled_burn_amount = amount;
+        config.tokenomics.scheduled_burn_ts = execute_ts;
+    }
+
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+/// Execute the scheduled rebase burn (permissionless once due)
+///
+/// Accounts:
+/// 0. `[signer]` Caller
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Mint PDA
+/// 3. `[writable]` Vault PDA
+/// 4. `[]` Token program
+pub fn process_execute(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let caller = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    if !caller.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    if mint_info.key != &config.addresses.mint {
+        return Err(YapError::InvalidMint.into());
+    }
+
+    if vault_info.key != &config.addresses.vault {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    let now = Clock::get()?.unix_timestamp;
+    let vault_balance = unpack_token_account(&vault_info.data.borrow())?.amount;
+    let amount = due_rebase_burn(&config, now, vault_balance)?;
+
+    msg!(
+        "ExecuteRebaseBurn: burning {} from vault (balance={})",
+        amount,
+        vault_balance
+    );
+
+    // Burn from vault (owner = config PDA)
+    invoke_signed(
+        &token_instruction::burn_checked(
+            &config.addresses.token_program,
+            vault_info.key,
+            mint_info.key,
+            config_info.key,
+            &[],
+            amount,
+            config.tokenomics.decimals,
+        )?,
+        &[
+            vault_info.clone(),
+            mint_info.clone(),
+            config_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[Config::SEED, &[config.bump]]],
+    )?;
+
+    config.tokenomics.current_supply = config
+        .tokenomics
+        .current_supply
+        .checked_sub(amount)
+        .ok_or(YapError::Overflow)?;
+    config.tokenomics.scheduled_burn_amount = 0;
+    config.tokenomics.scheduled_burn_ts = 0;
+
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    msg!(
+        "ExecuteRebaseBurn: new_supply={}",
+        config.tokenomics.current_supply
+    );
+
+    Ok(())
+}
+
+/// Return the scheduled burn amount if it is due and covered by the vault
+fn due_rebase_burn(config: &Config, now: i64, vault_balance: u64) -> Result<u64, YapError> {
+    if config.tokenomics.scheduled_burn_amount == 0 {
+        return Err(YapError::NoRebaseBurnScheduled);
+    }
+
+    if now < config.tokenomics.scheduled_burn_ts {
+        msg!(
+            "ExecuteRebaseBurn: not ready until {} (now={})",
+            config.tokenomics.scheduled_burn_ts,
+            now
+        );
+        return Err(YapError::RebaseBurnNotReady);
+    }
+
+    if config.tokenomics.scheduled_burn_amount > vault_balance {
+        return Err(YapError::InsufficientBalance);
+    }
+
+    Ok(config.tokenomics.scheduled_burn_amount)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Tokenomics;
+
+    fn scheduled(amount: u64, execute_ts: i64) -> Config {
+        Config {
+            tokenomics: Tokenomics {
+                scheduled_burn_amount: amount,
+                scheduled_burn_ts: execute_ts,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_rebase_burn_executes_when_due() {
+        let config = scheduled(500, 1_000);
+        assert_eq!(due_rebase_burn(&config, 1_000, 10_000).unwrap(), 500);
+        assert_eq!(due_rebase_burn(&config, 5_000, 10_000).unwrap(), 500);
+    }
+
+    #[test]
+    fn test_rebase_burn_rejects_early_execution() {
+        let config = scheduled(500, 1_000);
+        assert!(matches!(
+            due_rebase_burn(&config, 999, 10_000),
+            Err(YapError::RebaseBurnNotReady)
+        ));
+    }
+
+    #[test]
+    fn test_rebase_burn_rejects_unscheduled_and_underfunded() {
+        assert!(matches!(
+            due_rebase_burn(&Config::default(), 1_000, 10_000),
+            Err(YapError::NoRebaseBurnScheduled)
+        ));
+        assert!(matches!(
+            due_rebase_burn(&scheduled(500, 1_000), 1_000, 499),
+            Err(YapError::InsufficientBalance)
+        ));
+    }
+}
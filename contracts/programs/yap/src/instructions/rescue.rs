@@ -0,0 +1,278 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    program::invoke_signed,
+    pubkey::Pubkey,
+};
+use spl_token_2022_interface::instruction as token_instruction;
+
+use crate::{
+    error::YapError,
+    instructions::admin::load_config_for_admin,
+    state::{Config, ASSOCIATED_TOKEN_PROGRAM_ID, PENDING_CLAIMS_SEED, VAULT_SEED},
+    utils::{
+        return_data,
+        token::{is_supported_token_program, unpack_mint, unpack_token_account},
+    },
+};
+
+/// Transfer a foreign token out of a program PDA's ATA (admin only)
+///
+/// Tokens sent by mistake to the config, vault or pending_claims address end
+/// up in that PDA's ATA for the foreign mint, which only the program can sign
+/// for. The YAP mint is always refused, so this can never move distribution
+/// funds.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[]` Config PDA
+/// 2. `[]` PDA owning the source: config, vault or pending_claims
+/// 3. `[writable]` Source: that PDA's ATA for the foreign mint
+/// 4. `[writable]` Recipient token account for the foreign mint
+/// 5. `[]` Foreign mint
+/// 6. `[]` Token program owning the foreign mint
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let recipient = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let config = load_config_for_admin(program_id, admin, config_info)?;
+
+    // The foreign mint's own token program, which may differ from config's
+    if !is_supported_token_program(token_program.key) || mint_info.owner != token_program.key {
+        msg!(
+            "RescueTokens: Mint is not owned by token program {}",
+            token_program.key
+        );
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    let (seed, bump) = rescue_signer(
+        program_id,
+        &config,
+        owner_info.key,
+        source_info.key,
+        &source_info.data.borrow(),
+        mint_info.key,
+        token_program.key,
+    )?;
+    let decimals = unpack_mint(&mint_info.data.borrow())
+        .map_err(|_| YapError::InvalidMint)?
+        .decimals;
+
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            token_program.key,
+            source_info.key,
+            mint_info.key,
+            recipient.key,
+            owner_info.key, // source owner is the program PDA
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source_info.clone(),
+            mint_info.clone(),
+            recipient.clone(),
+            owner_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[seed, &[bump]]],
+    )?;
+
+    return_data::set_amount(amount);
+
+    msg!(
+        "RescueTokens: Moved {} of mint {} to {}",
+        amount,
+        mint_info.key,
+        recipient.key
+    );
+
+    Ok(())
+}
+
+/// Seed and bump `owner` signs with when moving out of `source`, its foreign-mint ATA
+///
+/// Rejects the YAP mint outright, an `owner` other than the config, vault or
+/// pending_claims PDA, and a source that isn't `owner`'s ATA for `mint`.
+fn rescue_signer(
+    program_id: &Pubkey,
+    config: &Config,
+    owner: &Pubkey,
+    source: &Pubkey,
+    source_data: &[u8],
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Result<(&'static [u8], u8), YapError> {
+    if *mint == config.addresses.mint {
+        msg!("RescueTokens: Refusing to move the YAP mint");
+        return Err(YapError::InvalidMint);
+    }
+
+    let signer = [Config::SEED, VAULT_SEED, PENDING_CLAIMS_SEED]
+        .into_iter()
+        .map(|seed| {
+            let (pda, bump) = Pubkey::find_program_address(&[seed], program_id);
+            (pda, seed, bump)
+        })
+        .find(|(pda, _, _)| pda == owner);
+    let Some((_, seed, bump)) = signer else {
+        msg!("RescueTokens: {} is not a program PDA", owner);
+        return Err(YapError::InvalidPda);
+    };
+
+    let account = unpack_token_account(source_data).map_err(|_| {
+        msg!("RescueTokens: Source account could not be unpacked");
+        YapError::UninitializedTokenAccount
+    })?;
+    if account.mint != *mint {
+        msg!("RescueTokens: Source holds a different mint");
+        return Err(YapError::InvalidMint);
+    }
+    if account.owner != *owner {
+        msg!("RescueTokens: Source not owned by {}", owner);
+        return Err(YapError::InvalidOwner);
+    }
+
+    let expected_ata = Pubkey::find_program_address(
+        &[owner.as_ref(), token_program.as_ref(), mint.as_ref()],
+        &ASSOCIATED_TOKEN_PROGRAM_ID,
+    )
+    .0;
+    if *source != expected_ata {
+        msg!(
+            "RescueTokens: Source is not the owner's ATA, expected {}",
+            expected_ata
+        );
+        return Err(YapError::InvalidTokenAccount);
+    }
+
+    Ok((seed, bump))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::Addresses;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::{Account as TokenAccount, AccountState};
+
+    /// `owner`'s ATA for `mint` holding `amount`, as (address, data)
+    fn ata(owner: &Pubkey, mint: &Pubkey, amount: u64) -> (Pubkey, Vec<u8>) {
+        let address = Pubkey::find_program_address(
+            &[owner.as_ref(), spl_token::id().as_ref(), mint.as_ref()],
+            &ASSOCIATED_TOKEN_PROGRAM_ID,
+        )
+        .0;
+        let mut data = vec![0u8; TokenAccount::LEN];
+        let account = TokenAccount {
+            mint: *mint,
+            owner: *owner,
+            amount,
+            state: AccountState::Initialized,
+            ..TokenAccount::default()
+        };
+        TokenAccount::pack(account, &mut data).unwrap();
+        (address, data)
+    }
+
+    fn config_with_mint(mint: Pubkey) -> Config {
+        Config {
+            addresses: Addresses {
+                mint,
+                ..Addresses::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_yap_mint_never_rescued() {
+        let program_id = Pubkey::new_unique();
+        let yap_mint = Pubkey::new_unique();
+        let config = config_with_mint(yap_mint);
+
+        for seed in [Config::SEED, VAULT_SEED, PENDING_CLAIMS_SEED] {
+            let owner = Pubkey::find_program_address(&[seed], &program_id).0;
+            let (source, data) = ata(&owner, &yap_mint, 1_000);
+            assert!(matches!(
+                rescue_signer(
+                    &program_id,
+                    &config,
+                    &owner,
+                    &source,
+                    &data,
+                    &yap_mint,
+                    &spl_token::id()
+                ),
+                Err(YapError::InvalidMint)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_foreign_mint_rescued_from_pda_ata() {
+        let program_id = Pubkey::new_unique();
+        let foreign_mint = Pubkey::new_unique();
+        let config = config_with_mint(Pubkey::new_unique());
+        let token_program = spl_token::id();
+
+        for seed in [Config::SEED, VAULT_SEED, PENDING_CLAIMS_SEED] {
+            let (owner, bump) = Pubkey::find_program_address(&[seed], &program_id);
+            let (source, data) = ata(&owner, &foreign_mint, 1_000);
+            let signer = rescue_signer(
+                &program_id,
+                &config,
+                &owner,
+                &source,
+                &data,
+                &foreign_mint,
+                &token_program,
+            )
+            .unwrap();
+            assert_eq!(signer, (seed, bump));
+        }
+
+        // Someone else's ATA isn't the program's to move
+        let stranger = Pubkey::new_unique();
+        let (source, data) = ata(&stranger, &foreign_mint, 1_000);
+        assert!(matches!(
+            rescue_signer(
+                &program_id,
+                &config,
+                &stranger,
+                &source,
+                &data,
+                &foreign_mint,
+                &token_program
+            ),
+            Err(YapError::InvalidPda)
+        ));
+
+        // A program-owned account that isn't the canonical ATA
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+        let (_, data) = ata(&config_pda, &foreign_mint, 1_000);
+        let not_ata = Pubkey::new_unique();
+        assert!(matches!(
+            rescue_signer(
+                &program_id,
+                &config,
+                &config_pda,
+                &not_ata,
+                &data,
+                &foreign_mint,
+                &token_program
+            ),
+            Err(YapError::InvalidTokenAccount)
+        ));
+    }
+}
@@ -0,0 +1,443 @@
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    program::{invoke, invoke_signed},
+    program_error::ProgramError,
+    pubkey::Pubkey,
+    rent::Rent,
+    sysvar::{Sysvar, SysvarSerialize},
+};
+use solana_system_interface::instruction as system_instruction;
+use spl_token_2022_interface::instruction as token_instruction;
+
+use crate::{
+    error::YapError,
+    events::{Event, StakeEvent, UnstakeEvent},
+    instructions::{
+        claim::{validate_claim_sysvars, validate_user_ata},
+        initialize::initialize_immutable_owner,
+    },
+    state::{
+        Config, StakeAccount, MIN_STAKE_DURATION, STAKE_ACCOUNT_DISCRIMINATOR, STAKE_VAULT_SEED,
+    },
+    utils::{account::close_account, token::token_account_space, token::validate_token_program},
+};
+
+/// Lock tokens in the stake vault
+///
+/// The wallet's `StakeAccount` tracks the locked amount; the off-chain
+/// allocator reads it to weight the wallet's share of future trees. Staking
+/// more restarts the `MIN_STAKE_DURATION` lock for the whole position.
+/// The stake vault is created on first use.
+///
+/// Accounts:
+/// 0. `[signer, writable]` User staking (pays for the StakeAccount PDA if new)
+/// 1. `[writable]` User's token account (ATA)
+/// 2. `[writable]` StakeAccount PDA
+/// 3. `[writable]` Stake vault PDA
+/// 4. `[]` Config PDA
+/// 5. `[]` Mint
+/// 6. `[]` Token program
+/// 7. `[]` System program
+/// 8. `[]` Rent sysvar
+pub fn process_stake(program_id: &Pubkey, accounts: &[AccountInfo], amount: u64) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let stake_info = next_account_info(account_info_iter)?;
+    let stake_vault_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let system_program = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    // Reject zero amount
+    if amount == 0 {
+        msg!("Stake: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    validate_claim_sysvars(system_program, rent_info)?;
+
+    let config = load_stake_config(program_id, config_info, mint_info, token_program)?;
+    validate_user_ata(&config, user.key, user_token_account)?;
+
+    let rent = Rent::from_account_info(rent_info)?;
+    create_stake_vault_if_needed(
+        program_id,
+        user,
+        stake_vault_info,
+        config_info,
+        mint_info,
+        token_program,
+        system_program,
+        &rent,
+    )?;
+
+    // Load or create the StakeAccount PDA
+    let (stake_pda, stake_bump) =
+        Pubkey::find_program_address(&[StakeAccount::SEED, user.key.as_ref()], program_id);
+    if stake_info.key != &stake_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    let mut stake = if stake_info.data_is_empty() {
+        let space = StakeAccount::LEN;
+        invoke_signed(
+            &system_instruction::create_account(
+                user.key,
+                stake_info.key,
+                rent.minimum_balance(space),
+                space as u64,
+                program_id,
+            ),
+            &[user.clone(), stake_info.clone(), system_program.clone()],
+            &[&[StakeAccount::SEED, user.key.as_ref(), &[stake_bump]]],
+        )?;
+
+        StakeAccount {
+            discriminator: STAKE_ACCOUNT_DISCRIMINATOR,
+            bump: stake_bump,
+            ..StakeAccount::default()
+        }
+    } else {
+        load_stake_account(program_id, stake_info)?
+    };
+
+    let now = Clock::get()?.unix_timestamp;
+    record_stake(&mut stake, amount, now)?;
+
+    invoke(
+        &token_instruction::transfer_checked(
+            &config.addresses.token_program,
+            user_token_account.key,
+            mint_info.key,
+            stake_vault_info.key,
+            user.key,
+            &[],
+            amount,
+            config.tokenomics.decimals,
+        )?,
+        &[
+            user_token_account.clone(),
+            mint_info.clone(),
+            stake_vault_info.clone(),
+            user.clone(),
+            token_program.clone(),
+        ],
+    )?;
+
+    stake.serialize(&mut &mut stake_info.data.borrow_mut()[..])?;
+
+    StakeEvent {
+        user: *user.key,
+        amount,
+        staked_amount: stake.staked_amount,
+        timestamp: now,
+    }
+    .emit()?;
+
+    msg!(
+        "Stake: user={}, amount={}, staked_amount={}",
+        user.key,
+        amount,
+        stake.staked_amount
+    );
+
+    Ok(())
+}
+
+/// Withdraw tokens from the stake vault once the lock has passed
+///
+/// Unstaking everything closes the StakeAccount and returns its rent to the user.
+///
+/// Accounts:
+/// 0. `[signer, writable]` User unstaking (receives the StakeAccount rent on full unstake)
+/// 1. `[writable]` User's token account (ATA)
+/// 2. `[writable]` StakeAccount PDA
+/// 3. `[writable]` Stake vault PDA
+/// 4. `[]` Config PDA
+/// 5. `[]` Mint
+/// 6. `[]` Token program
+pub fn process_unstake(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let user = next_account_info(account_info_iter)?;
+    let user_token_account = next_account_info(account_info_iter)?;
+    let stake_info = next_account_info(account_info_iter)?;
+    let stake_vault_info = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    // Verify user is signer
+    if !user.is_signer {
+        return Err(YapError::Unauthorized.into());
+    }
+
+    // Reject zero amount
+    if amount == 0 {
+        msg!("Unstake: Amount cannot be zero");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let config = load_stake_config(program_id, config_info, mint_info, token_program)?;
+    validate_user_ata(&config, user.key, user_token_account)?;
+
+    // Verify StakeAccount PDA
+    let (stake_pda, _) =
+        Pubkey::find_program_address(&[StakeAccount::SEED, user.key.as_ref()], program_id);
+    if stake_info.key != &stake_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    let mut stake = load_stake_account(program_id, stake_info)?;
+
+    // Verify stake vault
+    let (stake_vault_pda, _) = Pubkey::find_program_address(&[STAKE_VAULT_SEED], program_id);
+    if stake_vault_info.key != &stake_vault_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    record_unstake(&mut stake, amount, now)?;
+
+    // The stake vault is owned by the config PDA
+    invoke_signed(
+        &token_instruction::transfer_checked(
+            &config.addresses.token_program,
+            stake_vault_info.key,
+            mint_info.key,
+            user_token_account.key,
+            config_info.key,
+            &[],
+            amount,
+            config.tokenomics.decimals,
+        )?,
+        &[
+            stake_vault_info.clone(),
+            mint_info.clone(),
+            user_token_account.clone(),
+            config_info.clone(),
+            token_program.clone(),
+        ],
+        &[&[Config::SEED, &[config.bump]]],
+    )?;
+
+    if stake.staked_amount == 0 {
+        close_account(stake_info, user)?;
+    } else {
+        stake.serialize(&mut &mut stake_info.data.borrow_mut()[..])?;
+    }
+
+    UnstakeEvent {
+        user: *user.key,
+        amount,
+        staked_amount: stake.staked_amount,
+        timestamp: now,
+    }
+    .emit()?;
+
+    msg!(
+        "Unstake: user={}, amount={}, staked_amount={}",
+        user.key,
+        amount,
+        stake.staked_amount
+    );
+
+    Ok(())
+}
+
+/// Load config and verify the mint and token program used by both stake instructions
+fn load_stake_config(
+    program_id: &Pubkey,
+    config_info: &AccountInfo,
+    mint_info: &AccountInfo,
+    token_program: &AccountInfo,
+) -> Result<Config, ProgramError> {
+    let config = Config::try_load(program_id, config_info)?;
+
+    // Emergency pause
+    config.ensure_not_paused()?;
+
+    // Verify mint
+    if mint_info.key != &config.addresses.mint {
+        return Err(YapError::InvalidMint.into());
+    }
+
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    Ok(config)
+}
+
+/// Load an existing StakeAccount, checking owner and discriminator
+fn load_stake_account(
+    program_id: &Pubkey,
+    stake_info: &AccountInfo,
+) -> Result<StakeAccount, ProgramError> {
+    if stake_info.owner != program_id {
+        return Err(YapError::InvalidOwner.into());
+    }
+    let stake = StakeAccount::try_from_slice(&stake_info.data.borrow())?;
+    if !stake.is_valid() {
+        return Err(YapError::InvalidDiscriminator.into());
+    }
+    Ok(stake)
+}
+
+/// Create the shared stake vault (owner = config PDA) the first time anyone stakes
+#[allow(clippy::too_many_arguments)]
+fn create_stake_vault_if_needed<'a>(
+    program_id: &Pubkey,
+    payer: &AccountInfo<'a>,
+    stake_vault_info: &AccountInfo<'a>,
+    config_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    system_program: &AccountInfo<'a>,
+    rent: &Rent,
+) -> ProgramResult {
+    let (stake_vault_pda, stake_vault_bump) =
+        Pubkey::find_program_address(&[STAKE_VAULT_SEED], program_id);
+    if stake_vault_info.key != &stake_vault_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if !stake_vault_info.data_is_empty() {
+        return Ok(());
+    }
+
+    msg!("Stake: Creating stake vault...");
+    let space = token_account_space(token_program.key)?;
+    invoke_signed(
+        &system_instruction::create_account(
+            payer.key,
+            stake_vault_info.key,
+            rent.minimum_balance(space),
+            space as u64,
+            token_program.key,
+        ),
+        &[
+            payer.clone(),
+            stake_vault_info.clone(),
+            system_program.clone(),
+        ],
+        &[&[STAKE_VAULT_SEED, &[stake_vault_bump]]],
+    )?;
+
+    initialize_immutable_owner(token_program, stake_vault_info)?;
+    invoke(
+        &token_instruction::initialize_account3(
+            token_program.key,
+            stake_vault_info.key,
+            mint_info.key,
+            config_info.key, // owner = config PDA
+        )?,
+        &[
+            stake_vault_info.clone(),
+            mint_info.clone(),
+            token_program.clone(),
+        ],
+    )
+}
+
+/// Add `amount` to the position and restart the lock from `now`
+fn record_stake(stake: &mut StakeAccount, amount: u64, now: i64) -> Result<(), YapError> {
+    stake.staked_amount = stake
+        .staked_amount
+        .checked_add(amount)
+        .ok_or(YapError::Overflow)?;
+    stake.stake_start_ts = now;
+    Ok(())
+}
+
+/// Remove `amount` from the position once `MIN_STAKE_DURATION` has passed
+fn record_unstake(stake: &mut StakeAccount, amount: u64, now: i64) -> Result<(), YapError> {
+    let unlock_ts = stake.stake_start_ts.saturating_add(MIN_STAKE_DURATION);
+    if now < unlock_ts {
+        msg!("Unstake: Locked until {} (now {})", unlock_ts, now);
+        return Err(YapError::StakeLocked);
+    }
+
+    stake.staked_amount = stake.staked_amount.checked_sub(amount).ok_or_else(|| {
+        msg!(
+            "Unstake: Requested {} but only {} staked",
+            amount,
+            stake.staked_amount
+        );
+        YapError::InsufficientStakedBalance
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stake_accumulates_and_restarts_lock() {
+        let mut stake = StakeAccount::default();
+        record_stake(&mut stake, 1_000, 100).unwrap();
+        assert_eq!(stake.staked_amount, 1_000);
+        assert_eq!(stake.stake_start_ts, 100);
+
+        record_stake(&mut stake, 500, 200).unwrap();
+        assert_eq!(stake.staked_amount, 1_500);
+        assert_eq!(stake.stake_start_ts, 200);
+
+        assert!(matches!(
+            record_stake(&mut stake, u64::MAX, 300),
+            Err(YapError::Overflow)
+        ));
+    }
+
+    #[test]
+    fn test_unstake_rejected_while_locked() {
+        let mut stake = StakeAccount::default();
+        record_stake(&mut stake, 1_000, 100).unwrap();
+
+        for now in [100, 100 + MIN_STAKE_DURATION - 1] {
+            assert!(matches!(
+                record_unstake(&mut stake, 1, now),
+                Err(YapError::StakeLocked)
+            ));
+        }
+        assert_eq!(stake.staked_amount, 1_000);
+
+        // Topping up restarts the lock
+        record_stake(&mut stake, 1, 100 + MIN_STAKE_DURATION).unwrap();
+        assert!(matches!(
+            record_unstake(&mut stake, 1, 100 + MIN_STAKE_DURATION),
+            Err(YapError::StakeLocked)
+        ));
+    }
+
+    #[test]
+    fn test_full_unstake() {
+        let mut stake = StakeAccount::default();
+        record_stake(&mut stake, 1_000, 100).unwrap();
+        let unlocked = 100 + MIN_STAKE_DURATION;
+
+        assert!(matches!(
+            record_unstake(&mut stake, 1_001, unlocked),
+            Err(YapError::InsufficientStakedBalance)
+        ));
+
+        record_unstake(&mut stake, 400, unlocked).unwrap();
+        assert_eq!(stake.staked_amount, 600);
+        record_unstake(&mut stake, 600, unlocked).unwrap();
+        assert_eq!(stake.staked_amount, 0);
+    }
+}
@@ -0,0 +1,190 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    clock::Clock,
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+    sysvar::Sysvar,
+};
+
+use crate::{
+    error::YapError,
+    instructions::{admin::load_config_for_admin, claim::transfer_from_pending_claims},
+    state::Config,
+    utils::{return_data, token::unpack_token_account},
+};
+
+/// Move the unclaimed pending_claims balance out once the claim deadline has passed (admin only)
+///
+/// Everything still in pending_claims is forfeited by claimers: it goes back
+/// to the vault (and into future distribution budgets), or to the treasury
+/// when `back_to_vault` is false. Only possible once `claim_deadline_ts` set
+/// by the latest distribution has passed, so it can't cut a live round short.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Pending claims token account
+/// 3. `[writable]` Vault token account, or the treasury when `back_to_vault` is false
+/// 4. `[]` Mint
+/// 5. `[]` Token program
+pub fn process(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    back_to_vault: bool,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let pending_claims_info = next_account_info(account_info_iter)?;
+    let destination = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    // Verify pending_claims
+    if pending_claims_info.key != &config.addresses.pending_claims {
+        return Err(YapError::InvalidPda.into());
+    }
+
+    // Verify destination (vault or treasury)
+    if destination.key != &sweep_destination(&config, back_to_vault)? {
+        msg!("SweepUnclaimed: Unexpected destination {}", destination.key);
+        return Err(YapError::InvalidPda.into());
+    }
+
+    // Verify mint
+    if mint_info.key != &config.addresses.mint {
+        return Err(YapError::InvalidMint.into());
+    }
+
+    let now = Clock::get()?.unix_timestamp;
+    check_claim_window_closed(config.timing.claim_deadline_ts, now)?;
+
+    let unclaimed = unpack_token_account(&pending_claims_info.data.borrow())?.amount;
+    if unclaimed > 0 {
+        transfer_from_pending_claims(
+            &config,
+            config_info,
+            pending_claims_info,
+            mint_info,
+            destination,
+            token_program,
+            unclaimed,
+        )?;
+    }
+
+    // The early-bird and bonus reserves were held in pending_claims too
+    config.distribution.early_bird_reserved = 0;
+    config.distribution.bonus_reserved = 0;
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    return_data::set_amount(unclaimed);
+
+    msg!(
+        "SweepUnclaimed: Moved {} to {} ({})",
+        unclaimed,
+        destination.key,
+        if back_to_vault { "vault" } else { "treasury" }
+    );
+
+    Ok(())
+}
+
+/// Vault, or the configured treasury (`NotInitialized` while unset)
+fn sweep_destination(config: &Config, back_to_vault: bool) -> Result<Pubkey, YapError> {
+    if back_to_vault {
+        return Ok(config.addresses.vault);
+    }
+    if config.addresses.treasury == Pubkey::default() {
+        msg!("SweepUnclaimed: No treasury configured");
+        return Err(YapError::NotInitialized);
+    }
+    Ok(config.addresses.treasury)
+}
+
+/// Reject a sweep while the latest round is still claimable
+///
+/// With no deadline (`claim_deadline_ts` 0) the window never closes.
+fn check_claim_window_closed(claim_deadline_ts: i64, now: i64) -> Result<(), YapError> {
+    if claim_deadline_ts == 0 || now < claim_deadline_ts {
+        msg!(
+            "SweepUnclaimed: Claims open until {} (now {})",
+            claim_deadline_ts,
+            now
+        );
+        return Err(YapError::ClaimWindowOpen);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::instructions::distribute::claim_deadline;
+    use crate::state::Addresses;
+
+    #[test]
+    fn test_sweep_rejected_before_deadline() {
+        const WEEK: i64 = 7 * 24 * 60 * 60;
+        let deadline = claim_deadline(WEEK, 1_000);
+        assert_eq!(deadline, 1_000 + WEEK);
+
+        for now in [1_000, deadline - 1] {
+            assert!(matches!(
+                check_claim_window_closed(deadline, now),
+                Err(YapError::ClaimWindowOpen)
+            ));
+        }
+
+        // Without a claim window nothing is ever sweepable
+        assert_eq!(claim_deadline(0, 1_000), 0);
+        assert!(matches!(
+            check_claim_window_closed(0, i64::MAX),
+            Err(YapError::ClaimWindowOpen)
+        ));
+    }
+
+    #[test]
+    fn test_sweep_allowed_after_deadline() {
+        const WEEK: i64 = 7 * 24 * 60 * 60;
+        let deadline = claim_deadline(WEEK, 1_000);
+        check_claim_window_closed(deadline, deadline).unwrap();
+        check_claim_window_closed(deadline, deadline + WEEK).unwrap();
+
+        // A new distribution reopens the window
+        let next = claim_deadline(WEEK, deadline + 1);
+        assert!(matches!(
+            check_claim_window_closed(next, deadline + 1),
+            Err(YapError::ClaimWindowOpen)
+        ));
+    }
+
+    #[test]
+    fn test_sweep_destination() {
+        let mut config = Config {
+            addresses: Addresses {
+                vault: Pubkey::new_unique(),
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+        assert_eq!(
+            sweep_destination(&config, true).unwrap(),
+            config.addresses.vault
+        );
+        assert!(matches!(
+            sweep_destination(&config, false),
+            Err(YapError::NotInitialized)
+        ));
+
+        config.addresses.treasury = Pubkey::new_unique();
+        assert_eq!(
+            sweep_destination(&config, false).unwrap(),
+            config.addresses.treasury
+        );
+    }
+}
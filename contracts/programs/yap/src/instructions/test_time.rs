@@ -0,0 +1,96 @@
+use borsh::BorshSerialize;
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    msg,
+    pubkey::Pubkey,
+};
+
+use crate::{instructions::admin::load_config_for_admin, state::Config};
+
+/// Overwrite the accrual timestamps (admin only, `test-time` builds only)
+///
+/// Lets integration tests exercise inflation and distribution accrual
+/// deterministically without warping the bank clock.
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+pub fn process_set_last_timestamps(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    inflation_ts: i64,
+    distribution_ts: i64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+
+    let mut config = load_config_for_admin(program_id, admin, config_info)?;
+
+    msg!(
+        "SetLastTimestamps: inflation {} -> {}, distribution {} -> {}",
+        config.timing.last_inflation_ts,
+        inflation_ts,
+        config.timing.last_distribution_ts,
+        distribution_ts
+    );
+
+    set_last_timestamps(&mut config, inflation_ts, distribution_ts);
+    config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+
+    Ok(())
+}
+
+fn set_last_timestamps(config: &mut Config, inflation_ts: i64, distribution_ts: i64) {
+    config.timing.last_inflation_ts = inflation_ts;
+    config.timing.last_distribution_ts = distribution_ts;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        instructions::{distribute::Allocation, trigger_inflation::accrued_inflation},
+        state::{Timing, Tokenomics, INITIAL_SUPPLY, SECONDS_PER_YEAR},
+    };
+
+    #[test]
+    fn test_rewound_timestamps_drive_accrual() {
+        let now = 1_731_628_800;
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: INITIAL_SUPPLY,
+                inflation_rate_bps: 1000,
+                ..Tokenomics::default()
+            },
+            timing: Timing {
+                last_inflation_ts: now,
+                last_distribution_ts: now,
+                ..Timing::default()
+            },
+            ..Config::default()
+        };
+
+        set_last_timestamps(&mut config, now - SECONDS_PER_YEAR, now - 86_400);
+
+        // A full year at 10% mints 10% of supply
+        let minted = accrued_inflation(
+            config.tokenomics.current_supply,
+            config.tokenomics.inflation_rate_bps,
+            now - config.timing.last_inflation_ts,
+            SECONDS_PER_YEAR,
+        )
+        .unwrap();
+        assert_eq!(minted, INITIAL_SUPPLY / 10);
+
+        // One day of distribution accrues 1/365 of the vault
+        let vault = 365_000_000;
+        let elapsed = now - config.timing.last_distribution_ts;
+        let available = Allocation::new(elapsed, SECONDS_PER_YEAR, vault, 0, 0)
+            .unwrap()
+            .available();
+        assert_eq!(available, 1_000_000);
+    }
+}
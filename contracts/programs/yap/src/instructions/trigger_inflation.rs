@@ -1,21 +1,46 @@
-use borsh::{BorshDeserialize, BorshSerialize};
+use borsh::BorshSerialize;
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     clock::Clock,
     entrypoint::ProgramResult,
     msg,
     program::invoke_signed,
+    program_error::ProgramError,
     pubkey::Pubkey,
     sysvar::Sysvar,
 };
+use spl_token_2022_interface::instruction as token_instruction;
 
 use crate::{
     error::YapError,
-    state::{Config, DECIMALS, SECONDS_PER_YEAR},
+    events::{Event, InflationEvent},
+    instructions::claim::validate_token_account,
+    state::Config,
+    utils::{
+        clock::{elapsed_since, rate_period, validate_clock_account},
+        return_data,
+        token::{validate_mint_authority, validate_token_program},
+    },
 };
 
 /// Trigger inflation - mints accrued inflation to vault
 /// Uses continuous rate limiting: available = elapsed * supply * rate / year
+///
+/// With `inflation_burn_bps` set, that share of the minted amount is burned
+/// from the vault in the same instruction, so net inflation is mint - burn.
+/// With `treasury_inflation_bps` set, that share is minted to the treasury
+/// instead of the vault. With `halving_interval` set, the rate halves every
+/// interval since genesis (see `effective_inflation_rate`).
+///
+/// Accounts:
+/// 0. `[signer]` Admin
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Mint PDA
+/// 3. `[writable]` Vault PDA
+/// 4. `[]` Token program
+/// 5. `[]` (optional) Clock sysvar - cross-checked against `Clock::get()`
+/// 6. `[writable]` Treasury token account - required (after the clock) when
+///    `treasury_inflation_bps` is set
 pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
 
@@ -24,101 +49,1044 @@ pub fn process(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let mint_info = next_account_info(account_info_iter)?;
     let vault_info = next_account_info(account_info_iter)?;
     let token_program = next_account_info(account_info_iter)?;
+    let clock_info = account_info_iter.next();
+    let treasury_info = account_info_iter.next();
 
-    if !admin.is_signer {
-        return Err(YapError::Unauthorized.into());
-    }
-
-    let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], program_id);
-    if config_info.key != &config_pda {
-        return Err(YapError::InvalidPda.into());
-    }
+    mint_inflation(
+        program_id,
+        admin,
+        config_info,
+        mint_info,
+        vault_info,
+        None,
+        token_program,
+        clock_info,
+        treasury_info,
+    )
+}
 
-    if config_info.owner != program_id {
-        return Err(YapError::InvalidOwner.into());
-    }
+/// Trigger inflation without the admin, paying the caller a keeper reward
+///
+/// Requires `config.tokenomics.permissionless_inflation`. `keeper_reward_bps` of the
+/// newly minted amount is minted to the keeper's token account instead of the
+/// vault, so the reward never draws on the existing vault balance.
+///
+/// Accounts:
+/// 0. `[signer]` Keeper
+/// 1. `[writable]` Config PDA
+/// 2. `[writable]` Mint PDA
+/// 3. `[writable]` Vault PDA
+/// 4. `[writable]` Keeper's token account (configured mint, owned by the keeper)
+/// 5. `[]` Token program
+/// 6. `[]` (optional) Clock sysvar - cross-checked against `Clock::get()`
+/// 7. `[writable]` Treasury token account - required (after the clock) when
+///    `treasury_inflation_bps` is set
+pub fn process_permissionless(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
 
-    let mut config = Config::try_from_slice(&config_info.data.borrow())?;
+    let keeper = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let vault_info = next_account_info(account_info_iter)?;
+    let keeper_token_account = next_account_info(account_info_iter)?;
+    let token_program = next_account_info(account_info_iter)?;
+    let clock_info = account_info_iter.next();
+    let treasury_info = account_info_iter.next();
 
-    if !config.is_valid() {
-        return Err(YapError::InvalidDiscriminator.into());
-    }
+    mint_inflation(
+        program_id,
+        keeper,
+        config_info,
+        mint_info,
+        vault_info,
+        Some(keeper_token_account),
+        token_program,
+        clock_info,
+        treasury_info,
+    )
+}
 
-    if admin.key != &config.admin {
+/// Shared body of both triggers; `keeper_token_account` selects the keeper path
+#[allow(clippy::too_many_arguments)]
+fn mint_inflation<'a>(
+    program_id: &Pubkey,
+    caller: &AccountInfo<'a>,
+    config_info: &AccountInfo<'a>,
+    mint_info: &AccountInfo<'a>,
+    vault_info: &AccountInfo<'a>,
+    keeper_token_account: Option<&AccountInfo<'a>>,
+    token_program: &AccountInfo<'a>,
+    clock_info: Option<&AccountInfo<'a>>,
+    treasury_info: Option<&AccountInfo<'a>>,
+) -> ProgramResult {
+    if !caller.is_signer {
         return Err(YapError::Unauthorized.into());
     }
 
-    if mint_info.key != &config.mint {
+    let mut config = Config::try_load(program_id, config_info)?;
+
+    // Emergency pause
+    config.ensure_not_paused()?;
+
+    let keeper_reward_bps = match keeper_token_account {
+        None => {
+            if !config.is_admin(caller.key) {
+                return Err(YapError::Unauthorized.into());
+            }
+            0
+        }
+        Some(keeper_token_account) => {
+            if !config.tokenomics.permissionless_inflation {
+                msg!("TriggerInflation: Permissionless inflation is disabled");
+                return Err(YapError::Unauthorized.into());
+            }
+            validate_token_account(
+                &keeper_token_account.data.borrow(),
+                caller.key,
+                &config.addresses.mint,
+            )?;
+            config.tokenomics.keeper_reward_bps
+        }
+    };
+
+    if mint_info.key != &config.addresses.mint {
         return Err(YapError::InvalidMint.into());
     }
+    validate_mint_authority(&mint_info.data.borrow(), config_info.key)?;
 
-    if vault_info.key != &config.vault {
+    if vault_info.key != &config.addresses.vault {
         return Err(YapError::InvalidPda.into());
     }
 
+    validate_token_program(token_program, &config.addresses.token_program)?;
+
+    let treasury_info = if config.tokenomics.treasury_inflation_bps > 0 {
+        Some(validate_treasury(&config, treasury_info)?)
+    } else {
+        None
+    };
+
     let clock = Clock::get()?;
     let now = clock.unix_timestamp;
 
+    if let Some(clock_info) = clock_info {
+        validate_clock_account(clock_info, now)?;
+    }
+
     // Calculate elapsed time since last inflation
-    let elapsed = now.saturating_sub(config.last_inflation_ts);
+    let elapsed = elapsed_since(now, config.timing.last_inflation_ts)?;
     if elapsed <= 0 {
         return Err(YapError::InflationNotReady.into());
     }
 
-    // Calculate accrued inflation: supply * rate * elapsed / (10000 * SECONDS_PER_YEAR)
-    let inflation_amount = (config.current_supply as u128)
-        .checked_mul(config.inflation_rate_bps as u128)
-        .ok_or(YapError::Overflow)?
-        .checked_mul(elapsed as u128)
-        .ok_or(YapError::Overflow)?
-        .checked_div(10000)
-        .ok_or(YapError::Overflow)?
-        .checked_div(SECONDS_PER_YEAR as u128)
-        .ok_or(YapError::Overflow)? as u64;
-
-    if inflation_amount == 0 {
+    // Halvings since genesis lower the rate; once it floors to zero nothing accrues
+    let rate_bps = effective_inflation_rate(&config, now);
+    if rate_bps == 0 {
+        msg!("TriggerInflation: Inflation rate is zero");
         return Err(YapError::InflationNotReady.into());
     }
 
+    // Calculate accrued inflation:
+    // (supply * rate * elapsed + remainder) / (10000 * rate_period_secs)
+    let (accrued, remainder) = accrue_inflation(
+        config.tokenomics.current_supply,
+        rate_bps,
+        elapsed,
+        config.timing.rate_period_secs,
+        config.tokenomics.inflation_remainder,
+    )?;
+
+    if accrued == 0 {
+        // Less than one unit so far: bank the fraction and restart the window,
+        // so frequent calls on a small supply still add up to a mint
+        config.tokenomics.inflation_remainder = remainder;
+        config.timing.last_inflation_ts = now;
+        config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
+        return_data::set_amount(0);
+        msg!(
+            "TriggerInflation: Nothing to mint yet (remainder {})",
+            remainder
+        );
+        return Ok(());
+    }
+
+    // Never mint past the supply cap
+    let inflation_amount = clamp_to_max_supply(
+        config.tokenomics.current_supply,
+        config.tokenomics.max_supply,
+        accrued,
+    )?;
+    // A clamped mint forfeits the fraction along with the rest
+    config.tokenomics.inflation_remainder = if inflation_amount < accrued {
+        0
+    } else {
+        remainder
+    };
+
+    let split = InflationSplit::new(
+        inflation_amount,
+        keeper_reward_bps,
+        config.tokenomics.treasury_inflation_bps,
+        config.tokenomics.inflation_burn_bps,
+    )?;
+
     msg!(
-        "TriggerInflation: elapsed={}s, amount={}",
+        "TriggerInflation: elapsed={}s, amount={}, keeper_reward={}, treasury={}",
         elapsed,
-        inflation_amount
+        inflation_amount,
+        split.keeper_reward,
+        split.treasury
     );
 
+    let signer_seeds: &[&[u8]] = &[Config::SEED, &[config.bump]];
+
     // Mint inflation to vault
-    invoke_signed(
-        &spl_token::instruction::mint_to_checked(
-            &spl_token::id(),
-            mint_info.key,
-            vault_info.key,
-            &config_pda,
-            &[],
-            inflation_amount,
-            DECIMALS,
-        )?,
-        &[
-            mint_info.clone(),
-            vault_info.clone(),
-            config_info.clone(),
-            token_program.clone(),
-        ],
-        &[&[Config::SEED, &[config.bump]]],
-    )?;
+    if split.vault_amount() > 0 {
+        invoke_signed(
+            &token_instruction::mint_to_checked(
+                &config.addresses.token_program,
+                mint_info.key,
+                vault_info.key,
+                config_info.key,
+                &[],
+                split.vault_amount(),
+                config.tokenomics.decimals,
+            )?,
+            &[
+                mint_info.clone(),
+                vault_info.clone(),
+                config_info.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    // Mint the treasury's share directly to it
+    if let Some(treasury_info) = treasury_info.filter(|_| split.treasury > 0) {
+        invoke_signed(
+            &token_instruction::mint_to_checked(
+                &config.addresses.token_program,
+                mint_info.key,
+                treasury_info.key,
+                config_info.key,
+                &[],
+                split.treasury,
+                config.tokenomics.decimals,
+            )?,
+            &[
+                mint_info.clone(),
+                treasury_info.clone(),
+                config_info.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    // Mint the keeper's cut directly to them
+    if let Some(keeper_token_account) = keeper_token_account.filter(|_| split.keeper_reward > 0) {
+        invoke_signed(
+            &token_instruction::mint_to_checked(
+                &config.addresses.token_program,
+                mint_info.key,
+                keeper_token_account.key,
+                config_info.key,
+                &[],
+                split.keeper_reward,
+                config.tokenomics.decimals,
+            )?,
+            &[
+                mint_info.clone(),
+                keeper_token_account.clone(),
+                config_info.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    // Burn the configured share straight back out of the vault
+    if split.burned > 0 {
+        invoke_signed(
+            &token_instruction::burn_checked(
+                &config.addresses.token_program,
+                vault_info.key,
+                mint_info.key,
+                config_info.key,
+                &[],
+                split.burned,
+                config.tokenomics.decimals,
+            )?,
+            &[
+                vault_info.clone(),
+                mint_info.clone(),
+                config_info.clone(),
+                token_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
 
     // Update config
-    config.current_supply = config
-        .current_supply
-        .checked_add(inflation_amount)
-        .ok_or(YapError::Overflow)?;
-    config.last_inflation_ts = now;
+    apply_inflation(&mut config, &split, now)?;
 
     config.serialize(&mut &mut config_info.data.borrow_mut()[..])?;
 
+    InflationEvent {
+        amount: inflation_amount,
+        current_supply: config.tokenomics.current_supply,
+        inflation_rate_bps: rate_bps,
+        timestamp: now,
+        burned: split.burned,
+        keeper_reward: split.keeper_reward,
+        treasury_amount: split.treasury,
+        total_inflation_minted: config.tokenomics.total_inflation_minted,
+    }
+    .emit()?;
+    return_data::set_amount(split.minted);
+
     msg!(
-        "TriggerInflation: new_supply={}",
-        config.current_supply
+        "TriggerInflation: burned={}, new_supply={}, undistributed_inflation={}",
+        split.burned,
+        config.tokenomics.current_supply,
+        config.tokenomics.undistributed_inflation
     );
 
     Ok(())
 }
+
+/// Verify the treasury account passed for a treasury split is the configured one
+fn validate_treasury<'b, 'a>(
+    config: &Config,
+    treasury_info: Option<&'b AccountInfo<'a>>,
+) -> Result<&'b AccountInfo<'a>, ProgramError> {
+    let Some(treasury_info) = treasury_info else {
+        msg!("TriggerInflation: Treasury account required for the treasury split");
+        return Err(ProgramError::NotEnoughAccountKeys);
+    };
+    if treasury_info.key != &config.addresses.treasury {
+        msg!(
+            "TriggerInflation: Treasury mismatch (expected {}, got {})",
+            config.addresses.treasury,
+            treasury_info.key
+        );
+        return Err(YapError::InvalidPda.into());
+    }
+    Ok(treasury_info)
+}
+
+/// Annual rate in effect at `now`, after the halvings elapsed since genesis
+///
+/// `inflation_rate_bps >> min((now - genesis_ts) / halving_interval, halving_count_cap)`.
+/// A `halving_interval` of 0 keeps the rate flat. The whole elapsed window of
+/// a trigger accrues at the rate in effect when it is called.
+pub(crate) fn effective_inflation_rate(config: &Config, now: i64) -> u16 {
+    let tokenomics = &config.tokenomics;
+    if tokenomics.halving_interval <= 0 {
+        return tokenomics.inflation_rate_bps;
+    }
+
+    let since_genesis = now.saturating_sub(config.timing.genesis_ts).max(0);
+    let halvings = (since_genesis / tokenomics.halving_interval)
+        .min(tokenomics.halving_count_cap as i64) as u32;
+
+    tokenomics
+        .inflation_rate_bps
+        .checked_shr(halvings)
+        .unwrap_or(0)
+}
+
+/// Accrued inflation: supply * rate * elapsed / (10000 * rate_period_secs)
+///
+/// `rate_bps` is the rate per `rate_period_secs`; a period that isn't
+/// positive is `InvalidInstruction`. Nothing carried in, whole units out.
+#[cfg(test)]
+pub(crate) fn accrued_inflation(
+    supply: u64,
+    rate_bps: u16,
+    elapsed: i64,
+    rate_period_secs: i64,
+) -> Result<u64, YapError> {
+    accrue_inflation(supply, rate_bps, elapsed, rate_period_secs, 0).map(|(amount, _)| amount)
+}
+
+/// Accrued inflation plus the carried `remainder`, split into whole units and
+/// the new remainder
+///
+/// Works on the exact numerator `supply * rate * elapsed + remainder` over
+/// `10000 * rate_period_secs`, so the fraction each call floors away is kept
+/// for the next one instead of being lost.
+pub(crate) fn accrue_inflation(
+    supply: u64,
+    rate_bps: u16,
+    elapsed: i64,
+    rate_period_secs: i64,
+    remainder: u128,
+) -> Result<(u64, u128), YapError> {
+    let denominator = rate_period(rate_period_secs)?
+        .checked_mul(10000)
+        .ok_or(YapError::Overflow)?;
+
+    let numerator = (supply as u128)
+        .checked_mul(rate_bps as u128)
+        .ok_or(YapError::Overflow)?
+        .checked_mul(elapsed as u128)
+        .ok_or(YapError::Overflow)?
+        .checked_add(remainder)
+        .ok_or(YapError::Overflow)?;
+
+    let amount = u64::try_from(numerator / denominator).map_err(|_| YapError::Overflow)?;
+    Ok((amount, numerator % denominator))
+}
+
+/// Limit `amount` to the headroom left under `max_supply`
+///
+/// Inflation clamped here is forfeited rather than carried over: the caller
+/// still resets `last_inflation_ts` to now, so accrual restarts from the cap.
+pub(crate) fn clamp_to_max_supply(
+    supply: u64,
+    max_supply: u64,
+    amount: u64,
+) -> Result<u64, YapError> {
+    let headroom = max_supply.saturating_sub(supply);
+    if headroom == 0 {
+        msg!("Max supply {} reached (supply={})", max_supply, supply);
+        return Err(YapError::MaxSupplyReached);
+    }
+
+    Ok(amount.min(headroom))
+}
+
+/// Share of `amount` at `bps`: amount * bps / 10000 (rounded down)
+pub(crate) fn bps_share(amount: u64, bps: u16) -> Result<u64, YapError> {
+    let share = (amount as u128)
+        .checked_mul(bps as u128)
+        .ok_or(YapError::Overflow)?
+        / 10000;
+
+    u64::try_from(share).map_err(|_| YapError::Overflow)
+}
+
+/// How one inflation mint is divided between keeper, treasury, vault and burn
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct InflationSplit {
+    /// Total newly minted
+    pub minted: u64,
+    /// Minted straight to the keeper's token account
+    pub keeper_reward: u64,
+    /// Minted straight to the treasury
+    pub treasury: u64,
+    /// Burned back out of the vault
+    pub burned: u64,
+}
+
+impl InflationSplit {
+    /// Take the keeper's cut first, then the treasury's share of the rest; the
+    /// burn share applies to what reaches the vault
+    pub(crate) fn new(
+        minted: u64,
+        keeper_reward_bps: u16,
+        treasury_bps: u16,
+        burn_bps: u16,
+    ) -> Result<Self, YapError> {
+        let keeper_reward = bps_share(minted, keeper_reward_bps)?;
+        let treasury = bps_share(minted - keeper_reward, treasury_bps)?;
+        let vault_amount = minted
+            .checked_sub(keeper_reward)
+            .and_then(|rest| rest.checked_sub(treasury))
+            .ok_or(YapError::Overflow)?;
+        let burned = bps_share(vault_amount, burn_bps)?;
+
+        Ok(Self {
+            minted,
+            keeper_reward,
+            treasury,
+            burned,
+        })
+    }
+
+    /// Amount minted into the vault
+    pub(crate) fn vault_amount(&self) -> u64 {
+        self.minted - self.keeper_reward - self.treasury
+    }
+}
+
+/// Record minted (and immediately burned) inflation in config
+///
+/// Supply moves by the minted amount minus the burn. With `compound_inflation`
+/// enabled, what stays in the vault is also earmarked for the next
+/// distributions via `undistributed_inflation`.
+fn apply_inflation(config: &mut Config, split: &InflationSplit, now: i64) -> Result<(), YapError> {
+    let net_supply = split
+        .minted
+        .checked_sub(split.burned)
+        .ok_or(YapError::Overflow)?;
+    let net_vault = split
+        .vault_amount()
+        .checked_sub(split.burned)
+        .ok_or(YapError::Overflow)?;
+
+    config.tokenomics.current_supply = config
+        .tokenomics
+        .current_supply
+        .checked_add(net_supply)
+        .ok_or(YapError::Overflow)?;
+    config.tokenomics.total_inflation_minted = config
+        .tokenomics
+        .total_inflation_minted
+        .checked_add(split.minted)
+        .ok_or(YapError::Overflow)?;
+    config.timing.last_inflation_ts = now;
+
+    if config.tokenomics.compound_inflation {
+        config.tokenomics.undistributed_inflation = config
+            .tokenomics
+            .undistributed_inflation
+            .checked_add(net_vault)
+            .ok_or(YapError::Overflow)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{Addresses, Roles, Timing, Tokenomics, INITIAL_SUPPLY, SECONDS_PER_YEAR};
+    use crate::test_utils::TestAccount;
+    use solana_program::program_pack::Pack;
+    use spl_token::state::Mint;
+
+    fn minted(amount: u64) -> InflationSplit {
+        InflationSplit::new(amount, 0, 0, 0).unwrap()
+    }
+
+    #[test]
+    fn test_trigger_inflation_reverts_when_paused() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let paused = Config {
+            paused: true,
+            roles: Roles {
+                admin: admin_key,
+                ..Roles::default()
+            },
+            ..Config::default()
+        };
+
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, paused.clone());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+
+        {
+            let accounts = [
+                admin.info(),
+                config.info(),
+                mint.info(),
+                vault.info(),
+                token_program.info(),
+            ];
+            let err = process(&program_id, &accounts).unwrap_err();
+            assert_eq!(err, YapError::Paused.into());
+        }
+
+        // After unpause the guard passes (and the unknown mint is rejected next)
+        config.set_config(Config {
+            paused: false,
+            ..paused
+        });
+        let accounts = [
+            admin.info(),
+            config.info(),
+            mint.info(),
+            vault.info(),
+            token_program.info(),
+        ];
+        let err = process(&program_id, &accounts).unwrap_err();
+        assert_eq!(err, YapError::InvalidMint.into());
+    }
+
+    #[test]
+    fn test_rotated_mint_authority_fails_cleanly() {
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], &program_id);
+        let config = Config {
+            roles: Roles {
+                admin: admin_key,
+                ..Roles::default()
+            },
+            addresses: Addresses {
+                mint: mint_key,
+                vault: Pubkey::new_unique(),
+                token_program: spl_token::id(),
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+
+        let packed_mint = |authority: Pubkey| {
+            let mint = Mint {
+                mint_authority: Some(authority).into(),
+                supply: INITIAL_SUPPLY,
+                decimals: 9,
+                is_initialized: true,
+                freeze_authority: None.into(),
+            };
+            let mut data = vec![0u8; Mint::LEN];
+            Mint::pack(mint, &mut data).unwrap();
+            data
+        };
+
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(&program_id, config);
+        let mut mint = TestAccount {
+            owner: spl_token::id(),
+            data: packed_mint(Pubkey::new_unique()),
+            ..TestAccount::new(mint_key)
+        };
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+
+        // Authority moved away from the config PDA: rejected before any CPI
+        {
+            let accounts = [
+                admin.info(),
+                config.info(),
+                mint.info(),
+                vault.info(),
+                token_program.info(),
+            ];
+            let err = process(&program_id, &accounts).unwrap_err();
+            assert_eq!(err, YapError::InvalidMint.into());
+        }
+
+        // Restored authority passes (and the unknown vault is rejected next)
+        mint.data = packed_mint(config_pda);
+        let accounts = [
+            admin.info(),
+            config.info(),
+            mint.info(),
+            vault.info(),
+            token_program.info(),
+        ];
+        let err = process(&program_id, &accounts).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+    }
+
+    #[test]
+    fn test_inflation_accrues_counter_when_compounding() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 1_000,
+                compound_inflation: true,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        apply_inflation(&mut config, &minted(100), 10).unwrap();
+        apply_inflation(&mut config, &minted(50), 20).unwrap();
+
+        assert_eq!(config.tokenomics.current_supply, 1_150);
+        assert_eq!(config.tokenomics.undistributed_inflation, 150);
+        assert_eq!(config.timing.last_inflation_ts, 20);
+    }
+
+    #[test]
+    fn test_inflation_skips_counter_when_not_compounding() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 1_000,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        apply_inflation(&mut config, &minted(100), 10).unwrap();
+
+        assert_eq!(config.tokenomics.current_supply, 1_100);
+        assert_eq!(config.tokenomics.undistributed_inflation, 0);
+    }
+
+    #[test]
+    fn test_clamp_to_max_supply() {
+        // Well under the cap: full amount
+        assert_eq!(clamp_to_max_supply(1_000, 2_000, 100).unwrap(), 100);
+        // Landing exactly on the cap
+        assert_eq!(clamp_to_max_supply(1_000, 1_100, 100).unwrap(), 100);
+        // Crossing the cap: only the headroom
+        assert_eq!(clamp_to_max_supply(1_000, 1_001, 100).unwrap(), 1);
+        // At (or somehow above) the cap: nothing left to mint
+        assert!(matches!(
+            clamp_to_max_supply(1_000, 1_000, 100),
+            Err(YapError::MaxSupplyReached)
+        ));
+        assert!(matches!(
+            clamp_to_max_supply(1_001, 1_000, 100),
+            Err(YapError::MaxSupplyReached)
+        ));
+    }
+
+    #[test]
+    fn test_clamped_inflation_stops_at_cap() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 1_000,
+                max_supply: 1_050,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        let amount = clamp_to_max_supply(
+            config.tokenomics.current_supply,
+            config.tokenomics.max_supply,
+            100,
+        )
+        .unwrap();
+        apply_inflation(&mut config, &minted(amount), 10).unwrap();
+
+        assert_eq!(config.tokenomics.current_supply, 1_050);
+        assert_eq!(config.timing.last_inflation_ts, 10);
+        assert!(matches!(
+            clamp_to_max_supply(config.tokenomics.current_supply, config.tokenomics.max_supply, 1),
+            Err(YapError::MaxSupplyReached)
+        ));
+    }
+
+    #[test]
+    fn test_bps_share() {
+        assert_eq!(bps_share(1_000, 0).unwrap(), 0);
+        assert_eq!(bps_share(1_000, 2_500).unwrap(), 250);
+        assert_eq!(bps_share(1_000, 10_000).unwrap(), 1_000);
+        // Rounds down, so dust stays minted
+        assert_eq!(bps_share(3, 5_000).unwrap(), 1);
+        assert_eq!(bps_share(u64::MAX, 10_000).unwrap(), u64::MAX);
+    }
+
+    #[test]
+    fn test_net_supply_change_is_mint_minus_burn() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 1_000_000,
+                inflation_burn_bps: 4_000,
+                compound_inflation: true,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        let burn_bps = config.tokenomics.inflation_burn_bps;
+        let split = InflationSplit::new(10_000, 0, 0, burn_bps).unwrap();
+        apply_inflation(&mut config, &split, 10).unwrap();
+
+        assert_eq!(split.burned, 4_000);
+        assert_eq!(config.tokenomics.current_supply, 1_000_000 + split.minted - split.burned);
+        assert_eq!(config.tokenomics.undistributed_inflation, 6_000);
+
+        // Burning everything leaves supply unchanged
+        let before = config.tokenomics.current_supply;
+        let burn_all = InflationSplit::new(10_000, 0, 0, 10_000).unwrap();
+        apply_inflation(&mut config, &burn_all, 20).unwrap();
+        assert_eq!(config.tokenomics.current_supply, before);
+        assert_eq!(config.timing.last_inflation_ts, 20);
+    }
+
+    #[test]
+    fn test_total_inflation_minted_ignores_burns() {
+        use crate::{instructions::burn::record_burn, state::UserClaimStatus};
+
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 1_000_000,
+                inflation_burn_bps: 1_000,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        for (i, amount) in [10_000, 20_000, 30_000].into_iter().enumerate() {
+            let split = InflationSplit::new(amount, 0, 0, 1_000).unwrap();
+            apply_inflation(&mut config, &split, i as i64 + 1).unwrap();
+        }
+        // The inflation burn is counted as minted too
+        assert_eq!(config.tokenomics.total_inflation_minted, 60_000);
+        assert_eq!(config.tokenomics.current_supply, 1_054_000);
+
+        let mut status = UserClaimStatus::default();
+        record_burn(&mut config, &mut status, 50_000).unwrap();
+        record_burn(&mut config, &mut status, 4_000).unwrap();
+
+        assert_eq!(config.tokenomics.current_supply, 1_000_000);
+        assert_eq!(config.tokenomics.total_inflation_minted, 60_000);
+    }
+
+    #[test]
+    fn test_keeper_reward_comes_out_of_minted_amount() {
+        let mut config = Config {
+            tokenomics: Tokenomics {
+                current_supply: 1_000_000,
+                compound_inflation: true,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        let split = InflationSplit::new(10_000, 100, 0, 0).unwrap();
+        assert_eq!(split.keeper_reward, 100);
+        assert_eq!(split.vault_amount() + split.keeper_reward, split.minted);
+
+        apply_inflation(&mut config, &split, 10).unwrap();
+        assert_eq!(config.tokenomics.current_supply, 1_010_000);
+        // The keeper's cut never reaches the vault, so it isn't earmarked
+        assert_eq!(config.tokenomics.undistributed_inflation, 9_900);
+
+        // The burn share applies to the vault's portion only
+        let split = InflationSplit::new(10_000, 1_000, 0, 5_000).unwrap();
+        assert_eq!(split.keeper_reward, 1_000);
+        assert_eq!(split.burned, 4_500);
+        assert_eq!(split.vault_amount() - split.burned, 4_500);
+    }
+
+    #[test]
+    fn test_treasury_split_keeps_full_mint_in_supply() {
+        for (treasury_bps, treasury, undistributed) in
+            [(0, 0, 10_000), (10_000, 10_000, 0), (2_500, 2_500, 7_500)]
+        {
+            let mut config = Config {
+                tokenomics: Tokenomics {
+                    current_supply: 1_000_000,
+                    compound_inflation: true,
+                    ..Tokenomics::default()
+                },
+                ..Config::default()
+            };
+
+            let split = InflationSplit::new(10_000, 0, treasury_bps, 0).unwrap();
+            assert_eq!(split.treasury, treasury);
+            assert_eq!(split.vault_amount() + split.treasury, split.minted);
+
+            apply_inflation(&mut config, &split, 10).unwrap();
+            assert_eq!(config.tokenomics.current_supply, 1_010_000);
+            // Only what reaches the vault can be distributed
+            assert_eq!(config.tokenomics.undistributed_inflation, undistributed);
+        }
+
+        // Keeper first, then the treasury's share of the rest, then the burn
+        let split = InflationSplit::new(10_000, 1_000, 5_000, 2_000).unwrap();
+        assert_eq!(
+            (split.keeper_reward, split.treasury, split.burned),
+            (1_000, 4_500, 900)
+        );
+        assert_eq!(split.vault_amount(), 4_500);
+    }
+
+    #[test]
+    fn test_treasury_account_must_match_config() {
+        let treasury_key = Pubkey::new_unique();
+        let config = Config {
+            addresses: Addresses {
+                treasury: treasury_key,
+                ..Addresses::default()
+            },
+            ..Config::default()
+        };
+
+        let mut treasury = TestAccount::new(treasury_key);
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        assert!(validate_treasury(&config, Some(&treasury.info())).is_ok());
+        assert_eq!(
+            validate_treasury(&config, Some(&other.info())).unwrap_err(),
+            YapError::InvalidPda.into()
+        );
+        assert_eq!(
+            validate_treasury(&config, None).unwrap_err(),
+            ProgramError::NotEnoughAccountKeys
+        );
+    }
+
+    fn halving_config(halving_count_cap: u8) -> Config {
+        Config {
+            tokenomics: Tokenomics {
+                current_supply: INITIAL_SUPPLY,
+                inflation_rate_bps: 1600,
+                halving_interval: SECONDS_PER_YEAR,
+                halving_count_cap,
+                ..Tokenomics::default()
+            },
+            timing: Timing {
+                genesis_ts: 1_000,
+                ..Timing::default()
+            },
+            ..Config::default()
+        }
+    }
+
+    #[test]
+    fn test_halvings_shrink_minted_amount_geometrically() {
+        let config = halving_config(Config::MAX_HALVING_COUNT_CAP);
+        let genesis = config.timing.genesis_ts;
+
+        // One year of accrual at the start of each halving period
+        let mut previous = None;
+        for (period, rate) in [(0, 1600), (1, 800), (2, 400), (3, 200), (4, 100)] {
+            let now = genesis + period * SECONDS_PER_YEAR;
+            assert_eq!(effective_inflation_rate(&config, now), rate);
+
+            let minted =
+                accrued_inflation(INITIAL_SUPPLY, rate, SECONDS_PER_YEAR, SECONDS_PER_YEAR)
+                    .unwrap();
+            if let Some(previous) = previous {
+                assert_eq!(minted * 2, previous);
+            }
+            previous = Some(minted);
+        }
+
+        // The rate changes exactly on the boundary
+        let boundary = genesis + SECONDS_PER_YEAR;
+        assert_eq!(effective_inflation_rate(&config, boundary - 1), 1600);
+        assert_eq!(effective_inflation_rate(&config, boundary), 800);
+
+        // Eventually floors to zero
+        assert_eq!(effective_inflation_rate(&config, genesis + 11 * SECONDS_PER_YEAR), 0);
+    }
+
+    #[test]
+    fn test_minted_amount_scales_inversely_with_rate_period() {
+        const DAY: i64 = 24 * 60 * 60;
+        let elapsed = 45 * DAY;
+
+        // 10% per period: a 90-day period mints 4x what a 360-day one does
+        let quarterly = accrued_inflation(INITIAL_SUPPLY, 1000, elapsed, 90 * DAY).unwrap();
+        let yearly = accrued_inflation(INITIAL_SUPPLY, 1000, elapsed, 360 * DAY).unwrap();
+        assert_eq!(quarterly, INITIAL_SUPPLY / 20);
+        assert_eq!(quarterly, yearly * 4);
+
+        // A full period mints the full rate, whatever its length
+        for period in [DAY, 90 * DAY, SECONDS_PER_YEAR] {
+            let minted = accrued_inflation(INITIAL_SUPPLY, 1000, period, period).unwrap();
+            assert_eq!(minted, INITIAL_SUPPLY / 10);
+        }
+
+        for period in [0, -1] {
+            let err = accrued_inflation(INITIAL_SUPPLY, 1000, elapsed, period).unwrap_err();
+            assert!(matches!(err, YapError::InvalidInstruction));
+        }
+    }
+
+    #[test]
+    fn test_dust_inflation_carries_until_it_mints() {
+        const HOUR: i64 = 60 * 60;
+        let (supply, rate) = (1_000, 10);
+
+        // 0.1% of 1000 tokens per year is 1 token; an hour's share floors to 0
+        assert_eq!(
+            accrued_inflation(supply, rate, HOUR, SECONDS_PER_YEAR).unwrap(),
+            0
+        );
+
+        // Hourly calls bank the fraction and mint once a whole unit has built up
+        let mut remainder = 0;
+        let mut calls = 0;
+        let minted = loop {
+            let (amount, carried) =
+                accrue_inflation(supply, rate, HOUR, SECONDS_PER_YEAR, remainder).unwrap();
+            calls += 1;
+            remainder = carried;
+            if amount > 0 {
+                break amount;
+            }
+        };
+        assert_eq!(calls, SECONDS_PER_YEAR / HOUR);
+        assert_eq!(minted, 1);
+        assert_eq!(remainder, 0);
+
+        // Same total as one call over the whole stretch, remainder included
+        let (one_shot, one_shot_remainder) =
+            accrue_inflation(supply, rate, SECONDS_PER_YEAR + 1, SECONDS_PER_YEAR, 0).unwrap();
+        let (split, split_remainder) = accrue_inflation(supply, rate, 1, SECONDS_PER_YEAR, 0)
+            .and_then(|(_, r)| {
+                accrue_inflation(supply, rate, SECONDS_PER_YEAR, SECONDS_PER_YEAR, r)
+            })
+            .unwrap();
+        assert_eq!((split, split_remainder), (one_shot, one_shot_remainder));
+    }
+
+    #[test]
+    fn test_halving_count_cap_and_flat_rate() {
+        let capped = halving_config(2);
+        let far_future = capped.timing.genesis_ts + 50 * SECONDS_PER_YEAR;
+        assert_eq!(effective_inflation_rate(&capped, far_future), 400);
+
+        // No interval: flat rate, whatever the time
+        let mut flat = halving_config(2);
+        flat.tokenomics.halving_interval = 0;
+        assert_eq!(effective_inflation_rate(&flat, far_future), 1600);
+
+        // Clock before genesis counts as no halvings
+        assert_eq!(effective_inflation_rate(&capped, 0), 1600);
+    }
+
+    #[test]
+    fn test_permissionless_trigger_requires_flag_and_keeper_account() {
+        let program_id = Pubkey::new_unique();
+        let keeper_key = Pubkey::new_unique();
+        let mint_key = Pubkey::new_unique();
+        let disabled = Config {
+            addresses: Addresses {
+                mint: mint_key,
+                ..Addresses::default()
+            },
+            tokenomics: Tokenomics {
+                keeper_reward_bps: 100,
+                ..Tokenomics::default()
+            },
+            ..Config::default()
+        };
+
+        let mut keeper = TestAccount::signer(keeper_key);
+        let mut config = TestAccount::config(&program_id, disabled.clone());
+        let mut mint = TestAccount::new(mint_key);
+        let mut vault = TestAccount::new(Pubkey::new_unique());
+        let mut keeper_ata = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+
+        // Flag off: any keeper is rejected
+        {
+            let accounts = [
+                keeper.info(),
+                config.info(),
+                mint.info(),
+                vault.info(),
+                keeper_ata.info(),
+                token_program.info(),
+            ];
+            let err = process_permissionless(&program_id, &accounts).unwrap_err();
+            assert_eq!(err, YapError::Unauthorized.into());
+        }
+
+        // Flag on: the keeper's token account is checked (empty data can't unpack)
+        config.set_config(Config {
+            tokenomics: Tokenomics {
+                permissionless_inflation: true,
+                ..disabled.tokenomics
+            },
+            ..disabled
+        });
+        let accounts = [
+            keeper.info(),
+            config.info(),
+            mint.info(),
+            vault.info(),
+            keeper_ata.info(),
+            token_program.info(),
+        ];
+        let err = process_permissionless(&program_id, &accounts).unwrap_err();
+        assert_eq!(err, YapError::UninitializedTokenAccount.into());
+    }
+}
@@ -0,0 +1,174 @@
+use solana_program::{
+    account_info::{next_account_info, AccountInfo},
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program::invoke,
+    pubkey::Pubkey,
+};
+
+use crate::{
+    error::YapError,
+    instructions::admin::load_config_for_admin,
+    state::{MAX_METADATA_URI_LEN, METADATA_PROGRAM_ID, METADATA_SEED, TOKEN_NAME, TOKEN_SYMBOL},
+};
+
+/// Point the YAP token metadata at a new URI (admin only)
+///
+/// The admin is the metadata's update authority (set by `Initialize`) and
+/// signs the Metaplex CPI directly. Name and symbol are rewritten unchanged.
+///
+/// Accounts:
+/// 0. `[signer]` Admin (metadata update authority)
+/// 1. `[]` Config PDA
+/// 2. `[writable]` Metadata PDA
+/// 3. `[]` Metaplex Token Metadata program
+pub fn process(program_id: &Pubkey, accounts: &[AccountInfo], uri: String) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let admin = next_account_info(account_info_iter)?;
+    let config_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let metadata_program = next_account_info(account_info_iter)?;
+
+    validate_uri(&uri)?;
+
+    let config = load_config_for_admin(program_id, admin, config_info)?;
+
+    let (metadata_pda, _) = Pubkey::find_program_address(
+        &[
+            METADATA_SEED,
+            METADATA_PROGRAM_ID.as_ref(),
+            config.addresses.mint.as_ref(),
+        ],
+        &METADATA_PROGRAM_ID,
+    );
+    if metadata_info.key != &metadata_pda {
+        return Err(YapError::InvalidPda.into());
+    }
+    if metadata_program.key != &METADATA_PROGRAM_ID {
+        return Err(YapError::InvalidOwner.into());
+    }
+
+    msg!("UpdateMetadataUri: {}", uri);
+
+    invoke(
+        &build_update_metadata_v2_instruction(metadata_info.key, admin.key, &uri),
+        &[
+            metadata_info.clone(),
+            admin.clone(),
+            metadata_program.clone(),
+        ],
+    )
+}
+
+/// Reject an empty URI or one over the Metaplex limit
+fn validate_uri(uri: &str) -> Result<(), YapError> {
+    if uri.is_empty() || uri.len() > MAX_METADATA_URI_LEN {
+        msg!(
+            "UpdateMetadataUri: URI length {} outside 1..={}",
+            uri.len(),
+            MAX_METADATA_URI_LEN
+        );
+        return Err(YapError::InvalidMetadataUri);
+    }
+    Ok(())
+}
+
+/// Build UpdateMetadataAccountV2 instruction manually
+///
+/// Same approach as `build_create_metadata_v3_instruction`: only the data is
+/// replaced (with the initial name and symbol), while update authority,
+/// primary sale flag and mutability are left as they are.
+fn build_update_metadata_v2_instruction(
+    metadata: &Pubkey,
+    update_authority: &Pubkey,
+    uri: &str,
+) -> Instruction {
+    // UpdateMetadataAccountV2 instruction discriminator (index 15 in Metaplex instruction enum)
+    const UPDATE_METADATA_ACCOUNT_V2: u8 = 15;
+
+    let mut data = Vec::with_capacity(64 + uri.len());
+
+    // Discriminator
+    data.push(UPDATE_METADATA_ACCOUNT_V2);
+
+    // data (Option<DataV2>): Some
+    data.push(1);
+    for field in [TOKEN_NAME, TOKEN_SYMBOL, uri] {
+        // string: 4-byte length + bytes
+        data.extend_from_slice(&(field.len() as u32).to_le_bytes());
+        data.extend_from_slice(field.as_bytes());
+    }
+    // seller_fee_basis_points (u16)
+    data.extend_from_slice(&0u16.to_le_bytes());
+    // creators, collection, uses: None
+    data.extend_from_slice(&[0, 0, 0]);
+
+    // new_update_authority (Option<Pubkey>): None = keep
+    data.push(0);
+    // primary_sale_happened (Option<bool>): None = keep
+    data.push(0);
+    // is_mutable (Option<bool>): None = keep
+    data.push(0);
+
+    Instruction {
+        program_id: METADATA_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*metadata, false),
+            AccountMeta::new_readonly(*update_authority, true),
+        ],
+        data,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_update_metadata_instruction_layout() {
+        let (metadata, authority) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let uri = "https://example.com/yap.json";
+        let ix = build_update_metadata_v2_instruction(&metadata, &authority, uri);
+
+        assert_eq!(ix.program_id, METADATA_PROGRAM_ID);
+        assert_eq!(
+            ix.accounts,
+            vec![
+                AccountMeta::new(metadata, false),
+                AccountMeta::new_readonly(authority, true),
+            ]
+        );
+
+        // Discriminator, then Some(DataV2) with the fixed name and symbol
+        assert_eq!(&ix.data[..2], &[15, 1]);
+        let name_end = 2 + 4 + TOKEN_NAME.len();
+        assert_eq!(&ix.data[6..name_end], TOKEN_NAME.as_bytes());
+        let symbol_end = name_end + 4 + TOKEN_SYMBOL.len();
+        assert_eq!(&ix.data[name_end + 4..symbol_end], TOKEN_SYMBOL.as_bytes());
+        assert_eq!(
+            &ix.data[symbol_end..symbol_end + 4],
+            &(uri.len() as u32).to_le_bytes()
+        );
+        let uri_end = symbol_end + 4 + uri.len();
+        assert_eq!(&ix.data[symbol_end + 4..uri_end], uri.as_bytes());
+
+        // Zero fee, no creators/collection/uses, everything else kept
+        assert_eq!(&ix.data[uri_end..], &[0, 0, 0, 0, 0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_uri_length_bounds() {
+        assert!(validate_uri("a").is_ok());
+        assert!(validate_uri(&"a".repeat(MAX_METADATA_URI_LEN)).is_ok());
+        assert!(matches!(
+            validate_uri(""),
+            Err(YapError::InvalidMetadataUri)
+        ));
+        assert!(matches!(
+            validate_uri(&"a".repeat(MAX_METADATA_URI_LEN + 1)),
+            Err(YapError::InvalidMetadataUri)
+        ));
+    }
+}
@@ -1,22 +1,17 @@
-use solana_program::{
-    account_info::AccountInfo, declare_id, entrypoint, entrypoint::ProgramResult, pubkey::Pubkey,
-};
+use solana_program::declare_id;
 
+#[cfg(feature = "client")]
+pub mod client;
+#[cfg(not(feature = "no-entrypoint"))]
+mod entrypoint;
 pub mod error;
+pub mod events;
 pub mod instruction;
 pub mod instructions;
 pub mod processor;
 pub mod state;
+#[cfg(test)]
+mod test_utils;
 pub mod utils;
 
 declare_id!("54hRK2FzD33oxs6EaMtVSSNJo9TLWqTDncHLUz2eG6L1");
-
-entrypoint!(process_instruction);
-
-fn process_instruction<'a>(
-    program_id: &Pubkey,
-    accounts: &'a [AccountInfo<'a>],
-    instruction_data: &[u8],
-) -> ProgramResult {
-    processor::process(program_id, accounts, instruction_data)
-}
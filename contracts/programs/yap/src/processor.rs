@@ -1,20 +1,32 @@
-use borsh::BorshDeserialize;
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, msg, pubkey::Pubkey};
 
-use crate::{error::YapError, instruction::YapInstruction};
+use crate::instruction::YapInstruction;
 
 pub fn process(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     instruction_data: &[u8],
 ) -> ProgramResult {
-    let instruction = YapInstruction::try_from_slice(instruction_data)
-        .map_err(|_| YapError::InvalidInstruction)?;
+    let instruction = YapInstruction::unpack(instruction_data)?;
+
+    let expected = instruction.expected_accounts();
+    if !expected.contains(&accounts.len()) {
+        msg!(
+            "Expected {}..={} accounts, got {}",
+            expected.start(),
+            expected.end(),
+            accounts.len()
+        );
+        return Err(crate::error::YapError::InvalidInstruction.into());
+    }
 
     match instruction {
         YapInstruction::Initialize {
             merkle_updater,
             inflation_rate_bps,
+            max_supply,
+            min_distribution_interval,
+            rate_period_secs,
         } => {
             msg!("Instruction: Initialize");
             crate::instructions::initialize::process(
@@ -22,15 +34,28 @@ pub fn process(
                 accounts,
                 merkle_updater,
                 inflation_rate_bps,
+                max_supply,
+                min_distribution_interval,
+                rate_period_secs,
             )
         }
         YapInstruction::TriggerInflation => {
             msg!("Instruction: TriggerInflation");
             crate::instructions::trigger_inflation::process(program_id, accounts)
         }
-        YapInstruction::Distribute { amount, merkle_root } => {
+        YapInstruction::Distribute {
+            amount,
+            merkle_root,
+            tree_depth,
+        } => {
             msg!("Instruction: Distribute");
-            crate::instructions::distribute::process(program_id, accounts, amount, merkle_root)
+            crate::instructions::distribute::process(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                tree_depth,
+            )
         }
         YapInstruction::Claim { amount, proof } => {
             msg!("Instruction: Claim");
@@ -56,5 +81,1017 @@ pub fn process(
                 new_rate_bps,
             )
         }
+        YapInstruction::SetBonusRoot {
+            bonus_root,
+            funding,
+            tree_depth,
+        } => {
+            msg!("Instruction: SetBonusRoot");
+            crate::instructions::distribute::process_set_bonus_root(
+                program_id, accounts, bonus_root, funding, tree_depth,
+            )
+        }
+        YapInstruction::ClaimWithBonus {
+            amount,
+            proof,
+            bonus_amount,
+            bonus_proof,
+        } => {
+            msg!("Instruction: ClaimWithBonus");
+            crate::instructions::claim::process_with_bonus(
+                program_id,
+                accounts,
+                amount,
+                proof,
+                bonus_amount,
+                bonus_proof,
+            )
+        }
+        YapInstruction::DistributeWithMemo {
+            amount,
+            merkle_root,
+            memo,
+            tree_depth,
+        } => {
+            msg!("Instruction: DistributeWithMemo");
+            crate::instructions::distribute::process_with_memo(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                memo,
+                tree_depth,
+            )
+        }
+        YapInstruction::ScheduleRebaseBurn { amount, execute_ts } => {
+            msg!("Instruction: ScheduleRebaseBurn");
+            crate::instructions::rebase::process_schedule(program_id, accounts, amount, execute_ts)
+        }
+        YapInstruction::ExecuteRebaseBurn => {
+            msg!("Instruction: ExecuteRebaseBurn");
+            crate::instructions::rebase::process_execute(program_id, accounts)
+        }
+        YapInstruction::SetInflationCompounding { enabled } => {
+            msg!("Instruction: SetInflationCompounding");
+            crate::instructions::admin::process_set_inflation_compounding(
+                program_id, accounts, enabled,
+            )
+        }
+        #[cfg(feature = "test-time")]
+        YapInstruction::SetLastTimestamps {
+            inflation_ts,
+            distribution_ts,
+        } => {
+            msg!("Instruction: SetLastTimestamps");
+            crate::instructions::test_time::process_set_last_timestamps(
+                program_id,
+                accounts,
+                inflation_ts,
+                distribution_ts,
+            )
+        }
+        #[cfg(not(feature = "test-time"))]
+        YapInstruction::SetLastTimestamps { .. } => {
+            msg!("SetLastTimestamps is only available in test-time builds");
+            Err(crate::error::YapError::InvalidInstruction.into())
+        }
+        YapInstruction::BatchClaim { entries } => {
+            msg!("Instruction: BatchClaim");
+            crate::instructions::batch_claim::process(program_id, accounts, entries)
+        }
+        YapInstruction::ClaimWithExpiry {
+            amount,
+            proof,
+            expiry_ts,
+        } => {
+            msg!("Instruction: ClaimWithExpiry");
+            crate::instructions::claim::process_with_expiry(
+                program_id, accounts, amount, proof, expiry_ts,
+            )
+        }
+        YapInstruction::SetLeafHasExpiry { enabled } => {
+            msg!("Instruction: SetLeafHasExpiry");
+            crate::instructions::admin::process_set_leaf_has_expiry(program_id, accounts, enabled)
+        }
+        YapInstruction::SetStrictAtaCheck { enabled } => {
+            msg!("Instruction: SetStrictAtaCheck");
+            crate::instructions::admin::process_set_strict_ata_check(program_id, accounts, enabled)
+        }
+        YapInstruction::CloseUserClaim { amount, proof } => {
+            msg!("Instruction: CloseUserClaim");
+            crate::instructions::close_claim::process(program_id, accounts, amount, proof)
+        }
+        YapInstruction::LogRecentRounds { count } => {
+            msg!("Instruction: LogRecentRounds");
+            crate::instructions::history::process_log_recent_rounds(program_id, accounts, count)
+        }
+        YapInstruction::SetMinProofDepth { min_proof_depth } => {
+            msg!("Instruction: SetMinProofDepth");
+            crate::instructions::admin::process_set_min_proof_depth(
+                program_id,
+                accounts,
+                min_proof_depth,
+            )
+        }
+        YapInstruction::ConsolidateAndBurn { amount } => {
+            msg!("Instruction: ConsolidateAndBurn");
+            crate::instructions::burn::process_consolidate_and_burn(program_id, accounts, amount)
+        }
+        YapInstruction::TransferAdmin { new_admin } => {
+            msg!("Instruction: TransferAdmin");
+            crate::instructions::admin::process_transfer_admin(program_id, accounts, new_admin)
+        }
+        YapInstruction::AcceptAdmin => {
+            msg!("Instruction: AcceptAdmin");
+            crate::instructions::admin::process_accept_admin(program_id, accounts)
+        }
+        YapInstruction::MigrateConfig => {
+            msg!("Instruction: MigrateConfig");
+            crate::instructions::admin::process_migrate_config(program_id, accounts)
+        }
+        YapInstruction::SetPaused { paused } => {
+            msg!("Instruction: SetPaused");
+            crate::instructions::admin::process_set_paused(program_id, accounts, paused)
+        }
+        YapInstruction::SetMaxClaimAccounts { max_claim_accounts } => {
+            msg!("Instruction: SetMaxClaimAccounts");
+            crate::instructions::admin::process_set_max_claim_accounts(
+                program_id,
+                accounts,
+                max_claim_accounts,
+            )
+        }
+        YapInstruction::SetInflationBurnBps { inflation_burn_bps } => {
+            msg!("Instruction: SetInflationBurnBps");
+            crate::instructions::admin::process_set_inflation_burn_bps(
+                program_id,
+                accounts,
+                inflation_burn_bps,
+            )
+        }
+        YapInstruction::ClaimBurnReward => {
+            msg!("Instruction: ClaimBurnReward");
+            crate::instructions::burn_reward::process(program_id, accounts)
+        }
+        YapInstruction::SetBurnRewardBps { burn_reward_bps } => {
+            msg!("Instruction: SetBurnRewardBps");
+            crate::instructions::admin::process_set_burn_reward_bps(
+                program_id,
+                accounts,
+                burn_reward_bps,
+            )
+        }
+        YapInstruction::SetPermissionlessInflation {
+            enabled,
+            keeper_reward_bps,
+        } => {
+            msg!("Instruction: SetPermissionlessInflation");
+            crate::instructions::admin::process_set_permissionless_inflation(
+                program_id,
+                accounts,
+                enabled,
+                keeper_reward_bps,
+            )
+        }
+        YapInstruction::TriggerInflationPermissionless => {
+            msg!("Instruction: TriggerInflationPermissionless");
+            crate::instructions::trigger_inflation::process_permissionless(program_id, accounts)
+        }
+        YapInstruction::SetEarlyBirdBonus { early_bird_bonus } => {
+            msg!("Instruction: SetEarlyBirdBonus");
+            crate::instructions::admin::process_set_early_bird_bonus(
+                program_id,
+                accounts,
+                early_bird_bonus,
+            )
+        }
+        YapInstruction::BatchClaimMultiproof {
+            entries,
+            proof,
+            proof_flags,
+        } => {
+            msg!("Instruction: BatchClaimMultiproof");
+            crate::instructions::batch_claim::process_multiproof(
+                program_id,
+                accounts,
+                entries,
+                proof,
+                proof_flags,
+            )
+        }
+        YapInstruction::SetMinDistributionInterval {
+            min_distribution_interval,
+        } => {
+            msg!("Instruction: SetMinDistributionInterval");
+            crate::instructions::admin::process_set_min_distribution_interval(
+                program_id,
+                accounts,
+                min_distribution_interval,
+            )
+        }
+        YapInstruction::GetClaimable { amount, proof } => {
+            msg!("Instruction: GetClaimable");
+            crate::instructions::get_claimable::process(program_id, accounts, amount, proof)
+        }
+        YapInstruction::SetTreasury {
+            treasury_inflation_bps,
+        } => {
+            msg!("Instruction: SetTreasury");
+            crate::instructions::admin::process_set_treasury(
+                program_id,
+                accounts,
+                treasury_inflation_bps,
+            )
+        }
+        YapInstruction::SetHalvingSchedule {
+            halving_interval,
+            halving_count_cap,
+        } => {
+            msg!("Instruction: SetHalvingSchedule");
+            crate::instructions::admin::process_set_halving_schedule(
+                program_id,
+                accounts,
+                halving_interval,
+                halving_count_cap,
+            )
+        }
+        YapInstruction::RenounceAdmin => {
+            msg!("Instruction: RenounceAdmin");
+            crate::instructions::admin::process_renounce_admin(program_id, accounts)
+        }
+        YapInstruction::UpdateMetadataUri { uri } => {
+            msg!("Instruction: UpdateMetadataUri");
+            crate::instructions::update_metadata::process(program_id, accounts, uri)
+        }
+        YapInstruction::SetClaimCooldown { claim_cooldown } => {
+            msg!("Instruction: SetClaimCooldown");
+            crate::instructions::admin::process_set_claim_cooldown(
+                program_id,
+                accounts,
+                claim_cooldown,
+            )
+        }
+        YapInstruction::DistributeWithDepth {
+            amount,
+            merkle_root,
+            tree_depth,
+        } => {
+            msg!("Instruction: DistributeWithDepth");
+            crate::instructions::distribute::process(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                tree_depth,
+            )
+        }
+        YapInstruction::InitClaimBitmap {
+            distribution_id,
+            capacity,
+        } => {
+            msg!("Instruction: InitClaimBitmap");
+            crate::instructions::claim_indexed::process_init_bitmap(
+                program_id,
+                accounts,
+                distribution_id,
+                capacity,
+            )
+        }
+        YapInstruction::ClaimIndexed {
+            distribution_id,
+            index,
+            amount,
+            proof,
+        } => {
+            msg!("Instruction: ClaimIndexed");
+            crate::instructions::claim_indexed::process(
+                program_id,
+                accounts,
+                distribution_id,
+                index,
+                amount,
+                proof,
+            )
+        }
+        YapInstruction::FreezeWallet { wallet } => {
+            msg!("Instruction: FreezeWallet");
+            crate::instructions::admin::process_freeze_wallet(program_id, accounts, wallet)
+        }
+        YapInstruction::UnfreezeWallet { wallet } => {
+            msg!("Instruction: UnfreezeWallet");
+            crate::instructions::admin::process_unfreeze_wallet(program_id, accounts, wallet)
+        }
+        YapInstruction::SetUpdaterSet {
+            updater_keys,
+            updater_threshold,
+        } => {
+            msg!("Instruction: SetUpdaterSet");
+            crate::instructions::admin::process_set_updater_set(
+                program_id,
+                accounts,
+                updater_keys,
+                updater_threshold,
+            )
+        }
+        YapInstruction::QueueDistribution {
+            amount,
+            merkle_root,
+            eta,
+            tree_depth,
+        } => {
+            msg!("Instruction: QueueDistribution");
+            crate::instructions::queued_distribution::process_queue(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                eta,
+                tree_depth,
+            )
+        }
+        YapInstruction::ExecuteDistribution {
+            amount,
+            merkle_root,
+        } => {
+            msg!("Instruction: ExecuteDistribution");
+            crate::instructions::queued_distribution::process_execute(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+            )
+        }
+        YapInstruction::CancelDistribution => {
+            msg!("Instruction: CancelDistribution");
+            crate::instructions::queued_distribution::process_cancel(program_id, accounts)
+        }
+        YapInstruction::ClaimAndBurn {
+            amount,
+            proof,
+            burn_amount,
+        } => {
+            msg!("Instruction: ClaimAndBurn");
+            crate::instructions::claim::process_and_burn(
+                program_id,
+                accounts,
+                amount,
+                proof,
+                burn_amount,
+            )
+        }
+        YapInstruction::DistributeWithMinAmount {
+            amount,
+            merkle_root,
+            min_amount,
+            tree_depth,
+        } => {
+            msg!("Instruction: DistributeWithMinAmount");
+            crate::instructions::distribute::process_with_min_amount(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                min_amount,
+                tree_depth,
+            )
+        }
+        YapInstruction::DistributeCategory {
+            category,
+            amount,
+            merkle_root,
+            tree_depth,
+        } => {
+            msg!("Instruction: DistributeCategory");
+            crate::instructions::distribute::process_category(
+                program_id,
+                accounts,
+                category,
+                amount,
+                merkle_root,
+                tree_depth,
+            )
+        }
+        YapInstruction::ClaimCategory {
+            category,
+            amount,
+            proof,
+        } => {
+            msg!("Instruction: ClaimCategory");
+            crate::instructions::claim_category::process(
+                program_id, accounts, category, amount, proof,
+            )
+        }
+        YapInstruction::SetClaimWindow { claim_window } => {
+            msg!("Instruction: SetClaimWindow");
+            crate::instructions::admin::process_set_claim_window(program_id, accounts, claim_window)
+        }
+        YapInstruction::SweepUnclaimed { back_to_vault } => {
+            msg!("Instruction: SweepUnclaimed");
+            crate::instructions::sweep_unclaimed::process(program_id, accounts, back_to_vault)
+        }
+        YapInstruction::Stake { amount } => {
+            msg!("Instruction: Stake");
+            crate::instructions::stake::process_stake(program_id, accounts, amount)
+        }
+        YapInstruction::Unstake { amount } => {
+            msg!("Instruction: Unstake");
+            crate::instructions::stake::process_unstake(program_id, accounts, amount)
+        }
+        YapInstruction::DistributeWithVesting {
+            amount,
+            merkle_root,
+            cliff_ts,
+            vesting_duration,
+            tree_depth,
+        } => {
+            msg!("Instruction: DistributeWithVesting");
+            crate::instructions::distribute::process_with_vesting(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                cliff_ts,
+                vesting_duration,
+                tree_depth,
+            )
+        }
+        YapInstruction::SetClaimDelegate { delegate } => {
+            msg!("Instruction: SetClaimDelegate");
+            crate::instructions::claim_delegate::process_set(program_id, accounts, delegate)
+        }
+        YapInstruction::RevokeClaimDelegate => {
+            msg!("Instruction: RevokeClaimDelegate");
+            crate::instructions::claim_delegate::process_revoke(program_id, accounts)
+        }
+        YapInstruction::ClaimAsDelegate { amount, proof } => {
+            msg!("Instruction: ClaimAsDelegate");
+            crate::instructions::claim::process_as_delegate(program_id, accounts, amount, proof)
+        }
+        YapInstruction::WriteProofBuffer { offset, nodes } => {
+            msg!("Instruction: WriteProofBuffer");
+            crate::instructions::proof_buffer::process_write(program_id, accounts, offset, nodes)
+        }
+        YapInstruction::ClaimWithProofBuffer { amount } => {
+            msg!("Instruction: ClaimWithProofBuffer");
+            crate::instructions::claim::process_with_proof_buffer(program_id, accounts, amount)
+        }
+        YapInstruction::ClaimPartial {
+            amount,
+            proof,
+            claim_amount,
+        } => {
+            msg!("Instruction: ClaimPartial");
+            crate::instructions::claim::process_partial(
+                program_id,
+                accounts,
+                amount,
+                proof,
+                claim_amount,
+            )
+        }
+        #[cfg(feature = "devnet")]
+        YapInstruction::CloseProgram => {
+            msg!("Instruction: CloseProgram");
+            crate::instructions::close_program::process(program_id, accounts)
+        }
+        #[cfg(not(feature = "devnet"))]
+        YapInstruction::CloseProgram => {
+            msg!("CloseProgram is only available in devnet builds");
+            Err(crate::error::YapError::InvalidInstruction.into())
+        }
+        YapInstruction::InitializeExisting {
+            mint,
+            merkle_updater,
+            inflation_rate_bps,
+            max_supply,
+            min_distribution_interval,
+            rate_period_secs,
+        } => {
+            msg!("Instruction: InitializeExisting");
+            crate::instructions::initialize::process_existing(
+                program_id,
+                accounts,
+                mint,
+                merkle_updater,
+                inflation_rate_bps,
+                max_supply,
+                min_distribution_interval,
+                rate_period_secs,
+            )
+        }
+        YapInstruction::DistributeWithNonce {
+            amount,
+            merkle_root,
+            nonce,
+            tree_depth,
+        } => {
+            msg!("Instruction: DistributeWithNonce");
+            crate::instructions::distribute::process_with_nonce(
+                program_id,
+                accounts,
+                amount,
+                merkle_root,
+                nonce,
+                tree_depth,
+            )
+        }
+        YapInstruction::SetMaxClaimPerWallet {
+            max_claim_per_wallet,
+        } => {
+            msg!("Instruction: SetMaxClaimPerWallet");
+            crate::instructions::admin::process_set_max_claim_per_wallet(
+                program_id,
+                accounts,
+                max_claim_per_wallet,
+            )
+        }
+        YapInstruction::SetCategoryBps { category_bps } => {
+            msg!("Instruction: SetCategoryBps");
+            crate::instructions::admin::process_set_category_bps(program_id, accounts, category_bps)
+        }
+        YapInstruction::BurnAsDelegate { amount } => {
+            msg!("Instruction: BurnAsDelegate");
+            crate::instructions::burn::process_as_delegate(program_id, accounts, amount)
+        }
+        YapInstruction::SimulateDistribute { category } => {
+            msg!("Instruction: SimulateDistribute");
+            crate::instructions::distribute::process_simulate(program_id, accounts, category)
+        }
+        YapInstruction::RescueTokens { amount } => {
+            msg!("Instruction: RescueTokens");
+            crate::instructions::rescue::process(program_id, accounts, amount)
+        }
+        YapInstruction::SetMaxSingleClaim { max_single_claim } => {
+            msg!("Instruction: SetMaxSingleClaim");
+            crate::instructions::admin::process_set_max_single_claim(
+                program_id,
+                accounts,
+                max_single_claim,
+            )
+        }
+        YapInstruction::SetAllowedDistributionDest {
+            allowed_distribution_dest,
+        } => {
+            msg!("Instruction: SetAllowedDistributionDest");
+            crate::instructions::admin::process_set_allowed_distribution_dest(
+                program_id,
+                accounts,
+                allowed_distribution_dest,
+            )
+        }
+        YapInstruction::SetClaimFee { claim_fee_bps } => {
+            msg!("Instruction: SetClaimFee");
+            crate::instructions::admin::process_set_claim_fee(program_id, accounts, claim_fee_bps)
+        }
+        YapInstruction::SetDistributionTimelock {
+            distribution_timelock,
+        } => {
+            msg!("Instruction: SetDistributionTimelock");
+            crate::instructions::admin::process_set_distribution_timelock(
+                program_id,
+                accounts,
+                distribution_timelock,
+            )
+        }
+        YapInstruction::ClaimEpoch {
+            distribution_id,
+            amount,
+            proof,
+        } => {
+            msg!("Instruction: ClaimEpoch");
+            crate::instructions::claim_epoch::process(
+                program_id,
+                accounts,
+                distribution_id,
+                amount,
+                proof,
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        error::YapError,
+        instruction::{ClaimEntry, MultiproofEntry},
+        state::{MAX_BATCH_CLAIMS, MAX_PROOF_DEPTH, SECONDS_PER_YEAR},
+    };
+
+    #[test]
+    fn test_last_tag_matches_last_variant() {
+        let data = borsh::to_vec(&YapInstruction::SetDistributionTimelock {
+            distribution_timelock: 0,
+        })
+        .unwrap();
+        assert_eq!(data[0], YapInstruction::LAST_TAG);
+    }
+
+    #[test]
+    fn test_oversized_proof_prefix_rejected_before_unpack() {
+        // Claim { amount, proof } claiming u32::MAX nodes but carrying none
+        let mut data = vec![3];
+        data.extend_from_slice(&1_000u64.to_le_bytes());
+        data.extend_from_slice(&u32::MAX.to_le_bytes());
+
+        let err = process(&Pubkey::new_unique(), &[], &data).unwrap_err();
+        assert_eq!(err, YapError::ProofTooLong.into());
+
+        // Too short to hold the prefix
+        assert!(matches!(
+            YapInstruction::unpack(&data[..10]),
+            Err(YapError::MalformedInstructionData)
+        ));
+    }
+
+    #[test]
+    fn test_every_proof_is_bounded() {
+        // Every variant carrying a proof, with `proof` in one proof field
+        let proof_carrying = |proof: Vec<[u8; 32]>| {
+            vec![
+                YapInstruction::Claim {
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::ClaimWithBonus {
+                    amount: 1,
+                    proof: proof.clone(),
+                    bonus_amount: 1,
+                    bonus_proof: vec![],
+                },
+                YapInstruction::ClaimWithBonus {
+                    amount: 1,
+                    proof: vec![],
+                    bonus_amount: 1,
+                    bonus_proof: proof.clone(),
+                },
+                YapInstruction::BatchClaim {
+                    entries: vec![
+                        ClaimEntry {
+                            user: Pubkey::new_unique(),
+                            amount: 1,
+                            proof: vec![],
+                        },
+                        ClaimEntry {
+                            user: Pubkey::new_unique(),
+                            amount: 1,
+                            proof: proof.clone(),
+                        },
+                    ],
+                },
+                YapInstruction::ClaimWithExpiry {
+                    amount: 1,
+                    proof: proof.clone(),
+                    expiry_ts: 1,
+                },
+                YapInstruction::CloseUserClaim {
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::ClaimEpoch {
+                    distribution_id: 1,
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::GetClaimable {
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::ClaimIndexed {
+                    distribution_id: 1,
+                    index: 1,
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::ClaimAndBurn {
+                    amount: 1,
+                    proof: proof.clone(),
+                    burn_amount: 1,
+                },
+                YapInstruction::ClaimCategory {
+                    category: 1,
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::ClaimAsDelegate {
+                    amount: 1,
+                    proof: proof.clone(),
+                },
+                YapInstruction::WriteProofBuffer {
+                    offset: 0,
+                    nodes: proof.clone(),
+                },
+                YapInstruction::ClaimPartial {
+                    amount: 1,
+                    proof,
+                    claim_amount: 1,
+                },
+            ]
+        };
+
+        for instruction in proof_carrying(vec![[7; 32]; MAX_PROOF_DEPTH]) {
+            let data = borsh::to_vec(&instruction).unwrap();
+            assert!(YapInstruction::unpack(&data).is_ok(), "{:?}", instruction);
+        }
+        for instruction in proof_carrying(vec![[7; 32]; MAX_PROOF_DEPTH + 1]) {
+            let data = borsh::to_vec(&instruction).unwrap();
+            assert!(
+                matches!(YapInstruction::unpack(&data), Err(YapError::ProofTooLong)),
+                "{:?}",
+                instruction
+            );
+        }
+    }
+
+    #[test]
+    fn test_batch_vectors_are_bounded() {
+        let multiproof = |entries: usize, proof: usize, proof_flags: usize| {
+            let instruction = YapInstruction::BatchClaimMultiproof {
+                entries: vec![
+                    MultiproofEntry {
+                        user: Pubkey::new_unique(),
+                        amount: 1,
+                    };
+                    entries
+                ],
+                proof: vec![[7; 32]; proof],
+                proof_flags: vec![true; proof_flags],
+            };
+            YapInstruction::unpack(&borsh::to_vec(&instruction).unwrap())
+        };
+        let max_proof = MAX_PROOF_DEPTH * MAX_BATCH_CLAIMS;
+        let max_flags = MAX_BATCH_CLAIMS + max_proof;
+
+        assert!(multiproof(MAX_BATCH_CLAIMS, max_proof, max_flags).is_ok());
+        assert!(matches!(
+            multiproof(MAX_BATCH_CLAIMS + 1, 0, 0),
+            Err(YapError::BatchTooLarge)
+        ));
+        assert!(matches!(
+            multiproof(1, max_proof + 1, 0),
+            Err(YapError::ProofTooLong)
+        ));
+        assert!(matches!(
+            multiproof(1, 0, max_flags + 1),
+            Err(YapError::ProofTooLong)
+        ));
+
+        let batch = YapInstruction::BatchClaim {
+            entries: vec![
+                ClaimEntry {
+                    user: Pubkey::new_unique(),
+                    amount: 1,
+                    proof: vec![],
+                };
+                MAX_BATCH_CLAIMS + 1
+            ],
+        };
+        assert!(matches!(
+            YapInstruction::unpack(&borsh::to_vec(&batch).unwrap()),
+            Err(YapError::BatchTooLarge)
+        ));
+    }
+
+    #[test]
+    fn test_admin_paths_fail_after_renounce() {
+        use crate::{
+            state::{Config, Roles},
+            test_utils::TestAccount,
+        };
+        use borsh::BorshDeserialize;
+
+        let program_id = Pubkey::new_unique();
+        let admin_key = Pubkey::new_unique();
+        let mut admin = TestAccount::signer(admin_key);
+        let mut config = TestAccount::config(
+            &program_id,
+            Config {
+                roles: Roles {
+                    admin: admin_key,
+                    pending_admin: admin_key,
+                    ..Roles::default()
+                },
+                ..Config::default()
+            },
+        );
+        let mut system_program = TestAccount::new(solana_system_interface::program::id());
+        let mut other = TestAccount::new(Pubkey::new_unique());
+        let mut token_program = TestAccount::new(spl_token::id());
+        let mut mint = TestAccount::new(Pubkey::new_unique());
+        let mut recipient = TestAccount::new(Pubkey::new_unique());
+
+        let mut run = |instruction: &YapInstruction| {
+            let accounts = [
+                admin.info(),
+                config.info(),
+                system_program.info(),
+                other.info(),
+                token_program.info(),
+                mint.info(),
+                recipient.info(),
+            ];
+            let count = *instruction.expected_accounts().start();
+            process(
+                &program_id,
+                &accounts[..count],
+                &borsh::to_vec(instruction).unwrap(),
+            )
+        };
+
+        run(&YapInstruction::RenounceAdmin).unwrap();
+
+        let admin_instructions = [
+            YapInstruction::TriggerInflation,
+            YapInstruction::UpdateMerkleUpdater {
+                new_updater: admin_key,
+            },
+            YapInstruction::UpdateInflationRate { new_rate_bps: 100 },
+            YapInstruction::SetBonusRoot {
+                bonus_root: [1u8; 32],
+                funding: 0,
+                tree_depth: 1,
+            },
+            YapInstruction::ScheduleRebaseBurn {
+                amount: 0,
+                execute_ts: 0,
+            },
+            YapInstruction::SetInflationCompounding { enabled: true },
+            YapInstruction::SetLeafHasExpiry { enabled: true },
+            YapInstruction::SetStrictAtaCheck { enabled: true },
+            YapInstruction::SetMinProofDepth { min_proof_depth: 1 },
+            YapInstruction::TransferAdmin {
+                new_admin: admin_key,
+            },
+            YapInstruction::AcceptAdmin,
+            YapInstruction::MigrateConfig,
+            YapInstruction::SetPaused { paused: true },
+            YapInstruction::SetMaxClaimAccounts {
+                max_claim_accounts: 1,
+            },
+            YapInstruction::SetInflationBurnBps {
+                inflation_burn_bps: 1,
+            },
+            YapInstruction::SetBurnRewardBps { burn_reward_bps: 1 },
+            YapInstruction::SetPermissionlessInflation {
+                enabled: true,
+                keeper_reward_bps: 1,
+            },
+            YapInstruction::SetEarlyBirdBonus {
+                early_bird_bonus: 1,
+            },
+            YapInstruction::SetMinDistributionInterval {
+                min_distribution_interval: 1,
+            },
+            YapInstruction::SetTreasury {
+                treasury_inflation_bps: 1,
+            },
+            YapInstruction::SetHalvingSchedule {
+                halving_interval: 1,
+                halving_count_cap: 1,
+            },
+            YapInstruction::RenounceAdmin,
+            YapInstruction::UpdateMetadataUri {
+                uri: "https://example.com".to_string(),
+            },
+            YapInstruction::SetClaimCooldown { claim_cooldown: 1 },
+            YapInstruction::FreezeWallet {
+                wallet: Pubkey::new_unique(),
+            },
+            YapInstruction::UnfreezeWallet {
+                wallet: Pubkey::new_unique(),
+            },
+            YapInstruction::SetUpdaterSet {
+                updater_keys: vec![Pubkey::new_unique()],
+                updater_threshold: 1,
+            },
+            YapInstruction::CancelDistribution,
+            YapInstruction::SetClaimWindow { claim_window: 1 },
+            YapInstruction::SweepUnclaimed {
+                back_to_vault: true,
+            },
+            YapInstruction::SetMaxClaimPerWallet {
+                max_claim_per_wallet: 1,
+            },
+            YapInstruction::SetCategoryBps {
+                category_bps: [10_000, 0, 0, 0],
+            },
+            YapInstruction::RescueTokens { amount: 1 },
+            YapInstruction::SetMaxSingleClaim {
+                max_single_claim: 1,
+            },
+            YapInstruction::SetAllowedDistributionDest {
+                allowed_distribution_dest: Pubkey::new_unique(),
+            },
+            YapInstruction::SetClaimFee { claim_fee_bps: 1 },
+            YapInstruction::SetDistributionTimelock {
+                distribution_timelock: 1,
+            },
+        ];
+        for instruction in &admin_instructions {
+            let err = run(instruction).unwrap_err();
+            assert_eq!(err, YapError::Unauthorized.into(), "{:?}", instruction);
+        }
+
+        let stored = Config::try_from_slice(&config.data).unwrap();
+        assert!(stored.roles.admin_renounced);
+        assert_eq!(stored.roles.admin, Pubkey::default());
+        assert_eq!(stored.roles.pending_admin, Pubkey::default());
+        assert!(!stored.is_admin(&Pubkey::default()));
+    }
+
+    #[test]
+    fn test_account_count_checked_before_dispatch() {
+        use crate::test_utils::TestAccount;
+        use borsh::BorshDeserialize;
+
+        let program_id = Pubkey::new_unique();
+        let mut pool: Vec<TestAccount> = (0..16)
+            .map(|_| TestAccount::new(Pubkey::new_unique()))
+            .collect();
+
+        // Zeroed payloads decode for every tag: empty vecs and strings, false bools
+        for tag in 0..=YapInstruction::LAST_TAG {
+            let mut data = [0u8; 128];
+            data[0] = tag;
+            let instruction = YapInstruction::deserialize(&mut &data[..]).unwrap();
+            let expected = instruction.expected_accounts();
+            assert!(*expected.start() > 0, "{:?}", instruction);
+
+            let mut counts = vec![expected.start() - 1];
+            if *expected.end() != usize::MAX {
+                counts.push(expected.end() + 1);
+            }
+            for count in counts {
+                let accounts: Vec<AccountInfo> =
+                    pool.iter_mut().take(count).map(|a| a.info()).collect();
+                assert_eq!(accounts.len(), count);
+                let err = process(
+                    &program_id,
+                    &accounts,
+                    &borsh::to_vec(&instruction).unwrap(),
+                )
+                .unwrap_err();
+                assert_eq!(
+                    err,
+                    YapError::InvalidInstruction.into(),
+                    "{:?} with {}",
+                    instruction,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_batch_account_range_scales_with_entries() {
+        use crate::instruction::ClaimEntry;
+
+        let entry = ClaimEntry {
+            user: Pubkey::new_unique(),
+            amount: 1,
+            proof: vec![],
+        };
+        let batch = YapInstruction::BatchClaim {
+            entries: vec![entry.clone(), entry],
+        };
+        // Two accounts per entry, three while frozen markers are passed,
+        // plus the treasury while a fee is set
+        assert_eq!(batch.expected_accounts(), 11..=14);
+    }
+
+    #[test]
+    fn test_unknown_tag_vs_malformed_payload() {
+        let program_id = Pubkey::new_unique();
+
+        // Empty data and unknown tags are not instructions at all
+        for data in [
+            vec![],
+            vec![YapInstruction::LAST_TAG + 1],
+            vec![u8::MAX, 0, 0],
+        ] {
+            let err = process(&program_id, &[], &data).unwrap_err();
+            assert_eq!(err, YapError::InvalidInstruction.into());
+        }
+
+        // Known tags with truncated or trailing payloads are malformed
+        let mut initialize = borsh::to_vec(&YapInstruction::Initialize {
+            merkle_updater: Pubkey::new_unique(),
+            inflation_rate_bps: 1000,
+            max_supply: u64::MAX,
+            min_distribution_interval: 0,
+            rate_period_secs: SECONDS_PER_YEAR,
+        })
+        .unwrap();
+        let truncated = &initialize[..initialize.len() - 1];
+        let err = process(&program_id, &[], truncated).unwrap_err();
+        assert_eq!(err, YapError::MalformedInstructionData.into());
+
+        initialize.push(0);
+        let err = process(&program_id, &[], &initialize).unwrap_err();
+        assert_eq!(err, YapError::MalformedInstructionData.into());
+
+        let err = process(&program_id, &[], &[3, 1, 2]).unwrap_err();
+        assert_eq!(err, YapError::MalformedInstructionData.into());
     }
 }
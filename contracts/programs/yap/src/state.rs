@@ -1,38 +1,488 @@
 use borsh::{BorshDeserialize, BorshSerialize};
-use solana_program::pubkey::Pubkey;
+use solana_program::{
+    account_info::AccountInfo, entrypoint::ProgramResult, msg, program_error::ProgramError,
+    pubkey::Pubkey,
+};
+use std::io::{self, Read, Write};
+
+use crate::{error::YapError, utils::merkle::ct_eq};
 
 /// Account discriminators for safety
 pub const CONFIG_DISCRIMINATOR: [u8; 8] = *b"yapconfg";
 pub const USER_CLAIM_DISCRIMINATOR: [u8; 8] = *b"yapclaim";
+pub const EPOCH_CLAIM_DISCRIMINATOR: [u8; 8] = *b"yapepoch";
+pub const CLAIM_BITMAP_DISCRIMINATOR: [u8; 8] = *b"yapbitmp";
+pub const FROZEN_WALLET_DISCRIMINATOR: [u8; 8] = *b"yapfrozn";
+pub const PENDING_DISTRIBUTION_DISCRIMINATOR: [u8; 8] = *b"yapqueue";
+pub const STAKE_ACCOUNT_DISCRIMINATOR: [u8; 8] = *b"yapstake";
+pub const CLAIM_DELEGATE_DISCRIMINATOR: [u8; 8] = *b"yapdeleg";
+pub const PROOF_BUFFER_DISCRIMINATOR: [u8; 8] = *b"yapproof";
+
+/// Account layout versions; bump when appending fields
+///
+/// Accounts written before the version byte existed count as version 1.
+pub const CONFIG_VERSION: u8 = 28;
+pub const USER_CLAIM_VERSION: u8 = 4;
 
 /// Global configuration account (1 per program)
 /// PDA seeds: ["config"]
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+///
+/// Fields are grouped by concern in memory, but the account keeps the flat
+/// byte layout it has always had: see the `BorshSerialize` impl below.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Config {
     /// Discriminator for account type safety
     pub discriminator: [u8; 8],
+    /// Layout version, `CONFIG_VERSION` once migrated
+    pub version: u8,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Emergency switch: blocks claims, distributions, burns and inflation
+    pub paused: bool,
+    pub roles: Roles,
+    pub addresses: Addresses,
+    pub tokenomics: Tokenomics,
+    pub timing: Timing,
+    pub distribution: Distribution,
+    pub claims: ClaimRules,
+}
+
+/// Keys allowed to administer the program
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Roles {
+    /// Admin (devnet only, cleared for mainnet by `RenounceAdmin`)
+    pub admin: Pubkey,
+    /// Admin nominated by `TransferAdmin`, promoted by `AcceptAdmin` (default = none)
+    pub pending_admin: Pubkey,
+    /// Authorized merkle root updater
+    pub merkle_updater: Pubkey,
+    /// Set by `RenounceAdmin`; no admin-only instruction passes afterwards
+    pub admin_renounced: bool,
+    /// Co-signer set for distributions; used while `updater_threshold` > 0
+    pub updater_keys: [Pubkey; MAX_UPDATER_KEYS],
+    /// Distinct `updater_keys` signers a distribution needs (0 = `merkle_updater` alone)
+    pub updater_threshold: u8,
+}
+
+/// Program-owned token accounts
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Addresses {
     /// YAP token mint address
     pub mint: Pubkey,
     /// Vault holding undistributed tokens
     pub vault: Pubkey,
     /// Pending claims account holding distributed-but-unclaimed tokens
     pub pending_claims: Pubkey,
-    /// Current merkle root for distribution
-    pub merkle_root: [u8; 32],
-    /// Authorized merkle root updater
-    pub merkle_updater: Pubkey,
+    /// Token program owning the mint and token accounts (SPL Token or Token-2022)
+    pub token_program: Pubkey,
+    /// Token account receiving `treasury_inflation_bps` of each inflation mint (default = none)
+    pub treasury: Pubkey,
+    /// Token account `Distribute` may pay into instead of pending_claims (default = none)
+    pub allowed_distribution_dest: Pubkey,
+}
+
+/// Supply, inflation and burn parameters
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Tokenomics {
     /// Current total supply
     pub current_supply: u64,
+    /// Hard ceiling on `current_supply`; inflation mints at most the headroom
+    pub max_supply: u64,
+    /// Annual inflation rate in basis points (0-10000, e.g., 1000 = 10%)
+    pub inflation_rate_bps: u16,
+    /// When set, minted inflation is also credited to `undistributed_inflation`
+    pub compound_inflation: bool,
+    /// Minted inflation earmarked for distribution, drawn first by `Distribute`
+    pub undistributed_inflation: u64,
+    /// Share of each inflation mint burned straight back out of the vault (bps)
+    pub inflation_burn_bps: u16,
+    /// Burn reward rate: `ClaimBurnReward` pays this share of newly burned tokens (bps)
+    pub burn_reward_bps: u16,
+    /// Lets anyone call `TriggerInflationPermissionless`
+    pub permissionless_inflation: bool,
+    /// Keeper's cut of each permissionless inflation mint (bps)
+    pub keeper_reward_bps: u16,
+    /// Amount scheduled to be burned from the vault (0 = nothing scheduled)
+    pub scheduled_burn_amount: u64,
+    /// Earliest timestamp at which the scheduled burn may execute
+    pub scheduled_burn_ts: i64,
+    /// Share of each inflation mint routed to `treasury` instead of the vault (bps)
+    pub treasury_inflation_bps: u16,
+    /// Seconds between inflation rate halvings (0 = flat rate)
+    pub halving_interval: i64,
+    /// Most halvings ever applied; the rate stays at that level afterwards
+    pub halving_count_cap: u8,
+    /// Mint decimals, read back from the mint at initialization; passed to every checked CPI
+    pub decimals: u8,
+    /// Accrued inflation below one token unit, as a numerator over 10000 * rate_period_secs
+    pub inflation_remainder: u128,
+    /// Lifetime inflation minted, including burned, keeper and treasury shares;
+    /// burns never reduce it. Counts from the migration on older configs
+    pub total_inflation_minted: u64,
+}
+
+/// Timestamps of the last periodic operations
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Timing {
     /// Last inflation timestamp
     pub last_inflation_ts: i64,
     /// Last distribution timestamp
     pub last_distribution_ts: i64,
-    /// Admin (devnet only, set to system program for mainnet)
-    pub admin: Pubkey,
-    /// Annual inflation rate in basis points (0-10000, e.g., 1000 = 10%)
-    pub inflation_rate_bps: u16,
-    /// PDA bump seed
-    pub bump: u8,
+    /// Minimum seconds between distributions (0 = no limit)
+    pub min_distribution_interval: i64,
+    /// Initialization timestamp; halvings are counted from here
+    pub genesis_ts: i64,
+    /// Seconds a round stays claimable before `SweepUnclaimed` (0 = no deadline)
+    pub claim_window: i64,
+    /// End of the latest round's claim window, set by `Distribute` (0 = no deadline)
+    pub claim_deadline_ts: i64,
+    /// Start of the current root's vesting, set by `DistributeWithVesting`
+    pub vesting_start_ts: i64,
+    /// Nothing of the current root is claimable before this (0 = no cliff)
+    pub vesting_cliff_ts: i64,
+    /// Seconds over which the current root vests linearly (0 = no linear vesting)
+    pub vesting_duration: i64,
+    /// Seconds over which the vault distributes and `inflation_rate_bps` accrues
+    pub rate_period_secs: i64,
+    /// Minimum seconds between queueing and executing a distribution (0 = no timelock)
+    pub distribution_timelock: i64,
+}
+
+/// Merkle roots, round history and distribution accounting
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Distribution {
+    /// Current merkle root for distribution
+    pub merkle_root: [u8; 32],
+    /// Optional bonus merkle root layered on the main distribution (zero = disabled)
+    pub bonus_root: [u8; 32],
+    /// Id of the latest distribution (incremented by every `Distribute`, first is 1)
+    pub distribution_id: u64,
+    /// Ring buffer of the most recent distribution rounds
+    pub round_history: [RoundRecord; ROUND_HISTORY_LEN],
+    /// Total rounds ever recorded; the next slot is `rounds_recorded % ROUND_HISTORY_LEN`
+    pub rounds_recorded: u64,
+    /// Lifetime tokens moved from vault to pending_claims
+    pub total_distributed: u64,
+    /// Lifetime tokens paid out of pending_claims by claims
+    pub total_claimed: u64,
+    /// Bonus for the first claimer of each round (0 = disabled)
+    pub early_bird_bonus: u64,
+    /// Early-bird bonus moved into pending_claims by `Distribute`, not yet paid
+    pub early_bird_reserved: u64,
+    /// Last `distribution_id` whose first claim has happened
+    pub early_bird_round: u64,
+    /// Time-accrued distribution budget left unspent by earlier rounds
+    pub accrued_allocation: u64,
+    /// Proof length every claim against `merkle_root` must have (0 = not enforced)
+    pub tree_depth: u8,
+    /// Per-category merkle roots set by `DistributeCategory` (zero = category unused)
+    pub category_roots: [[u8; 32]; MAX_CATEGORIES],
+    /// Roots replaced by the latest `Distribute`s, newest first (zero = empty slot)
+    pub recent_roots: [[u8; 32]; RECENT_ROOTS_LEN],
+    /// Highest nonce accepted by `DistributeWithNonce` (0 = none yet)
+    pub last_distribution_nonce: u64,
+    /// Each category's share of the budget in bps, summing to 10000 (all zero = unsplit)
+    pub category_bps: [u16; MAX_CATEGORIES],
+    /// Bonus tokens moved into pending_claims by `SetBonusRoot`, not yet paid
+    pub bonus_reserved: u64,
+    /// Budget credited to each category under `category_bps` and not yet distributed
+    pub category_accrued: [u64; MAX_CATEGORIES],
+    /// Proof length committed for each of `recent_roots` (0 = not enforced)
+    pub recent_root_depths: [u8; RECENT_ROOTS_LEN],
+    /// Proof length committed for each of `category_roots` (0 = not enforced)
+    pub category_root_depths: [u8; MAX_CATEGORIES],
+    /// Proof length committed for `bonus_root` (0 = not enforced)
+    pub bonus_tree_depth: u8,
+    /// Proof length committed for each root in `round_history`, same slots (0 = not enforced)
+    pub round_depths: [u8; ROUND_HISTORY_LEN],
+}
+
+/// How claims are verified and bounded
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ClaimRules {
+    /// When set, claim leaves also commit to a per-wallet `expiry_ts`
+    pub leaf_has_expiry: bool,
+    /// When set, claims also unpack the user's ATA and require `owner == user`
+    pub strict_ata_check: bool,
+    /// Minimum accepted claim proof length (0 = no minimum)
+    pub min_proof_depth: u8,
+    /// Number of live UserClaimStatus PDAs
+    pub total_claim_accounts: u64,
+    /// Ceiling on `total_claim_accounts` (u64::MAX = unlimited)
+    pub max_claim_accounts: u64,
+    /// Minimum seconds between a wallet's claims (0 = no cooldown)
+    pub claim_cooldown: i64,
+    /// Number of FrozenWallet markers; while non-zero, claims and burns must pass
+    /// the wallet's marker
+    pub frozen_wallets: u64,
+    /// Cap on any wallet's cumulative `claimed_amount` (0 = unlimited)
+    pub max_claim_per_wallet: u64,
+    /// Per-deployment salt folded into claim leaves (all zeros = unsalted)
+    pub domain_salt: [u8; DOMAIN_SALT_LEN],
+    /// Largest `claimable` one claim may pay out (0 = unlimited)
+    pub max_single_claim: u64,
+    /// Share of each claim payout sent to `treasury` (bps, 0 = no fee)
+    pub claim_fee_bps: u16,
+}
+
+// On-chain field order. New fields are appended here and to `Config::LEN`,
+// whatever group they live in.
+impl BorshSerialize for Config {
+    fn serialize<W: Write>(&self, writer: &mut W) -> io::Result<()> {
+        self.discriminator.serialize(writer)?;
+        self.addresses.mint.serialize(writer)?;
+        self.addresses.vault.serialize(writer)?;
+        self.addresses.pending_claims.serialize(writer)?;
+        self.distribution.merkle_root.serialize(writer)?;
+        self.roles.merkle_updater.serialize(writer)?;
+        self.tokenomics.current_supply.serialize(writer)?;
+        self.timing.last_inflation_ts.serialize(writer)?;
+        self.timing.last_distribution_ts.serialize(writer)?;
+        self.roles.admin.serialize(writer)?;
+        self.tokenomics.inflation_rate_bps.serialize(writer)?;
+        self.bump.serialize(writer)?;
+        self.distribution.bonus_root.serialize(writer)?;
+        self.distribution.total_distributed.serialize(writer)?;
+        self.distribution.total_claimed.serialize(writer)?;
+        self.tokenomics.scheduled_burn_amount.serialize(writer)?;
+        self.tokenomics.scheduled_burn_ts.serialize(writer)?;
+        self.tokenomics.compound_inflation.serialize(writer)?;
+        self.tokenomics.undistributed_inflation.serialize(writer)?;
+        self.claims.leaf_has_expiry.serialize(writer)?;
+        self.claims.strict_ata_check.serialize(writer)?;
+        self.distribution.round_history.serialize(writer)?;
+        self.distribution.rounds_recorded.serialize(writer)?;
+        self.distribution.distribution_id.serialize(writer)?;
+        self.claims.min_proof_depth.serialize(writer)?;
+        self.roles.pending_admin.serialize(writer)?;
+        self.paused.serialize(writer)?;
+        self.claims.total_claim_accounts.serialize(writer)?;
+        self.claims.max_claim_accounts.serialize(writer)?;
+        self.tokenomics.max_supply.serialize(writer)?;
+        self.tokenomics.inflation_burn_bps.serialize(writer)?;
+        self.tokenomics.burn_reward_bps.serialize(writer)?;
+        self.tokenomics.permissionless_inflation.serialize(writer)?;
+        self.tokenomics.keeper_reward_bps.serialize(writer)?;
+        self.distribution.early_bird_bonus.serialize(writer)?;
+        self.distribution.early_bird_reserved.serialize(writer)?;
+        self.distribution.early_bird_round.serialize(writer)?;
+        self.addresses.token_program.serialize(writer)?;
+        self.distribution.accrued_allocation.serialize(writer)?;
+        self.timing.min_distribution_interval.serialize(writer)?;
+        self.version.serialize(writer)?;
+        self.addresses.treasury.serialize(writer)?;
+        self.tokenomics.treasury_inflation_bps.serialize(writer)?;
+        self.timing.genesis_ts.serialize(writer)?;
+        self.tokenomics.halving_interval.serialize(writer)?;
+        self.tokenomics.halving_count_cap.serialize(writer)?;
+        self.roles.admin_renounced.serialize(writer)?;
+        self.claims.claim_cooldown.serialize(writer)?;
+        self.distribution.tree_depth.serialize(writer)?;
+        self.claims.frozen_wallets.serialize(writer)?;
+        self.roles.updater_keys.serialize(writer)?;
+        self.roles.updater_threshold.serialize(writer)?;
+        self.distribution.category_roots.serialize(writer)?;
+        self.timing.claim_window.serialize(writer)?;
+        self.timing.claim_deadline_ts.serialize(writer)?;
+        self.timing.vesting_start_ts.serialize(writer)?;
+        self.timing.vesting_cliff_ts.serialize(writer)?;
+        self.timing.vesting_duration.serialize(writer)?;
+        self.tokenomics.decimals.serialize(writer)?;
+        self.timing.rate_period_secs.serialize(writer)?;
+        self.tokenomics.inflation_remainder.serialize(writer)?;
+        self.distribution.recent_roots.serialize(writer)?;
+        self.distribution
+            .last_distribution_nonce
+            .serialize(writer)?;
+        self.claims.max_claim_per_wallet.serialize(writer)?;
+        self.distribution.category_bps.serialize(writer)?;
+        self.tokenomics.total_inflation_minted.serialize(writer)?;
+        self.claims.domain_salt.serialize(writer)?;
+        self.claims.max_single_claim.serialize(writer)?;
+        self.addresses.allowed_distribution_dest.serialize(writer)?;
+        self.claims.claim_fee_bps.serialize(writer)?;
+        self.distribution.bonus_reserved.serialize(writer)?;
+        self.timing.distribution_timelock.serialize(writer)?;
+        self.distribution.category_accrued.serialize(writer)?;
+        self.distribution.recent_root_depths.serialize(writer)?;
+        self.distribution.category_root_depths.serialize(writer)?;
+        self.distribution.bonus_tree_depth.serialize(writer)?;
+        self.distribution.round_depths.serialize(writer)
+    }
+}
+
+impl BorshDeserialize for Config {
+    fn deserialize_reader<R: Read>(reader: &mut R) -> io::Result<Self> {
+        let discriminator = BorshDeserialize::deserialize_reader(reader)?;
+        let mint = BorshDeserialize::deserialize_reader(reader)?;
+        let vault = BorshDeserialize::deserialize_reader(reader)?;
+        let pending_claims = BorshDeserialize::deserialize_reader(reader)?;
+        let merkle_root = BorshDeserialize::deserialize_reader(reader)?;
+        let merkle_updater = BorshDeserialize::deserialize_reader(reader)?;
+        let current_supply = BorshDeserialize::deserialize_reader(reader)?;
+        let last_inflation_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let last_distribution_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let admin = BorshDeserialize::deserialize_reader(reader)?;
+        let inflation_rate_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let bump = BorshDeserialize::deserialize_reader(reader)?;
+        let bonus_root = BorshDeserialize::deserialize_reader(reader)?;
+        let total_distributed = BorshDeserialize::deserialize_reader(reader)?;
+        let total_claimed = BorshDeserialize::deserialize_reader(reader)?;
+        let scheduled_burn_amount = BorshDeserialize::deserialize_reader(reader)?;
+        let scheduled_burn_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let compound_inflation = BorshDeserialize::deserialize_reader(reader)?;
+        let undistributed_inflation = BorshDeserialize::deserialize_reader(reader)?;
+        let leaf_has_expiry = BorshDeserialize::deserialize_reader(reader)?;
+        let strict_ata_check = BorshDeserialize::deserialize_reader(reader)?;
+        let round_history = BorshDeserialize::deserialize_reader(reader)?;
+        let rounds_recorded = BorshDeserialize::deserialize_reader(reader)?;
+        let distribution_id = BorshDeserialize::deserialize_reader(reader)?;
+        let min_proof_depth = BorshDeserialize::deserialize_reader(reader)?;
+        let pending_admin = BorshDeserialize::deserialize_reader(reader)?;
+        let paused = BorshDeserialize::deserialize_reader(reader)?;
+        let total_claim_accounts = BorshDeserialize::deserialize_reader(reader)?;
+        let max_claim_accounts = BorshDeserialize::deserialize_reader(reader)?;
+        let max_supply = BorshDeserialize::deserialize_reader(reader)?;
+        let inflation_burn_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let burn_reward_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let permissionless_inflation = BorshDeserialize::deserialize_reader(reader)?;
+        let keeper_reward_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let early_bird_bonus = BorshDeserialize::deserialize_reader(reader)?;
+        let early_bird_reserved = BorshDeserialize::deserialize_reader(reader)?;
+        let early_bird_round = BorshDeserialize::deserialize_reader(reader)?;
+        let token_program = BorshDeserialize::deserialize_reader(reader)?;
+        let accrued_allocation = BorshDeserialize::deserialize_reader(reader)?;
+        let min_distribution_interval = BorshDeserialize::deserialize_reader(reader)?;
+        let version = BorshDeserialize::deserialize_reader(reader)?;
+        let treasury = BorshDeserialize::deserialize_reader(reader)?;
+        let treasury_inflation_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let genesis_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let halving_interval = BorshDeserialize::deserialize_reader(reader)?;
+        let halving_count_cap = BorshDeserialize::deserialize_reader(reader)?;
+        let admin_renounced = BorshDeserialize::deserialize_reader(reader)?;
+        let claim_cooldown = BorshDeserialize::deserialize_reader(reader)?;
+        let tree_depth = BorshDeserialize::deserialize_reader(reader)?;
+        let frozen_wallets = BorshDeserialize::deserialize_reader(reader)?;
+        let updater_keys = BorshDeserialize::deserialize_reader(reader)?;
+        let updater_threshold = BorshDeserialize::deserialize_reader(reader)?;
+        let category_roots = BorshDeserialize::deserialize_reader(reader)?;
+        let claim_window = BorshDeserialize::deserialize_reader(reader)?;
+        let claim_deadline_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let vesting_start_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let vesting_cliff_ts = BorshDeserialize::deserialize_reader(reader)?;
+        let vesting_duration = BorshDeserialize::deserialize_reader(reader)?;
+        let decimals = BorshDeserialize::deserialize_reader(reader)?;
+        let rate_period_secs = BorshDeserialize::deserialize_reader(reader)?;
+        let inflation_remainder = BorshDeserialize::deserialize_reader(reader)?;
+        let recent_roots = BorshDeserialize::deserialize_reader(reader)?;
+        let last_distribution_nonce = BorshDeserialize::deserialize_reader(reader)?;
+        let max_claim_per_wallet = BorshDeserialize::deserialize_reader(reader)?;
+        let category_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let total_inflation_minted = BorshDeserialize::deserialize_reader(reader)?;
+        let domain_salt = BorshDeserialize::deserialize_reader(reader)?;
+        let max_single_claim = BorshDeserialize::deserialize_reader(reader)?;
+        let allowed_distribution_dest = BorshDeserialize::deserialize_reader(reader)?;
+        let claim_fee_bps = BorshDeserialize::deserialize_reader(reader)?;
+        let bonus_reserved = BorshDeserialize::deserialize_reader(reader)?;
+        let distribution_timelock = BorshDeserialize::deserialize_reader(reader)?;
+        let category_accrued = BorshDeserialize::deserialize_reader(reader)?;
+        let recent_root_depths = BorshDeserialize::deserialize_reader(reader)?;
+        let category_root_depths = BorshDeserialize::deserialize_reader(reader)?;
+        let bonus_tree_depth = BorshDeserialize::deserialize_reader(reader)?;
+        let round_depths = BorshDeserialize::deserialize_reader(reader)?;
+
+        Ok(Config {
+            discriminator,
+            version,
+            bump,
+            paused,
+            roles: Roles {
+                admin,
+                pending_admin,
+                merkle_updater,
+                admin_renounced,
+                updater_keys,
+                updater_threshold,
+            },
+            addresses: Addresses {
+                mint,
+                vault,
+                pending_claims,
+                token_program,
+                treasury,
+                allowed_distribution_dest,
+            },
+            tokenomics: Tokenomics {
+                current_supply,
+                max_supply,
+                inflation_rate_bps,
+                compound_inflation,
+                undistributed_inflation,
+                inflation_burn_bps,
+                burn_reward_bps,
+                permissionless_inflation,
+                keeper_reward_bps,
+                scheduled_burn_amount,
+                scheduled_burn_ts,
+                treasury_inflation_bps,
+                halving_interval,
+                halving_count_cap,
+                decimals,
+                inflation_remainder,
+                total_inflation_minted,
+            },
+            timing: Timing {
+                last_inflation_ts,
+                last_distribution_ts,
+                min_distribution_interval,
+                genesis_ts,
+                claim_window,
+                claim_deadline_ts,
+                vesting_start_ts,
+                vesting_cliff_ts,
+                vesting_duration,
+                rate_period_secs,
+                distribution_timelock,
+            },
+            distribution: Distribution {
+                merkle_root,
+                bonus_root,
+                distribution_id,
+                round_history,
+                rounds_recorded,
+                total_distributed,
+                total_claimed,
+                early_bird_bonus,
+                early_bird_reserved,
+                early_bird_round,
+                accrued_allocation,
+                tree_depth,
+                category_roots,
+                recent_roots,
+                last_distribution_nonce,
+                category_bps,
+                bonus_reserved,
+                category_accrued,
+                recent_root_depths,
+                category_root_depths,
+                bonus_tree_depth,
+                round_depths,
+            },
+            claims: ClaimRules {
+                leaf_has_expiry,
+                strict_ata_check,
+                min_proof_depth,
+                total_claim_accounts,
+                max_claim_accounts,
+                claim_cooldown,
+                frozen_wallets,
+                max_claim_per_wallet,
+                domain_salt,
+                max_single_claim,
+                claim_fee_bps,
+            },
+        })
+    }
 }
 
 impl Config {
@@ -47,20 +497,298 @@ impl Config {
         + 8      // last_distribution_ts
         + 32     // admin
         + 2      // inflation_rate_bps
-        + 1; // bump
+        + 1      // bump
+        + 32     // bonus_root
+        + 8      // total_distributed
+        + 8      // total_claimed
+        + 8      // scheduled_burn_amount
+        + 8      // scheduled_burn_ts
+        + 1      // compound_inflation
+        + 8      // undistributed_inflation
+        + 1      // leaf_has_expiry
+        + 1      // strict_ata_check
+        + RoundRecord::LEN * ROUND_HISTORY_LEN // round_history
+        + 8      // rounds_recorded
+        + 8      // distribution_id
+        + 1      // min_proof_depth
+        + 32     // pending_admin
+        + 1      // paused
+        + 8      // total_claim_accounts
+        + 8      // max_claim_accounts
+        + 8      // max_supply
+        + 2      // inflation_burn_bps
+        + 2      // burn_reward_bps
+        + 1      // permissionless_inflation
+        + 2      // keeper_reward_bps
+        + 8      // early_bird_bonus
+        + 8      // early_bird_reserved
+        + 8      // early_bird_round
+        + 32     // token_program
+        + 8      // accrued_allocation
+        + 8      // min_distribution_interval
+        + 1      // version
+        + 32     // treasury
+        + 2      // treasury_inflation_bps
+        + 8      // genesis_ts
+        + 8      // halving_interval
+        + 1      // halving_count_cap
+        + 1      // admin_renounced
+        + 8      // claim_cooldown
+        + 1      // tree_depth
+        + 8      // frozen_wallets
+        + 160    // updater_keys
+        + 1      // updater_threshold
+        + 128    // category_roots
+        + 8      // claim_window
+        + 8      // claim_deadline_ts
+        + 8      // vesting_start_ts
+        + 8      // vesting_cliff_ts
+        + 8      // vesting_duration
+        + 1      // decimals
+        + 8      // rate_period_secs
+        + 16     // inflation_remainder
+        + 32 * RECENT_ROOTS_LEN // recent_roots
+        + 8      // last_distribution_nonce
+        + 8      // max_claim_per_wallet
+        + 2 * MAX_CATEGORIES // category_bps
+        + 8      // total_inflation_minted
+        + DOMAIN_SALT_LEN // domain_salt
+        + 8      // max_single_claim
+        + 32     // allowed_distribution_dest
+        + 2      // claim_fee_bps
+        + 8      // bonus_reserved
+        + 8      // distribution_timelock
+        + 8 * MAX_CATEGORIES // category_accrued
+        + RECENT_ROOTS_LEN // recent_root_depths
+        + MAX_CATEGORIES // category_root_depths
+        + 1      // bonus_tree_depth
+        + ROUND_HISTORY_LEN; // round_depths
 
     pub const MAX_INFLATION_BPS: u16 = 10000; // 100%
+    pub const MAX_INFLATION_BURN_BPS: u16 = 10000; // burn all minted inflation
+    pub const MAX_BURN_REWARD_BPS: u16 = 10000; // reward 1:1 with burned tokens
+    pub const MAX_KEEPER_REWARD_BPS: u16 = 1000; // 10% of a mint
+    pub const MAX_TREASURY_INFLATION_BPS: u16 = 10000; // route the whole mint to treasury
+    pub const MAX_CLAIM_FEE_BPS: u16 = 500; // 5% of each claim payout
+    pub const CATEGORY_BPS_TOTAL: u16 = 10000; // category shares split the whole budget
+    pub const MAX_HALVING_COUNT_CAP: u8 = 16; // a u16 rate is zero after 16 halvings
+    pub const MAX_MIN_DISTRIBUTION_INTERVAL: i64 = SECONDS_PER_YEAR; // at least yearly
+    pub const MAX_CLAIM_COOLDOWN: i64 = 7 * 24 * 60 * 60; // at least weekly
+    pub const MAX_DISTRIBUTION_TIMELOCK: i64 = 30 * 24 * 60 * 60; // a round within a month
+    pub const MAX_CLAIM_WINDOW: i64 = 5 * SECONDS_PER_YEAR; // sweepable within five years
 
     pub const SEED: &'static [u8] = b"config";
 
     pub fn is_valid(&self) -> bool {
-        self.discriminator == CONFIG_DISCRIMINATOR
+        self.discriminator == CONFIG_DISCRIMINATOR && self.version == CONFIG_VERSION
+    }
+
+    /// Load the config PDA, checking its address, owner and discriminator
+    pub fn try_load(program_id: &Pubkey, account: &AccountInfo) -> Result<Self, ProgramError> {
+        let (config_pda, _) = Pubkey::find_program_address(&[Self::SEED], program_id);
+        if account.key != &config_pda {
+            return Err(YapError::InvalidPda.into());
+        }
+        if account.owner != program_id {
+            return Err(YapError::InvalidOwner.into());
+        }
+
+        let config = Self::try_from_slice(&account.data.borrow())?;
+        if !config.is_valid() {
+            return Err(YapError::InvalidDiscriminator.into());
+        }
+        Ok(config)
+    }
+
+    /// Whether `key` may act as admin; never true once the admin is renounced
+    pub fn is_admin(&self, key: &Pubkey) -> bool {
+        !self.roles.admin_renounced && *key == self.roles.admin
+    }
+
+    /// Count a new UserClaimStatus PDA, failing once `max_claim_accounts` is reached
+    pub fn reserve_claim_account(&mut self) -> Result<(), YapError> {
+        if self.claims.total_claim_accounts >= self.claims.max_claim_accounts {
+            msg!(
+                "Claim account limit reached ({}/{})",
+                self.claims.total_claim_accounts,
+                self.claims.max_claim_accounts
+            );
+            return Err(YapError::ClaimAccountLimitReached);
+        }
+        self.claims.total_claim_accounts += 1;
+        Ok(())
+    }
+
+    /// How much more a wallet that has claimed `claimed_amount` may still claim
+    ///
+    /// `u64::MAX` while `max_claim_per_wallet` is 0 (unlimited).
+    pub fn wallet_claim_headroom(&self, claimed_amount: u64) -> u64 {
+        match self.claims.max_claim_per_wallet {
+            0 => u64::MAX,
+            cap => cap.saturating_sub(claimed_amount),
+        }
+    }
+
+    /// Fail with `Paused` while the emergency switch is on
+    pub fn ensure_not_paused(&self) -> Result<(), YapError> {
+        if self.paused {
+            msg!("Program is paused");
+            return Err(YapError::Paused);
+        }
+        Ok(())
+    }
+
+    /// Hand the reserved early-bird bonus to the round's first claimer
+    ///
+    /// Returns 0 once the current round has had its first claim.
+    pub fn take_early_bird_bonus(&mut self) -> u64 {
+        if self.distribution.distribution_id == 0
+            || self.distribution.early_bird_round == self.distribution.distribution_id
+        {
+            return 0;
+        }
+        self.distribution.early_bird_round = self.distribution.distribution_id;
+        std::mem::take(&mut self.distribution.early_bird_reserved)
+    }
+
+    /// Pay `amount` of bonus out of the reserve `SetBonusRoot` funded
+    ///
+    /// Bonus claims never dip into the pending_claims balance backing
+    /// main-tree entitlements: past the reserve they fail with `BonusNotFunded`.
+    pub fn draw_bonus_reserve(&mut self, amount: u64) -> Result<(), YapError> {
+        let reserved = self.distribution.bonus_reserved;
+        self.distribution.bonus_reserved = reserved.checked_sub(amount).ok_or_else(|| {
+            msg!("Bonus {} exceeds the funded reserve {}", amount, reserved);
+            YapError::BonusNotFunded
+        })?;
+        Ok(())
+    }
+
+    /// Append a distribution round to the history, overwriting the oldest
+    ///
+    /// The round is tagged with the current `distribution_id`; its tree depth
+    /// goes to the same slot of `round_depths`.
+    pub fn record_round(
+        &mut self,
+        merkle_root: [u8; 32],
+        tree_depth: u8,
+        timestamp: i64,
+        amount: u64,
+    ) {
+        let slot = (self.distribution.rounds_recorded % ROUND_HISTORY_LEN as u64) as usize;
+        self.distribution.round_history[slot] = RoundRecord {
+            distribution_id: self.distribution.distribution_id,
+            merkle_root,
+            timestamp,
+            amount,
+        };
+        self.distribution.round_depths[slot] = tree_depth;
+        self.distribution.rounds_recorded = self.distribution.rounds_recorded.saturating_add(1);
+    }
+
+    /// Replace `merkle_root` and its `tree_depth`, keeping the previous pair in
+    /// `recent_roots` / `recent_root_depths`
+    ///
+    /// The oldest recent root is evicted. Re-sending the current root leaves
+    /// the history untouched.
+    pub fn rotate_root(&mut self, merkle_root: [u8; 32], tree_depth: u8) {
+        let previous = std::mem::replace(&mut self.distribution.merkle_root, merkle_root);
+        let previous_depth = std::mem::replace(&mut self.distribution.tree_depth, tree_depth);
+        if previous == merkle_root {
+            return;
+        }
+        let distribution = &mut self.distribution;
+        distribution.recent_roots.rotate_right(1);
+        distribution.recent_roots[0] = previous;
+        distribution.recent_root_depths.rotate_right(1);
+        distribution.recent_root_depths[0] = previous_depth;
+    }
+
+    /// Tree depth of the root a claim proof resolved to, if claims may use it:
+    /// the current root or a recent one
+    ///
+    /// Every stored root is compared with `ct_eq`, without stopping at the
+    /// first match, so timing doesn't reveal which root (if any) matched.
+    pub fn claim_root_depth(&self, root: &[u8; 32]) -> Option<u8> {
+        let distribution = &self.distribution;
+        let (matched, depth) =
+            std::iter::once((&distribution.merkle_root, distribution.tree_depth))
+                .chain(
+                    distribution
+                        .recent_roots
+                        .iter()
+                        .zip(distribution.recent_root_depths),
+                )
+                .fold(
+                    (false, 0u8),
+                    |(matched, depth), (candidate, candidate_depth)| {
+                        let hit = ct_eq(candidate, root);
+                        // Branch-free select of the first matching root's depth
+                        let mask = 0u8.wrapping_sub((hit & !matched) as u8);
+                        (matched | hit, depth | (candidate_depth & mask))
+                    },
+                );
+        (*root != [0; 32] && matched).then_some(depth)
+    }
+
+    /// The last `count` rounds (bounded by what's stored), oldest first
+    pub fn recent_rounds(&self, count: usize) -> Vec<RoundRecord> {
+        let stored = self
+            .distribution
+            .rounds_recorded
+            .min(ROUND_HISTORY_LEN as u64);
+        let count = (count as u64).min(stored);
+
+        (self.distribution.rounds_recorded - count..self.distribution.rounds_recorded)
+            .map(|round| {
+                self.distribution.round_history[(round % ROUND_HISTORY_LEN as u64) as usize]
+            })
+            .collect()
+    }
+
+    /// Merkle root and tree depth of `distribution_id`, if that round is still
+    /// in the history
+    pub fn round_root(&self, distribution_id: u64) -> Option<([u8; 32], u8)> {
+        if distribution_id == 0 {
+            return None;
+        }
+        let recorded = self.distribution.rounds_recorded;
+        let stored = recorded.min(ROUND_HISTORY_LEN as u64);
+        (recorded - stored..recorded)
+            .map(|round| (round % ROUND_HISTORY_LEN as u64) as usize)
+            .find(|&slot| self.distribution.round_history[slot].distribution_id == distribution_id)
+            .map(|slot| {
+                (
+                    self.distribution.round_history[slot].merkle_root,
+                    self.distribution.round_depths[slot],
+                )
+            })
     }
 }
 
+/// A single distribution round kept in `Config::round_history`
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RoundRecord {
+    /// `Config::distribution_id` of the round
+    pub distribution_id: u64,
+    /// Merkle root set by the round
+    pub merkle_root: [u8; 32],
+    /// Distribution timestamp
+    pub timestamp: i64,
+    /// Tokens moved from vault to pending_claims
+    pub amount: u64,
+}
+
+impl RoundRecord {
+    pub const LEN: usize = 8 // distribution_id
+        + 32     // merkle_root
+        + 8      // timestamp
+        + 8; // amount
+}
+
 /// Per-user claim status account
 /// PDA seeds: ["user_claim", user_wallet]
-#[derive(BorshSerialize, BorshDeserialize, Debug, Clone)]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
 pub struct UserClaimStatus {
     /// Discriminator for account type safety
     pub discriminator: [u8; 8],
@@ -70,18 +798,346 @@ pub struct UserClaimStatus {
     pub total_burned: u64,
     /// PDA bump seed
     pub bump: u8,
+    /// Cumulative bonus amount claimed against `Config::bonus_root`
+    pub bonus_claimed_amount: u64,
+    /// Portion of `total_burned` already paid out by `ClaimBurnReward`
+    pub burn_rewarded: u64,
+    /// Layout version (1 = created before the version byte, see `load`)
+    pub version: u8,
+    /// Unix timestamp of the last successful claim (0 = never)
+    pub last_claim_ts: i64,
+    /// Cumulative amount claimed per `DistributeCategory` category
+    pub category_claimed: [u64; MAX_CATEGORIES],
 }
 
 impl UserClaimStatus {
     pub const LEN: usize = 8      // discriminator
         + 8      // claimed_amount
         + 8      // total_burned
-        + 1; // bump
+        + 1      // bump
+        + 8      // bonus_claimed_amount
+        + 8      // burn_rewarded
+        + 1      // version
+        + 8      // last_claim_ts
+        + 8 * MAX_CATEGORIES; // category_claimed
+
+    /// Size of accounts created before `version` was appended
+    pub const LEGACY_LEN: usize = Self::V3_LEN - 9;
+
+    /// Size of version 2 accounts, created before `last_claim_ts` was appended
+    pub const V2_LEN: usize = Self::V3_LEN - 8;
+
+    /// Size of version 3 accounts, created before `category_claimed` was appended
+    pub const V3_LEN: usize = Self::LEN - 8 * MAX_CATEGORIES;
 
     pub const SEED: &'static [u8] = b"user_claim";
 
     pub fn is_valid(&self) -> bool {
         self.discriminator == USER_CLAIM_DISCRIMINATOR
+            && (1..=USER_CLAIM_VERSION).contains(&self.version)
+    }
+
+    /// Load `user`'s claim status PDA, checking its address, owner and discriminator
+    pub fn try_load(
+        program_id: &Pubkey,
+        user: &Pubkey,
+        account: &AccountInfo,
+    ) -> Result<Self, ProgramError> {
+        let (user_claim_pda, _) =
+            Pubkey::find_program_address(&[Self::SEED, user.as_ref()], program_id);
+        if account.key != &user_claim_pda {
+            return Err(YapError::InvalidPda.into());
+        }
+        if account.owner != program_id {
+            return Err(YapError::InvalidOwner.into());
+        }
+
+        let status = Self::load(&account.data.borrow())?;
+        if !status.is_valid() {
+            return Err(YapError::InvalidDiscriminator.into());
+        }
+        Ok(status)
+    }
+
+    /// Deserialize account data of the current or an older, shorter layout
+    ///
+    /// Older accounts keep their size until a claim grows them; missing fields
+    /// read as 0 and an account without the version byte reads as version 1.
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        if ![Self::LEGACY_LEN, Self::V2_LEN, Self::V3_LEN].contains(&data.len()) {
+            return Ok(Self::try_from_slice(data)?);
+        }
+
+        let mut padded = [0u8; Self::LEN];
+        padded[..data.len()].copy_from_slice(data);
+        let status = Self::try_from_slice(&padded)?;
+        if data.len() == Self::LEGACY_LEN {
+            return Ok(Self {
+                version: 1,
+                ..status
+            });
+        }
+        Ok(status)
+    }
+
+    /// Serialize into account data, keeping an older account's shorter layout
+    pub fn store(&self, data: &mut [u8]) -> ProgramResult {
+        let bytes = borsh::to_vec(self)?;
+        if ![Self::LEN, Self::V3_LEN, Self::V2_LEN, Self::LEGACY_LEN].contains(&data.len()) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        data.copy_from_slice(&bytes[..data.len()]);
+        Ok(())
+    }
+}
+
+/// Per-user, per-distribution claim receipt (exists = claimed)
+/// PDA seeds: ["epoch_claim", user_wallet, distribution_id_le]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct EpochClaimStatus {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// Distribution this receipt belongs to
+    pub distribution_id: u64,
+    /// Amount claimed for that distribution
+    pub amount: u64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl EpochClaimStatus {
+    pub const LEN: usize = 8      // discriminator
+        + 8      // distribution_id
+        + 8      // amount
+        + 1; // bump
+
+    pub const SEED: &'static [u8] = b"epoch_claim";
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == EPOCH_CLAIM_DISCRIMINATOR
+    }
+}
+
+/// Marks a wallet as blocked from claiming and burning (exists = frozen)
+/// PDA seeds: ["frozen", wallet]
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct FrozenWallet {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// The frozen wallet
+    pub wallet: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl FrozenWallet {
+    pub const LEN: usize = 8      // discriminator
+        + 32     // wallet
+        + 1; // bump
+
+    pub const SEED: &'static [u8] = b"frozen";
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == FROZEN_WALLET_DISCRIMINATOR
+    }
+}
+
+/// A wallet's chosen claim delegate
+/// PDA seeds: ["delegate", user_wallet]
+///
+/// Lets `delegate` sign `ClaimAsDelegate` for the wallet; tokens still go to
+/// the wallet's own ATA. Closed by `RevokeClaimDelegate`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ClaimDelegate {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// Wallet that set the delegate
+    pub user: Pubkey,
+    /// Key allowed to claim on the wallet's behalf
+    pub delegate: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ClaimDelegate {
+    pub const LEN: usize = 8      // discriminator
+        + 32     // user
+        + 32     // delegate
+        + 1; // bump
+
+    pub const SEED: &'static [u8] = b"delegate";
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == CLAIM_DELEGATE_DISCRIMINATOR
+    }
+}
+
+/// Scratch space for a merkle proof too long to pass inline
+/// PDA seeds: ["proof_buffer", user_wallet]
+///
+/// Filled by `WriteProofBuffer`, consumed and zeroed by `ClaimWithProofBuffer`.
+/// The wallet keeps the account (and its rent) for the next claim.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ProofBuffer {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// Wallet the buffer belongs to
+    pub user: Pubkey,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Number of proof nodes written
+    pub len: u8,
+    /// Proof nodes, leaf first; only the first `len` are used
+    pub nodes: [[u8; 32]; MAX_PROOF_DEPTH],
+}
+
+impl ProofBuffer {
+    pub const LEN: usize = 8      // discriminator
+        + 32     // user
+        + 1      // bump
+        + 1      // len
+        + 32 * MAX_PROOF_DEPTH; // nodes
+
+    pub const SEED: &'static [u8] = b"proof_buffer";
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == PROOF_BUFFER_DISCRIMINATOR
+    }
+
+    /// The proof written so far
+    pub fn proof(&self) -> &[[u8; 32]] {
+        &self.nodes[..(self.len as usize).min(MAX_PROOF_DEPTH)]
+    }
+}
+
+/// Tokens a wallet has locked in the stake vault
+/// PDA seeds: ["stake", user_wallet]
+///
+/// Closed back to the wallet once everything is unstaked.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct StakeAccount {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// Tokens currently staked
+    pub staked_amount: u64,
+    /// Unix timestamp of the latest stake; the lock runs from here
+    pub stake_start_ts: i64,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl StakeAccount {
+    pub const LEN: usize = 8      // discriminator
+        + 8      // staked_amount
+        + 8      // stake_start_ts
+        + 1; // bump
+
+    pub const SEED: &'static [u8] = b"stake";
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == STAKE_ACCOUNT_DISCRIMINATOR
+    }
+}
+
+/// A distribution queued behind a timelock (exists = queued)
+/// PDA seeds: ["pending_distribution"]
+///
+/// Only one distribution can be queued at a time. `ExecuteDistribution` replays
+/// exactly these parameters once `now >= eta`.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct PendingDistribution {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// Amount to move from the vault to pending_claims
+    pub amount: u64,
+    /// Root to commit
+    pub merkle_root: [u8; 32],
+    /// Earliest unix timestamp the distribution may execute
+    pub eta: i64,
+    /// PDA bump seed
+    pub bump: u8,
+    /// Proof length claims against `merkle_root` must have
+    pub tree_depth: u8,
+}
+
+impl PendingDistribution {
+    pub const LEN: usize = 8      // discriminator
+        + 8      // amount
+        + 32     // merkle_root
+        + 8      // eta
+        + 1      // bump
+        + 1; // tree_depth
+
+    pub const SEED: &'static [u8] = b"pending_distribution";
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == PENDING_DISTRIBUTION_DISCRIMINATOR
+    }
+}
+
+/// Claimed bits for an indexed (fixed recipient list) distribution
+/// PDA seeds: ["claim_bitmap", distribution_id_le]
+///
+/// The header is followed by `ceil(capacity / 8)` bitmap bytes. Index `i`
+/// is claimed once bit `i % 8` of byte `i / 8` is set.
+#[derive(BorshSerialize, BorshDeserialize, Debug, Clone, Default)]
+pub struct ClaimBitmap {
+    /// Discriminator for account type safety
+    pub discriminator: [u8; 8],
+    /// Distribution whose root the indexed leaves are proven against
+    pub distribution_id: u64,
+    /// Number of recipient indices (0..capacity)
+    pub capacity: u32,
+    /// PDA bump seed
+    pub bump: u8,
+}
+
+impl ClaimBitmap {
+    pub const HEADER_LEN: usize = 8      // discriminator
+        + 8      // distribution_id
+        + 4      // capacity
+        + 1; // bump
+
+    /// 8 KiB of bits, well within what a single `create_account` can allocate
+    pub const MAX_CAPACITY: u32 = 65_536;
+
+    pub const SEED: &'static [u8] = b"claim_bitmap";
+
+    /// Account size for `capacity` recipients
+    pub fn space(capacity: u32) -> usize {
+        Self::HEADER_LEN + (capacity as usize).div_ceil(8)
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.discriminator == CLAIM_BITMAP_DISCRIMINATOR
+    }
+
+    /// Read the header, checking the account is sized for its capacity
+    pub fn load(data: &[u8]) -> Result<Self, ProgramError> {
+        let header = data
+            .get(..Self::HEADER_LEN)
+            .ok_or(ProgramError::InvalidAccountData)?;
+        let bitmap = Self::try_from_slice(header)?;
+        if data.len() != Self::space(bitmap.capacity) {
+            return Err(ProgramError::InvalidAccountData);
+        }
+        Ok(bitmap)
+    }
+
+    /// Set the bit for `index` in the account `data`, failing if it was already set
+    pub fn claim(&self, data: &mut [u8], index: u32) -> Result<(), YapError> {
+        if index >= self.capacity {
+            return Err(YapError::InvalidInstruction);
+        }
+        let byte = data
+            .get_mut(Self::HEADER_LEN + index as usize / 8)
+            .ok_or(YapError::InvalidInstruction)?;
+        let mask = 1u8 << (index % 8);
+        if *byte & mask != 0 {
+            return Err(YapError::AlreadyClaimed);
+        }
+        *byte |= mask;
+        Ok(())
     }
 }
 
@@ -90,11 +1146,19 @@ pub const DECIMALS: u8 = 9;
 pub const INITIAL_SUPPLY: u64 = 1_000_000_000 * 10u64.pow(DECIMALS as u32); // 1B tokens
 pub const SECONDS_PER_YEAR: i64 = 365 * 24 * 60 * 60; // 31,536,000 seconds
 pub const MAX_PROOF_DEPTH: usize = 32; // Supports up to 2^32 = 4B users
+pub const MAX_BATCH_CLAIMS: usize = 8; // Keeps BatchClaim within compute limits
+pub const ROUND_HISTORY_LEN: usize = 8; // Distribution rounds kept in Config
+pub const RECENT_ROOTS_LEN: usize = 4; // Replaced merkle roots still accepted by claims
+pub const MAX_UPDATER_KEYS: usize = 5; // Size of the multisig updater set
+pub const MAX_CATEGORIES: usize = 4; // Independent drop categories in Config
+pub const DOMAIN_SALT_LEN: usize = 16; // Per-deployment salt folded into claim leaves
+pub const MIN_STAKE_DURATION: i64 = 7 * 24 * 60 * 60; // Lock before a stake can be withdrawn
 
 // PDA seeds
 pub const MINT_SEED: &[u8] = b"mint";
 pub const VAULT_SEED: &[u8] = b"vault";
 pub const PENDING_CLAIMS_SEED: &[u8] = b"pending_claims";
+pub const STAKE_VAULT_SEED: &[u8] = b"stake_vault";
 
 // Associated Token Program ID: ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL
 pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
@@ -102,6 +1166,15 @@ pub const ASSOCIATED_TOKEN_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
     255, 16, 132, 4, 142, 123, 216, 219, 233, 248, 89,
 ]);
 
+// SPL Memo Program ID (v2): MemoSq4gqABAXKb96qnH8TysNcWxMyWCqXgDLGmfcHr
+pub const MEMO_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
+    5, 74, 83, 90, 153, 41, 33, 6, 77, 36, 232, 113, 96, 218, 56, 124, 124, 53, 181, 221, 188,
+    146, 187, 129, 228, 31, 168, 64, 65, 5, 68, 141,
+]);
+
+// Maximum memo length attached to a distribution (keeps the tx well under 1232 bytes)
+pub const MAX_MEMO_LEN: usize = 256;
+
 // Metaplex Token Metadata Program ID: metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s
 pub const METADATA_PROGRAM_ID: Pubkey = Pubkey::new_from_array([
     11, 112, 101, 177, 227, 209, 124, 69, 56, 157, 82, 127, 107, 4, 195, 205,
@@ -113,10 +1186,494 @@ pub const TOKEN_NAME: &str = "YAP Token";
 pub const TOKEN_SYMBOL: &str = "YAP";
 pub const TOKEN_URI: &str = "https://gist.githubusercontent.com/pushkarm029/ce82baabdda37b1aaa17b3177b3805e8/raw/yap-metadata.json";
 
+// Metaplex limit on the metadata URI, also enforced at runtime by `UpdateMetadataUri`
+pub const MAX_METADATA_URI_LEN: usize = 200;
+
 // Compile-time assertions for Metaplex metadata field limits
 const _: () = assert!(TOKEN_NAME.len() <= 32, "TOKEN_NAME exceeds Metaplex 32-byte limit");
 const _: () = assert!(TOKEN_SYMBOL.len() <= 10, "TOKEN_SYMBOL exceeds Metaplex 10-byte limit");
-const _: () = assert!(TOKEN_URI.len() <= 200, "TOKEN_URI exceeds Metaplex 200-byte limit");
+const _: () = assert!(
+    TOKEN_URI.len() <= MAX_METADATA_URI_LEN,
+    "TOKEN_URI exceeds Metaplex 200-byte limit"
+);
 
 // Metadata PDA seed (used by Metaplex)
 pub const METADATA_SEED: &[u8] = b"metadata";
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAccount;
+
+    /// Every field set to a distinct, non-default value
+    fn populated_config() -> Config {
+        let mut round_history = [RoundRecord::default(); ROUND_HISTORY_LEN];
+        for (i, round) in round_history.iter_mut().enumerate() {
+            *round = RoundRecord {
+                distribution_id: i as u64 + 1,
+                merkle_root: [i as u8 + 1; 32],
+                timestamp: 1_000 + i as i64,
+                amount: 10_000 + i as u64,
+            };
+        }
+
+        Config {
+            discriminator: CONFIG_DISCRIMINATOR,
+            version: 24,
+            bump: 253,
+            paused: true,
+            roles: Roles {
+                admin: Pubkey::new_unique(),
+                pending_admin: Pubkey::new_unique(),
+                merkle_updater: Pubkey::new_unique(),
+                admin_renounced: true,
+                updater_keys: [Pubkey::new_from_array([32; 32]); MAX_UPDATER_KEYS],
+                updater_threshold: 33,
+            },
+            addresses: Addresses {
+                mint: Pubkey::new_unique(),
+                vault: Pubkey::new_unique(),
+                pending_claims: Pubkey::new_unique(),
+                token_program: Pubkey::new_unique(),
+                treasury: Pubkey::new_from_array([25; 32]),
+                allowed_distribution_dest: Pubkey::new_from_array([50; 32]),
+            },
+            tokenomics: Tokenomics {
+                current_supply: 1,
+                max_supply: 2,
+                inflation_rate_bps: 3,
+                compound_inflation: true,
+                undistributed_inflation: 4,
+                inflation_burn_bps: 5,
+                burn_reward_bps: 6,
+                permissionless_inflation: true,
+                keeper_reward_bps: 7,
+                scheduled_burn_amount: 8,
+                scheduled_burn_ts: -9,
+                treasury_inflation_bps: 2_600,
+                halving_interval: 27,
+                halving_count_cap: 28,
+                decimals: 40,
+                inflation_remainder: 42,
+                total_inflation_minted: 47,
+            },
+            timing: Timing {
+                last_inflation_ts: -10,
+                last_distribution_ts: 11,
+                min_distribution_interval: 23,
+                genesis_ts: -26,
+                claim_window: 35,
+                claim_deadline_ts: 36,
+                vesting_start_ts: 37,
+                vesting_cliff_ts: 38,
+                vesting_duration: 39,
+                rate_period_secs: 41,
+                distribution_timelock: 52,
+            },
+            distribution: Distribution {
+                merkle_root: [0xaa; 32],
+                bonus_root: [0xbb; 32],
+                distribution_id: 12,
+                round_history,
+                rounds_recorded: 13,
+                total_distributed: 14,
+                total_claimed: 15,
+                early_bird_bonus: 16,
+                early_bird_reserved: 17,
+                early_bird_round: 18,
+                accrued_allocation: 22,
+                tree_depth: 30,
+                category_roots: [[34; 32]; MAX_CATEGORIES],
+                recent_roots: [[43; 32]; RECENT_ROOTS_LEN],
+                last_distribution_nonce: 44,
+                category_bps: [46; MAX_CATEGORIES],
+                bonus_reserved: 51,
+                category_accrued: [53; MAX_CATEGORIES],
+                recent_root_depths: [54; RECENT_ROOTS_LEN],
+                category_root_depths: [55; MAX_CATEGORIES],
+                bonus_tree_depth: 56,
+                round_depths: [57; ROUND_HISTORY_LEN],
+            },
+            claims: ClaimRules {
+                leaf_has_expiry: true,
+                strict_ata_check: true,
+                min_proof_depth: 19,
+                total_claim_accounts: 20,
+                max_claim_accounts: 21,
+                claim_cooldown: 29,
+                frozen_wallets: 31,
+                max_claim_per_wallet: 45,
+                domain_salt: [48; DOMAIN_SALT_LEN],
+                max_single_claim: 49,
+                claim_fee_bps: 2_700,
+            },
+        }
+    }
+
+    #[test]
+    fn test_legacy_user_claim_status_loads_as_version_1() {
+        let status = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 500,
+            bump: 254,
+            burn_rewarded: 9,
+            version: USER_CLAIM_VERSION,
+            ..UserClaimStatus::default()
+        };
+        let data = borsh::to_vec(&status).unwrap();
+        assert_eq!(data.len(), UserClaimStatus::LEN);
+        assert_eq!(UserClaimStatus::load(&data).unwrap().version, USER_CLAIM_VERSION);
+
+        // Account created before the version byte
+        let mut legacy = data[..UserClaimStatus::LEGACY_LEN].to_vec();
+        let mut loaded = UserClaimStatus::load(&legacy).unwrap();
+        assert!(loaded.is_valid());
+        assert_eq!((loaded.version, loaded.claimed_amount, loaded.burn_rewarded), (1, 500, 9));
+
+        // Writing back keeps the legacy size
+        loaded.claimed_amount = 800;
+        loaded.store(&mut legacy).unwrap();
+        assert_eq!(legacy.len(), UserClaimStatus::LEGACY_LEN);
+        assert_eq!(UserClaimStatus::load(&legacy).unwrap().claimed_amount, 800);
+
+        // Unknown versions and sizes are rejected
+        let future = UserClaimStatus {
+            version: USER_CLAIM_VERSION + 1,
+            ..status
+        };
+        assert!(!future.is_valid());
+        assert!(status.store(&mut [0u8; 10]).is_err());
+    }
+
+    #[test]
+    fn test_version_2_user_claim_status_loads_without_last_claim_ts() {
+        let status = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 500,
+            version: 2,
+            last_claim_ts: 1_700_000_000,
+            ..UserClaimStatus::default()
+        };
+        let mut v2 = borsh::to_vec(&status).unwrap()[..UserClaimStatus::V2_LEN].to_vec();
+
+        let mut loaded = UserClaimStatus::load(&v2).unwrap();
+        assert!(loaded.is_valid());
+        assert_eq!((loaded.version, loaded.claimed_amount, loaded.last_claim_ts), (2, 500, 0));
+
+        loaded.claimed_amount = 800;
+        loaded.store(&mut v2).unwrap();
+        assert_eq!(v2.len(), UserClaimStatus::V2_LEN);
+        assert_eq!(UserClaimStatus::load(&v2).unwrap().claimed_amount, 800);
+    }
+
+    #[test]
+    fn test_version_3_user_claim_status_loads_without_category_claimed() {
+        let status = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 500,
+            version: 3,
+            last_claim_ts: 1_700_000_000,
+            category_claimed: [7; MAX_CATEGORIES],
+            ..UserClaimStatus::default()
+        };
+        let mut v3 = borsh::to_vec(&status).unwrap()[..UserClaimStatus::V3_LEN].to_vec();
+
+        let loaded = UserClaimStatus::load(&v3).unwrap();
+        assert!(loaded.is_valid());
+        assert_eq!((loaded.version, loaded.last_claim_ts), (3, 1_700_000_000));
+        assert_eq!(loaded.category_claimed, [0; MAX_CATEGORIES]);
+
+        loaded.store(&mut v3).unwrap();
+        assert_eq!(v3.len(), UserClaimStatus::V3_LEN);
+    }
+
+    #[test]
+    fn test_config_try_load_checks_address_owner_and_discriminator() {
+        let program_id = Pubkey::new_unique();
+        let mut account = TestAccount::config(&program_id, populated_config());
+        let config = Config::try_load(&program_id, &account.info()).unwrap();
+        assert_eq!(borsh::to_vec(&config).unwrap(), account.data);
+
+        let other_program = Pubkey::new_unique();
+        let err = Config::try_load(&other_program, &account.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+
+        account.owner = Pubkey::new_unique();
+        let err = Config::try_load(&program_id, &account.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+
+        account.owner = program_id;
+        account.data[..8].copy_from_slice(&USER_CLAIM_DISCRIMINATOR);
+        let err = Config::try_load(&program_id, &account.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidDiscriminator.into());
+    }
+
+    #[test]
+    fn test_user_claim_status_try_load_checks_address_owner_and_discriminator() {
+        let program_id = Pubkey::new_unique();
+        let user = Pubkey::new_unique();
+        let (pda, _) =
+            Pubkey::find_program_address(&[UserClaimStatus::SEED, user.as_ref()], &program_id);
+        let status = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 500,
+            version: USER_CLAIM_VERSION,
+            ..UserClaimStatus::default()
+        };
+        let mut account = TestAccount {
+            owner: program_id,
+            data: borsh::to_vec(&status).unwrap()[..UserClaimStatus::V2_LEN].to_vec(),
+            ..TestAccount::new(pda)
+        };
+        let loaded = UserClaimStatus::try_load(&program_id, &user, &account.info()).unwrap();
+        assert_eq!(loaded.claimed_amount, 500);
+
+        let other_user = Pubkey::new_unique();
+        let err = UserClaimStatus::try_load(&program_id, &other_user, &account.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidPda.into());
+
+        account.owner = Pubkey::new_unique();
+        let err = UserClaimStatus::try_load(&program_id, &user, &account.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+
+        account.owner = program_id;
+        account.data[..8].copy_from_slice(&CONFIG_DISCRIMINATOR);
+        let err = UserClaimStatus::try_load(&program_id, &user, &account.info()).unwrap_err();
+        assert_eq!(err, YapError::InvalidDiscriminator.into());
+    }
+
+    #[test]
+    fn test_config_serialized_size_matches_len() {
+        assert_eq!(borsh::to_vec(&Config::default()).unwrap().len(), Config::LEN);
+        assert_eq!(borsh::to_vec(&populated_config()).unwrap().len(), Config::LEN);
+    }
+
+    #[test]
+    fn test_config_round_trip_preserves_every_field() {
+        let config = populated_config();
+        let data = borsh::to_vec(&config).unwrap();
+        assert_eq!(Config::try_from_slice(&data).unwrap(), config);
+    }
+
+    #[test]
+    fn test_config_keeps_flat_layout() {
+        let config = populated_config();
+        let data = borsh::to_vec(&config).unwrap();
+
+        // Offsets of the original fields are unchanged by the grouping
+        assert_eq!(&data[..8], &CONFIG_DISCRIMINATOR);
+        assert_eq!(&data[8..40], config.addresses.mint.as_ref());
+        assert_eq!(&data[104..136], &config.distribution.merkle_root);
+        assert_eq!(&data[136..168], config.roles.merkle_updater.as_ref());
+        assert_eq!(&data[192..224], config.roles.admin.as_ref());
+        assert_eq!(data[226], config.bump);
+
+        // ... and the newest fields are appended at the end
+        let tail = &data[Config::LEN - 755..];
+        assert_eq!(&tail[..32], config.addresses.token_program.as_ref());
+        assert_eq!(&tail[32..40], &22u64.to_le_bytes()); // accrued_allocation
+        assert_eq!(&tail[40..48], &23i64.to_le_bytes()); // min_distribution_interval
+        assert_eq!(tail[48], 24); // version
+        assert_eq!(&tail[49..81], &[25; 32]); // treasury
+        assert_eq!(&tail[81..83], &2_600u16.to_le_bytes()); // treasury_inflation_bps
+        assert_eq!(&tail[83..91], &(-26i64).to_le_bytes()); // genesis_ts
+        assert_eq!(&tail[91..99], &27i64.to_le_bytes()); // halving_interval
+        assert_eq!(tail[99], 28); // halving_count_cap
+        assert_eq!(tail[100], 1); // admin_renounced
+        assert_eq!(&tail[101..109], &29i64.to_le_bytes()); // claim_cooldown
+        assert_eq!(tail[109], 30); // tree_depth
+        assert_eq!(&tail[110..118], &31u64.to_le_bytes()); // frozen_wallets
+        assert_eq!(&tail[118..278], &[32; 160]); // updater_keys
+        assert_eq!(tail[278], 33); // updater_threshold
+        assert_eq!(&tail[279..407], &[34; 128]); // category_roots
+        assert_eq!(&tail[407..415], &35i64.to_le_bytes()); // claim_window
+        assert_eq!(&tail[415..423], &36i64.to_le_bytes()); // claim_deadline_ts
+        assert_eq!(&tail[423..431], &37i64.to_le_bytes()); // vesting_start_ts
+        assert_eq!(&tail[431..439], &38i64.to_le_bytes()); // vesting_cliff_ts
+        assert_eq!(&tail[439..447], &39i64.to_le_bytes()); // vesting_duration
+        assert_eq!(tail[447], 40); // decimals
+        assert_eq!(&tail[448..456], &41i64.to_le_bytes()); // rate_period_secs
+        assert_eq!(&tail[456..472], &42u128.to_le_bytes()); // inflation_remainder
+        assert_eq!(&tail[472..600], &[43; 128]); // recent_roots
+        assert_eq!(&tail[600..608], &44u64.to_le_bytes()); // last_distribution_nonce
+        assert_eq!(&tail[608..616], &45u64.to_le_bytes()); // max_claim_per_wallet
+        assert_eq!(&tail[616..624], &[46, 0, 46, 0, 46, 0, 46, 0]); // category_bps
+        assert_eq!(&tail[624..632], &47u64.to_le_bytes()); // total_inflation_minted
+        assert_eq!(&tail[632..648], &[48; DOMAIN_SALT_LEN]); // domain_salt
+        assert_eq!(&tail[648..656], &49u64.to_le_bytes()); // max_single_claim
+        assert_eq!(&tail[656..688], &[50; 32]); // allowed_distribution_dest
+        assert_eq!(&tail[688..690], &2_700u16.to_le_bytes()); // claim_fee_bps
+        assert_eq!(&tail[690..698], &51u64.to_le_bytes()); // bonus_reserved
+        assert_eq!(&tail[698..706], &52i64.to_le_bytes()); // distribution_timelock
+        assert_eq!(&tail[706..714], &53u64.to_le_bytes()); // category_accrued
+        assert_eq!(&tail[738..742], &[54; RECENT_ROOTS_LEN]); // recent_root_depths
+        assert_eq!(&tail[742..746], &[55; MAX_CATEGORIES]); // category_root_depths
+        assert_eq!(tail[746], 56); // bonus_tree_depth
+        assert_eq!(&tail[747..755], &[57; ROUND_HISTORY_LEN]); // round_depths
+    }
+
+    /// Walks serialized account data in order, checking each field's offset and bytes
+    struct Layout<'a> {
+        data: &'a [u8],
+        at: usize,
+    }
+
+    impl<'a> Layout<'a> {
+        fn new(data: &'a [u8]) -> Self {
+            Self { data, at: 0 }
+        }
+
+        fn field<T: BorshSerialize>(&mut self, name: &str, offset: usize, value: &T) {
+            let bytes = borsh::to_vec(value).unwrap();
+            assert_eq!(self.at, offset, "offset of {name}");
+            let actual = &self.data[offset..offset + bytes.len()];
+            assert_eq!(actual, &bytes[..], "bytes of {name}");
+            self.at += bytes.len();
+        }
+
+        fn end(&self, len: usize) {
+            assert_eq!(self.at, len, "fields don't add up to LEN");
+            assert_eq!(self.data.len(), len, "serialized size differs from LEN");
+        }
+    }
+
+    /// Check that the next field in `$layout` starts at `$offset` and holds `$value`
+    macro_rules! field {
+        ($layout:ident, $offset:expr, $value:expr) => {
+            $layout.field(stringify!($value), $offset, &$value)
+        };
+    }
+
+    #[test]
+    fn test_config_field_offsets() {
+        let c = populated_config();
+        let data = borsh::to_vec(&c).unwrap();
+        let mut l = Layout::new(&data);
+
+        field!(l, 0, c.discriminator);
+        field!(l, 8, c.addresses.mint);
+        field!(l, 40, c.addresses.vault);
+        field!(l, 72, c.addresses.pending_claims);
+        field!(l, 104, c.distribution.merkle_root);
+        field!(l, 136, c.roles.merkle_updater);
+        field!(l, 168, c.tokenomics.current_supply);
+        field!(l, 176, c.timing.last_inflation_ts);
+        field!(l, 184, c.timing.last_distribution_ts);
+        field!(l, 192, c.roles.admin);
+        field!(l, 224, c.tokenomics.inflation_rate_bps);
+        field!(l, 226, c.bump);
+        field!(l, 227, c.distribution.bonus_root);
+        field!(l, 259, c.distribution.total_distributed);
+        field!(l, 267, c.distribution.total_claimed);
+        field!(l, 275, c.tokenomics.scheduled_burn_amount);
+        field!(l, 283, c.tokenomics.scheduled_burn_ts);
+        field!(l, 291, c.tokenomics.compound_inflation);
+        field!(l, 292, c.tokenomics.undistributed_inflation);
+        field!(l, 300, c.claims.leaf_has_expiry);
+        field!(l, 301, c.claims.strict_ata_check);
+        field!(l, 302, c.distribution.round_history);
+        field!(l, 750, c.distribution.rounds_recorded);
+        field!(l, 758, c.distribution.distribution_id);
+        field!(l, 766, c.claims.min_proof_depth);
+        field!(l, 767, c.roles.pending_admin);
+        field!(l, 799, c.paused);
+        field!(l, 800, c.claims.total_claim_accounts);
+        field!(l, 808, c.claims.max_claim_accounts);
+        field!(l, 816, c.tokenomics.max_supply);
+        field!(l, 824, c.tokenomics.inflation_burn_bps);
+        field!(l, 826, c.tokenomics.burn_reward_bps);
+        field!(l, 828, c.tokenomics.permissionless_inflation);
+        field!(l, 829, c.tokenomics.keeper_reward_bps);
+        field!(l, 831, c.distribution.early_bird_bonus);
+        field!(l, 839, c.distribution.early_bird_reserved);
+        field!(l, 847, c.distribution.early_bird_round);
+        field!(l, 855, c.addresses.token_program);
+        field!(l, 887, c.distribution.accrued_allocation);
+        field!(l, 895, c.timing.min_distribution_interval);
+        field!(l, 903, c.version);
+        field!(l, 904, c.addresses.treasury);
+        field!(l, 936, c.tokenomics.treasury_inflation_bps);
+        field!(l, 938, c.timing.genesis_ts);
+        field!(l, 946, c.tokenomics.halving_interval);
+        field!(l, 954, c.tokenomics.halving_count_cap);
+        field!(l, 955, c.roles.admin_renounced);
+        field!(l, 956, c.claims.claim_cooldown);
+        field!(l, 964, c.distribution.tree_depth);
+        field!(l, 965, c.claims.frozen_wallets);
+        field!(l, 973, c.roles.updater_keys);
+        field!(l, 1133, c.roles.updater_threshold);
+        field!(l, 1134, c.distribution.category_roots);
+        field!(l, 1262, c.timing.claim_window);
+        field!(l, 1270, c.timing.claim_deadline_ts);
+        field!(l, 1278, c.timing.vesting_start_ts);
+        field!(l, 1286, c.timing.vesting_cliff_ts);
+        field!(l, 1294, c.timing.vesting_duration);
+        field!(l, 1302, c.tokenomics.decimals);
+        field!(l, 1303, c.timing.rate_period_secs);
+        field!(l, 1311, c.tokenomics.inflation_remainder);
+        field!(l, 1327, c.distribution.recent_roots);
+        field!(l, 1455, c.distribution.last_distribution_nonce);
+        field!(l, 1463, c.claims.max_claim_per_wallet);
+        field!(l, 1471, c.distribution.category_bps);
+        field!(l, 1479, c.tokenomics.total_inflation_minted);
+        field!(l, 1487, c.claims.domain_salt);
+        field!(l, 1503, c.claims.max_single_claim);
+        field!(l, 1511, c.addresses.allowed_distribution_dest);
+        field!(l, 1543, c.claims.claim_fee_bps);
+        field!(l, 1545, c.distribution.bonus_reserved);
+        field!(l, 1553, c.timing.distribution_timelock);
+        field!(l, 1561, c.distribution.category_accrued);
+        field!(l, 1593, c.distribution.recent_root_depths);
+        field!(l, 1597, c.distribution.category_root_depths);
+        field!(l, 1601, c.distribution.bonus_tree_depth);
+        field!(l, 1602, c.distribution.round_depths);
+        l.end(Config::LEN);
+    }
+
+    #[test]
+    fn test_user_claim_status_field_offsets() {
+        let s = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 1,
+            total_burned: 2,
+            bump: 3,
+            bonus_claimed_amount: 4,
+            burn_rewarded: 5,
+            version: USER_CLAIM_VERSION,
+            last_claim_ts: -6,
+            category_claimed: [7, 8, 9, 10],
+        };
+        let data = borsh::to_vec(&s).unwrap();
+        let mut l = Layout::new(&data);
+
+        field!(l, 0, s.discriminator);
+        field!(l, 8, s.claimed_amount);
+        field!(l, 16, s.total_burned);
+        field!(l, 24, s.bump);
+        field!(l, 25, s.bonus_claimed_amount);
+        field!(l, 33, s.burn_rewarded);
+        field!(l, 41, s.version);
+        field!(l, 42, s.last_claim_ts);
+        field!(l, 50, s.category_claimed);
+        l.end(UserClaimStatus::LEN);
+
+        // Older layouts are prefixes of the current one
+        assert_eq!(UserClaimStatus::V3_LEN, 50);
+        assert_eq!(UserClaimStatus::V2_LEN, 42);
+        assert_eq!(UserClaimStatus::LEGACY_LEN, 41);
+    }
+
+    #[test]
+    fn test_fixed_size_accounts_match_len() {
+        fn len_of<T: BorshSerialize + Default>() -> usize {
+            borsh::to_vec(&T::default()).unwrap().len()
+        }
+        assert_eq!(len_of::<RoundRecord>(), RoundRecord::LEN);
+        assert_eq!(len_of::<EpochClaimStatus>(), EpochClaimStatus::LEN);
+        assert_eq!(len_of::<FrozenWallet>(), FrozenWallet::LEN);
+        assert_eq!(len_of::<StakeAccount>(), StakeAccount::LEN);
+        assert_eq!(len_of::<ClaimDelegate>(), ClaimDelegate::LEN);
+        assert_eq!(len_of::<ProofBuffer>(), ProofBuffer::LEN);
+        assert_eq!(len_of::<PendingDistribution>(), PendingDistribution::LEN);
+    }
+}
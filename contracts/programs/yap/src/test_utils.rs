@@ -0,0 +1,103 @@
+//! Account fixtures for driving processors in unit tests
+
+use std::cell::RefCell;
+
+use borsh::BorshSerialize;
+use solana_program::{account_info::AccountInfo, program_stubs, pubkey::Pubkey};
+
+use crate::state::{Config, CONFIG_DISCRIMINATOR, CONFIG_VERSION};
+
+thread_local! {
+    static RETURN_DATA: RefCell<Vec<u8>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Host stubs that keep the last return data instead of dropping it
+///
+/// The data is per thread, so tests running in parallel don't see each
+/// other's writes.
+struct ReturnDataStubs;
+
+impl program_stubs::SyscallStubs for ReturnDataStubs {
+    fn sol_set_return_data(&self, data: &[u8]) {
+        RETURN_DATA.with(|stored| *stored.borrow_mut() = data.to_vec());
+    }
+
+    fn sol_get_return_data(&self) -> Option<(Pubkey, Vec<u8>)> {
+        Some((
+            crate::id(),
+            RETURN_DATA.with(|stored| stored.borrow().clone()),
+        ))
+    }
+}
+
+/// Run `f` and return whatever it last published with `set_return_data`
+pub(crate) fn capture_return_data(f: impl FnOnce()) -> Vec<u8> {
+    program_stubs::set_syscall_stubs(Box::new(ReturnDataStubs));
+    RETURN_DATA.with(|stored| stored.borrow_mut().clear());
+    f();
+    RETURN_DATA.with(|stored| stored.borrow().clone())
+}
+
+/// Owned backing storage for an `AccountInfo`
+pub(crate) struct TestAccount {
+    pub key: Pubkey,
+    pub owner: Pubkey,
+    pub lamports: u64,
+    pub data: Vec<u8>,
+    pub is_signer: bool,
+}
+
+impl TestAccount {
+    /// Empty, system-owned account at `key`
+    pub fn new(key: Pubkey) -> Self {
+        Self {
+            key,
+            owner: solana_system_interface::program::id(),
+            lamports: 0,
+            data: Vec::new(),
+            is_signer: false,
+        }
+    }
+
+    /// Empty account that signs the transaction
+    pub fn signer(key: Pubkey) -> Self {
+        Self {
+            is_signer: true,
+            ..Self::new(key)
+        }
+    }
+
+    /// Config PDA for `program_id` holding `config` (discriminator and version set)
+    pub fn config(program_id: &Pubkey, config: Config) -> Self {
+        let (config_pda, _) = Pubkey::find_program_address(&[Config::SEED], program_id);
+        let mut account = Self {
+            owner: *program_id,
+            data: vec![0u8; Config::LEN],
+            ..Self::new(config_pda)
+        };
+        account.set_config(config);
+        account
+    }
+
+    /// Overwrite the stored config (discriminator and version set)
+    pub fn set_config(&mut self, config: Config) {
+        let config = Config {
+            discriminator: CONFIG_DISCRIMINATOR,
+            version: CONFIG_VERSION,
+            ..config
+        };
+        config.serialize(&mut &mut self.data[..]).unwrap();
+    }
+
+    pub fn info(&mut self) -> AccountInfo<'_> {
+        AccountInfo::new(
+            &self.key,
+            self.is_signer,
+            true,
+            &mut self.lamports,
+            &mut self.data,
+            &self.owner,
+            false,
+        )
+    }
+}
@@ -0,0 +1,122 @@
+use solana_program::{account_info::AccountInfo, msg, program_error::ProgramError};
+
+use crate::error::YapError;
+
+/// Close a program-owned account, returning all of its lamports to `recipient`
+///
+/// Follows the standard close pattern so the account can't be revived later in
+/// the same transaction: lamports are drained, data is zeroed (the discriminator
+/// no longer matches), ownership returns to the system program, and the data
+/// length is set to 0 so the runtime garbage-collects it.
+///
+/// Returns the exact number of lamports moved to `recipient`.
+pub fn close_account(account: &AccountInfo, recipient: &AccountInfo) -> Result<u64, ProgramError> {
+    if account.key == recipient.key {
+        msg!("Close: recipient cannot be the closed account");
+        return Err(YapError::InvalidInstruction.into());
+    }
+
+    let lamports = account.lamports();
+    let recipient_lamports = recipient
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(YapError::Overflow)?;
+
+    **recipient.try_borrow_mut_lamports()? = recipient_lamports;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.try_borrow_mut_data()?.fill(0);
+    account.assign(&solana_system_interface::program::id());
+    account.resize(0)?;
+
+    msg!(
+        "Close: {} returned {} lamports to {}",
+        account.key,
+        lamports,
+        recipient.key
+    );
+
+    Ok(lamports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state::{
+        UserClaimStatus, MAX_CATEGORIES, USER_CLAIM_DISCRIMINATOR, USER_CLAIM_VERSION,
+    };
+    use borsh::{BorshDeserialize, BorshSerialize};
+    use solana_program::pubkey::Pubkey;
+
+    /// Mirrors the runtime input layout `resize` relies on: the original data
+    /// length sits in the 4 bytes before the key.
+    #[repr(C)]
+    struct SerializedKey {
+        original_data_len: u32,
+        key: Pubkey,
+    }
+
+    /// Account data with the runtime's 8-byte length prefix in front
+    #[repr(C, align(8))]
+    struct SerializedData([u8; 8 + UserClaimStatus::LEN]);
+
+    #[test]
+    fn test_close_account_returns_all_lamports_and_invalidates_data() {
+        let status = UserClaimStatus {
+            discriminator: USER_CLAIM_DISCRIMINATOR,
+            claimed_amount: 1_000,
+            total_burned: 0,
+            bump: 255,
+            bonus_claimed_amount: 0,
+            burn_rewarded: 0,
+            version: USER_CLAIM_VERSION,
+            last_claim_ts: 0,
+            category_claimed: [0; MAX_CATEGORIES],
+        };
+
+        // 8-byte length prefix precedes the account data, as in the runtime
+        let mut serialized = SerializedData([0u8; 8 + UserClaimStatus::LEN]);
+        let buffer = &mut serialized.0;
+        buffer[..8].copy_from_slice(&(UserClaimStatus::LEN as u64).to_le_bytes());
+        status.serialize(&mut &mut buffer[8..]).unwrap();
+
+        let closed_key = SerializedKey {
+            original_data_len: UserClaimStatus::LEN as u32,
+            key: Pubkey::new_unique(),
+        };
+        let program_id = Pubkey::new_unique();
+        let mut closed_lamports = 1_113_600u64;
+        let closed = AccountInfo::new(
+            &closed_key.key,
+            false,
+            true,
+            &mut closed_lamports,
+            &mut buffer[8..],
+            &program_id,
+            false,
+        );
+
+        let recipient_key = Pubkey::new_unique();
+        let system_program = solana_system_interface::program::id();
+        let mut recipient_lamports = 5_000u64;
+        let mut recipient_data = [];
+        let recipient = AccountInfo::new(
+            &recipient_key,
+            true,
+            true,
+            &mut recipient_lamports,
+            &mut recipient_data,
+            &system_program,
+            false,
+        );
+
+        let returned = close_account(&closed, &recipient).unwrap();
+
+        assert_eq!(returned, 1_113_600);
+        assert_eq!(recipient.lamports(), 5_000 + 1_113_600);
+        assert_eq!(closed.lamports(), 0);
+        assert_eq!(closed.data_len(), 0);
+        assert_eq!(*closed.owner, system_program);
+        assert!(UserClaimStatus::try_from_slice(&closed.data.borrow()).is_err());
+    }
+}
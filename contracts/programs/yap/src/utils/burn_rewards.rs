@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+
+use solana_program::pubkey::Pubkey;
+
+use crate::{
+    state::DOMAIN_SALT_LEN,
+    utils::merkle::{compute_salted_leaf, MerkleTree},
+};
+
+/// Claim tree splitting a reward pool by lifetime burns
+///
+/// Built from a snapshot of `UserClaimStatus.total_burned`: each wallet gets
+/// `pool * burned / total_burned`, rounded down, as a regular claim leaf
+/// salted with the deployment's `domain_salt`, so the root goes straight into
+/// `Distribute` and the proofs into `Claim`. Leaves are cumulative, so `pool`
+/// is everything paid out through this tree so far, not just the new top-up.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BurnRewardTree {
+    /// `(wallet, amount)` per leaf, sorted by wallet
+    entries: Vec<(Pubkey, u64)>,
+    tree: MerkleTree,
+}
+
+impl BurnRewardTree {
+    /// Split `pool` over `(wallet, total_burned)` pairs
+    ///
+    /// Repeated wallets are summed and wallets that burned nothing are left
+    /// out. The rounding remainder stays unallocated; `total_amount` is what
+    /// the leaves add up to.
+    pub fn build(
+        burns: &[(Pubkey, u64)],
+        pool: u64,
+        salt: &[u8; DOMAIN_SALT_LEN],
+    ) -> BurnRewardTree {
+        let mut burned: BTreeMap<Pubkey, u128> = BTreeMap::new();
+        for (wallet, amount) in burns.iter().filter(|(_, amount)| *amount > 0) {
+            *burned.entry(*wallet).or_default() += *amount as u128;
+        }
+        let total_burned: u128 = burned.values().sum();
+
+        // amount <= pool since burned <= total_burned, so it fits in u64
+        let entries: Vec<(Pubkey, u64)> = burned
+            .into_iter()
+            .map(|(wallet, burned)| (wallet, (pool as u128 * burned / total_burned) as u64))
+            .collect();
+        let leaves: Vec<[u8; 32]> = entries
+            .iter()
+            .map(|(wallet, amount)| compute_salted_leaf(salt, wallet, *amount))
+            .collect();
+
+        BurnRewardTree {
+            tree: MerkleTree::from_leaves(&leaves),
+            entries,
+        }
+    }
+
+    /// Root to publish with `Distribute`
+    pub fn root(&self) -> [u8; 32] {
+        self.tree.root()
+    }
+
+    /// `tree_depth` to publish with the root
+    pub fn depth(&self) -> u8 {
+        self.tree.depth() as u8
+    }
+
+    /// Sum of all leaf amounts
+    pub fn total_amount(&self) -> u64 {
+        self.entries.iter().map(|(_, amount)| amount).sum()
+    }
+
+    /// `(wallet, amount)` per leaf, sorted by wallet
+    pub fn entries(&self) -> &[(Pubkey, u64)] {
+        &self.entries
+    }
+
+    /// Leaf amount and proof for `wallet`, the arguments to `Claim`
+    pub fn claim(&self, wallet: &Pubkey) -> Option<(u64, Vec<[u8; 32]>)> {
+        let index = self
+            .entries
+            .binary_search_by(|(key, _)| key.cmp(wallet))
+            .ok()?;
+        Some((self.entries[index].1, self.tree.proof(index)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::YapError;
+    use crate::instructions::claim::{record_claim, verify_claim_proof, vested_claimable};
+    use crate::state::{Config, UserClaimStatus};
+
+    #[test]
+    fn test_split_is_pro_rata() {
+        let a = Pubkey::new_unique();
+        let b = Pubkey::new_unique();
+        let c = Pubkey::new_unique();
+        let none = Pubkey::new_unique();
+
+        let burns = [(a, 100), (b, 200), (none, 0), (a, 100), (c, 1)];
+        let tree = BurnRewardTree::build(&burns, 1_000, &[0; DOMAIN_SALT_LEN]);
+
+        // a burned 200 of 401, b 200, c 1; `none` gets no leaf
+        assert_eq!(tree.entries().len(), 3);
+        assert_eq!(tree.claim(&a).unwrap().0, 498);
+        assert_eq!(tree.claim(&b).unwrap().0, 498);
+        assert_eq!(tree.claim(&c).unwrap().0, 2);
+        assert!(tree.claim(&none).is_none());
+        assert_eq!(tree.total_amount(), 998);
+
+        // Input order doesn't change the tree
+        let shuffled =
+            BurnRewardTree::build(&[(c, 1), (b, 200), (a, 200)], 1_000, &[0; DOMAIN_SALT_LEN]);
+        assert_eq!(shuffled.root(), tree.root());
+
+        // No burns at all: empty tree
+        let empty = BurnRewardTree::build(&[(none, 0)], 1_000, &[0; DOMAIN_SALT_LEN]);
+        assert!(empty.entries().is_empty());
+    }
+
+    #[test]
+    fn test_root_round_trips_through_claim() {
+        let burns: Vec<(Pubkey, u64)> = (1..=9u64)
+            .map(|i| (Pubkey::new_unique(), i * 1_000))
+            .collect();
+        let salt = [5; DOMAIN_SALT_LEN];
+        let tree = BurnRewardTree::build(&burns, 45_000_000, &salt);
+
+        // Publish the root as Distribute would
+        let mut config = Config::default();
+        config.claims.domain_salt = salt;
+        config.rotate_root(tree.root(), tree.depth());
+        config.distribution.total_distributed = tree.total_amount();
+
+        for (wallet, burned) in &burns {
+            let (amount, proof) = tree.claim(wallet).unwrap();
+            assert_eq!(amount, burned * 1_000);
+
+            verify_claim_proof(&config, wallet, amount, None, &proof).unwrap();
+
+            let mut status = UserClaimStatus::default();
+            let (_, claimable) = vested_claimable(&config, &status, amount, 0).unwrap();
+            assert_eq!(claimable, amount);
+            record_claim(&mut config, &mut status, wallet, claimable, None, claimable).unwrap();
+            assert_eq!(status.claimed_amount, amount);
+
+            // A leaf can't be claimed for more than it holds
+            let err = verify_claim_proof(&config, wallet, amount + 1, None, &proof).unwrap_err();
+            assert_eq!(err, YapError::InvalidProof.into());
+        }
+
+        assert_eq!(config.distribution.total_claimed, tree.total_amount());
+    }
+}
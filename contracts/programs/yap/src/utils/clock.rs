@@ -0,0 +1,158 @@
+use solana_program::{
+    account_info::AccountInfo, clock::Clock, entrypoint::ProgramResult, msg,
+    sysvar::SysvarSerialize,
+};
+
+use crate::error::YapError;
+
+/// Validate an explicitly passed Clock sysvar account against `Clock::get()`
+///
+/// Time-dependent instructions accept the clock as an optional trailing
+/// account for auditability. When passed, it must be the real sysvar and
+/// report the same `unix_timestamp` as the syscall.
+pub fn validate_clock_account(clock_info: &AccountInfo, now: i64) -> ProgramResult {
+    if *clock_info.key != solana_program::sysvar::clock::ID {
+        msg!("Clock: {} is not the clock sysvar", clock_info.key);
+        return Err(YapError::InvalidClock.into());
+    }
+
+    let clock = Clock::from_account_info(clock_info).map_err(|_| {
+        msg!("Clock: Sysvar data could not be read");
+        YapError::InvalidClock
+    })?;
+
+    if clock.unix_timestamp != now {
+        msg!(
+            "Clock: Sysvar timestamp {} differs from syscall {}",
+            clock.unix_timestamp,
+            now
+        );
+        return Err(YapError::InvalidClock.into());
+    }
+
+    Ok(())
+}
+
+/// Seconds elapsed from `last_ts` to `now`
+///
+/// Validators can report a `unix_timestamp` slightly behind a previously
+/// observed one. Rather than treat that as zero elapsed time and overwrite the
+/// stored timestamp with an older value, fail with `ClockWentBackwards` so the
+/// caller retries once the clock has caught up.
+pub fn elapsed_since(now: i64, last_ts: i64) -> Result<i64, YapError> {
+    if now < last_ts {
+        msg!("Clock: now {} is before last recorded {}", now, last_ts);
+        return Err(YapError::ClockWentBackwards);
+    }
+
+    Ok(now - last_ts)
+}
+
+/// `rate_period_secs` as a divisor; `InvalidInstruction` unless positive
+pub fn rate_period(rate_period_secs: i64) -> Result<u128, YapError> {
+    if rate_period_secs <= 0 {
+        msg!("Clock: rate period {} is not positive", rate_period_secs);
+        return Err(YapError::InvalidInstruction);
+    }
+
+    Ok(rate_period_secs as u128)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use solana_program::pubkey::Pubkey;
+
+    const CLOCK_LEN: usize = 40;
+
+    fn clock_data(unix_timestamp: i64) -> [u8; CLOCK_LEN] {
+        let mut data = [0u8; CLOCK_LEN];
+        let mut info_lamports = 0;
+        let key = solana_program::sysvar::clock::ID;
+        let owner = Pubkey::default();
+        let mut account = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut info_lamports,
+            &mut data,
+            &owner,
+            false,
+        );
+        Clock {
+            unix_timestamp,
+            ..Clock::default()
+        }
+        .to_account_info(&mut account)
+        .unwrap();
+        data
+    }
+
+    #[test]
+    fn test_valid_clock_account() {
+        let now = 1_700_000_000;
+        let key = solana_program::sysvar::clock::ID;
+        let owner = Pubkey::default();
+        let mut data = clock_data(now);
+        let mut lamports = 0;
+        let clock_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+        );
+
+        assert!(validate_clock_account(&clock_info, now).is_ok());
+    }
+
+    #[test]
+    fn test_invalid_clock_account() {
+        let now = 1_700_000_000;
+        let owner = Pubkey::default();
+
+        // Wrong key, even with well-formed clock data
+        let fake_key = Pubkey::new_unique();
+        let mut data = clock_data(now);
+        let mut lamports = 0;
+        let fake = AccountInfo::new(
+            &fake_key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+        );
+        let err = validate_clock_account(&fake, now).unwrap_err();
+        assert_eq!(err, YapError::InvalidClock.into());
+
+        // Real key, but the timestamp disagrees with the syscall
+        let key = solana_program::sysvar::clock::ID;
+        let mut data = clock_data(now - 3_600);
+        let mut lamports = 0;
+        let skewed = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+        );
+        let err = validate_clock_account(&skewed, now).unwrap_err();
+        assert_eq!(err, YapError::InvalidClock.into());
+    }
+
+    #[test]
+    fn test_elapsed_since_rejects_backwards_clock() {
+        assert_eq!(elapsed_since(1_000, 400).unwrap(), 600);
+        assert_eq!(elapsed_since(1_000, 1_000).unwrap(), 0);
+        assert!(matches!(
+            elapsed_since(999, 1_000),
+            Err(YapError::ClockWentBackwards)
+        ));
+    }
+}
@@ -1,39 +1,538 @@
-use solana_program::{hash::hash, pubkey::Pubkey};
+use solana_program::{keccak, pubkey::Pubkey};
 
-/// Compute leaf hash: hash(wallet || amount)
+use crate::state::DOMAIN_SALT_LEN;
+
+/// First byte of every leaf preimage, so a leaf can never pass for an internal node
+pub const LEAF_PREFIX: u8 = 0x00;
+
+/// First byte of every internal node preimage
+pub const NODE_PREFIX: u8 = 0x01;
+
+/// Domain separator to prevent cross-protocol replay attacks
+pub const LEAF_DOMAIN: &[u8] = b"YAP_CLAIM_V1";
+
+/// Domain separator for bonus leaves, so a main leaf can never double as a bonus leaf
+pub const BONUS_LEAF_DOMAIN: &[u8] = b"YAP_BONUS_V1";
+
+/// Domain separator for per-distribution leaves claimed via `ClaimEpoch`
+pub const EPOCH_LEAF_DOMAIN: &[u8] = b"YAP_EPOCH_V1";
+
+/// Domain separator for fixed-index leaves claimed via `ClaimIndexed`
+pub const INDEXED_LEAF_DOMAIN: &[u8] = b"YAP_INDEX_V1";
+
+/// Domain separator for category leaves claimed via `ClaimCategory`
+pub const CATEGORY_LEAF_DOMAIN: &[u8] = b"YAP_CATEGORY_V1";
+
+/// Domain separator for deriving a deployment's `domain_salt`
+pub const SALT_DOMAIN: &[u8] = b"YAP_SALT_V1";
+
+/// Leaf salt `Initialize` stores for a deployment: the first 16 bytes of
+/// keccak256(salt_domain || program_id)
+///
+/// Staging and production run under different program ids, so their claim
+/// trees never share leaves even for identical `(wallet, amount)` pairs.
+pub fn deployment_salt(program_id: &Pubkey) -> [u8; DOMAIN_SALT_LEN] {
+    let hash = keccak::hashv(&[SALT_DOMAIN, program_id.as_ref()]).to_bytes();
+    let mut salt = [0u8; DOMAIN_SALT_LEN];
+    salt.copy_from_slice(&hash[..DOMAIN_SALT_LEN]);
+    salt
+}
+
+/// The salt as it enters a leaf preimage; an all-zero salt (configs migrated
+/// from before the salt) adds nothing, so their leaves are unchanged
+fn salt_bytes(salt: &[u8; DOMAIN_SALT_LEN]) -> &[u8] {
+    if *salt == [0; DOMAIN_SALT_LEN] {
+        &[]
+    } else {
+        salt
+    }
+}
+
+/// Compute leaf hash: keccak256(0x00 || domain || wallet_pubkey || amount)
+///
+/// The unsalted leaf, i.e. `compute_salted_leaf` with an all-zero salt.
 pub fn compute_leaf(wallet: &Pubkey, amount: u64) -> [u8; 32] {
-    let mut data = Vec::with_capacity(40);
-    data.extend_from_slice(wallet.as_ref());
-    data.extend_from_slice(&amount.to_le_bytes());
-    hash(&data).to_bytes()
+    compute_salted_leaf(&[0; DOMAIN_SALT_LEN], wallet, amount)
+}
+
+/// Compute a deployment's leaf hash: keccak256(0x00 || domain || salt || wallet_pubkey || amount)
+pub fn compute_salted_leaf(salt: &[u8; DOMAIN_SALT_LEN], wallet: &Pubkey, amount: u64) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        LEAF_DOMAIN,
+        salt_bytes(salt),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Compute bonus leaf hash:
+/// keccak256(0x00 || bonus_domain || salt || wallet_pubkey || bonus_amount)
+pub fn compute_bonus_leaf(
+    salt: &[u8; DOMAIN_SALT_LEN],
+    wallet: &Pubkey,
+    bonus_amount: u64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        BONUS_LEAF_DOMAIN,
+        salt_bytes(salt),
+        wallet.as_ref(),
+        &bonus_amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Compute expiring leaf hash:
+/// keccak256(0x00 || domain || salt || wallet_pubkey || amount || expiry_ts)
+pub fn compute_leaf_with_expiry(
+    salt: &[u8; DOMAIN_SALT_LEN],
+    wallet: &Pubkey,
+    amount: u64,
+    expiry_ts: i64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        LEAF_DOMAIN,
+        salt_bytes(salt),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+        &expiry_ts.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Compute epoch leaf hash:
+/// keccak256(0x00 || epoch_domain || salt || distribution_id || wallet || amount)
+pub fn compute_epoch_leaf(
+    salt: &[u8; DOMAIN_SALT_LEN],
+    distribution_id: u64,
+    wallet: &Pubkey,
+    amount: u64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        EPOCH_LEAF_DOMAIN,
+        salt_bytes(salt),
+        &distribution_id.to_le_bytes(),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Compute indexed leaf hash: keccak256(0x00 || index_domain || salt || index || wallet || amount)
+pub fn compute_indexed_leaf(
+    salt: &[u8; DOMAIN_SALT_LEN],
+    index: u32,
+    wallet: &Pubkey,
+    amount: u64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        INDEXED_LEAF_DOMAIN,
+        salt_bytes(salt),
+        &index.to_le_bytes(),
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
+}
+
+/// Compute category leaf hash:
+/// keccak256(0x00 || category_domain || salt || category || wallet || amount)
+pub fn compute_category_leaf(
+    salt: &[u8; DOMAIN_SALT_LEN],
+    category: u8,
+    wallet: &Pubkey,
+    amount: u64,
+) -> [u8; 32] {
+    keccak::hashv(&[
+        &[LEAF_PREFIX],
+        CATEGORY_LEAF_DOMAIN,
+        salt_bytes(salt),
+        &[category],
+        wallet.as_ref(),
+        &amount.to_le_bytes(),
+    ])
+    .to_bytes()
 }
 
 /// Verify merkle proof
+///
+/// Allocation-free: each level is hashed through the fixed stack buffer in
+/// `hash_pair`, so cost grows only with the number of keccak calls.
+///
+/// Timing: every level of `proof` is hashed before anything is compared, and
+/// the final root check goes through `ct_eq`, so how long a rejection takes
+/// says nothing about how close the proof came to `root`. Leaves, proofs and
+/// roots are all public on-chain, and compute units are metered rather than
+/// wall-clock, so this is defense in depth for off-chain verifiers sharing
+/// this code (e.g. a claim API checking proofs before relaying them), not a
+/// fix for a known on-chain leak.
 pub fn verify_proof(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> bool {
+    ct_eq(&compute_root(leaf, proof), root)
+}
+
+/// Compare two hashes without stopping at the first differing byte
+///
+/// XORs every byte pair into one accumulator, read back through
+/// `read_volatile` each round so the optimizer can't turn the loop back into
+/// an early-exit `memcmp`. Only the final result is data-dependent.
+pub fn ct_eq(a: &[u8; 32], b: &[u8; 32]) -> bool {
+    let mut diff = 0u8;
+    for (x, y) in a.iter().zip(b) {
+        diff |= x ^ y;
+        // SAFETY: `diff` is a live, aligned local; the read only hides its
+        // value from the optimizer.
+        diff = unsafe { core::ptr::read_volatile(&diff) };
+    }
+    diff == 0
+}
+
+/// Root reached by hashing `leaf` up through `proof`
+///
+/// Lets a caller check one proof against several candidate roots without
+/// rehashing it for each.
+pub fn compute_root(leaf: &[u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
     let mut computed = *leaf;
 
     for sibling in proof {
-        computed = if computed <= *sibling {
-            hash_pair(&computed, sibling)
+        computed = hash_sorted(&computed, sibling);
+    }
+
+    computed
+}
+
+/// Verify a proof and return the left/right decisions along its path
+///
+/// Bit `i` is set when the node at proof level `i` was hashed as the
+/// right-hand operand of `proof[i]`. `None` if the proof doesn't reach `root`
+/// or has more than 32 nodes.
+///
+/// Pairs are hashed in sorted order, so the bits follow the hash values, not
+/// the tree position: they are not the leaf's index in `from_leaves` order.
+/// Compare them with `MerkleTree::path(index)` to confirm that a proof belongs
+/// to the expected leaf. Reading the index off the proof alone would take
+/// position-aware (unsorted) hashing or direction bits in the proof, which
+/// changes every root and invalidates the proofs of live trees.
+pub fn verify_proof_with_path(root: &[u8; 32], leaf: &[u8; 32], proof: &[[u8; 32]]) -> Option<u32> {
+    if proof.len() > u32::BITS as usize {
+        return None;
+    }
+
+    let mut computed = *leaf;
+    let mut path = 0u32;
+    for (level, sibling) in proof.iter().enumerate() {
+        if computed > *sibling {
+            path |= 1 << level;
+        }
+        computed = hash_sorted(&computed, sibling);
+    }
+
+    ct_eq(&computed, root).then_some(path)
+}
+
+/// Verify several leaves against one shared multiproof (OpenZeppelin-style flags)
+///
+/// `leaves` must be in the order the proof was generated for. Each flag
+/// consumes the next node: `true` takes it from the leaves (then computed
+/// hashes), `false` from `proof`. Uses the same sorted-pair hashing as
+/// `verify_proof`, and every proof element must be consumed.
+pub fn verify_multiproof(
+    root: &[u8; 32],
+    leaves: &[[u8; 32]],
+    proof: &[[u8; 32]],
+    proof_flags: &[bool],
+) -> bool {
+    multiproof_root(leaves, proof, proof_flags).is_some_and(|(computed, _)| ct_eq(&computed, root))
+}
+
+/// `verify_multiproof`, also requiring every leaf to sit exactly `depth` levels below `root`
+///
+/// A proof node stands in for a subtree as tall as the node it is paired
+/// with, and two nodes the proof computes itself must be equally tall. That
+/// holds every leaf at the same height, the multiproof version of a
+/// single proof having exactly `depth` elements.
+pub fn verify_multiproof_at_depth(
+    root: &[u8; 32],
+    leaves: &[[u8; 32]],
+    proof: &[[u8; 32]],
+    proof_flags: &[bool],
+    depth: usize,
+) -> bool {
+    multiproof_root(leaves, proof, proof_flags)
+        .is_some_and(|(computed, height)| (height == Some(depth)) & ct_eq(&computed, root))
+}
+
+/// Root a multiproof computes, with its height above the leaves
+///
+/// The height is `None` when two computed nodes of different heights were
+/// hashed together. `None` overall for a malformed proof shape.
+fn multiproof_root(
+    leaves: &[[u8; 32]],
+    proof: &[[u8; 32]],
+    proof_flags: &[bool],
+) -> Option<([u8; 32], Option<usize>)> {
+    if leaves.is_empty() || leaves.len() + proof.len() != proof_flags.len() + 1 {
+        return None;
+    }
+
+    // Leaves are at height 0; `heights` runs alongside `hashes`
+    let mut hashes: Vec<[u8; 32]> = Vec::with_capacity(proof_flags.len());
+    let mut heights: Vec<Option<usize>> = Vec::with_capacity(proof_flags.len());
+    let mut leaf_pos = 0;
+    let mut hash_pos = 0;
+    let mut proof_pos = 0;
+
+    for &flag in proof_flags {
+        let a_height = node_height(leaves, leaf_pos, &heights, hash_pos);
+        let a = next_node(leaves, &mut leaf_pos, &hashes, &mut hash_pos)?;
+        let (b, b_height) = if flag {
+            let b_height = node_height(leaves, leaf_pos, &heights, hash_pos);
+            (
+                next_node(leaves, &mut leaf_pos, &hashes, &mut hash_pos)?,
+                b_height,
+            )
         } else {
-            hash_pair(sibling, &computed)
+            proof_pos += 1;
+            (*proof.get(proof_pos - 1)?, a_height)
         };
+        hashes.push(hash_sorted(&a, &b));
+        heights.push(
+            a_height
+                .filter(|&height| Some(height) == b_height)
+                .map(|height| height + 1),
+        );
+    }
+
+    if proof_pos != proof.len() {
+        return None;
+    }
+    let computed = hashes.last().copied().unwrap_or(leaves[0]);
+    let height = heights.last().copied().unwrap_or(Some(0));
+    Some((computed, height))
+}
+
+/// Height of the node `next_node` would return next
+fn node_height(
+    leaves: &[[u8; 32]],
+    leaf_pos: usize,
+    heights: &[Option<usize>],
+    hash_pos: usize,
+) -> Option<usize> {
+    if leaf_pos < leaves.len() {
+        Some(0)
+    } else {
+        heights.get(hash_pos).copied().flatten()
+    }
+}
+
+/// Next multiproof node: remaining leaves first, then already computed hashes
+fn next_node(
+    leaves: &[[u8; 32]],
+    leaf_pos: &mut usize,
+    hashes: &[[u8; 32]],
+    hash_pos: &mut usize,
+) -> Option<[u8; 32]> {
+    if let Some(leaf) = leaves.get(*leaf_pos) {
+        *leaf_pos += 1;
+        return Some(*leaf);
+    }
+    let hash = hashes.get(*hash_pos).copied()?;
+    *hash_pos += 1;
+    Some(hash)
+}
+
+/// Merkle tree over pre-hashed leaves, matching `verify_proof`
+///
+/// Built bottom-up with the same sorted-pair, node-tagged hashing as the
+/// verifier. Leaves are used as given (hash them with `compute_leaf` and
+/// friends first). The leaf level is padded with zero leaves up to a power of
+/// two (at least two), so no node is ever promoted and every proof has exactly
+/// `depth()` elements, as `Distribute` requires. A zero leaf is unclaimable:
+/// it would take a leaf preimage hashing to all zeros.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MerkleTree {
+    /// `levels[0]` holds the padded leaves, the last level holds the root
+    levels: Vec<Vec<[u8; 32]>>,
+    /// Leaves before padding
+    leaf_count: usize,
+}
+
+impl MerkleTree {
+    /// Build the tree; leaf order determines each leaf's proof index
+    pub fn from_leaves(leaves: &[[u8; 32]]) -> MerkleTree {
+        let mut padded = leaves.to_vec();
+        if !padded.is_empty() {
+            padded.resize(padded.len().next_power_of_two().max(2), [0u8; 32]);
+        }
+
+        let mut levels = vec![padded];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks_exact(2)
+                .map(|pair| hash_sorted(&pair[0], &pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        MerkleTree {
+            levels,
+            leaf_count: leaves.len(),
+        }
+    }
+
+    /// Root to publish with `Distribute` (all zeros for an empty tree)
+    pub fn root(&self) -> [u8; 32] {
+        self.levels
+            .last()
+            .and_then(|level| level.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// Number of leaves, not counting the padding
+    pub fn len(&self) -> usize {
+        self.leaf_count
     }
 
-    computed == *root
+    pub fn is_empty(&self) -> bool {
+        self.leaf_count == 0
+    }
+
+    /// Number of levels above the leaves, the `tree_depth` to publish with the root
+    ///
+    /// Every proof has exactly this length.
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    /// Proof for the leaf at `index`, ordered from the leaf up
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        assert!(index < self.len(), "leaf index {} out of range", index);
+
+        let mut index = index;
+        self.levels[..self.levels.len() - 1]
+            .iter()
+            .map(|level| {
+                let sibling = level[index ^ 1];
+                index /= 2;
+                sibling
+            })
+            .collect()
+    }
+
+    /// Path bits `verify_proof_with_path` returns for the leaf at `index`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of range.
+    pub fn path(&self, index: usize) -> u32 {
+        assert!(index < self.len(), "leaf index {} out of range", index);
+
+        let mut path = 0u32;
+        let mut index = index;
+        for (proof_level, level) in self.levels[..self.levels.len() - 1].iter().enumerate() {
+            if level[index] > level[index ^ 1] {
+                path |= 1 << proof_level;
+            }
+            index /= 2;
+        }
+        path
+    }
 }
 
-/// Hash two nodes together (sorted)
+/// Hash two nodes in sorted order (smaller hash first)
+fn hash_sorted(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    if a <= b {
+        hash_pair(a, b)
+    } else {
+        hash_pair(b, a)
+    }
+}
+
+/// Hash two nodes together (sorted): keccak256(0x01 || left || right)
 fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
-    let mut combined = [0u8; 64];
-    combined[..32].copy_from_slice(left);
-    combined[32..].copy_from_slice(right);
-    hash(&combined).to_bytes()
+    let mut combined = [0u8; 65];
+    combined[0] = NODE_PREFIX;
+    combined[1..33].copy_from_slice(left);
+    combined[33..].copy_from_slice(right);
+    keccak::hash(&combined).to_bytes()
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::MAX_PROOF_DEPTH;
+    use std::collections::VecDeque;
+
+    /// Complete binary tree in array form: root at 0, leaves at the end
+    fn build_tree(leaves: &[[u8; 32]]) -> Vec<[u8; 32]> {
+        let n = leaves.len();
+        let mut tree = vec![[0u8; 32]; 2 * n - 1];
+        for (i, leaf) in leaves.iter().enumerate() {
+            tree[2 * n - 2 - i] = *leaf;
+        }
+        for i in (0..n - 1).rev() {
+            tree[i] = hash_sorted(&tree[2 * i + 1], &tree[2 * i + 2]);
+        }
+        tree
+    }
+
+    fn sibling(index: usize) -> usize {
+        if index % 2 == 1 {
+            index + 1
+        } else {
+            index - 1
+        }
+    }
+
+    fn single_proof(tree: &[[u8; 32]], mut index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        while index > 0 {
+            proof.push(tree[sibling(index)]);
+            index = (index - 1) / 2;
+        }
+        proof
+    }
+
+    /// Multiproof for the given tree indices, as OpenZeppelin's `getMultiProof` builds it
+    fn multiproof(
+        tree: &[[u8; 32]],
+        indices: &[usize],
+    ) -> (Vec<[u8; 32]>, Vec<[u8; 32]>, Vec<bool>) {
+        let mut sorted = indices.to_vec();
+        sorted.sort_unstable_by(|a, b| b.cmp(a));
+
+        let mut stack: VecDeque<usize> = sorted.iter().copied().collect();
+        let mut proof = Vec::new();
+        let mut flags = Vec::new();
+        while let Some(&index) = stack.front() {
+            if index == 0 {
+                break;
+            }
+            stack.pop_front();
+            let s = sibling(index);
+            if stack.front() == Some(&s) {
+                flags.push(true);
+                stack.pop_front();
+            } else {
+                flags.push(false);
+                proof.push(tree[s]);
+            }
+            stack.push_back((index - 1) / 2);
+        }
+
+        let leaves = sorted.iter().map(|&i| tree[i]).collect();
+        (leaves, proof, flags)
+    }
 
     #[test]
     fn test_leaf_hash() {
@@ -42,4 +541,388 @@ mod tests {
         let leaf = compute_leaf(&wallet, amount);
         assert_eq!(leaf.len(), 32);
     }
+
+    #[test]
+    fn test_leaf_matches_claim_scheme() {
+        // keccak256(0x00 || "YAP_CLAIM_V1" || wallet || amount_le), as built by off-chain tooling
+        let wallet = Pubkey::new_unique();
+        let mut data = vec![0x00];
+        data.extend_from_slice(b"YAP_CLAIM_V1");
+        data.extend_from_slice(wallet.as_ref());
+        data.extend_from_slice(&42u64.to_le_bytes());
+
+        assert_eq!(compute_leaf(&wallet, 42), keccak::hash(&data).to_bytes());
+    }
+
+    #[test]
+    fn test_salt_follows_domain_in_leaf() {
+        let program_id = Pubkey::new_unique();
+        let salt = deployment_salt(&program_id);
+        assert_eq!(
+            &salt[..],
+            &keccak::hashv(&[b"YAP_SALT_V1", program_id.as_ref()]).to_bytes()[..16]
+        );
+        assert_ne!(salt, deployment_salt(&Pubkey::new_unique()));
+
+        // keccak256(0x00 || "YAP_CLAIM_V1" || salt || wallet || amount_le)
+        let wallet = Pubkey::new_unique();
+        let mut data = vec![0x00];
+        data.extend_from_slice(b"YAP_CLAIM_V1");
+        data.extend_from_slice(&salt);
+        data.extend_from_slice(wallet.as_ref());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(
+            compute_salted_leaf(&salt, &wallet, 42),
+            keccak::hash(&data).to_bytes()
+        );
+
+        // No salt keeps the original scheme
+        assert_eq!(
+            compute_salted_leaf(&[0; DOMAIN_SALT_LEN], &wallet, 42),
+            compute_leaf(&wallet, 42)
+        );
+    }
+
+    #[test]
+    fn test_salted_leaf_vector() {
+        // Pinned for lib/solana/merkle.ts, whose hashLeaf test expects the same bytes
+        let salt: [u8; DOMAIN_SALT_LEN] = std::array::from_fn(|i| i as u8 + 1);
+        let leaf = compute_salted_leaf(&salt, &spl_token::id(), 1_000_000_000);
+        let hex: String = leaf.iter().map(|b| format!("{:02x}", b)).collect();
+        assert_eq!(
+            hex,
+            "3eb0e65585f272a5a1b8a432c561d4c5ca32c78968adbd28288300425e7569df"
+        );
+    }
+
+    #[test]
+    fn test_every_leaf_kind_is_salted() {
+        let wallet = Pubkey::new_unique();
+        let none = [0; DOMAIN_SALT_LEN];
+        let salt = deployment_salt(&Pubkey::new_unique());
+        let leaves = |salt: &[u8; DOMAIN_SALT_LEN]| {
+            [
+                compute_salted_leaf(salt, &wallet, 42),
+                compute_leaf_with_expiry(salt, &wallet, 42, 1_000),
+                compute_bonus_leaf(salt, &wallet, 42),
+                compute_epoch_leaf(salt, 1, &wallet, 42),
+                compute_indexed_leaf(salt, 1, &wallet, 42),
+                compute_category_leaf(salt, 1, &wallet, 42),
+            ]
+        };
+
+        // A deployment's leaves never match another deployment's (or an unsalted one)
+        for (salted, unsalted) in leaves(&salt).iter().zip(leaves(&none)) {
+            assert_ne!(*salted, unsalted);
+        }
+
+        // An all-zero salt leaves the hash out: keccak256(0x00 || domain || category || ..)
+        let mut data = vec![0x00];
+        data.extend_from_slice(b"YAP_CATEGORY_V1");
+        data.push(1);
+        data.extend_from_slice(wallet.as_ref());
+        data.extend_from_slice(&42u64.to_le_bytes());
+        assert_eq!(leaves(&none)[5], keccak::hash(&data).to_bytes());
+    }
+
+    #[test]
+    fn test_small_tree_proofs_verify() {
+        let wallets: Vec<Pubkey> = (0..4).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets
+            .iter()
+            .enumerate()
+            .map(|(i, w)| compute_leaf(w, (i as u64 + 1) * 100))
+            .collect();
+
+        let left = hash_sorted(&leaves[0], &leaves[1]);
+        let right = hash_sorted(&leaves[2], &leaves[3]);
+        let root = hash_sorted(&left, &right);
+
+        assert!(verify_proof(&root, &leaves[0], &[leaves[1], right]));
+        assert!(verify_proof(&root, &leaves[1], &[leaves[0], right]));
+        assert!(verify_proof(&root, &leaves[2], &[leaves[3], left]));
+        assert!(verify_proof(&root, &leaves[3], &[leaves[2], left]));
+
+        // Wrong amount for a valid wallet
+        let forged = compute_leaf(&wallets[0], 1_000_000);
+        assert!(!verify_proof(&root, &forged, &[leaves[1], right]));
+    }
+
+    #[test]
+    fn test_internal_node_is_not_a_valid_leaf() {
+        let leaves: Vec<[u8; 32]> = (0..4)
+            .map(|i| compute_leaf(&Pubkey::new_unique(), i + 1))
+            .collect();
+        let left = hash_sorted(&leaves[0], &leaves[1]);
+        let right = hash_sorted(&leaves[2], &leaves[3]);
+        let root = hash_sorted(&left, &right);
+
+        // The node's own preimage, hashed as leaf data, does not reproduce the node
+        let (a, b) = if leaves[0] <= leaves[1] {
+            (leaves[0], leaves[1])
+        } else {
+            (leaves[1], leaves[0])
+        };
+        let forged = keccak::hashv(&[&[LEAF_PREFIX], &a, &b]).to_bytes();
+        assert_ne!(forged, left);
+        assert!(!verify_proof(&root, &forged, &[right]));
+
+        // Only the node tag reproduces it
+        assert_eq!(keccak::hashv(&[&[NODE_PREFIX], &a, &b]).to_bytes(), left);
+    }
+
+    #[test]
+    fn test_multiproof_matches_single_proofs() {
+        let wallets: Vec<Pubkey> = (0..7).map(|_| Pubkey::new_unique()).collect();
+        let leaves: Vec<[u8; 32]> = wallets
+            .iter()
+            .enumerate()
+            .map(|(i, w)| compute_leaf(w, (i as u64 + 1) * 100))
+            .collect();
+        let tree = build_tree(&leaves);
+        let root = tree[0];
+        let n = leaves.len();
+        let tree_index = |leaf: usize| 2 * n - 2 - leaf;
+
+        // Every non-empty subset: the multiproof verifies exactly when each single proof does
+        for mask in 1u32..(1 << n) {
+            let subset: Vec<usize> = (0..n).filter(|i| mask & (1 << i) != 0).collect();
+            let indices: Vec<usize> = subset.iter().map(|&i| tree_index(i)).collect();
+
+            let singles_ok = indices
+                .iter()
+                .all(|&i| verify_proof(&root, &tree[i], &single_proof(&tree, i)));
+            let (multi_leaves, proof, flags) = multiproof(&tree, &indices);
+            assert!(singles_ok);
+            assert!(verify_multiproof(&root, &multi_leaves, &proof, &flags));
+            assert!(proof.len() <= indices.iter().map(|&i| single_proof(&tree, i).len()).sum());
+
+            // A forged amount fails both ways
+            let forged = compute_leaf(&wallets[subset[0]], 1_000_000);
+            let forged_index = tree_index(subset[0]);
+            assert!(!verify_proof(&root, &forged, &single_proof(&tree, forged_index)));
+            let mut forged_leaves = multi_leaves.clone();
+            let pos = multi_leaves.iter().position(|l| *l == leaves[subset[0]]).unwrap();
+            forged_leaves[pos] = forged;
+            assert!(!verify_multiproof(&root, &forged_leaves, &proof, &flags));
+        }
+    }
+
+    #[test]
+    fn test_merkle_tree_proofs_round_trip() {
+        for n in 1..=17u64 {
+            let leaves: Vec<[u8; 32]> = (0..n)
+                .map(|i| compute_leaf(&Pubkey::new_unique(), (i + 1) * 100))
+                .collect();
+            let tree = MerkleTree::from_leaves(&leaves);
+            assert_eq!(tree.len(), leaves.len());
+
+            for (i, leaf) in leaves.iter().enumerate() {
+                let proof = tree.proof(i);
+                assert_eq!(proof.len(), tree.depth(), "n={} i={}", n, i);
+                assert!(verify_proof(&tree.root(), leaf, &proof), "n={} i={}", n, i);
+
+                // Another leaf's proof doesn't carry this one
+                let other = (i + 1) % leaves.len();
+                if other != i {
+                    assert!(!verify_proof(&tree.root(), leaf, &tree.proof(other)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_max_depth_proof_verifies() {
+        // Walk a leaf up MAX_PROOF_DEPTH levels with arbitrary siblings
+        let leaf = compute_leaf(&Pubkey::new_unique(), 1_000);
+        let proof: Vec<[u8; 32]> = (0..MAX_PROOF_DEPTH as u8).map(|i| [i; 32]).collect();
+        let root = proof.iter().fold(leaf, |node, sibling| {
+            let (left, right) = if node <= *sibling {
+                (node, *sibling)
+            } else {
+                (*sibling, node)
+            };
+            keccak::hashv(&[&[NODE_PREFIX], &left, &right]).to_bytes()
+        });
+
+        assert!(verify_proof(&root, &leaf, &proof));
+        assert!(!verify_proof(&root, &leaf, &proof[..MAX_PROOF_DEPTH - 1]));
+        let mut tampered = proof.clone();
+        tampered[MAX_PROOF_DEPTH - 1][0] ^= 1;
+        assert!(!verify_proof(&root, &leaf, &tampered));
+    }
+
+    #[test]
+    fn test_constant_time_results_unchanged() {
+        let a = compute_leaf(&Pubkey::new_unique(), 1_000);
+        assert!(ct_eq(&a, &a));
+        for byte in 0..32 {
+            for bit in 0..8 {
+                let mut b = a;
+                b[byte] ^= 1 << bit;
+                assert!(!ct_eq(&a, &b));
+            }
+        }
+
+        // Every verifier still agrees with a plain `==` on the computed root
+        let leaves: Vec<[u8; 32]> = (0..7u64)
+            .map(|i| compute_leaf(&Pubkey::new_unique(), i))
+            .collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let root = tree.root();
+        for (i, leaf) in leaves.iter().enumerate() {
+            let proof = tree.proof(i);
+            for candidate in [root, *leaf, [0u8; 32]] {
+                let expected = compute_root(leaf, &proof) == candidate;
+                assert_eq!(verify_proof(&candidate, leaf, &proof), expected);
+                assert_eq!(
+                    verify_proof_with_path(&candidate, leaf, &proof).is_some(),
+                    expected
+                );
+                assert_eq!(
+                    verify_multiproof(&candidate, &[*leaf], &proof, &vec![false; proof.len()]),
+                    expected
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_merkle_tree_matches_manual_hashing() {
+        let leaves: Vec<[u8; 32]> = (0..4u8).map(|i| [i; 32]).collect();
+        let left = hash_sorted(&leaves[0], &leaves[1]);
+        let right = hash_sorted(&leaves[2], &leaves[3]);
+        assert_eq!(MerkleTree::from_leaves(&leaves).root(), hash_sorted(&left, &right));
+        assert_eq!(MerkleTree::from_leaves(&leaves).proof(2), vec![leaves[3], left]);
+
+        // Odd count: the lone third leaf is paired with a zero leaf, not promoted
+        let tree = MerkleTree::from_leaves(&leaves[..3]);
+        let padded = hash_sorted(&leaves[2], &[0u8; 32]);
+        assert_eq!(tree.root(), hash_sorted(&left, &padded));
+        assert_eq!(tree.proof(2), vec![[0u8; 32], left]);
+        assert_eq!(tree.len(), 3);
+
+        // A single leaf gets a zero sibling too; no leaves give the unset root
+        let single = MerkleTree::from_leaves(&leaves[..1]);
+        assert_eq!(single.root(), hash_sorted(&leaves[0], &[0u8; 32]));
+        assert_eq!(single.proof(0), vec![[0u8; 32]]);
+        assert_eq!((single.depth(), tree.depth()), (1, 2));
+        assert!(MerkleTree::from_leaves(&[]).is_empty());
+        assert_eq!(MerkleTree::from_leaves(&[]).root(), [0u8; 32]);
+    }
+
+    #[test]
+    fn test_proof_path_follows_hash_order() {
+        let leaves: Vec<[u8; 32]> = (0..4u8).map(|i| [i; 32]).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let root = tree.root();
+        let left = hash_sorted(&leaves[0], &leaves[1]);
+        let right = hash_sorted(&leaves[2], &leaves[3]);
+        let upper = (left > right) as u32;
+
+        // Leaves are ascending, so at the bottom level hash order matches position
+        for (i, leaf) in leaves.iter().enumerate() {
+            let path = verify_proof_with_path(&root, leaf, &tree.proof(i));
+            assert_eq!(path, Some(tree.path(i)));
+            assert_eq!(path.unwrap() & 1, i as u32 & 1);
+        }
+        assert_eq!(tree.path(0) >> 1, upper);
+        assert_eq!(tree.path(2) >> 1, 1 - upper);
+
+        // Descending leaves: the leftmost leaf sorts right, so the bits are not its index
+        let reversed: Vec<[u8; 32]> = leaves.iter().rev().copied().collect();
+        let tree = MerkleTree::from_leaves(&reversed);
+        assert_eq!(tree.path(0) & 1, 1);
+        assert_eq!(
+            verify_proof_with_path(&tree.root(), &reversed[0], &tree.proof(0)),
+            Some(tree.path(0))
+        );
+
+        // The lone third leaf sits above its zero sibling, so its bottom bit is set
+        let odd = MerkleTree::from_leaves(&leaves[..3]);
+        let padded = hash_sorted(&leaves[2], &[0u8; 32]);
+        assert_eq!(odd.proof(2).len(), 2);
+        assert_eq!(odd.path(2), 1 | ((padded > left) as u32) << 1);
+        assert_eq!(
+            verify_proof_with_path(&odd.root(), &leaves[2], &odd.proof(2)),
+            Some(odd.path(2))
+        );
+    }
+
+    #[test]
+    fn test_proof_path_none_when_invalid() {
+        let leaves: Vec<[u8; 32]> = (0..4u8).map(|i| [i; 32]).collect();
+        let tree = MerkleTree::from_leaves(&leaves);
+        let root = tree.root();
+
+        assert!(verify_proof_with_path(&root, &leaves[0], &tree.proof(1)).is_none());
+        assert!(verify_proof_with_path(&root, &[9; 32], &tree.proof(0)).is_none());
+
+        // Too long for the path bits, even though it hashes to the root
+        let proof: Vec<[u8; 32]> = (0..33u8).map(|i| [i; 32]).collect();
+        let root = compute_root(&leaves[0], &proof);
+        assert!(verify_proof(&root, &leaves[0], &proof));
+        assert_eq!(verify_proof_with_path(&root, &leaves[0], &proof), None);
+    }
+
+    #[test]
+    fn test_multiproof_rejects_malformed_shapes() {
+        let leaves: Vec<[u8; 32]> = (0..4u8).map(|i| [i; 32]).collect();
+        let tree = build_tree(&leaves);
+        let root = tree[0];
+        let (multi_leaves, proof, flags) = multiproof(&tree, &[3, 4]);
+        assert!(verify_multiproof(&root, &multi_leaves, &proof, &flags));
+
+        // No leaves, a flag too many or an unused proof element
+        assert!(!verify_multiproof(&root, &[], &[root], &[]));
+        let mut extra_flag = flags.clone();
+        extra_flag.push(true);
+        assert!(!verify_multiproof(&root, &multi_leaves, &proof, &extra_flag));
+        let mut extra_proof = proof.clone();
+        extra_proof.push([9u8; 32]);
+        assert!(!verify_multiproof(&root, &multi_leaves, &extra_proof, &flags));
+
+        // Single leaf with no proof is the root itself
+        assert!(verify_multiproof(&leaves[0], &leaves[..1], &[], &[]));
+    }
+
+    #[test]
+    fn test_multiproof_at_depth_holds_every_leaf_at_that_depth() {
+        // Seven leaves in array form: tree index 6 sits a level above the other six
+        let leaves: Vec<[u8; 32]> = (0..7u64)
+            .map(|i| compute_leaf(&Pubkey::new_unique(), i + 1))
+            .collect();
+        let tree = build_tree(&leaves);
+        let root = tree[0];
+        let n = leaves.len();
+
+        for mask in 1u32..(1 << n) {
+            let indices: Vec<usize> = (0..n)
+                .filter(|i| mask & (1 << i) != 0)
+                .map(|i| 2 * n - 2 - i)
+                .collect();
+            let (multi_leaves, proof, flags) = multiproof(&tree, &indices);
+            assert!(verify_multiproof(&root, &multi_leaves, &proof, &flags));
+
+            let shallow = indices.contains(&6);
+            let verify_at =
+                |depth| verify_multiproof_at_depth(&root, &multi_leaves, &proof, &flags, depth);
+            assert_eq!(verify_at(3), !shallow, "mask={:b}", mask);
+            assert_eq!(verify_at(2), indices == [6], "mask={:b}", mask);
+            assert!(!verify_at(4));
+        }
+
+        // A padded MerkleTree has every leaf at depth()
+        let padded = MerkleTree::from_leaves(&leaves);
+        let leaf = [leaves[2]];
+        let proof = padded.proof(2);
+        let flags = vec![false; proof.len()];
+        assert!(verify_multiproof_at_depth(
+            &padded.root(),
+            &leaf,
+            &proof,
+            &flags,
+            padded.depth()
+        ));
+    }
 }
@@ -1 +1,7 @@
+pub mod account;
+pub mod burn_rewards;
+pub mod clock;
 pub mod merkle;
+pub mod return_data;
+pub mod token;
+pub mod vesting;
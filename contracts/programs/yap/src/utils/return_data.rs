@@ -0,0 +1,67 @@
+//! Amounts reported to simulating clients via `set_return_data`
+//!
+//! `Claim`, `Distribute` and `TriggerInflation` end by publishing the amount
+//! they moved as a little-endian u64, so a `simulateTransaction` caller can
+//! read it from `returnData` instead of parsing logs. `GetClaimable` and
+//! `SimulateDistribute` publish the amount a claim or distribution would move.
+//!
+//! A rejected claim proof instead publishes two bytes, the proof length and
+//! the committed tree depth, so a wallet can tell a truncated proof from one
+//! built for the wrong tree.
+
+use solana_program::program::set_return_data;
+
+/// Publish `amount` as the instruction's return data
+pub fn set_amount(amount: u64) {
+    set_return_data(&amount.to_le_bytes());
+}
+
+/// Decode return data written by `set_amount`
+pub fn decode_amount(data: &[u8]) -> Option<u64> {
+    data.try_into().ok().map(u64::from_le_bytes)
+}
+
+/// Publish `[proof_len, tree_depth]` for a rejected proof
+///
+/// Lengths past `u8::MAX` saturate; they are far beyond `MAX_PROOF_DEPTH`.
+pub fn set_proof_depth(proof_len: usize, tree_depth: u8) {
+    let proof_len = u8::try_from(proof_len).unwrap_or(u8::MAX);
+    set_return_data(&[proof_len, tree_depth]);
+}
+
+/// Decode return data written by `set_proof_depth` into `(proof_len, tree_depth)`
+pub fn decode_proof_depth(data: &[u8]) -> Option<(u8, u8)> {
+    match data {
+        [proof_len, tree_depth] => Some((*proof_len, *tree_depth)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::capture_return_data;
+    use solana_program::program::get_return_data;
+
+    #[test]
+    fn test_amount_return_data_decodes() {
+        let data = capture_return_data(|| {
+            set_amount(1_234_567_890_123);
+            let (program_id, _) = get_return_data().unwrap();
+            assert_eq!(program_id, crate::id());
+        });
+        assert_eq!(data.len(), 8);
+        assert_eq!(decode_amount(&data), Some(1_234_567_890_123));
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_length() {
+        assert_eq!(decode_amount(&[]), None);
+        assert_eq!(decode_amount(&[1u8; 7]), None);
+        assert_eq!(decode_amount(&[1u8; 9]), None);
+
+        // Amounts and proof diagnostics never decode as each other
+        assert_eq!(decode_proof_depth(&[1u8; 8]), None);
+        assert_eq!(decode_amount(&[1u8; 2]), None);
+    }
+}
@@ -0,0 +1,307 @@
+//! SPL Token / Token-2022 helpers
+//!
+//! The program works with either token program; the one chosen at
+//! `Initialize` is stored in `Config::addresses.token_program`. CPIs are built
+//! with the Token-2022 interface, whose builders accept both program ids and
+//! encode the shared instructions identically.
+
+use solana_program::{
+    account_info::AccountInfo,
+    entrypoint::ProgramResult,
+    instruction::{AccountMeta, Instruction},
+    msg,
+    program_error::ProgramError,
+    program_pack::Pack,
+    pubkey::Pubkey,
+};
+use spl_token_2022_interface::{
+    extension::{BaseState, ExtensionType, StateWithExtensions},
+    state::{Account, Mint},
+};
+
+use crate::{error::YapError, state::ASSOCIATED_TOKEN_PROGRAM_ID};
+
+/// Token-2022 program ID: TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb
+pub const TOKEN_2022_PROGRAM_ID: Pubkey = spl_token_2022_interface::ID;
+
+/// Whether `key` is SPL Token or Token-2022
+pub fn is_supported_token_program(key: &Pubkey) -> bool {
+    *key == spl_token::id() || *key == TOKEN_2022_PROGRAM_ID
+}
+
+/// Verify `token_program` is the program recorded in config
+pub fn validate_token_program(token_program: &AccountInfo, expected: &Pubkey) -> ProgramResult {
+    if token_program.key != expected {
+        msg!(
+            "Token program mismatch: expected {}, got {}",
+            expected,
+            token_program.key
+        );
+        return Err(YapError::InvalidOwner.into());
+    }
+    Ok(())
+}
+
+/// Associated Token program `Create` for `wallet`'s ATA of `mint`, funded by `payer`
+///
+/// Fails if the ATA already exists, so only use it on an empty account.
+pub fn create_associated_token_account(
+    payer: &Pubkey,
+    ata: &Pubkey,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+    token_program: &Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id: ASSOCIATED_TOKEN_PROGRAM_ID,
+        accounts: vec![
+            AccountMeta::new(*payer, true),
+            AccountMeta::new(*ata, false),
+            AccountMeta::new_readonly(*wallet, false),
+            AccountMeta::new_readonly(*mint, false),
+            AccountMeta::new_readonly(solana_system_interface::program::id(), false),
+            AccountMeta::new_readonly(*token_program, false),
+        ],
+        data: vec![0], // AssociatedTokenAccountInstruction::Create
+    }
+}
+
+/// Unpack a token account owned by either token program (extensions ignored)
+pub fn unpack_token_account(data: &[u8]) -> Result<Account, ProgramError> {
+    unpack_base::<Account>(data)
+}
+
+/// Unpack a mint owned by either token program (extensions ignored)
+pub fn unpack_mint(data: &[u8]) -> Result<Mint, ProgramError> {
+    unpack_base::<Mint>(data)
+}
+
+/// Verify the mint can still be minted by `authority` (the config PDA)
+///
+/// An authority rotated out-of-band would otherwise only surface as an opaque
+/// owner error from inside the token program's mint CPI.
+pub fn validate_mint_authority(mint_data: &[u8], authority: &Pubkey) -> ProgramResult {
+    let mint = unpack_mint(mint_data).map_err(|_| {
+        msg!("Mint account could not be unpacked");
+        YapError::InvalidMint
+    })?;
+
+    if mint.mint_authority != Some(*authority).into() {
+        msg!(
+            "Mint authority mismatch: expected {}, got {:?}",
+            authority,
+            mint.mint_authority
+        );
+        return Err(YapError::InvalidMint.into());
+    }
+    Ok(())
+}
+
+fn unpack_base<S: BaseState + Pack>(data: &[u8]) -> Result<S, ProgramError> {
+    let state = StateWithExtensions::<S>::unpack(data).inspect_err(|_| {
+        // A short account points at a layout change, not a bad account
+        if data.len() < S::get_packed_len() {
+            msg!(
+                "Token state holds {} bytes, below the {}-byte base layout",
+                data.len(),
+                S::get_packed_len()
+            );
+        }
+    })?;
+    Ok(state.base)
+}
+
+/// Space for the program's mint; created without extensions under both programs
+pub fn mint_space(token_program: &Pubkey) -> Result<usize, ProgramError> {
+    if *token_program == TOKEN_2022_PROGRAM_ID {
+        ExtensionType::try_calculate_account_len::<Mint>(&[])
+    } else {
+        debug_assert_eq!(Mint::LEN, spl_token::state::Mint::get_packed_len());
+        Ok(Mint::LEN)
+    }
+}
+
+/// Space for a program-owned token account
+///
+/// Token-2022 accounts also carry `ImmutableOwner`, so the vault and
+/// pending_claims owner can never be reassigned.
+pub fn token_account_space(token_program: &Pubkey) -> Result<usize, ProgramError> {
+    if *token_program == TOKEN_2022_PROGRAM_ID {
+        ExtensionType::try_calculate_account_len::<Account>(&[ExtensionType::ImmutableOwner])
+    } else {
+        debug_assert_eq!(Account::LEN, spl_token::state::Account::get_packed_len());
+        Ok(Account::LEN)
+    }
+}
+
+/// Verify an account just created with `space` bytes unpacks as `S`
+///
+/// `mint_space` / `token_account_space` size accounts from the interface
+/// crate's `LEN`s. If a token crate bump ever drifts from the deployed
+/// program's layout, this names the sizes involved instead of leaving the
+/// first later unpack to fail opaquely.
+pub fn check_created_layout<S: BaseState + Pack>(
+    label: &str,
+    data: &[u8],
+    space: usize,
+) -> Result<(), ProgramError> {
+    if data.len() != space || unpack_base::<S>(data).is_err() {
+        msg!(
+            "{} does not unpack: created with {} bytes, holds {}, base layout is {} bytes",
+            label,
+            space,
+            data.len(),
+            S::get_packed_len()
+        );
+        return Err(ProgramError::InvalidAccountData);
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_utils::TestAccount;
+    use spl_token_2022_interface::{
+        extension::{BaseStateWithExtensionsMut, StateWithExtensionsMut},
+        state::AccountState,
+    };
+
+    fn account(mint: Pubkey, owner: Pubkey, amount: u64) -> Account {
+        Account {
+            mint,
+            owner,
+            amount,
+            state: AccountState::Initialized,
+            ..Account::default()
+        }
+    }
+
+    #[test]
+    fn test_supported_token_programs() {
+        assert!(is_supported_token_program(&spl_token::id()));
+        assert!(is_supported_token_program(&TOKEN_2022_PROGRAM_ID));
+        assert!(!is_supported_token_program(&Pubkey::new_unique()));
+        assert!(!is_supported_token_program(&Pubkey::default()));
+    }
+
+    #[test]
+    fn test_account_spaces_per_program() {
+        assert_eq!(mint_space(&spl_token::id()).unwrap(), 82);
+        assert_eq!(token_account_space(&spl_token::id()).unwrap(), 165);
+
+        // Extension-free mint keeps the base size; accounts grow by the
+        // account type byte and the ImmutableOwner TLV header
+        assert_eq!(mint_space(&TOKEN_2022_PROGRAM_ID).unwrap(), 82);
+        assert_eq!(token_account_space(&TOKEN_2022_PROGRAM_ID).unwrap(), 170);
+    }
+
+    #[test]
+    fn test_account_spaces_match_spl_packed_len() {
+        // The interface crate sizes accounts; spl-token is what runs them
+        assert_eq!(
+            mint_space(&spl_token::id()).unwrap(),
+            spl_token::state::Mint::get_packed_len()
+        );
+        assert_eq!(
+            token_account_space(&spl_token::id()).unwrap(),
+            spl_token::state::Account::get_packed_len()
+        );
+    }
+
+    #[test]
+    fn test_created_layout_must_unpack_at_its_space() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account(mint, owner, 0), &mut data).unwrap();
+        check_created_layout::<Account>("vault", &data, Account::LEN).unwrap();
+
+        // Sized for a different layout than the one it was created with
+        assert!(check_created_layout::<Account>("vault", &data, Account::LEN + 1).is_err());
+        // Never initialized by the token program
+        assert!(check_created_layout::<Mint>("mint", &[0u8; Mint::LEN], Mint::LEN).is_err());
+    }
+
+    #[test]
+    fn test_unpack_spl_token_account() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; Account::LEN];
+        Account::pack(account(mint, owner, 42), &mut data).unwrap();
+
+        let unpacked = unpack_token_account(&data).unwrap();
+        assert_eq!(
+            (unpacked.mint, unpacked.owner, unpacked.amount),
+            (mint, owner, 42)
+        );
+    }
+
+    #[test]
+    fn test_unpack_token_2022_account_with_extension() {
+        let (mint, owner) = (Pubkey::new_unique(), Pubkey::new_unique());
+        let mut data = vec![0u8; token_account_space(&TOKEN_2022_PROGRAM_ID).unwrap()];
+        let mut state = StateWithExtensionsMut::<Account>::unpack_uninitialized(&mut data).unwrap();
+        state
+            .init_extension::<spl_token_2022_interface::extension::immutable_owner::ImmutableOwner>(
+                true,
+            )
+            .unwrap();
+        state.base = account(mint, owner, 7);
+        state.pack_base();
+        state.init_account_type().unwrap();
+
+        // Plain `Pack::unpack` rejects the longer account; the helper reads the base
+        assert!(Account::unpack(&data).is_err());
+        let unpacked = unpack_token_account(&data).unwrap();
+        assert_eq!(
+            (unpacked.mint, unpacked.owner, unpacked.amount),
+            (mint, owner, 7)
+        );
+    }
+
+    #[test]
+    fn test_token_program_must_match_config() {
+        let mut token_2022 = TestAccount::new(TOKEN_2022_PROGRAM_ID);
+        assert!(validate_token_program(&token_2022.info(), &TOKEN_2022_PROGRAM_ID).is_ok());
+
+        // Passing SPL Token to a Token-2022 deployment (or vice versa) is rejected
+        let mut spl = TestAccount::new(spl_token::id());
+        let err = validate_token_program(&spl.info(), &TOKEN_2022_PROGRAM_ID).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+        let err = validate_token_program(&token_2022.info(), &spl_token::id()).unwrap_err();
+        assert_eq!(err, YapError::InvalidOwner.into());
+    }
+
+    #[test]
+    fn test_create_associated_token_account_instruction() {
+        let (payer, ata, mint) = (
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+            Pubkey::new_unique(),
+        );
+        let ix =
+            create_associated_token_account(&payer, &ata, &payer, &mint, &TOKEN_2022_PROGRAM_ID);
+
+        assert_eq!(ix.program_id, ASSOCIATED_TOKEN_PROGRAM_ID);
+        assert_eq!(ix.data, vec![0]);
+        let keys: Vec<Pubkey> = ix.accounts.iter().map(|meta| meta.pubkey).collect();
+        assert_eq!(
+            keys,
+            [
+                payer,
+                ata,
+                payer,
+                mint,
+                solana_system_interface::program::id(),
+                TOKEN_2022_PROGRAM_ID
+            ]
+        );
+        assert!(ix.accounts[0].is_signer && ix.accounts[0].is_writable);
+        assert!(ix.accounts[1].is_writable && !ix.accounts[1].is_signer);
+    }
+
+    #[test]
+    fn test_unpack_rejects_garbage() {
+        assert!(unpack_token_account(&[1u8; 10]).is_err());
+        assert!(unpack_mint(&[]).is_err());
+    }
+}
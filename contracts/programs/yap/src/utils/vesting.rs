@@ -0,0 +1,118 @@
+use crate::state::Timing;
+
+/// Linear vesting applied to the current root's leaf amounts
+///
+/// Leaves stay cumulative: the vested share of the proven `amount` is what
+/// the wallet may have claimed by `now`, and `claimed_amount` is subtracted
+/// from that. The default (all zero) schedule vests everything immediately.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct VestingSchedule {
+    /// Linear vesting starts here
+    pub start_ts: i64,
+    /// Nothing is vested before this; 0 for no cliff
+    pub cliff_ts: i64,
+    /// Seconds from `start_ts` to fully vested; 0 unlocks everything at the cliff
+    pub duration: i64,
+}
+
+impl VestingSchedule {
+    /// The schedule stored for the current root
+    pub fn from_timing(timing: &Timing) -> Self {
+        Self {
+            start_ts: timing.vesting_start_ts,
+            cliff_ts: timing.vesting_cliff_ts,
+            duration: timing.vesting_duration,
+        }
+    }
+
+    /// Store as the schedule of the current root
+    pub fn apply(&self, timing: &mut Timing) {
+        timing.vesting_start_ts = self.start_ts;
+        timing.vesting_cliff_ts = self.cliff_ts;
+        timing.vesting_duration = self.duration;
+    }
+
+    /// Whether this schedule locks anything at all
+    pub fn is_active(&self) -> bool {
+        self.cliff_ts != 0 || self.duration != 0
+    }
+
+    /// The part of `amount` vested at `now`
+    ///
+    /// Zero before the cliff; at the cliff everything accrued since `start_ts`
+    /// unlocks at once, then the rest follows linearly.
+    pub fn vested_amount(&self, amount: u64, now: i64) -> u64 {
+        if !self.is_active() {
+            return amount;
+        }
+        if now < self.cliff_ts {
+            return 0;
+        }
+        let elapsed = now.saturating_sub(self.start_ts);
+        if self.duration == 0 || elapsed >= self.duration {
+            return amount;
+        }
+        if elapsed <= 0 {
+            return 0;
+        }
+
+        // elapsed < duration, so the result is below `amount` and fits in u64
+        (amount as u128 * elapsed as u128 / self.duration as u128) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: i64 = 24 * 60 * 60;
+
+    #[test]
+    fn test_vested_amount_across_schedule() {
+        // 30-day linear vest from day 0 with a 10-day cliff
+        let schedule = VestingSchedule {
+            start_ts: 0,
+            cliff_ts: 10 * DAY,
+            duration: 30 * DAY,
+        };
+
+        // Pre-cliff: nothing, even though time has accrued
+        assert_eq!(schedule.vested_amount(3_000, 0), 0);
+        assert_eq!(schedule.vested_amount(3_000, 10 * DAY - 1), 0);
+
+        // At the cliff the first 10 days unlock at once, then linearly
+        assert_eq!(schedule.vested_amount(3_000, 10 * DAY), 1_000);
+        assert_eq!(schedule.vested_amount(3_000, 15 * DAY), 1_500);
+
+        // Post-vest: everything
+        assert_eq!(schedule.vested_amount(3_000, 30 * DAY), 3_000);
+        assert_eq!(schedule.vested_amount(3_000, i64::MAX), 3_000);
+    }
+
+    #[test]
+    fn test_vesting_edge_cases() {
+        // No schedule vests immediately
+        let none = VestingSchedule::default();
+        assert!(!none.is_active());
+        assert_eq!(none.vested_amount(u64::MAX, i64::MIN), u64::MAX);
+
+        // Cliff only: all or nothing
+        let cliff = VestingSchedule {
+            cliff_ts: 5 * DAY,
+            ..VestingSchedule::default()
+        };
+        assert!(cliff.is_active());
+        assert_eq!(cliff.vested_amount(700, 5 * DAY - 1), 0);
+        assert_eq!(cliff.vested_amount(700, 5 * DAY), 700);
+
+        // No cliff: linear from the start, no overflow on large amounts
+        let linear = VestingSchedule {
+            start_ts: 100,
+            cliff_ts: 0,
+            duration: 4,
+        };
+        assert_eq!(linear.vested_amount(u64::MAX, 100), 0);
+        assert_eq!(linear.vested_amount(u64::MAX, 103), u64::MAX / 4 * 3 + 2);
+        assert_eq!(linear.vested_amount(u64::MAX, 104), u64::MAX);
+    }
+}
@@ -55,9 +55,16 @@ function getMetadataPda(mint: PublicKey): [PublicKey, number] {
 }
 
 // Build initialize instruction data
-// Layout: [discriminator(1)] [merkle_updater(32)] [inflation_rate_bps(2)]
-function buildInitializeData(merkleUpdater: PublicKey, inflationRateBps: number): Buffer {
-  const data = Buffer.alloc(35);
+// Layout: [discriminator(1)] [merkle_updater(32)] [inflation_rate_bps(2)] [max_supply(8)]
+//         [min_distribution_interval(8)] [rate_period_secs(8)]
+function buildInitializeData(
+  merkleUpdater: PublicKey,
+  inflationRateBps: number,
+  maxSupply: bigint,
+  minDistributionInterval: bigint,
+  ratePeriodSecs: bigint
+): Buffer {
+  const data = Buffer.alloc(59);
 
   // Instruction discriminator (0 = Initialize)
   data.writeUInt8(0, 0);
@@ -68,6 +75,15 @@ function buildInitializeData(merkleUpdater: PublicKey, inflationRateBps: number)
   // Inflation rate in basis points (little-endian u16)
   data.writeUInt16LE(inflationRateBps, 33);
 
+  // Supply cap in base units (little-endian u64)
+  data.writeBigUInt64LE(maxSupply, 35);
+
+  // Minimum seconds between distributions (little-endian i64)
+  data.writeBigInt64LE(minDistributionInterval, 43);
+
+  // Period the vault is distributed and the inflation rate accrues over (little-endian i64)
+  data.writeBigInt64LE(ratePeriodSecs, 51);
+
   return data;
 }
 
@@ -120,14 +136,29 @@ async function main() {
   // Initialize with:
   // - merkle_updater = admin (for testing, update in production)
   // - inflation_rate_bps = 1000 (10% annual)
+  // - max_supply = u64::MAX (no cap; set a real ceiling in production)
+  // - min_distribution_interval = 0 (no limit; set e.g. 1 day in production)
+  // - rate_period_secs = 31_536_000 (one year)
   const merkleUpdater = admin.publicKey;
   const inflationRateBps = 1000; // 10%
+  const maxSupply = BigInt('18446744073709551615');
+  const minDistributionInterval = BigInt(0);
+  const ratePeriodSecs = BigInt(31_536_000);
 
   console.log('\nInitializing with:');
   console.log('  Merkle Updater:', merkleUpdater.toBase58());
   console.log('  Inflation Rate:', inflationRateBps / 100, '%');
-
-  const data = buildInitializeData(merkleUpdater, inflationRateBps);
+  console.log('  Max Supply:', maxSupply.toString());
+  console.log('  Min Distribution Interval:', minDistributionInterval.toString(), 's');
+  console.log('  Rate Period:', ratePeriodSecs.toString(), 's');
+
+  const data = buildInitializeData(
+    merkleUpdater,
+    inflationRateBps,
+    maxSupply,
+    minDistributionInterval,
+    ratePeriodSecs
+  );
 
   const instruction = new TransactionInstruction({
     programId: PROGRAM_ID,
@@ -12,6 +12,7 @@ import {
   LAMPORTS_PER_SOL,
 } from '@solana/web3.js';
 import { TOKEN_PROGRAM_ID } from '@solana/spl-token';
+import { TransactionMetadata } from 'litesvm';
 import * as borsh from 'borsh';
 
 import {
@@ -27,6 +28,11 @@ import {
   claimSchema,
   computeLeaf,
   buildMerkleTree,
+  sortAndHash,
+  createAta,
+  claim,
+  writeProofBuffer,
+  buildClaimWithProofBufferIx,
   findUserClaimPda,
   TestEnv,
   DECIMALS,
@@ -64,12 +70,12 @@ describe('claim', () => {
     merkleRoot: Buffer;
     proof: Buffer[];
   } {
-    const leaf = computeLeaf(userPubkey, amount);
+    const leaf = computeLeaf(userPubkey, amount, env.domainSalt);
     const tree = buildMerkleTree([leaf]);
     const proof = tree.getProof(0);
 
     // Distribute with this merkle root
-    const distributeResult = distribute(env, amount, tree.root);
+    const distributeResult = distribute(env, amount, tree.root, tree.depth);
     expect(isSuccess(distributeResult)).toBe(true);
 
     return { merkleRoot: tree.root, proof };
@@ -109,9 +115,9 @@ describe('claim', () => {
     const claimAmount = BigInt(500) * BigInt(10 ** DECIMALS);
 
     // First set up a valid distribution
-    const leaf = computeLeaf(env.user.publicKey, claimAmount);
+    const leaf = computeLeaf(env.user.publicKey, claimAmount, env.domainSalt);
     const tree = buildMerkleTree([leaf]);
-    const distributeResult = distribute(env, claimAmount, tree.root);
+    const distributeResult = distribute(env, claimAmount, tree.root, tree.depth);
     expect(isSuccess(distributeResult)).toBe(true);
 
     // Now try to claim with fake proof
@@ -136,12 +142,12 @@ describe('claim', () => {
     const wrongAmount = BigInt(2000) * BigInt(10 ** DECIMALS);
 
     // Build tree with correct amount
-    const leaf = computeLeaf(env.user.publicKey, correctAmount);
+    const leaf = computeLeaf(env.user.publicKey, correctAmount, env.domainSalt);
     const tree = buildMerkleTree([leaf]);
     const proof = tree.getProof(0);
 
     // Distribute with correct amount tree
-    const distributeResult = distribute(env, correctAmount, tree.root);
+    const distributeResult = distribute(env, correctAmount, tree.root, tree.depth);
     expect(isSuccess(distributeResult)).toBe(true);
 
     // Try to claim wrong amount (proof won't match)
@@ -163,11 +169,11 @@ describe('claim', () => {
     const claimAmount = BigInt(100) * BigInt(10 ** DECIMALS);
     const wrongUserClaimPda = Keypair.generate().publicKey;
 
-    const leaf = computeLeaf(env.user.publicKey, claimAmount);
+    const leaf = computeLeaf(env.user.publicKey, claimAmount, env.domainSalt);
     const tree = buildMerkleTree([leaf]);
     const proof = tree.getProof(0);
 
-    const distributeResult = distribute(env, claimAmount, tree.root);
+    const distributeResult = distribute(env, claimAmount, tree.root, tree.depth);
     expect(isSuccess(distributeResult)).toBe(true);
 
     const userAta = getOrCreateAta(env.user.publicKey, env.mintPda);
@@ -184,7 +190,7 @@ describe('claim', () => {
         { pubkey: env.user.publicKey, isSigner: true, isWritable: true },
         { pubkey: userAta, isSigner: false, isWritable: true },
         { pubkey: wrongUserClaimPda, isSigner: false, isWritable: true },
-        { pubkey: env.configPda, isSigner: false, isWritable: false },
+        { pubkey: env.configPda, isSigner: false, isWritable: true },
         { pubkey: env.pendingClaimsPda, isSigner: false, isWritable: true },
         { pubkey: env.mintPda, isSigner: false, isWritable: false },
         { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
@@ -217,13 +223,13 @@ describe('claim', () => {
     env.svm.airdrop(user2.publicKey, BigInt(LAMPORTS_PER_SOL));
 
     // Build tree with both users
-    const leaf1 = computeLeaf(env.user.publicKey, amount1);
-    const leaf2 = computeLeaf(user2.publicKey, amount2);
+    const leaf1 = computeLeaf(env.user.publicKey, amount1, env.domainSalt);
+    const leaf2 = computeLeaf(user2.publicKey, amount2, env.domainSalt);
     const tree = buildMerkleTree([leaf1, leaf2]);
 
     // Distribute total amount
     const totalAmount = amount1 + amount2;
-    const distributeResult = distribute(env, totalAmount, tree.root);
+    const distributeResult = distribute(env, totalAmount, tree.root, tree.depth);
     expect(isSuccess(distributeResult)).toBe(true);
 
     // Get proofs for both users
@@ -257,19 +263,56 @@ describe('claim', () => {
     const amount = BigInt(500) * BigInt(10 ** DECIMALS);
 
     // Single leaf tree
-    const leaf = computeLeaf(env.user.publicKey, amount);
+    const leaf = computeLeaf(env.user.publicKey, amount, env.domainSalt);
     const tree = buildMerkleTree([leaf]);
 
-    // Proof should be empty for single leaf
+    // A single leaf is paired with a zero leaf: one node, at depth 1
     const proof = tree.getProof(0);
-    expect(proof.length).toBe(0);
-
-    // Root should equal the leaf
-    expect(tree.root).toEqual(leaf);
+    expect(proof).toEqual([Buffer.alloc(32)]);
+    expect(tree.depth).toBe(1);
+    expect(tree.root).not.toEqual(leaf);
 
     console.log('Single leaf merkle tree: OK');
   });
 
+  it('claims the odd leaf of a padded tree at the committed depth', () => {
+    const amount = BigInt(100) * BigInt(10 ** DECIMALS);
+    const users = [env.user, Keypair.generate(), Keypair.generate()];
+    for (const u of users.slice(1)) {
+      env.svm.airdrop(u.publicKey, BigInt(LAMPORTS_PER_SOL));
+    }
+    const leaves = users.map((u) => computeLeaf(u.publicKey, amount, env.domainSalt));
+
+    // A builder that promotes the third leaf lets it reach the root one level early
+    const pair = sortAndHash(leaves[0], leaves[1]);
+    const promotedRoot = sortAndHash(pair, leaves[2]);
+    expect(isSuccess(distribute(env, amount * 3n, promotedRoot, 2))).toBe(true);
+
+    const third = users[2];
+    const { ata } = createAta(env, third, third.publicKey);
+    const short = claim(env, third, ata, amount, [pair]);
+    expect(isSuccess(short)).toBe(false);
+    expect(getLogs(short).some((l) => l.includes('tree depth 2'))).toBe(true);
+
+    // The padded tree gives it a zero sibling and a full-length proof
+    warpTime(env, 86400);
+    const tree = buildMerkleTree(leaves);
+    expect(tree.depth).toBe(2);
+    expect(isSuccess(distribute(env, 0n, tree.root, tree.depth))).toBe(true);
+
+    const proof = tree.getProof(2);
+    expect(proof).toHaveLength(2);
+    expect(proof[0]).toEqual(Buffer.alloc(32));
+    const result = claim(env, third, ata, amount, proof);
+    if (!isSuccess(result)) {
+      console.log('Claim failed:', getLogs(result));
+    }
+    expect(isSuccess(result)).toBe(true);
+    expect(getTokenBalance(env, ata)).toBe(amount);
+
+    console.log('Padded odd leaf claimed at depth 2: OK');
+  });
+
   it('merkle proof verification handles larger trees', () => {
     // Create tree with 8 users
     const users: { pubkey: PublicKey; amount: bigint }[] = [];
@@ -281,7 +324,7 @@ describe('claim', () => {
       });
     }
 
-    const leaves = users.map((u) => computeLeaf(u.pubkey, u.amount));
+    const leaves = users.map((u) => computeLeaf(u.pubkey, u.amount, env.domainSalt));
     const tree = buildMerkleTree(leaves);
 
     // Verify all proofs work
@@ -304,7 +347,8 @@ describe('claim', () => {
     // - Signatures ~64 bytes
     // - Remaining for proof: ~830 bytes / 32 = ~25 elements max
     //
-    // So MAX_PROOF_DEPTH=32 is unreachable in practice, but provides:
+    // So MAX_PROOF_DEPTH=32 is unreachable inline (a proof buffer stages it,
+    // see below), but provides:
     // 1. Explicit documentation of the limit
     // 2. Clear error message if somehow bypassed
     // 3. Defense in depth
@@ -315,4 +359,42 @@ describe('claim', () => {
     console.log('Transaction size limit: ~25 elements (inherent Solana protection)');
     expect(true).toBe(true);
   });
+
+  it('claims with a MAX_PROOF_DEPTH proof from the proof buffer within a CU bound', () => {
+    // A depth-32 proof plus the claim accounts doesn't fit in one transaction,
+    // so it is staged with WriteProofBuffer. The claim's CU cost pins the
+    // verifier: it hashes each level through a fixed stack buffer and
+    // allocates nothing per level, so it needs no rewrite for deep proofs.
+    const MAX_PROOF_DEPTH = 32;
+    const MAX_DEPTH_CLAIM_CU = 100_000;
+
+    const amount = BigInt(100) * BigInt(10 ** DECIMALS);
+    const leaf = computeLeaf(env.user.publicKey, amount, env.domainSalt);
+    const proof = Array.from({ length: MAX_PROOF_DEPTH }, (_, i) => Buffer.alloc(32, i + 1));
+    const root = proof.reduce((node, sibling) => sortAndHash(node, sibling), leaf);
+    expect(isSuccess(distribute(env, amount, root, MAX_PROOF_DEPTH))).toBe(true);
+
+    // Two writes of 16 nodes each
+    const half = MAX_PROOF_DEPTH / 2;
+    expect(isSuccess(writeProofBuffer(env, env.user, 0, proof.slice(0, half)))).toBe(true);
+    expect(isSuccess(writeProofBuffer(env, env.user, half, proof.slice(half)))).toBe(true);
+
+    const { ata } = createAta(env, env.user, env.user.publicKey);
+    const tx = new Transaction();
+    tx.recentBlockhash = env.svm.latestBlockhash();
+    tx.add(buildClaimWithProofBufferIx(env, env.user, ata, amount));
+    tx.sign(env.user);
+    const result = env.svm.sendTransaction(tx);
+    if (!isSuccess(result)) {
+      console.log('ClaimWithProofBuffer failed:', getLogs(result));
+    }
+    expect(isSuccess(result)).toBe(true);
+    expect(getTokenBalance(env, ata)).toBe(amount);
+
+    const computeUnits = (result as TransactionMetadata).computeUnitsConsumed();
+    console.log(`Depth-${MAX_PROOF_DEPTH} claim used ${computeUnits} CU`);
+    expect(computeUnits).toBeLessThan(BigInt(MAX_DEPTH_CLAIM_CU));
+
+    console.log('MAX_PROOF_DEPTH claim via proof buffer: OK');
+  });
 });
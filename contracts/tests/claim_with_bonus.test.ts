@@ -0,0 +1,117 @@
+/**
+ * ClaimWithBonus instruction tests using LiteSVM
+ * Tests bonus proofs against the bonus root and the bonus reserve accounting
+ */
+import { describe, it, expect, beforeEach } from 'bun:test';
+import { Keypair, PublicKey, Transaction, LAMPORTS_PER_SOL } from '@solana/web3.js';
+
+import {
+  createTestEnv,
+  initializeProgram,
+  distribute,
+  setBonusRoot,
+  warpTime,
+  getTokenBalance,
+  getBonusReserved,
+  isSuccess,
+  getLogs,
+  buildClaimWithBonusIx,
+  computeLeaf,
+  computeBonusLeaf,
+  buildMerkleTree,
+  createAta,
+  TestEnv,
+  DECIMALS,
+} from './helpers/litesvm-setup';
+
+const TOKEN = BigInt(10 ** DECIMALS);
+
+describe('claim_with_bonus', () => {
+  let env: TestEnv;
+  let other: Keypair;
+  let ata: PublicKey;
+
+  const amount = 1_000n * TOKEN;
+  const bonusAmount = 50n * TOKEN;
+  const funding = 125n * TOKEN; // covers both users' bonuses
+
+  let proof: Buffer[];
+  let bonusProof: Buffer[];
+
+  beforeEach(() => {
+    env = createTestEnv();
+    expect(isSuccess(initializeProgram(env))).toBe(true);
+    warpTime(env, 86400 * 7);
+
+    other = Keypair.generate();
+    env.svm.airdrop(other.publicKey, BigInt(LAMPORTS_PER_SOL));
+    ({ ata } = createAta(env, env.user, env.user.publicKey));
+
+    const tree = buildMerkleTree([
+      computeLeaf(env.user.publicKey, amount, env.domainSalt),
+      computeLeaf(other.publicKey, 2_000n * TOKEN, env.domainSalt),
+    ]);
+    expect(isSuccess(distribute(env, 3_000n * TOKEN, tree.root, tree.depth))).toBe(true);
+    proof = tree.getProof(0);
+
+    // Bonus funding is held to the distribution interval like a round
+    warpTime(env, 86400);
+    const bonusTree = buildMerkleTree([
+      computeBonusLeaf(env.user.publicKey, bonusAmount, env.domainSalt),
+      computeBonusLeaf(other.publicKey, 75n * TOKEN, env.domainSalt),
+    ]);
+    const result = setBonusRoot(env, bonusTree.root, funding, bonusTree.depth);
+    if (!isSuccess(result)) {
+      console.log('SetBonusRoot failed:', getLogs(result));
+    }
+    expect(isSuccess(result)).toBe(true);
+    expect(getBonusReserved(env)).toBe(funding);
+    bonusProof = bonusTree.getProof(0);
+  });
+
+  function claimWithBonus(bonus: bigint, bonusProofNodes: Buffer[]) {
+    const ix = buildClaimWithBonusIx(env, env.user, ata, amount, proof, bonus, bonusProofNodes);
+    const tx = new Transaction();
+    tx.recentBlockhash = env.svm.latestBlockhash();
+    tx.add(ix);
+    tx.sign(env.user);
+    return env.svm.sendTransaction(tx);
+  }
+
+  it('pays the bonus on top of the claim and draws down the reserve', () => {
+    const result = claimWithBonus(bonusAmount, bonusProof);
+    if (!isSuccess(result)) {
+      console.log('ClaimWithBonus failed:', getLogs(result));
+    }
+    expect(isSuccess(result)).toBe(true);
+
+    expect(getTokenBalance(env, ata)).toBe(amount + bonusAmount);
+    expect(getBonusReserved(env)).toBe(funding - bonusAmount);
+
+    // The bonus is cumulative: claiming it again pays nothing more
+    env.svm.expireBlockhash();
+    expect(isSuccess(claimWithBonus(bonusAmount, bonusProof))).toBe(false);
+    expect(getTokenBalance(env, ata)).toBe(amount + bonusAmount);
+    expect(getBonusReserved(env)).toBe(funding - bonusAmount);
+
+    console.log('Bonus claimed and reserve drawn down: OK');
+  });
+
+  it('rejects a forged bonus proof', () => {
+    // An inflated bonus amount doesn't hash to the user's leaf
+    const inflated = claimWithBonus(bonusAmount * 2n, bonusProof);
+    expect(isSuccess(inflated)).toBe(false);
+    expect(getLogs(inflated).some((l) => l.includes('Invalid bonus merkle proof'))).toBe(true);
+
+    // Nor does a made-up sibling reach the bonus root
+    env.svm.expireBlockhash();
+    const forged = claimWithBonus(bonusAmount, [Buffer.alloc(32, 0xaa)]);
+    expect(isSuccess(forged)).toBe(false);
+
+    // Neither paid out anything, main claim included
+    expect(getTokenBalance(env, ata)).toBe(0n);
+    expect(getBonusReserved(env)).toBe(funding);
+
+    console.log('Forged bonus proof rejected: OK');
+  });
+});
@@ -0,0 +1,84 @@
+/**
+ * Clock regression tests using LiteSVM
+ * Tests that stored timestamps ahead of the clock fail instead of accruing
+ *
+ * Needs the test-time build (`cargo build-sbf --features test-time`) for
+ * SetLastTimestamps.
+ */
+import { describe, it, expect, beforeEach } from 'bun:test';
+import { Transaction } from '@solana/web3.js';
+
+import {
+  createTestEnv,
+  initializeProgram,
+  distribute,
+  setLastTimestamps,
+  warpTime,
+  getConfig,
+  isSuccess,
+  getLogs,
+  buildTriggerInflationIx,
+  computeLeaf,
+  buildMerkleTree,
+  toBuffer,
+  TestEnv,
+  DECIMALS,
+} from './helpers/litesvm-setup';
+
+describe('clock', () => {
+  let env: TestEnv;
+  let ahead: bigint;
+
+  beforeEach(() => {
+    env = createTestEnv();
+    expect(isSuccess(initializeProgram(env))).toBe(true);
+    warpTime(env, 86400 * 7);
+
+    // Both timestamps a day past the clock, as after a validator clock rollback
+    ahead = env.svm.getClock().unixTimestamp + 86400n;
+    const result = setLastTimestamps(env, ahead, ahead);
+    if (!isSuccess(result)) {
+      console.log('SetLastTimestamps failed (test-time build?):', getLogs(result));
+    }
+    expect(isSuccess(result)).toBe(true);
+
+    const config = getConfig(env);
+    expect(BigInt(config.last_inflation_ts)).toBe(ahead);
+    expect(BigInt(config.last_distribution_ts)).toBe(ahead);
+    env.svm.expireBlockhash();
+  });
+
+  function configBytes(): Buffer {
+    return Buffer.from(toBuffer(env.svm.getAccount(env.configPda)!.data));
+  }
+
+  function expectClockWentBackwards(result: ReturnType<typeof distribute>) {
+    expect(isSuccess(result)).toBe(false);
+    const logs = getLogs(result);
+    expect(logs.some((l) => l.includes(`is before last recorded ${ahead}`))).toBe(true);
+  }
+
+  it('Distribute fails with ClockWentBackwards and leaves config unchanged', () => {
+    const amount = BigInt(100) * BigInt(10 ** DECIMALS);
+    const tree = buildMerkleTree([computeLeaf(env.user.publicKey, amount, env.domainSalt)]);
+    const before = configBytes();
+
+    expectClockWentBackwards(distribute(env, amount, tree.root, tree.depth));
+    expect(configBytes().equals(before)).toBe(true);
+
+    console.log('Distribute with last_distribution_ts ahead of clock rejected: OK');
+  });
+
+  it('TriggerInflation fails with ClockWentBackwards and leaves config unchanged', () => {
+    const before = configBytes();
+
+    const tx = new Transaction();
+    tx.recentBlockhash = env.svm.latestBlockhash();
+    tx.add(buildTriggerInflationIx(env));
+    tx.sign(env.admin);
+    expectClockWentBackwards(env.svm.sendTransaction(tx));
+    expect(configBytes().equals(before)).toBe(true);
+
+    console.log('TriggerInflation with last_inflation_ts ahead of clock rejected: OK');
+  });
+});
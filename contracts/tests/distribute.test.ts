@@ -134,6 +134,7 @@ describe('distribute', () => {
       instruction: 2,
       amount: 0n,
       merkle_root: Array.from(merkleRoot),
+      tree_depth: 1,
     });
 
     const ix = new TransactionInstruction({
@@ -230,6 +231,7 @@ describe('distribute', () => {
       instruction: 2,
       amount: 0n,
       merkle_root: Array.from(merkleRoot),
+      tree_depth: 1,
     });
 
     const ix = new TransactionInstruction({
@@ -26,6 +26,7 @@ import { keccak256 as keccak } from 'js-sha3';
 
 export const DECIMALS = 9;
 export const INITIAL_SUPPLY = BigInt(1_000_000_000) * BigInt(10 ** DECIMALS);
+export const U64_MAX = BigInt('18446744073709551615'); // max_supply "no cap"
 export const SECONDS_PER_YEAR = 31_536_000;
 
 // Metaplex Token Metadata Program ID
@@ -37,10 +38,20 @@ export const MINT_SEED = Buffer.from('mint');
 export const VAULT_SEED = Buffer.from('vault');
 export const PENDING_CLAIMS_SEED = Buffer.from('pending_claims');
 export const USER_CLAIM_SEED = Buffer.from('user_claim');
+export const PROOF_BUFFER_SEED = Buffer.from('proof_buffer');
 export const METADATA_SEED = Buffer.from('metadata');
 
 // Merkle tree domain separator
 export const LEAF_DOMAIN = Buffer.from('YAP_CLAIM_V1');
+export const LEAF_PREFIX = Buffer.from([0x00]);
+export const NODE_PREFIX = Buffer.from([0x01]);
+// Domain separator for the per-deployment leaf salt
+export const SALT_DOMAIN = Buffer.from('YAP_SALT_V1');
+// Bonus tree domain separator
+export const BONUS_LEAF_DOMAIN = Buffer.from('YAP_BONUS_V1');
+
+// Config byte offsets past the prefix `configSchema` decodes
+export const CONFIG_BONUS_RESERVED_OFFSET = 1545;
 
 // ============== Schemas ==============
 
@@ -49,6 +60,9 @@ export const initializeSchema = {
     instruction: 'u8',
     merkle_updater: { array: { type: 'u8', len: 32 } },
     inflation_rate_bps: 'u16',
+    max_supply: 'u64',
+    min_distribution_interval: 'i64',
+    rate_period_secs: 'i64',
   },
 };
 
@@ -57,6 +71,7 @@ export const distributeSchema = {
     instruction: 'u8',
     amount: 'u64',
     merkle_root: { array: { type: 'u8', len: 32 } },
+    tree_depth: 'u8',
   },
 };
 
@@ -138,42 +153,76 @@ export function keccak256(data: Buffer): Buffer {
   return Buffer.from(keccak.arrayBuffer(data));
 }
 
-export function computeLeaf(wallet: PublicKey, amount: bigint): Buffer {
+/**
+ * Leaf salt Initialize stores for a deployment: first 16 bytes of
+ * keccak256(SALT_DOMAIN || programId)
+ */
+export function deploymentSalt(programId: PublicKey): Buffer {
+  return keccak256(Buffer.concat([SALT_DOMAIN, programId.toBuffer()])).subarray(0, 16);
+}
+
+export function computeLeaf(
+  wallet: PublicKey,
+  amount: bigint,
+  salt: Buffer = Buffer.alloc(0)
+): Buffer {
   const data = Buffer.concat([
+    LEAF_PREFIX,
     LEAF_DOMAIN,
+    salt,
     wallet.toBuffer(),
     Buffer.from(new BigUint64Array([amount]).buffer),
   ]);
   return keccak256(data);
 }
 
+export function computeBonusLeaf(
+  wallet: PublicKey,
+  bonusAmount: bigint,
+  salt: Buffer = Buffer.alloc(0)
+): Buffer {
+  const data = Buffer.concat([
+    LEAF_PREFIX,
+    BONUS_LEAF_DOMAIN,
+    salt,
+    wallet.toBuffer(),
+    Buffer.from(new BigUint64Array([bonusAmount]).buffer),
+  ]);
+  return keccak256(data);
+}
+
 export function sortAndHash(a: Buffer, b: Buffer): Buffer {
   if (a.compare(b) <= 0) {
-    return keccak256(Buffer.concat([a, b]));
+    return keccak256(Buffer.concat([NODE_PREFIX, a, b]));
   } else {
-    return keccak256(Buffer.concat([b, a]));
+    return keccak256(Buffer.concat([NODE_PREFIX, b, a]));
   }
 }
 
 export interface MerkleTree {
   root: Buffer;
+  // Proof length of every leaf, the tree_depth to distribute with
+  depth: number;
   getProof: (index: number) => Buffer[];
 }
 
 export function buildMerkleTree(leaves: Buffer[]): MerkleTree {
   if (leaves.length === 0) throw new Error('No leaves');
 
-  const levels: Buffer[][] = [leaves];
-  let currentLevel = leaves;
+  // Pad with zero leaves up to a power of two (at least 2), like the
+  // contract's MerkleTree, so every proof is exactly `depth` long
+  const padded = [...leaves];
+  while (padded.length < 2 || (padded.length & (padded.length - 1)) !== 0) {
+    padded.push(Buffer.alloc(32));
+  }
+
+  const levels: Buffer[][] = [padded];
+  let currentLevel = padded;
 
   while (currentLevel.length > 1) {
     const nextLevel: Buffer[] = [];
     for (let i = 0; i < currentLevel.length; i += 2) {
-      if (i + 1 < currentLevel.length) {
-        nextLevel.push(sortAndHash(currentLevel[i], currentLevel[i + 1]));
-      } else {
-        nextLevel.push(currentLevel[i]);
-      }
+      nextLevel.push(sortAndHash(currentLevel[i], currentLevel[i + 1]));
     }
     levels.push(nextLevel);
     currentLevel = nextLevel;
@@ -181,18 +230,13 @@ export function buildMerkleTree(leaves: Buffer[]): MerkleTree {
 
   return {
     root: currentLevel[0],
+    depth: levels.length - 1,
     getProof(index: number): Buffer[] {
       const proof: Buffer[] = [];
       let idx = index;
 
       for (let level = 0; level < levels.length - 1; level++) {
-        const levelNodes = levels[level];
-        const siblingIdx = idx % 2 === 1 ? idx - 1 : idx + 1;
-
-        if (siblingIdx < levelNodes.length) {
-          proof.push(levelNodes[siblingIdx]);
-        }
-
+        proof.push(levels[level][idx ^ 1]);
         idx = Math.floor(idx / 2);
       }
 
@@ -215,6 +259,8 @@ export interface TestEnv {
   readonly vaultPda: PublicKey;
   readonly pendingClaimsPda: PublicKey;
   readonly metadataPda: PublicKey;
+  // Salt folded into claim leaves by a freshly initialized config
+  readonly domainSalt: Buffer;
 }
 
 /**
@@ -266,6 +312,7 @@ export function createTestEnv(): TestEnv {
     vaultPda,
     pendingClaimsPda,
     metadataPda,
+    domainSalt: deploymentSalt(programId),
   };
 }
 
@@ -276,6 +323,10 @@ export function findUserClaimPda(programId: PublicKey, user: PublicKey): [Public
   return PublicKey.findProgramAddressSync([USER_CLAIM_SEED, user.toBuffer()], programId);
 }
 
+export function findProofBufferPda(programId: PublicKey, user: PublicKey): [PublicKey, number] {
+  return PublicKey.findProgramAddressSync([PROOF_BUFFER_SEED, user.toBuffer()], programId);
+}
+
 // ============== Instruction Builders ==============
 
 /**
@@ -286,12 +337,18 @@ export function findUserClaimPda(programId: PublicKey, user: PublicKey): [Public
  */
 export function buildInitializeIx(
   env: TestEnv,
-  inflationRateBps: number = 1000
+  inflationRateBps: number = 1000,
+  maxSupply: bigint = U64_MAX,
+  minDistributionInterval: bigint = BigInt(0),
+  ratePeriodSecs: bigint = BigInt(SECONDS_PER_YEAR)
 ): TransactionInstruction {
   const data = borsh.serialize(initializeSchema, {
     instruction: 0,
     merkle_updater: Array.from(env.merkleUpdater.publicKey.toBytes()),
     inflation_rate_bps: inflationRateBps,
+    max_supply: maxSupply,
+    min_distribution_interval: minDistributionInterval,
+    rate_period_secs: ratePeriodSecs,
   });
 
   return new TransactionInstruction({
@@ -320,12 +377,14 @@ export function buildInitializeIx(
 export function buildDistributeIx(
   env: TestEnv,
   amount: bigint,
-  merkleRoot: Buffer
+  merkleRoot: Buffer,
+  treeDepth: number = 1
 ): TransactionInstruction {
   const data = borsh.serialize(distributeSchema, {
     instruction: 2,
     amount,
     merkle_root: Array.from(merkleRoot),
+    tree_depth: treeDepth,
   });
 
   return new TransactionInstruction({
@@ -363,7 +422,7 @@ export function buildClaimIx(
       { pubkey: claimer.publicKey, isSigner: true, isWritable: true },
       { pubkey: claimerAta, isSigner: false, isWritable: true },
       { pubkey: userClaimPda, isSigner: false, isWritable: true },
-      { pubkey: env.configPda, isSigner: false, isWritable: false },
+      { pubkey: env.configPda, isSigner: false, isWritable: true },
       { pubkey: env.pendingClaimsPda, isSigner: false, isWritable: true },
       { pubkey: env.mintPda, isSigner: false, isWritable: false },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
@@ -373,6 +432,8 @@ export function buildClaimIx(
         isSigner: false,
         isWritable: false,
       },
+      // Lets the program create the claimer's ATA if it doesn't exist yet
+      { pubkey: ASSOCIATED_TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
     ],
     data: Buffer.from(data),
   });
@@ -414,14 +475,15 @@ export function initializeProgram(
 }
 
 /**
- * Distribute tokens with a new merkle root
+ * Distribute tokens with a new merkle root and its tree depth
  */
 export function distribute(
   env: TestEnv,
   amount: bigint,
-  merkleRoot: Buffer
+  merkleRoot: Buffer,
+  treeDepth: number = 1
 ): TransactionMetadata | FailedTransactionMetadata {
-  const ix = buildDistributeIx(env, amount, merkleRoot);
+  const ix = buildDistributeIx(env, amount, merkleRoot, treeDepth);
   const tx = new Transaction();
   tx.recentBlockhash = env.svm.latestBlockhash();
   tx.add(ix);
@@ -488,15 +550,19 @@ export function buildBurnIx(
     instruction: 4, // Burn instruction index
     amount,
   });
+  const [burnerClaimPda] = findUserClaimPda(env.programId, burner.publicKey);
 
   return new TransactionInstruction({
     programId: env.programId,
     keys: [
-      { pubkey: burner.publicKey, isSigner: true, isWritable: false },
+      { pubkey: burner.publicKey, isSigner: true, isWritable: true },
       { pubkey: burnerAta, isSigner: false, isWritable: true },
       { pubkey: env.configPda, isSigner: false, isWritable: true },
       { pubkey: env.mintPda, isSigner: false, isWritable: true },
       { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+      { pubkey: burnerClaimPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
     ],
     data: Buffer.from(data),
   });
@@ -519,6 +585,192 @@ export function burn(
   return env.svm.sendTransaction(tx);
 }
 
+// ============== Bonus Helpers ==============
+
+export const setBonusRootSchema = {
+  struct: {
+    instruction: 'u8',
+    bonus_root: { array: { type: 'u8', len: 32 } },
+    funding: 'u64',
+    tree_depth: 'u8',
+  },
+};
+
+export const claimWithBonusSchema = {
+  struct: {
+    instruction: 'u8',
+    amount: 'u64',
+    proof: { array: { type: { array: { type: 'u8', len: 32 } } } },
+    bonus_amount: 'u64',
+    bonus_proof: { array: { type: { array: { type: 'u8', len: 32 } } } },
+  },
+};
+
+/**
+ * Build SetBonusRoot instruction, moving `funding` from the vault into the bonus reserve
+ */
+export function buildSetBonusRootIx(
+  env: TestEnv,
+  bonusRoot: Buffer,
+  funding: bigint,
+  treeDepth: number = 1
+): TransactionInstruction {
+  const data = borsh.serialize(setBonusRootSchema, {
+    instruction: 7, // SetBonusRoot instruction index
+    bonus_root: Array.from(bonusRoot),
+    funding,
+    tree_depth: treeDepth,
+  });
+
+  return new TransactionInstruction({
+    programId: env.programId,
+    keys: [
+      { pubkey: env.merkleUpdater.publicKey, isSigner: true, isWritable: false },
+      { pubkey: env.configPda, isSigner: false, isWritable: true },
+      { pubkey: env.vaultPda, isSigner: false, isWritable: true },
+      { pubkey: env.pendingClaimsPda, isSigner: false, isWritable: true },
+      { pubkey: env.mintPda, isSigner: false, isWritable: false },
+      { pubkey: TOKEN_PROGRAM_ID, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from(data),
+  });
+}
+
+/**
+ * Execute SetBonusRoot transaction
+ */
+export function setBonusRoot(
+  env: TestEnv,
+  bonusRoot: Buffer,
+  funding: bigint,
+  treeDepth: number = 1
+): TransactionMetadata | FailedTransactionMetadata {
+  const ix = buildSetBonusRootIx(env, bonusRoot, funding, treeDepth);
+  const tx = new Transaction();
+  tx.recentBlockhash = env.svm.latestBlockhash();
+  tx.add(ix);
+  tx.sign(env.merkleUpdater);
+  return env.svm.sendTransaction(tx);
+}
+
+/**
+ * Build ClaimWithBonus instruction (same accounts as Claim)
+ */
+export function buildClaimWithBonusIx(
+  env: TestEnv,
+  claimer: Keypair,
+  claimerAta: PublicKey,
+  amount: bigint,
+  proof: Buffer[],
+  bonusAmount: bigint,
+  bonusProof: Buffer[]
+): TransactionInstruction {
+  const ix = buildClaimIx(env, claimer, claimerAta, amount, proof);
+  ix.data = Buffer.from(
+    borsh.serialize(claimWithBonusSchema, {
+      instruction: 8, // ClaimWithBonus instruction index
+      amount,
+      proof: proof.map((p) => Array.from(p)),
+      bonus_amount: bonusAmount,
+      bonus_proof: bonusProof.map((p) => Array.from(p)),
+    })
+  );
+  return ix;
+}
+
+/**
+ * Read the bonus reserve SetBonusRoot funds and bonus claims draw down
+ */
+export function getBonusReserved(env: TestEnv): bigint {
+  const account = env.svm.getAccount(env.configPda);
+  if (!account) return 0n;
+  return readU64LE(account.data, CONFIG_BONUS_RESERVED_OFFSET);
+}
+
+// ============== Proof Buffer Helpers ==============
+
+export const writeProofBufferSchema = {
+  struct: {
+    instruction: 'u8',
+    offset: 'u8',
+    nodes: { array: { type: { array: { type: 'u8', len: 32 } } } },
+  },
+};
+
+export const claimWithProofBufferSchema = {
+  struct: {
+    instruction: 'u8',
+    amount: 'u64',
+  },
+};
+
+/**
+ * Build WriteProofBuffer instruction, replacing the nodes from `offset` on
+ */
+export function buildWriteProofBufferIx(
+  env: TestEnv,
+  user: Keypair,
+  offset: number,
+  nodes: Buffer[]
+): TransactionInstruction {
+  const [proofBufferPda] = findProofBufferPda(env.programId, user.publicKey);
+
+  const data = borsh.serialize(writeProofBufferSchema, {
+    instruction: 63, // WriteProofBuffer instruction index
+    offset,
+    nodes: nodes.map((n) => Array.from(n)),
+  });
+
+  return new TransactionInstruction({
+    programId: env.programId,
+    keys: [
+      { pubkey: user.publicKey, isSigner: true, isWritable: true },
+      { pubkey: proofBufferPda, isSigner: false, isWritable: true },
+      { pubkey: SystemProgram.programId, isSigner: false, isWritable: false },
+      { pubkey: SYSVAR_RENT_PUBKEY, isSigner: false, isWritable: false },
+    ],
+    data: Buffer.from(data),
+  });
+}
+
+/**
+ * Execute WriteProofBuffer transaction
+ */
+export function writeProofBuffer(
+  env: TestEnv,
+  user: Keypair,
+  offset: number,
+  nodes: Buffer[]
+): TransactionMetadata | FailedTransactionMetadata {
+  const ix = buildWriteProofBufferIx(env, user, offset, nodes);
+  const tx = new Transaction();
+  tx.recentBlockhash = env.svm.latestBlockhash();
+  tx.add(ix);
+  tx.sign(user);
+  return env.svm.sendTransaction(tx);
+}
+
+/**
+ * Build ClaimWithProofBuffer instruction: the proof buffer, then the Claim accounts
+ */
+export function buildClaimWithProofBufferIx(
+  env: TestEnv,
+  claimer: Keypair,
+  claimerAta: PublicKey,
+  amount: bigint
+): TransactionInstruction {
+  const [proofBufferPda] = findProofBufferPda(env.programId, claimer.publicKey);
+  const ix = buildClaimIx(env, claimer, claimerAta, amount, []);
+  ix.keys.unshift({ pubkey: proofBufferPda, isSigner: false, isWritable: true });
+  ix.data = Buffer.from(
+    borsh.serialize(claimWithProofBufferSchema, {
+      instruction: 64, // ClaimWithProofBuffer instruction index
+      amount,
+    })
+  );
+  return ix;
+}
+
 // ============== Admin Helpers ==============
 
 export const updateMerkleUpdaterSchema = {
@@ -575,12 +827,14 @@ export function buildDistributeIxWithSigner(
   env: TestEnv,
   signer: Keypair,
   amount: bigint,
-  merkleRoot: Buffer
+  merkleRoot: Buffer,
+  treeDepth: number = 1
 ): TransactionInstruction {
   const data = borsh.serialize(distributeSchema, {
     instruction: 2,
     amount,
     merkle_root: Array.from(merkleRoot),
+    tree_depth: treeDepth,
   });
 
   return new TransactionInstruction({
@@ -604,9 +858,10 @@ export function distributeWithSigner(
   env: TestEnv,
   signer: Keypair,
   amount: bigint,
-  merkleRoot: Buffer
+  merkleRoot: Buffer,
+  treeDepth: number = 1
 ): TransactionMetadata | FailedTransactionMetadata {
-  const ix = buildDistributeIxWithSigner(env, signer, amount, merkleRoot);
+  const ix = buildDistributeIxWithSigner(env, signer, amount, merkleRoot, treeDepth);
   const tx = new Transaction();
   tx.recentBlockhash = env.svm.latestBlockhash();
   tx.add(ix);
@@ -614,6 +869,57 @@ export function distributeWithSigner(
   return env.svm.sendTransaction(tx);
 }
 
+// ============== Test-time Helpers ==============
+
+export const setLastTimestampsSchema = {
+  struct: {
+    instruction: 'u8',
+    inflation_ts: 'i64',
+    distribution_ts: 'i64',
+  },
+};
+
+/**
+ * Build SetLastTimestamps instruction
+ * Only handled by a `--features test-time` build; others reject it
+ */
+export function buildSetLastTimestampsIx(
+  env: TestEnv,
+  inflationTs: bigint,
+  distributionTs: bigint
+): TransactionInstruction {
+  const data = borsh.serialize(setLastTimestampsSchema, {
+    instruction: 13, // SetLastTimestamps instruction index
+    inflation_ts: inflationTs,
+    distribution_ts: distributionTs,
+  });
+
+  return new TransactionInstruction({
+    programId: env.programId,
+    keys: [
+      { pubkey: env.admin.publicKey, isSigner: true, isWritable: false },
+      { pubkey: env.configPda, isSigner: false, isWritable: true },
+    ],
+    data: Buffer.from(data),
+  });
+}
+
+/**
+ * Overwrite last_inflation_ts and last_distribution_ts (test-time builds only)
+ */
+export function setLastTimestamps(
+  env: TestEnv,
+  inflationTs: bigint,
+  distributionTs: bigint
+): TransactionMetadata | FailedTransactionMetadata {
+  const ix = buildSetLastTimestampsIx(env, inflationTs, distributionTs);
+  const tx = new Transaction();
+  tx.recentBlockhash = env.svm.latestBlockhash();
+  tx.add(ix);
+  tx.sign(env.admin);
+  return env.svm.sendTransaction(tx);
+}
+
 // ============== ATA Helpers ==============
 
 /**
@@ -672,11 +978,11 @@ export function fundUserViaClaim(
   warpTime(env, warpDays * 86400);
 
   // 3. Build merkle tree with user's allocation and distribute
-  const leaf = computeLeaf(user.publicKey, amount);
+  const leaf = computeLeaf(user.publicKey, amount, env.domainSalt);
   const tree = buildMerkleTree([leaf]);
   const proof = tree.getProof(0);
 
-  const distributeResult = distribute(env, amount, tree.root);
+  const distributeResult = distribute(env, amount, tree.root, tree.depth);
   if (!isSuccess(distributeResult)) {
     return { ata, success: false, logs: getLogs(distributeResult) };
   }
@@ -402,6 +402,45 @@ describe('initialize', () => {
     console.log('Wrong token program rejected: OK');
   });
 
+  it('failing metadata CPI leaves nothing half-initialized', () => {
+    // Occupy the metadata PDA so Metaplex refuses to create it
+    env.svm.setAccount(env.metadataPda, {
+      executable: false,
+      owner: METADATA_PROGRAM_ID,
+      lamports: LAMPORTS_PER_SOL,
+      data: new Uint8Array(64).fill(1),
+      rentEpoch: 0,
+    });
+
+    const failed = initializeProgram(env);
+    expect(isSuccess(failed)).toBe(false);
+
+    // The config written before the CPI is rolled back with everything else
+    for (const pda of [env.configPda, env.mintPda, env.vaultPda, env.pendingClaimsPda]) {
+      const account = env.svm.getAccount(pda);
+      expect(account === null || account.lamports === 0).toBe(true);
+    }
+
+    // Clear the obstacle: the same PDAs initialize cleanly on retry
+    env.svm.setAccount(env.metadataPda, {
+      executable: false,
+      owner: SystemProgram.programId,
+      lamports: 0,
+      data: new Uint8Array(0),
+      rentEpoch: 0,
+    });
+    env.svm.expireBlockhash();
+
+    const retried = initializeProgram(env);
+    if (!isSuccess(retried)) {
+      console.log('Retry failed:', getLogs(retried));
+    }
+    expect(isSuccess(retried)).toBe(true);
+    expect(getConfig(env).current_supply).toBe(INITIAL_SUPPLY);
+
+    console.log('Failed metadata CPI is retryable: OK');
+  });
+
   it('allows custom inflation rate', () => {
     const customRate = 500; // 5%
     const result = initializeProgram(env, customRate);
@@ -54,14 +54,24 @@ const METADATA_PROGRAM_ID = new PublicKey('metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt
 
 // Merkle tree utilities
 const LEAF_DOMAIN = Buffer.from('YAP_CLAIM_V1');
+const SALT_DOMAIN = Buffer.from('YAP_SALT_V1');
+const LEAF_PREFIX = Buffer.from([0x00]);
+const NODE_PREFIX = Buffer.from([0x01]);
 
 function keccak256(data: Buffer): Buffer {
   return Buffer.from(keccak.arrayBuffer(data));
 }
 
-function computeLeaf(wallet: PublicKey, amount: bigint): Buffer {
+// Leaf salt Initialize derives from the program id
+function deploymentSalt(programId: PublicKey): Buffer {
+  return keccak256(Buffer.concat([SALT_DOMAIN, programId.toBuffer()])).subarray(0, 16);
+}
+
+function computeLeaf(wallet: PublicKey, amount: bigint, salt: Buffer): Buffer {
   const data = Buffer.concat([
+    LEAF_PREFIX,
     LEAF_DOMAIN,
+    salt,
     wallet.toBuffer(),
     Buffer.from(new BigUint64Array([amount]).buffer),
   ]);
@@ -70,29 +80,33 @@ function computeLeaf(wallet: PublicKey, amount: bigint): Buffer {
 
 function sortAndHash(a: Buffer, b: Buffer): Buffer {
   if (a.compare(b) <= 0) {
-    return keccak256(Buffer.concat([a, b]));
+    return keccak256(Buffer.concat([NODE_PREFIX, a, b]));
   } else {
-    return keccak256(Buffer.concat([b, a]));
+    return keccak256(Buffer.concat([NODE_PREFIX, b, a]));
   }
 }
 
 function buildMerkleTree(leaves: Buffer[]): {
   root: Buffer;
+  depth: number;
   getProof: (index: number) => Buffer[];
 } {
   if (leaves.length === 0) throw new Error('No leaves');
 
-  const levels: Buffer[][] = [leaves];
-  let currentLevel = leaves;
+  // Pad with zero leaves up to a power of two (at least 2), like the
+  // contract's MerkleTree, so every proof is exactly `depth` long
+  const padded = [...leaves];
+  while (padded.length < 2 || (padded.length & (padded.length - 1)) !== 0) {
+    padded.push(Buffer.alloc(32));
+  }
+
+  const levels: Buffer[][] = [padded];
+  let currentLevel = padded;
 
   while (currentLevel.length > 1) {
     const nextLevel: Buffer[] = [];
     for (let i = 0; i < currentLevel.length; i += 2) {
-      if (i + 1 < currentLevel.length) {
-        nextLevel.push(sortAndHash(currentLevel[i], currentLevel[i + 1]));
-      } else {
-        nextLevel.push(currentLevel[i]);
-      }
+      nextLevel.push(sortAndHash(currentLevel[i], currentLevel[i + 1]));
     }
     levels.push(nextLevel);
     currentLevel = nextLevel;
@@ -100,18 +114,13 @@ function buildMerkleTree(leaves: Buffer[]): {
 
   return {
     root: currentLevel[0],
+    depth: levels.length - 1,
     getProof(index: number): Buffer[] {
       const proof: Buffer[] = [];
       let idx = index;
 
       for (let level = 0; level < levels.length - 1; level++) {
-        const levelNodes = levels[level];
-        const siblingIdx = idx % 2 === 1 ? idx - 1 : idx + 1;
-
-        if (siblingIdx < levelNodes.length) {
-          proof.push(levelNodes[siblingIdx]);
-        }
-
+        proof.push(levels[level][idx ^ 1]);
         idx = Math.floor(idx / 2);
       }
 
@@ -134,6 +143,7 @@ const distributeSchema = {
     instruction: 'u8',
     amount: 'u64',
     merkle_root: { array: { type: 'u8', len: 32 } },
+    tree_depth: 'u8',
   },
 };
 
@@ -303,7 +313,7 @@ describe('YAP Integration Test', () => {
 
     // Build merkle tree with user allocation
     const userAmount = BigInt(1000 * 10 ** DECIMALS); // 1000 tokens
-    const leaf = computeLeaf(user.publicKey, userAmount);
+    const leaf = computeLeaf(user.publicKey, userAmount, deploymentSalt(programId));
     const tree = buildMerkleTree([leaf]);
 
     // Calculate available based on 1 day elapsed
@@ -320,6 +330,7 @@ describe('YAP Integration Test', () => {
       instruction: 2,
       amount: distributeAmount,
       merkle_root: Array.from(tree.root),
+      tree_depth: tree.depth,
     });
 
     const ix = new TransactionInstruction({
@@ -352,13 +363,14 @@ describe('YAP Integration Test', () => {
   });
 
   it('3. Distribute - immediate second distribution fails (rate limited)', async () => {
-    const tree = buildMerkleTree([computeLeaf(user.publicKey, 1000n)]);
+    const tree = buildMerkleTree([computeLeaf(user.publicKey, 1000n, deploymentSalt(programId))]);
 
     // Try to distribute any amount immediately - should fail
     const data = borsh.serialize(distributeSchema, {
       instruction: 2,
       amount: BigInt(1), // Even 1 token should fail
       merkle_root: Array.from(tree.root),
+      tree_depth: tree.depth,
     });
 
     const ix = new TransactionInstruction({
@@ -398,7 +410,9 @@ describe('YAP Integration Test', () => {
     );
 
     const userAmount = BigInt(2000 * 10 ** DECIMALS);
-    const tree = buildMerkleTree([computeLeaf(user.publicKey, userAmount)]);
+    const tree = buildMerkleTree([
+      computeLeaf(user.publicKey, userAmount, deploymentSalt(programId)),
+    ]);
 
     // After warping, we have 1 day of new allocation available
     const configAccount = svm.getAccount(configPda);
@@ -429,6 +443,7 @@ describe('YAP Integration Test', () => {
       instruction: 2,
       amount: distributeAmount,
       merkle_root: Array.from(tree.root),
+      tree_depth: tree.depth,
     });
 
     const ix = new TransactionInstruction({
@@ -471,7 +486,7 @@ describe('YAP Integration Test', () => {
 
     // Build tree matching what's in config
     const userAmount = BigInt(2000 * 10 ** DECIMALS);
-    const leaf = computeLeaf(user.publicKey, userAmount);
+    const leaf = computeLeaf(user.publicKey, userAmount, deploymentSalt(programId));
     const tree = buildMerkleTree([leaf]);
 
     // Verify our tree root matches config
@@ -665,12 +680,13 @@ describe('YAP Integration Test', () => {
     const fakeUpdater = Keypair.generate();
     svm.airdrop(fakeUpdater.publicKey, BigInt(LAMPORTS_PER_SOL));
 
-    const tree = buildMerkleTree([computeLeaf(user.publicKey, 1000n)]);
+    const tree = buildMerkleTree([computeLeaf(user.publicKey, 1000n, deploymentSalt(programId))]);
 
     const data = borsh.serialize(distributeSchema, {
       instruction: 2,
       amount: BigInt(0),
       merkle_root: Array.from(tree.root),
+      tree_depth: tree.depth,
     });
 
     const ix = new TransactionInstruction({
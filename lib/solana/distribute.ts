@@ -49,16 +49,20 @@ export function getMerkleUpdaterKeypair(): Keypair {
 }
 
 // Build distribute instruction data
-// Layout: [discriminator(1)] [amount(8)] [merkle_root(32)]
-function buildDistributeData(amount: bigint, merkleRoot: Uint8Array): Buffer {
+// Layout: [discriminator(1)] [amount(8)] [merkle_root(32)] [tree_depth(1)]
+function buildDistributeData(amount: bigint, merkleRoot: Uint8Array, treeDepth: number): Buffer {
   if (merkleRoot.length !== 32) {
     throw new Error('Merkle root must be 32 bytes');
   }
+  if (!Number.isInteger(treeDepth) || treeDepth < 1 || treeDepth > 32) {
+    throw new Error('Tree depth must be 1-32');
+  }
 
-  const data = Buffer.alloc(41); // 1 + 8 + 32
+  const data = Buffer.alloc(42); // 1 + 8 + 32 + 1
   data[0] = DISTRIBUTE_DISCRIMINATOR;
   writeBigUInt64LE(data, amount, 1);
   Buffer.from(merkleRoot).copy(data, 9);
+  data[41] = treeDepth;
 
   return data;
 }
@@ -74,13 +78,14 @@ function buildDistributeData(amount: bigint, merkleRoot: Uint8Array): Buffer {
 export function createDistributeInstruction(
   updater: PublicKey,
   amount: bigint,
-  merkleRoot: Uint8Array
+  merkleRoot: Uint8Array,
+  treeDepth: number
 ): TransactionInstruction {
   const [configPda] = getConfigPda();
   const [vaultPda] = getVaultPda();
   const [pendingClaimsPda] = getPendingClaimsPda();
   const [mintPda] = getMintPda();
-  const data = buildDistributeData(amount, merkleRoot);
+  const data = buildDistributeData(amount, merkleRoot, treeDepth);
 
   return new TransactionInstruction({
     programId: YAP_PROGRAM_ID,
@@ -97,11 +102,16 @@ export function createDistributeInstruction(
 }
 
 // Submit distribution onchain (transfers tokens from vault to pending_claims and sets merkle root)
-export async function submitMerkleRoot(merkleRoot: Uint8Array, amount: bigint): Promise<string> {
+// `treeDepth` is the tree's proof length (MerkleDistribution.depth)
+export async function submitMerkleRoot(
+  merkleRoot: Uint8Array,
+  amount: bigint,
+  treeDepth: number
+): Promise<string> {
   const connection = new Connection(getRpcEndpoint(), 'confirmed');
   const updater = getMerkleUpdaterKeypair();
 
-  const instruction = createDistributeInstruction(updater.publicKey, amount, merkleRoot);
+  const instruction = createDistributeInstruction(updater.publicKey, amount, merkleRoot, treeDepth);
 
   const transaction = new Transaction().add(instruction);
 
@@ -156,6 +166,37 @@ const CONFIG_OFFSETS = {
   bump: 226,
 } as const;
 
+// Config.claims.domain_salt - see test_config_field_offsets in state.rs
+const DOMAIN_SALT_OFFSET = 1487;
+const DOMAIN_SALT_LEN = 16;
+
+/**
+ * Read the deployment's leaf salt from a Config account's data
+ * A config from before the salt existed has none (empty = unsalted leaves)
+ */
+export function readDomainSalt(configData: Uint8Array): Uint8Array {
+  if (configData.length < DOMAIN_SALT_OFFSET + DOMAIN_SALT_LEN) {
+    return new Uint8Array(0);
+  }
+  return configData.slice(DOMAIN_SALT_OFFSET, DOMAIN_SALT_OFFSET + DOMAIN_SALT_LEN);
+}
+
+/**
+ * Get the domain_salt claim leaves must be built with
+ * Pass it to buildMerkleTree / hashLeaf / verifyProof, or the root won't verify on-chain
+ */
+export async function getDomainSalt(): Promise<Uint8Array> {
+  const connection = new Connection(getRpcEndpoint(), 'confirmed');
+  const [configPda] = getConfigPda();
+
+  const configInfo = await connection.getAccountInfo(configPda);
+  if (!configInfo) {
+    throw new Error('Config account not found - program not initialized');
+  }
+
+  return readDomainSalt(configInfo.data);
+}
+
 /**
  * Get vault token account balance (undistributed tokens)
  */
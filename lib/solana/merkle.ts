@@ -20,6 +20,10 @@ const LEAF_DOMAIN = new Uint8Array([
   49, // "YAP_CLAIM_V1"
 ]);
 
+// Leaf vs internal node tags - MUST match contract's LEAF_PREFIX / NODE_PREFIX
+const LEAF_PREFIX = 0x00;
+const NODE_PREFIX = 0x01;
+
 export interface RewardEntry {
   wallet: Address;
   amount: bigint;
@@ -28,7 +32,11 @@ export interface RewardEntry {
 export interface MerkleDistribution {
   root: Uint8Array;
   tree: MerkleTree;
+  // Proof length of every leaf, published as Distribute's tree_depth
+  depth: number;
   entries: RewardEntry[];
+  // Config's domain_salt the leaves were built with (empty = unsalted)
+  salt?: Uint8Array;
 }
 
 export interface ClaimProof {
@@ -42,9 +50,25 @@ function keccak256Hash(data: Uint8Array): Uint8Array {
   return new Uint8Array(keccak256.arrayBuffer(data));
 }
 
-// Hash a leaf node: keccak256(domain || wallet || amount)
-// MUST match contract's compute_leaf function
-export function hashLeaf(wallet: Address, amount: bigint): Uint8Array {
+// Hash an internal node: keccak256(0x01 || left || right)
+// merkletreejs passes the already sorted, concatenated children
+function hashNode(data: Uint8Array): Uint8Array {
+  const combined = new Uint8Array(1 + data.length);
+  combined[0] = NODE_PREFIX;
+  combined.set(data, 1);
+  return keccak256Hash(combined);
+}
+
+// Hash a leaf node: keccak256(0x00 || domain || salt || wallet || amount)
+// MUST match contract's compute_salted_leaf function. `salt` is the config's
+// domain_salt; an all-zero salt (migrated configs) is left out of the hash.
+export function hashLeaf(
+  wallet: Address,
+  amount: bigint,
+  domainSalt: Uint8Array = new Uint8Array(0)
+): Uint8Array {
+  const salt = domainSalt.every((b) => b === 0) ? new Uint8Array(0) : domainSalt;
+
   const walletBytes = encoder.encode(wallet);
 
   // Amount as little-endian u64
@@ -52,32 +76,51 @@ export function hashLeaf(wallet: Address, amount: bigint): Uint8Array {
   const view = new DataView(amountBytes.buffer);
   view.setBigUint64(0, amount, true); // true = little-endian
 
-  // Combine: domain (12) + wallet (32) + amount (8) = 52 bytes
-  const combined = new Uint8Array(LEAF_DOMAIN.length + walletBytes.length + amountBytes.length);
-  combined.set(LEAF_DOMAIN, 0);
-  combined.set(walletBytes, LEAF_DOMAIN.length);
-  combined.set(amountBytes, LEAF_DOMAIN.length + walletBytes.length);
+  // Combine: prefix (1) + domain (12) + salt (0 or 16) + wallet (32) + amount (8)
+  const combined = new Uint8Array(
+    1 + LEAF_DOMAIN.length + salt.length + walletBytes.length + amountBytes.length
+  );
+  combined[0] = LEAF_PREFIX;
+  combined.set(LEAF_DOMAIN, 1);
+  combined.set(salt, 1 + LEAF_DOMAIN.length);
+  combined.set(walletBytes, 1 + LEAF_DOMAIN.length + salt.length);
+  combined.set(amountBytes, 1 + LEAF_DOMAIN.length + salt.length + walletBytes.length);
 
   return keccak256Hash(combined);
 }
 
 // Build merkle tree from reward entries
-export function buildMerkleTree(entries: RewardEntry[]): MerkleDistribution {
+export function buildMerkleTree(entries: RewardEntry[], salt?: Uint8Array): MerkleDistribution {
   if (entries.length === 0) {
     throw new Error('Cannot build tree with no entries');
   }
 
-  const leaves = entries.map((e) => hashLeaf(e.wallet, e.amount));
+  const leaves = entries.map((e) => hashLeaf(e.wallet, e.amount, salt));
+
+  // Pad with zero leaves up to a power of two (at least 2) so no node is
+  // promoted and every proof is exactly `depth` long, as the contract checks.
+  // MUST match contract's MerkleTree::from_leaves
+  let size = 2;
+  let depth = 1;
+  while (size < leaves.length) {
+    size *= 2;
+    depth += 1;
+  }
+  while (leaves.length < size) {
+    leaves.push(new Uint8Array(32));
+  }
 
-  // Use keccak256 for internal nodes, sortPairs for consistent ordering
-  const tree = new MerkleTree(leaves, (data: Uint8Array) => keccak256Hash(data), {
+  // Use tagged keccak256 for internal nodes, sortPairs for consistent ordering
+  const tree = new MerkleTree(leaves, hashNode, {
     sortPairs: true,
   });
 
   return {
     root: new Uint8Array(tree.getRoot()),
     tree,
+    depth,
     entries,
+    salt,
   };
 }
 
@@ -86,7 +129,7 @@ export function getProof(distribution: MerkleDistribution, wallet: Address): Cla
   const entry = distribution.entries.find((e) => e.wallet === wallet);
   if (!entry) return null;
 
-  const leaf = hashLeaf(wallet, entry.amount);
+  const leaf = hashLeaf(wallet, entry.amount, distribution.salt);
   const proof = distribution.tree.getProof(Buffer.from(leaf)).map((p) => new Uint8Array(p.data));
 
   return {
@@ -101,10 +144,11 @@ export function verifyProof(
   root: Uint8Array,
   wallet: Address,
   amount: bigint,
-  proof: Uint8Array[]
+  proof: Uint8Array[],
+  salt?: Uint8Array
 ): boolean {
-  const leaf = hashLeaf(wallet, amount);
-  const tree = new MerkleTree([], (data: Uint8Array) => keccak256Hash(data), {
+  const leaf = hashLeaf(wallet, amount, salt);
+  const tree = new MerkleTree([], hashNode, {
     sortPairs: true,
   });
 
@@ -117,7 +161,8 @@ export function verifyProof(
 
 // Generate distribution from database rewards
 export async function generateDistribution(
-  getRewards: () => Promise<{ wallet: Address; points: number }[]>
+  getRewards: () => Promise<{ wallet: Address; points: number }[]>,
+  salt?: Uint8Array
 ): Promise<MerkleDistribution> {
   const rewards = await getRewards();
 
@@ -132,7 +177,7 @@ export async function generateDistribution(
     throw new Error('No rewards to distribute');
   }
 
-  return buildMerkleTree(entries);
+  return buildMerkleTree(entries, salt);
 }
 
 // Export distribution data for storage
@@ -150,16 +195,19 @@ export function exportDistribution(distribution: MerkleDistribution): {
 }
 
 // Import distribution data from storage
-export function importDistribution(data: {
-  root: string;
-  entries: { wallet: Address; amount: string }[];
-}): MerkleDistribution {
+export function importDistribution(
+  data: {
+    root: string;
+    entries: { wallet: Address; amount: string }[];
+  },
+  salt?: Uint8Array
+): MerkleDistribution {
   const entries: RewardEntry[] = data.entries.map((e) => ({
     wallet: e.wallet,
     amount: BigInt(e.amount),
   }));
 
-  const distribution = buildMerkleTree(entries);
+  const distribution = buildMerkleTree(entries, salt);
 
   const expectedRoot = Buffer.from(data.root, 'hex');
   if (!Buffer.from(distribution.root).equals(expectedRoot)) {
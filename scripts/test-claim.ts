@@ -50,6 +50,7 @@ async function testClaim() {
 
   // Import merkle functions
   const { buildMerkleTree, getProof } = await import('../lib/solana/merkle');
+  const { readDomainSalt } = await import('../lib/solana/distribute');
   const { address } = await import('@solana/kit');
   type RewardEntry = { wallet: ReturnType<typeof address>; amount: bigint };
 
@@ -58,7 +59,15 @@ async function testClaim() {
     amount: BigInt(r.amount),
   }));
 
-  const distribution = buildMerkleTree(entries);
+  // Leaves carry the deployment's domain_salt, read from the config
+  const [configPda] = PublicKey.findProgramAddressSync([CONFIG_SEED], PROGRAM_ID);
+  const configInfo = await connection.getAccountInfo(configPda);
+  if (!configInfo) {
+    console.log('Config account not found');
+    return;
+  }
+
+  const distribution = buildMerkleTree(entries, readDomainSalt(configInfo.data));
   const proof = getProof(distribution, address(userWallet.toBase58()));
 
   if (!proof) {
@@ -71,7 +80,6 @@ async function testClaim() {
   console.log('Merkle root:', Buffer.from(distribution.root).toString('hex'));
 
   // Derive PDAs
-  const [configPda] = PublicKey.findProgramAddressSync([CONFIG_SEED], PROGRAM_ID);
   const [mintPda] = PublicKey.findProgramAddressSync([MINT_SEED], PROGRAM_ID);
   const [vaultPda] = PublicKey.findProgramAddressSync([VAULT_SEED], PROGRAM_ID);
   const [userClaimPda] = PublicKey.findProgramAddressSync(